@@ -0,0 +1,20 @@
+//! JSON representations of the account actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs};
+use fvm_shared::address::Address;
+
+use crate::types::AuthenticateMessageParams;
+use crate::Method;
+
+json_struct! {
+    AuthenticateMessageParams { signature, message }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (Address, ()),
+        PubkeyAddress => ((), Address),
+        AuthenticateMessageExported => (AuthenticateMessageParams, ()),
+    }
+}
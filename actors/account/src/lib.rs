@@ -18,6 +18,8 @@ use crate::types::AuthenticateMessageParams;
 
 pub use self::state::State;
 
+#[cfg(feature = "json")]
+pub mod json;
 mod state;
 pub mod testing;
 pub mod types;
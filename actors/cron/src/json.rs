@@ -0,0 +1,18 @@
+//! JSON representations of the cron actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs};
+
+use crate::{ConstructorParams, Entry, Method};
+
+json_struct! {
+    ConstructorParams { entries }
+    Entry { receiver, method_num }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (ConstructorParams, ()),
+        EpochTick => ((), ()),
+    }
+}
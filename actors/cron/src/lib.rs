@@ -15,6 +15,8 @@ use num_traits::Zero;
 
 pub use self::state::{Entry, State};
 
+#[cfg(feature = "json")]
+pub mod json;
 mod state;
 pub mod testing;
 
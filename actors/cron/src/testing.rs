@@ -3,6 +3,7 @@ use fvm_shared::address::Protocol;
 
 use crate::State;
 
+#[derive(Debug)]
 pub struct StateSummary {
     pub entry_count: usize,
 }
@@ -11,11 +12,12 @@ pub fn check_state_invariants(state: &State) -> (StateSummary, MessageAccumulato
     let acc = MessageAccumulator::default();
 
     state.entries.iter().enumerate().for_each(|(i, entry)| {
-        acc.require(
+        let acc = acc.with_context("entry", i);
+        acc.with_code("cron.entry.receiver_protocol").require(
             entry.receiver.protocol() == Protocol::ID,
             format!("entry {i} receiver address {} must be ID protocol", entry.receiver),
         );
-        acc.require(
+        acc.with_code("cron.entry.method_num").require(
             entry.method_num > 0,
             format!("entry {i} has invalid method number {}", entry.method_num),
         );
@@ -0,0 +1,61 @@
+//! JSON representations of the datacap actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs};
+use frc46_token::token::types::{
+    BurnFromParams, BurnFromReturn, BurnParams, BurnReturn, DecreaseAllowanceParams,
+    GetAllowanceParams, IncreaseAllowanceParams, MintReturn, RevokeAllowanceParams,
+    TransferFromParams, TransferFromReturn, TransferParams, TransferReturn,
+};
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+
+use crate::{DestroyParams, GranularityReturn, Method, MintExpiringParams, MintParams};
+
+json_struct! {
+    MintParams { to, amount, operators }
+    MintExpiringParams { to, amount, operators, expiration }
+    DestroyParams { owner, amount }
+    transparent GranularityReturn { granularity }
+    remote MintReturnJson(MintReturn) { balance, supply, recipient_data }
+    remote TransferParamsJson(TransferParams) { to, amount, operator_data }
+    remote TransferReturnJson(TransferReturn) { from_balance, to_balance, recipient_data }
+    remote TransferFromParamsJson(TransferFromParams) { from, to, amount, operator_data }
+    remote TransferFromReturnJson(TransferFromReturn) {
+        from_balance,
+        to_balance,
+        allowance,
+        recipient_data,
+    }
+    remote IncreaseAllowanceParamsJson(IncreaseAllowanceParams) { operator, increase }
+    remote DecreaseAllowanceParamsJson(DecreaseAllowanceParams) { operator, decrease }
+    remote RevokeAllowanceParamsJson(RevokeAllowanceParams) { operator }
+    remote GetAllowanceParamsJson(GetAllowanceParams) { owner, operator }
+    remote BurnParamsJson(BurnParams) { amount }
+    remote BurnReturnJson(BurnReturn) { balance }
+    remote BurnFromParamsJson(BurnFromParams) { owner, amount }
+    remote BurnFromReturnJson(BurnFromReturn) { balance, allowance }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (Address, ()),
+        MintExported => (MintParams, MintReturnJson),
+        DestroyExported => (DestroyParams, BurnReturnJson),
+        NameExported => ((), String),
+        SymbolExported => ((), String),
+        GranularityExported => ((), GranularityReturn),
+        TotalSupplyExported => ((), TokenAmount),
+        BalanceExported => (Address, TokenAmount),
+        TransferExported => (TransferParamsJson, TransferReturnJson),
+        TransferFromExported => (TransferFromParamsJson, TransferFromReturnJson),
+        IncreaseAllowanceExported => (IncreaseAllowanceParamsJson, TokenAmount),
+        DecreaseAllowanceExported => (DecreaseAllowanceParamsJson, TokenAmount),
+        RevokeAllowanceExported => (RevokeAllowanceParamsJson, TokenAmount),
+        BurnExported => (BurnParamsJson, BurnReturnJson),
+        BurnFromExported => (BurnFromParamsJson, BurnFromReturnJson),
+        AllowanceExported => (GetAllowanceParamsJson, TokenAmount),
+        LapseExported => (Address, TokenAmount),
+        MintExpiringExported => (MintExpiringParams, MintReturnJson),
+    }
+}
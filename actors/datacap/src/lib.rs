@@ -8,9 +8,11 @@ use frc46_token::token::{Token, TokenError, TOKEN_PRECISION};
 use fvm_actor_utils::receiver::ReceiverHookError;
 use fvm_actor_utils::syscalls::{NoStateError, Syscalls};
 use fvm_actor_utils::util::ActorRuntime;
+use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::BigInt;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::{ErrorNumber, ExitCode};
 use fvm_shared::Response;
@@ -18,6 +20,7 @@ use fvm_shared::{ActorID, MethodNum, METHOD_CONSTRUCTOR};
 use lazy_static::lazy_static;
 use log::info;
 use num_derive::FromPrimitive;
+use num_traits::Zero;
 
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fil_actors_runtime::{
@@ -26,12 +29,14 @@ use fil_actors_runtime::{
 };
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 
-pub use self::state::State;
+pub use self::state::{Grant, State};
 pub use self::types::*;
 
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+#[cfg(feature = "json")]
+pub mod json;
 mod state;
 pub mod testing;
 mod types;
@@ -82,6 +87,8 @@ pub enum Method {
     BurnExported = frc42_dispatch::method_hash!("Burn"),
     BurnFromExported = frc42_dispatch::method_hash!("BurnFrom"),
     AllowanceExported = frc42_dispatch::method_hash!("Allowance"),
+    LapseExported = frc42_dispatch::method_hash!("Lapse"),
+    MintExpiringExported = frc42_dispatch::method_hash!("MintExpiring"),
 }
 
 pub struct Actor;
@@ -148,40 +155,19 @@ impl Actor {
     /// Only the governor can call this method.
     /// This method is not part of the fungible token standard.
     pub fn mint(rt: &mut impl Runtime, params: MintParams) -> Result<MintReturn, ActorError> {
-        let mut hook = rt
-            .transaction(|st: &mut State, rt| {
-                // Only the governor can mint datacap tokens.
-                rt.validate_immediate_caller_is(std::iter::once(&st.governor))?;
-                let operator = st.governor;
-
-                let msg = SyscallProvider { rt };
-                let mut token = as_token(st, &msg);
-                // Mint tokens "from" the operator to the beneficiary.
-                let ret = token
-                    .mint(
-                        &operator,
-                        &params.to,
-                        &params.amount,
-                        RawBytes::default(),
-                        RawBytes::default(),
-                    )
-                    .actor_result();
-
-                // Set allowance for any specified operators.
-                for delegate in &params.operators {
-                    token
-                        .set_allowance(&params.to, delegate, &INFINITE_ALLOWANCE)
-                        .actor_result()?;
-                }
-
-                ret
-            })
-            .context("state transaction failed")?;
+        mint_tokens(rt, params, None)
+    }
 
-        let mut st: State = rt.state()?;
-        let sys_provider = SyscallProvider { rt };
-        let intermediate = hook.call(&as_actor_runtime(&sys_provider)).actor_result()?;
-        as_token(&mut st, &sys_provider).mint_return(intermediate).actor_result()
+    /// Mints new data cap tokens for an address, which lapse if not spent by the expiration.
+    /// Simultaneously sets the allowance for any specified operators to effectively infinite.
+    /// Only the governor can call this method.
+    /// This method is not part of the fungible token standard.
+    pub fn mint_expiring(
+        rt: &mut impl Runtime,
+        params: MintExpiringParams,
+    ) -> Result<MintReturn, ActorError> {
+        let MintExpiringParams { to, amount, operators, expiration } = params;
+        mint_tokens(rt, MintParams { to, amount, operators }, Some(expiration))
     }
 
     /// Destroys data cap tokens for an address (a verified client).
@@ -192,12 +178,19 @@ impl Actor {
         rt.transaction(|st: &mut State, rt| {
             // Only the governor can destroy datacap tokens on behalf of a holder.
             rt.validate_immediate_caller_is(std::iter::once(&st.governor))?;
+            let owner = rt.resolve_address(&params.owner);
+            if let Some(owner) = owner {
+                lapse_expired(st, rt, owner)?;
+            }
 
             let msg = SyscallProvider { rt };
-            let mut token = as_token(st, &msg);
             // Burn tokens as if the holder had invoked burn() themselves.
             // The governor doesn't need an allowance.
-            token.burn(&params.owner, &params.amount).actor_result()
+            let ret = as_token(st, &msg).burn(&params.owner, &params.amount).actor_result()?;
+            if let Some(owner) = owner {
+                debit_grants(st, msg.rt.store(), owner, &params.amount)?;
+            }
+            Ok(ret)
         })
         .context("state transaction failed")
     }
@@ -205,6 +198,7 @@ impl Actor {
     /// Transfers data cap tokens to an address.
     /// Data cap tokens are not generally transferable.
     /// Succeeds if the to or from address is the governor, otherwise always fails.
+    /// Tokens transferred from the governor do not expire.
     pub fn transfer(
         rt: &mut impl Runtime,
         params: TransferParams,
@@ -231,9 +225,11 @@ impl Actor {
                     ));
                 }
 
+                let owner = from.id().unwrap();
+                lapse_expired(st, rt, owner)?;
+
                 let msg = SyscallProvider { rt };
-                let mut token = as_token(st, &msg);
-                token
+                let hook = as_token(st, &msg)
                     .transfer(
                         from,
                         &to_address,
@@ -241,7 +237,9 @@ impl Actor {
                         params.operator_data.clone(),
                         RawBytes::default(),
                     )
-                    .actor_result()
+                    .actor_result()?;
+                debit_grants(st, msg.rt.store(), owner, &params.amount)?;
+                Ok(hook)
             })
             .context("state transaction failed")?;
 
@@ -280,9 +278,13 @@ impl Actor {
                     ));
                 }
 
+                let owner = rt.resolve_address(&from);
+                if let Some(owner) = owner {
+                    lapse_expired(st, rt, owner)?;
+                }
+
                 let msg = SyscallProvider { rt };
-                let mut token = as_token(st, &msg);
-                token
+                let hook = as_token(st, &msg)
                     .transfer_from(
                         &operator,
                         &from,
@@ -291,7 +293,11 @@ impl Actor {
                         params.operator_data.clone(),
                         RawBytes::default(),
                     )
-                    .actor_result()
+                    .actor_result()?;
+                if let Some(owner) = owner {
+                    debit_grants(st, msg.rt.store(), owner, &params.amount)?;
+                }
+                Ok(hook)
             })
             .context("state transaction failed")?;
 
@@ -352,11 +358,14 @@ impl Actor {
     pub fn burn(rt: &mut impl Runtime, params: BurnParams) -> Result<BurnReturn, ActorError> {
         rt.validate_immediate_caller_accept_any()?;
         let owner = &rt.message().caller();
+        let owner_id = owner.id().unwrap();
 
         rt.transaction(|st: &mut State, rt| {
+            lapse_expired(st, rt, owner_id)?;
             let msg = SyscallProvider { rt };
-            let mut token = as_token(st, &msg);
-            token.burn(owner, &params.amount).actor_result()
+            let ret = as_token(st, &msg).burn(owner, &params.amount).actor_result()?;
+            debit_grants(st, msg.rt.store(), owner_id, &params.amount)?;
+            Ok(ret)
         })
         .context("state transaction failed")
     }
@@ -368,14 +377,171 @@ impl Actor {
         rt.validate_immediate_caller_accept_any()?;
         let operator = &rt.message().caller();
         let owner = &params.owner;
+        let owner_id = rt.resolve_address(owner);
 
         rt.transaction(|st: &mut State, rt| {
+            if let Some(owner_id) = owner_id {
+                lapse_expired(st, rt, owner_id)?;
+            }
             let msg = SyscallProvider { rt };
-            let mut token = as_token(st, &msg);
-            token.burn_from(operator, owner, &params.amount).actor_result()
+            let ret =
+                as_token(st, &msg).burn_from(operator, owner, &params.amount).actor_result()?;
+            if let Some(owner_id) = owner_id {
+                debit_grants(st, msg.rt.store(), owner_id, &params.amount)?;
+            }
+            Ok(ret)
         })
         .context("state transaction failed")
     }
+
+    /// Burns a holder's tokens that have passed their expiration without being spent.
+    /// Expired tokens also lapse whenever the holder's balance is spent or credited by minting,
+    /// so this method need only be called to bring a dormant holder's balance up to date.
+    /// Returns the amount of tokens which lapsed.
+    /// This method is not part of the fungible token standard.
+    pub fn lapse(rt: &mut impl Runtime, params: Address) -> Result<TokenAmount, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let owner = match rt.resolve_address(&params) {
+            Some(owner) => owner,
+            None => return Ok(TokenAmount::zero()),
+        };
+        rt.transaction(|st: &mut State, rt| lapse_expired(st, rt, owner))
+            .context("state transaction failed")
+    }
+}
+
+// Mints tokens to a recipient, recording a grant of them if they expire.
+fn mint_tokens(
+    rt: &mut impl Runtime,
+    params: MintParams,
+    expiration: Option<ChainEpoch>,
+) -> Result<MintReturn, ActorError> {
+    let mut hook = rt
+        .transaction(|st: &mut State, rt| {
+            // Only the governor can mint datacap tokens.
+            rt.validate_immediate_caller_is(std::iter::once(&st.governor))?;
+            let operator = st.governor;
+            if let Some(expiration) = expiration {
+                if expiration < rt.curr_epoch() {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "mint expiration {} has passed current epoch {}",
+                        expiration,
+                        rt.curr_epoch()
+                    ));
+                }
+            }
+            // Lapse the recipient's expired tokens before crediting new ones.
+            if let Some(owner) = rt.resolve_address(&params.to) {
+                lapse_expired(st, rt, owner)?;
+            }
+
+            let msg = SyscallProvider { rt };
+            let hook = {
+                let mut token = as_token(st, &msg);
+                // Mint tokens "from" the operator to the beneficiary.
+                let ret = token
+                    .mint(
+                        &operator,
+                        &params.to,
+                        &params.amount,
+                        RawBytes::default(),
+                        RawBytes::default(),
+                    )
+                    .actor_result();
+
+                // Set allowance for any specified operators.
+                for delegate in &params.operators {
+                    token
+                        .set_allowance(&params.to, delegate, &INFINITE_ALLOWANCE)
+                        .actor_result()?;
+                }
+                ret?
+            };
+
+            // Record the expiration of the minted tokens.
+            if let Some(expiration) = expiration {
+                let owner = msg
+                    .rt
+                    .resolve_address(&params.to)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to resolve recipient")?;
+                add_grant(st, msg.rt.store(), owner, &params.amount, expiration)?;
+            }
+            Ok(hook)
+        })
+        .context("state transaction failed")?;
+
+    let mut st: State = rt.state()?;
+    let sys_provider = SyscallProvider { rt };
+    let intermediate = hook.call(&as_actor_runtime(&sys_provider)).actor_result()?;
+    as_token(&mut st, &sys_provider).mint_return(intermediate).actor_result()
+}
+
+// Burns a holder's tokens from grants which have passed their expiration.
+// Only the portion of the grants remaining in the holder's balance is burnt.
+// Returns the amount burnt.
+fn lapse_expired<RT: Runtime>(
+    st: &mut State,
+    rt: &mut RT,
+    owner: ActorID,
+) -> Result<TokenAmount, ActorError> {
+    let curr_epoch = rt.curr_epoch();
+    let grants = st.get_grants(rt.store(), owner)?;
+    let (expired, live): (Vec<Grant>, Vec<Grant>) =
+        grants.into_iter().partition(|g| g.expiration < curr_epoch);
+    if expired.is_empty() {
+        return Ok(TokenAmount::zero());
+    }
+    st.put_grants(rt.store(), owner, live)?;
+
+    let expired_total = expired.iter().fold(TokenAmount::zero(), |acc, g| acc + &g.amount);
+    let owner = Address::new_id(owner);
+    let msg = SyscallProvider { rt };
+    let mut token = as_token(st, &msg);
+    let balance = token.balance_of(&owner).actor_result()?;
+    let lapsed = std::cmp::min(expired_total, balance);
+    if lapsed.is_positive() {
+        token.burn(&owner, &lapsed).actor_result()?;
+    }
+    Ok(lapsed)
+}
+
+// Reduces a holder's grants by an amount spent, those expiring soonest first.
+fn debit_grants<BS: Blockstore>(
+    st: &mut State,
+    store: &BS,
+    owner: ActorID,
+    amount: &TokenAmount,
+) -> Result<(), ActorError> {
+    let mut grants = st.get_grants(store, owner)?;
+    if grants.is_empty() {
+        return Ok(());
+    }
+    let mut remaining = amount.clone();
+    for grant in grants.iter_mut() {
+        if remaining.is_zero() {
+            break;
+        }
+        let debit = std::cmp::min(grant.amount.clone(), remaining.clone());
+        grant.amount -= &debit;
+        remaining -= &debit;
+    }
+    grants.retain(|g| g.amount.is_positive());
+    st.put_grants(store, owner, grants)
+}
+
+// Records a new grant of tokens to a holder, keeping grants in order of expiration.
+fn add_grant<BS: Blockstore>(
+    st: &mut State,
+    store: &BS,
+    owner: ActorID,
+    amount: &TokenAmount,
+    expiration: ChainEpoch,
+) -> Result<(), ActorError> {
+    let mut grants = st.get_grants(store, owner)?;
+    let idx = grants.partition_point(|g| g.expiration <= expiration);
+    grants.insert(idx, Grant { amount: amount.clone(), expiration });
+    st.put_grants(store, owner, grants)
 }
 
 /// Implementation of the token library's messenger trait in terms of the built-in actors'
@@ -480,5 +646,7 @@ impl ActorCode for Actor {
         BurnExported => burn,
         BurnFromExported => burn_from,
         AllowanceExported => allowance,
+        LapseExported => lapse,
+        MintExpiringExported => mint_expiring,
     }
 }
@@ -1,24 +1,86 @@
+use cid::Cid;
 use frc46_token::token;
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
-use fvm_shared::ActorID;
+use fvm_shared::{ActorID, HAMT_BIT_WIDTH};
 
-use fil_actors_runtime::{ActorError, AsActorError};
+use fil_actors_runtime::{
+    make_empty_map, make_map_with_root_and_bitwidth, u64_key, ActorError, AsActorError, Map,
+};
 
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct State {
     pub governor: Address,
     pub token: token::state::TokenState,
+    // Maps holders to the portions of their balance which lapse after some epoch,
+    // in order of expiration.
+    pub grants: Cid, // HAMT[ActorID]Vec<Grant>
+}
+
+// A portion of a holder's balance which lapses if not spent by its expiration.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct Grant {
+    // The amount of tokens remaining unspent.
+    pub amount: TokenAmount,
+    // The last epoch at which the tokens may be spent.
+    pub expiration: ChainEpoch,
 }
 
 impl State {
     pub fn new<BS: Blockstore>(store: &BS, governor: Address) -> Result<State, ActorError> {
         let token_state = token::state::TokenState::new(store)
             .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to create token state")?;
-        Ok(State { governor, token: token_state })
+        let empty_grants = make_empty_map::<_, ()>(store, HAMT_BIT_WIDTH)
+            .flush()
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to create empty grants map")?;
+        Ok(State { governor, token: token_state, grants: empty_grants })
+    }
+
+    pub fn load_grants<'a, BS: Blockstore>(
+        &self,
+        store: &'a BS,
+    ) -> Result<Map<'a, BS, Vec<Grant>>, ActorError> {
+        make_map_with_root_and_bitwidth(&self.grants, store, HAMT_BIT_WIDTH)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load grants")
+    }
+
+    // Returns a holder's expiring grants, in order of expiration.
+    pub fn get_grants<BS: Blockstore>(
+        &self,
+        store: &BS,
+        owner: ActorID,
+    ) -> Result<Vec<Grant>, ActorError> {
+        let grants = self.load_grants(store)?;
+        let found = grants
+            .get(&u64_key(owner))
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to get grants")?;
+        Ok(found.cloned().unwrap_or_default())
+    }
+
+    // Replaces a holder's expiring grants, removing the entry if there are none.
+    pub fn put_grants<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        owner: ActorID,
+        owner_grants: Vec<Grant>,
+    ) -> Result<(), ActorError> {
+        let mut grants = self.load_grants(store)?;
+        if owner_grants.is_empty() {
+            grants
+                .delete(&u64_key(owner))
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to delete grants")?;
+        } else {
+            grants
+                .set(u64_key(owner), owner_grants)
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to set grants")?;
+        }
+        self.grants =
+            grants.flush().context_code(ExitCode::USR_ILLEGAL_STATE, "failed to flush grants")?;
+        Ok(())
     }
 
     // Visible for testing
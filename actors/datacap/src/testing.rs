@@ -1,10 +1,12 @@
-use frc46_token::token::state::StateSummary;
+use frc46_token::token::state::{decode_actor_id, StateSummary};
 use fvm_ipld_blockstore::Blockstore;
 use fvm_shared::address::Protocol;
+use fvm_shared::econ::TokenAmount;
+use num_traits::Zero;
 
 use fil_actors_runtime::MessageAccumulator;
 
-use crate::{State, DATACAP_GRANULARITY};
+use crate::{Grant, State, DATACAP_GRANULARITY};
 
 /// Checks internal invariants of data cap token actor state.
 pub fn check_state_invariants<BS: Blockstore>(
@@ -12,10 +14,39 @@ pub fn check_state_invariants<BS: Blockstore>(
     store: &BS,
 ) -> (StateSummary, MessageAccumulator) {
     let acc = MessageAccumulator::default();
-    acc.require(state.governor.protocol() == Protocol::ID, "governor must be ID address");
+    acc.with_code("datacap.governor_protocol")
+        .require(state.governor.protocol() == Protocol::ID, "governor must be ID address");
     let (summary, msgs) = state.token.check_invariants(store, DATACAP_GRANULARITY);
+    let token_acc = acc.with_code("datacap.token");
     for e in msgs {
-        acc.add(e.to_string());
+        token_acc.add(e.to_string());
+    }
+
+    // Expiring grants are ordered and covered by their holder's balance.
+    let grants_acc = acc.with_code("datacap.grants");
+    match state.load_grants(store) {
+        Ok(grants) => {
+            let ret = grants.for_each(|key, owner_grants: &Vec<Grant>| {
+                let owner = decode_actor_id(key).unwrap();
+                let total = owner_grants.iter().fold(TokenAmount::zero(), |acc, g| acc + &g.amount);
+                grants_acc.require(
+                    owner_grants.iter().all(|g| g.amount.is_positive()),
+                    format!("holder {owner} has a non-positive grant"),
+                );
+                grants_acc.require(
+                    owner_grants.windows(2).all(|w| w[0].expiration <= w[1].expiration),
+                    format!("holder {owner} grants are not ordered by expiration"),
+                );
+                let balance = summary.balance_map.as_ref().and_then(|m| m.get(&owner).cloned());
+                grants_acc.require(
+                    total <= balance.unwrap_or_default(),
+                    format!("holder {owner} grants {total} exceed balance"),
+                );
+                Ok(())
+            });
+            grants_acc.require_no_error(ret, "error iterating grants");
+        }
+        Err(e) => grants_acc.add(format!("error loading grants {e}")),
     }
     (summary, acc)
 }
@@ -262,6 +262,80 @@ mod destroy {
     }
 }
 
+mod expiration {
+    use crate::{make_harness, ALICE, BOB};
+    use fil_actor_datacap::Grant;
+    use fil_actors_runtime::test_utils::{expect_abort, expect_abort_contains_message};
+    use fvm_ipld_encoding::RawBytes;
+    use fvm_shared::econ::TokenAmount;
+    use fvm_shared::error::ExitCode;
+    use num_traits::Zero;
+
+    #[test]
+    fn spends_expiring_tokens_first() {
+        let (mut rt, h) = make_harness();
+        let amt = TokenAmount::from_whole(1);
+        h.mint_expiring(&mut rt, &ALICE, &(3 * amt.clone()), vec![], Some(20)).unwrap();
+        h.mint_expiring(&mut rt, &ALICE, &amt, vec![], Some(10)).unwrap();
+        h.mint(&mut rt, &ALICE, &(2 * amt.clone()), vec![]).unwrap();
+        assert_eq!(
+            vec![
+                Grant { amount: amt.clone(), expiration: 10 },
+                Grant { amount: 3 * amt.clone(), expiration: 20 },
+            ],
+            h.get_grants(&rt, &ALICE)
+        );
+        h.check_state(&rt);
+
+        // Spending consumes the soonest-expiring grant first.
+        rt.set_epoch(5);
+        h.transfer(&mut rt, &ALICE, &h.governor, &(2 * amt.clone()), RawBytes::default()).unwrap();
+        assert_eq!(
+            vec![Grant { amount: 2 * amt.clone(), expiration: 20 }],
+            h.get_grants(&rt, &ALICE)
+        );
+        h.check_state(&rt);
+
+        // Nothing lapses until after the expiration.
+        rt.set_epoch(20);
+        assert!(h.lapse(&mut rt, &ALICE).unwrap().is_zero());
+        rt.set_epoch(21);
+        assert_eq!(2 * amt.clone(), h.lapse(&mut rt, &ALICE).unwrap());
+        assert_eq!(2 * amt.clone(), h.get_balance(&mut rt, &ALICE));
+        assert!(h.get_grants(&rt, &ALICE).is_empty());
+        h.check_state(&rt);
+
+        // Non-expiring tokens don't lapse.
+        rt.set_epoch(1000);
+        assert!(h.lapse(&mut rt, &ALICE).unwrap().is_zero());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn expired_tokens_cannot_be_spent() {
+        let (mut rt, h) = make_harness();
+        let amt = TokenAmount::from_whole(1);
+        h.mint_expiring(&mut rt, &ALICE, &amt, vec![*BOB], Some(10)).unwrap();
+
+        rt.set_epoch(11);
+        expect_abort(
+            ExitCode::USR_INSUFFICIENT_FUNDS,
+            h.transfer_from(&mut rt, &BOB, &ALICE, &h.governor, &amt, RawBytes::default()),
+        );
+        rt.reset();
+        h.check_state(&rt);
+
+        // Minting can't create already-expired tokens.
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "mint expiration 10 has passed",
+            h.mint_expiring(&mut rt, &ALICE, &amt, vec![], Some(10)),
+        );
+        rt.reset();
+        h.check_state(&rt);
+    }
+}
+
 fn make_harness() -> (MockRuntime, Harness) {
     let mut rt = new_runtime();
     let h = Harness { governor: VERIFIED_REGISTRY_ACTOR_ADDR };
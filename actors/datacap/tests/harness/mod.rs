@@ -5,13 +5,16 @@ use frc46_token::token::types::{
 use fvm_actor_utils::receiver::UniversalReceiverParams;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use fvm_shared::MethodNum;
 use num_traits::Zero;
 
 use fil_actor_datacap::testing::check_state_invariants;
-use fil_actor_datacap::{Actor as DataCapActor, DestroyParams, Method, MintParams, State};
+use fil_actor_datacap::{
+    Actor as DataCapActor, DestroyParams, Grant, Method, MintExpiringParams, MintParams, State,
+};
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::runtime::Runtime;
 use fil_actors_runtime::test_utils::*;
@@ -65,6 +68,17 @@ impl Harness {
         to: &Address,
         amount: &TokenAmount,
         operators: Vec<Address>,
+    ) -> Result<MintReturn, ActorError> {
+        self.mint_expiring(rt, to, amount, operators, None)
+    }
+
+    pub fn mint_expiring(
+        &self,
+        rt: &mut MockRuntime,
+        to: &Address,
+        amount: &TokenAmount,
+        operators: Vec<Address>,
+        expiration: Option<ChainEpoch>,
     ) -> Result<MintReturn, ActorError> {
         rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
 
@@ -93,12 +107,24 @@ impl Harness {
             ExitCode::OK,
         );
 
-        let params = MintParams { to: *to, amount: amount.clone(), operators };
         rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
-        let ret = rt.call::<DataCapActor>(
-            Method::MintExported as MethodNum,
-            IpldBlock::serialize_cbor(&params).unwrap(),
-        )?;
+        let ret = match expiration {
+            None => {
+                let params = MintParams { to: *to, amount: amount.clone(), operators };
+                rt.call::<DataCapActor>(
+                    Method::MintExported as MethodNum,
+                    IpldBlock::serialize_cbor(&params).unwrap(),
+                )?
+            }
+            Some(expiration) => {
+                let params =
+                    MintExpiringParams { to: *to, amount: amount.clone(), operators, expiration };
+                rt.call::<DataCapActor>(
+                    Method::MintExpiringExported as MethodNum,
+                    IpldBlock::serialize_cbor(&params).unwrap(),
+                )?
+            }
+        };
 
         rt.verify();
         Ok(ret.unwrap().deserialize().unwrap())
@@ -218,6 +244,21 @@ impl Harness {
         Ok(ret.unwrap().deserialize().unwrap())
     }
 
+    pub fn lapse(&self, rt: &mut MockRuntime, owner: &Address) -> Result<TokenAmount, ActorError> {
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<DataCapActor>(
+            Method::LapseExported as MethodNum,
+            IpldBlock::serialize_cbor(owner).unwrap(),
+        )?;
+        rt.verify();
+        Ok(ret.unwrap().deserialize().unwrap())
+    }
+
+    // Reads a holder's expiring grants from state directly.
+    pub fn get_grants(&self, rt: &MockRuntime, owner: &Address) -> Vec<Grant> {
+        rt.get_state::<State>().get_grants(rt.store(), owner.id().unwrap()).unwrap()
+    }
+
     // Reads the total supply from state directly.
     pub fn get_supply(&self, rt: &MockRuntime) -> TokenAmount {
         rt.get_state::<State>().token.supply
@@ -30,6 +30,7 @@ pub mod init {
 pub mod evm {
     use super::*;
     use fil_actors_evm_shared::address::EthAddress;
+    use fil_actors_evm_shared::uints::U256;
 
     #[derive(Serialize_tuple, Deserialize_tuple, Clone)]
     pub struct ConstructorParams {
@@ -39,7 +40,23 @@ pub mod evm {
         pub initcode: RawBytes,
     }
 
+    #[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+    pub struct StorageSlot {
+        pub key: U256,
+        pub value: U256,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct InstallBytecodeParams {
+        /// CID of the existing bytecode to install.
+        pub bytecode: Cid,
+        /// Storage slots to initialize, in place of running a constructor.
+        pub storage: Vec<StorageSlot>,
+    }
+
     pub const RESURRECT_METHOD: u64 = 2;
+    pub const GET_BYTECODE_METHOD: u64 = 3;
+    pub const INSTALL_BYTECODE_METHOD: u64 = 7;
 }
 
 pub mod account {
@@ -0,0 +1,37 @@
+//! JSON representations of the EAM actor's method parameters and return values.
+
+use fil_actors_runtime::json::{JsonCodec, JsonError, MethodCodec, Value};
+use fil_actors_runtime::{json_struct, method_codecs};
+
+use crate::ext::evm::StorageSlot;
+use crate::{
+    Create2Params, CreateExternalParams, CreateFromBytecodeParams, CreateParams, Method, Return,
+};
+
+impl JsonCodec for CreateExternalParams {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        JsonCodec::from_json(value).map(CreateExternalParams)
+    }
+}
+
+json_struct! {
+    CreateParams { initcode, nonce }
+    Create2Params { initcode, salt }
+    StorageSlot { key, value }
+    CreateFromBytecodeParams { bytecode, template, salt, storage }
+    Return { actor_id, robust_address, eth_address }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => ((), ()),
+        Create => (CreateParams, Return),
+        Create2 => (Create2Params, Return),
+        CreateExternal => (CreateExternalParams, Return),
+        CreateFromBytecode => (CreateFromBytecodeParams, Return),
+    }
+}
@@ -3,9 +3,13 @@ use std::iter;
 use fil_actors_evm_shared::address::EthAddress;
 use num_traits::Zero;
 
+use cid::Cid;
 use ext::{
     account::PUBKEY_ADDRESS_METHOD,
-    evm::RESURRECT_METHOD,
+    evm::{
+        InstallBytecodeParams, StorageSlot, GET_BYTECODE_METHOD, INSTALL_BYTECODE_METHOD,
+        RESURRECT_METHOD,
+    },
     init::{Exec4Params, Exec4Return},
 };
 use fil_actors_runtime::{
@@ -18,6 +22,9 @@ use fvm_shared::{error::ExitCode, sys::SendFlags, ActorID, METHOD_CONSTRUCTOR};
 use serde::{Deserialize, Serialize};
 
 pub mod ext;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod testing;
 
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
@@ -37,6 +44,7 @@ pub enum Method {
     Create = 2,
     Create2 = 3,
     CreateExternal = 4,
+    CreateFromBytecode = 5,
 }
 
 /// Compute the a new actor address using the EVM's CREATE rules.
@@ -57,6 +65,24 @@ pub fn compute_address_create2(
     EthAddress(hash_20(rt, &[&[0xff], &from.0[..], salt, &inithash].concat()))
 }
 
+/// Compute a new actor address for a contract created from existing bytecode.
+/// This follows the EVM's CREATE2 rules, substituting the bytecode CID followed by the
+/// initial storage slots for the initcode.
+pub fn compute_address_create_from_bytecode(
+    rt: &impl Runtime,
+    from: &EthAddress,
+    salt: &[u8; 32],
+    bytecode: &Cid,
+    storage: &[StorageSlot],
+) -> EthAddress {
+    let mut initdata = bytecode.to_bytes();
+    for slot in storage {
+        initdata.extend_from_slice(&slot.key.to_bytes());
+        initdata.extend_from_slice(&slot.value.to_bytes());
+    }
+    compute_address_create2(rt, from, salt, &initdata)
+}
+
 pub fn compute_address_create_external(rt: &impl Runtime, from: &EthAddress) -> EthAddress {
     compute_address_create(rt, from, rt.message().nonce())
 }
@@ -76,6 +102,18 @@ pub struct Create2Params {
     pub salt: [u8; 32],
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct CreateFromBytecodeParams {
+    /// CID of the existing bytecode to instantiate. Exactly one of this and `template` is set.
+    pub bytecode: Option<Cid>,
+    /// Address of a contract whose bytecode to instantiate.
+    pub template: Option<Address>,
+    #[serde(with = "strict_bytes")]
+    pub salt: [u8; 32],
+    /// Storage slots to initialize, in place of running a constructor.
+    pub storage: Vec<StorageSlot>,
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct CreateExternalParams(#[serde(with = "strict_bytes")] pub Vec<u8>);
@@ -90,6 +128,7 @@ pub struct Return {
 pub type CreateReturn = Return;
 pub type Create2Return = Return;
 pub type CreateExternalReturn = Return;
+pub type CreateFromBytecodeReturn = Return;
 
 impl Return {
     fn from_exec4(exec4: Exec4Return, eth_address: EthAddress) -> Self {
@@ -172,6 +211,44 @@ fn create_actor(
     Ok(Return::from_exec4(ret, new_addr))
 }
 
+/// Resolves the bytecode to instantiate from either its CID or a template contract.
+fn resolve_bytecode(
+    rt: &mut impl Runtime,
+    bytecode: Option<Cid>,
+    template: Option<Address>,
+) -> Result<Cid, ActorError> {
+    let template = match (bytecode, template) {
+        (Some(bytecode), None) => return Ok(bytecode),
+        (None, Some(template)) => template,
+        _ => {
+            return Err(actor_error!(illegal_argument; "must specify one of bytecode or template"));
+        }
+    };
+
+    let id =
+        rt.resolve_address(&template).with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
+            format!("template {template} not found")
+        })?;
+    let code_cid = rt
+        .get_actor_code_cid(&id)
+        .ok_or_else(|| actor_error!(illegal_argument; "template {template} not found"))?;
+    if rt.resolve_builtin_actor_type(&code_cid) != Some(Type::EVM) {
+        return Err(actor_error!(illegal_argument; "template {template} is not an EVM contract"));
+    }
+
+    let ret: Option<Cid> = deserialize_block(extract_send_result(rt.send(
+        &Address::new_id(id),
+        GET_BYTECODE_METHOD,
+        None,
+        Zero::zero(),
+        None,
+        SendFlags::READ_ONLY,
+    ))?)?;
+    ret.with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
+        format!("template {template} has been deleted")
+    })
+}
+
 fn resolve_eth_address(rt: &mut impl Runtime, actor_id: ActorID) -> Result<EthAddress, ActorError> {
     match rt.lookup_delegated_address(actor_id).map(|a| *a.payload()) {
         Some(Payload::Delegated(addr)) if addr.namespace() == EAM_ACTOR_ID => Ok(EthAddress(
@@ -289,6 +366,43 @@ impl EamActor {
         let eth_addr = compute_address_create_external(rt, &stable_addr);
         create_actor(rt, owner_addr, eth_addr, params.0)
     }
+
+    /// Create a new contract from existing bytecode, referenced by CID or by a template contract,
+    /// without running initcode. The new contract's storage is initialized with the given slots.
+    /// The address is computed per the EVM's CREATE2 rules, with the bytecode CID and storage
+    /// slots in place of the initcode.
+    ///
+    /// Permissions: May be called by the EVM.
+    pub fn create_from_bytecode(
+        rt: &mut impl Runtime,
+        params: CreateFromBytecodeParams,
+    ) -> Result<CreateFromBytecodeReturn, ActorError> {
+        // We only allow EVM actors to call this.
+        rt.validate_immediate_caller_type(&[Type::EVM])?;
+        let caller_addr = resolve_eth_address(rt, rt.message().caller().id().unwrap())?;
+
+        let bytecode = resolve_bytecode(rt, params.bytecode, params.template)?;
+        let eth_addr = compute_address_create_from_bytecode(
+            rt,
+            &caller_addr,
+            &params.salt,
+            &bytecode,
+            &params.storage,
+        );
+
+        // Create the contract with no code, then install the bytecode.
+        let ret = create_actor(rt, caller_addr, eth_addr, Vec::new())?;
+        extract_send_result(rt.send_simple(
+            &Address::new_id(ret.actor_id),
+            INSTALL_BYTECODE_METHOD,
+            IpldBlock::serialize_dag_cbor(&InstallBytecodeParams {
+                bytecode,
+                storage: params.storage,
+            })?,
+            Zero::zero(),
+        ))?;
+        Ok(ret)
+    }
 }
 
 impl ActorCode for EamActor {
@@ -298,6 +412,7 @@ impl ActorCode for EamActor {
         Create => create,
         Create2 => create2,
         CreateExternal => create_external,
+        CreateFromBytecode => create_from_bytecode,
     }
 }
 
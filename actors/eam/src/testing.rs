@@ -0,0 +1,17 @@
+use fil_actors_runtime::{MessageAccumulator, EAM_ACTOR_ID};
+use fvm_shared::address::Address;
+
+/// Checks invariants of the (stateless) Ethereum Address Manager.
+pub fn check_state_invariants(id_address: &Address) -> MessageAccumulator {
+    let acc = MessageAccumulator::default();
+    match id_address.id() {
+        Ok(id) => acc.with_code("eam.actor_id").require(
+            id == EAM_ACTOR_ID,
+            format!("EAM must be deployed at {EAM_ACTOR_ID}, found at {id}"),
+        ),
+        Err(e) => acc
+            .with_code("eam.id_address")
+            .add(format!("error extracting actor ID from address: {e}")),
+    }
+    acc
+}
@@ -1,9 +1,17 @@
-use eam::ext::evm::RESURRECT_METHOD;
+use cid::Cid;
+use eam::ext::evm::{
+    InstallBytecodeParams, StorageSlot, GET_BYTECODE_METHOD, INSTALL_BYTECODE_METHOD,
+    RESURRECT_METHOD,
+};
 use eam::ext::init::{Exec4Params, Exec4Return, EXEC4_METHOD};
-use eam::{compute_address_create, Create2Params, CreateParams, Return};
+use eam::{
+    compute_address_create, compute_address_create_from_bytecode, Create2Params,
+    CreateFromBytecodeParams, CreateParams, Return,
+};
 use fil_actor_eam as eam;
 use fil_actor_eam::CreateExternalParams;
 use fil_actors_evm_shared::address::EthAddress;
+use fil_actors_evm_shared::uints::U256;
 use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::Primitives;
 use fil_actors_runtime::test_utils::{
@@ -16,6 +24,7 @@ use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
+use fvm_shared::sys::SendFlags;
 
 #[test]
 fn call_create_new() {
@@ -266,6 +275,151 @@ fn call_create2() {
     rt.verify();
 }
 
+#[test]
+fn call_create_from_template() {
+    let mut rt = construct_and_verify();
+
+    let id_addr = Address::new_id(110);
+    let eth_addr = EthAddress(hex_literal::hex!("CAFEB0BA00000000000000000000000000000000"));
+    let f4_eth_addr = Address::new_delegated(10, &eth_addr.0).unwrap();
+    rt.set_delegated_address(id_addr.id().unwrap(), f4_eth_addr);
+    rt.set_caller(*EVM_ACTOR_CODE_ID, id_addr);
+
+    let template = Address::new_id(120);
+    rt.set_address_actor_type(template, *EVM_ACTOR_CODE_ID);
+    let bytecode = Cid::new_v1(
+        fvm_shared::IPLD_RAW,
+        multihash::Multihash::wrap(0, b"template bytecode").unwrap(),
+    );
+    let storage = vec![StorageSlot { key: U256::zero(), value: U256::from(0x42) }];
+
+    rt.expect_validate_caller_type(vec![Type::EVM]);
+    rt.expect_send(
+        template,
+        GET_BYTECODE_METHOD,
+        None,
+        TokenAmount::from_atto(0),
+        None,
+        SendFlags::READ_ONLY,
+        IpldBlock::serialize_dag_cbor(&Some(bytecode)).unwrap(),
+        ExitCode::OK,
+        None,
+    );
+
+    let new_eth_addr =
+        compute_address_create_from_bytecode(&rt, &eth_addr, &[0; 32], &bytecode, &storage);
+    let evm_params =
+        eam::ext::evm::ConstructorParams { creator: eth_addr, initcode: vec![].into() };
+    let params = Exec4Params {
+        code_cid: *EVM_ACTOR_CODE_ID,
+        constructor_params: RawBytes::serialize(evm_params).unwrap(),
+        subaddress: new_eth_addr.0[..].to_owned().into(),
+    };
+    let send_return = IpldBlock::serialize_cbor(&Exec4Return {
+        id_address: Address::new_id(111),
+        robust_address: Address::new_id(0),
+    })
+    .unwrap();
+    rt.expect_send_simple(
+        INIT_ACTOR_ADDR,
+        EXEC4_METHOD,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+        TokenAmount::from_atto(0),
+        send_return,
+        ExitCode::OK,
+    );
+    rt.expect_send_simple(
+        Address::new_id(111),
+        INSTALL_BYTECODE_METHOD,
+        IpldBlock::serialize_dag_cbor(&InstallBytecodeParams {
+            bytecode,
+            storage: storage.clone(),
+        })
+        .unwrap(),
+        TokenAmount::from_atto(0),
+        None,
+        ExitCode::OK,
+    );
+
+    let result = rt
+        .call::<eam::EamActor>(
+            eam::Method::CreateFromBytecode as u64,
+            IpldBlock::serialize_cbor(&CreateFromBytecodeParams {
+                bytecode: None,
+                template: Some(template),
+                salt: [0; 32],
+                storage,
+            })
+            .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize::<Return>()
+        .unwrap();
+
+    let expected_return = Return {
+        actor_id: 111,
+        robust_address: Some(Address::new_id(0)),
+        eth_address: new_eth_addr,
+    };
+    assert_eq!(result, expected_return);
+    rt.verify();
+}
+
+#[test]
+fn call_create_from_bytecode_requires_one_source() {
+    let mut rt = construct_and_verify();
+
+    let id_addr = Address::new_id(110);
+    let eth_addr = EthAddress(hex_literal::hex!("CAFEB0BA00000000000000000000000000000000"));
+    let f4_eth_addr = Address::new_delegated(10, &eth_addr.0).unwrap();
+    rt.set_delegated_address(id_addr.id().unwrap(), f4_eth_addr);
+    rt.set_caller(*EVM_ACTOR_CODE_ID, id_addr);
+
+    rt.expect_validate_caller_type(vec![Type::EVM]);
+    let err = rt
+        .call::<eam::EamActor>(
+            eam::Method::CreateFromBytecode as u64,
+            IpldBlock::serialize_cbor(&CreateFromBytecodeParams {
+                bytecode: None,
+                template: None,
+                salt: [0; 32],
+                storage: vec![],
+            })
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+    rt.verify();
+}
+
+#[test]
+fn call_create_from_missing_template() {
+    let mut rt = construct_and_verify();
+
+    let id_addr = Address::new_id(110);
+    let eth_addr = EthAddress(hex_literal::hex!("CAFEB0BA00000000000000000000000000000000"));
+    let f4_eth_addr = Address::new_delegated(10, &eth_addr.0).unwrap();
+    rt.set_delegated_address(id_addr.id().unwrap(), f4_eth_addr);
+    rt.set_caller(*EVM_ACTOR_CODE_ID, id_addr);
+
+    rt.expect_validate_caller_type(vec![Type::EVM]);
+    let err = rt
+        .call::<eam::EamActor>(
+            eam::Method::CreateFromBytecode as u64,
+            IpldBlock::serialize_cbor(&CreateFromBytecodeParams {
+                bytecode: None,
+                template: Some(Address::new_id(404)),
+                salt: [0; 32],
+                storage: vec![],
+            })
+            .unwrap(),
+        )
+        .unwrap_err();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+    rt.verify();
+}
+
 pub fn construct_and_verify() -> MockRuntime {
     let mut rt = MockRuntime { receiver: Address::new_id(10), ..Default::default() };
 
@@ -0,0 +1,14 @@
+use fil_actor_eam::testing::check_state_invariants;
+use fil_actors_runtime::{EAM_ACTOR_ADDR, EAM_ACTOR_ID};
+use fvm_shared::address::Address;
+
+#[test]
+fn requires_eam_at_its_actor_id() {
+    check_state_invariants(&EAM_ACTOR_ADDR).assert_empty();
+
+    let acc = check_state_invariants(&Address::new_id(EAM_ACTOR_ID + 1));
+    assert_eq!(vec!["eam.actor_id"], acc.codes());
+
+    let acc = check_state_invariants(&Address::new_delegated(EAM_ACTOR_ID, &[0xff; 20]).unwrap());
+    assert_eq!(vec!["eam.id_address"], acc.codes());
+}
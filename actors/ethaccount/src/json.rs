@@ -0,0 +1,18 @@
+//! JSON representations of the Ethereum account actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs};
+
+use crate::types::AuthenticateMessageParams;
+use crate::Method;
+
+json_struct! {
+    AuthenticateMessageParams { signature, message }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => ((), ()),
+        AuthenticateMessageExported => (AuthenticateMessageParams, ()),
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(feature = "json")]
+pub mod json;
+pub mod testing;
 pub mod types;
 
 use fvm_ipld_encoding::ipld_block::IpldBlock;
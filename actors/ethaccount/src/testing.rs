@@ -0,0 +1,21 @@
+use fil_actors_runtime::{MessageAccumulator, EAM_ACTOR_ID};
+use fvm_shared::address::{Address, Payload};
+
+/// Checks invariants of the (stateless) Ethereum account actor.
+pub fn check_state_invariants(delegated_address: Option<&Address>) -> MessageAccumulator {
+    let acc = MessageAccumulator::default();
+    match delegated_address.map(|a| *a.payload()) {
+        Some(Payload::Delegated(da)) => acc.with_code("ethaccount.delegated_address").require(
+            da.namespace() == EAM_ACTOR_ID,
+            format!("delegated address namespace {} is not the EAM", da.namespace()),
+        ),
+        Some(_) => acc.with_code("ethaccount.delegated_address").add(format!(
+            "predictable address {} is not a delegated address",
+            delegated_address.unwrap()
+        )),
+        None => {
+            acc.with_code("ethaccount.delegated_address").add("ethaccount has no delegated address")
+        }
+    }
+    acc
+}
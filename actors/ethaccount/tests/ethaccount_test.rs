@@ -6,6 +6,7 @@ use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 
+use fil_actor_ethaccount::testing::check_state_invariants;
 use fil_actor_ethaccount::{EthAccountActor, Method};
 use fvm_shared::error::ExitCode;
 use fvm_shared::MethodNum;
@@ -13,7 +14,7 @@ use fvm_shared::MethodNum;
 use fil_actors_runtime::test_utils::{
     expect_abort_contains_message, ACCOUNT_ACTOR_CODE_ID, SYSTEM_ACTOR_CODE_ID,
 };
-use fil_actors_runtime::SYSTEM_ACTOR_ADDR;
+use fil_actors_runtime::{EAM_ACTOR_ID, SYSTEM_ACTOR_ADDR};
 
 #[test]
 fn no_delegated_cant_deploy() {
@@ -46,3 +47,14 @@ fn token_receiver() {
         .unwrap();
     assert!(ret.is_none());
 }
+
+#[test]
+fn state_invariants_require_eam_delegated_address() {
+    let eth_address = Address::new_delegated(EAM_ACTOR_ID, &[0xff; 20]).unwrap();
+    check_state_invariants(Some(&eth_address)).assert_empty();
+
+    let foreign = Address::new_delegated(EAM_ACTOR_ID + 1, &[0xff; 20]).unwrap();
+    for address in [Some(&foreign), Some(&Address::new_id(1234)), None] {
+        assert_eq!(vec!["ethaccount.delegated_address"], check_state_invariants(address).codes());
+    }
+}
@@ -0,0 +1,67 @@
+//! Runs arbitrary bytecode through the interpreter, checking that it never panics and that the
+//! stack and memory stay within their bounds.
+
+#![no_main]
+
+use fil_actor_evm::interpreter::{
+    opcodes, Bytecode, ExecutionState, Machine, System, FUZZING_MAX_MEMORY,
+};
+use fil_actors_evm_shared::address::EthAddress;
+use fil_actors_runtime::test_utils::MockRuntime;
+use fvm_shared::econ::TokenAmount;
+use libfuzzer_sys::fuzz_target;
+
+/// The most instructions executed per run, as there's no gas limit to stop infinite loops.
+const MAX_STEPS: usize = 100_000;
+
+/// The most values held by the stack.
+const STACK_LIMIT: usize = 1024;
+
+fuzz_target!(|input: (Vec<u8>, Vec<u8>)| {
+    let (mut code, input_data) = input;
+    neutralize(&mut code);
+
+    let mut rt = MockRuntime::default();
+    rt.in_call = true;
+    let mut system = System::create(&mut rt).unwrap();
+    let mut state = ExecutionState::new(
+        EthAddress::from_id(1000),
+        EthAddress::from_id(1001),
+        TokenAmount::from_atto(0),
+        input_data,
+    );
+    let bytecode = Bytecode::new(code);
+    let mut machine = Machine::new(&mut system, &mut state, &bytecode);
+
+    for _ in 0..MAX_STEPS {
+        if machine.pc >= machine.bytecode.len() || machine.step().is_err() {
+            break;
+        }
+        assert!(machine.state.stack.len() <= STACK_LIMIT);
+        assert!(machine.state.memory.len() <= FUZZING_MAX_MEMORY as usize);
+    }
+});
+
+/// Replaces the instructions that call into parts of the runtime the mock can't serve without
+/// expectations (sends, events, gas and randomness) with INVALID.
+fn neutralize(code: &mut [u8]) {
+    let mut pc = 0;
+    while pc < code.len() {
+        let op = code[pc];
+        match op {
+            opcodes::PUSH1..=opcodes::PUSH32 => pc += (op - opcodes::PUSH1) as usize + 1,
+            opcodes::PREVRANDAO
+            | opcodes::GAS
+            | opcodes::LOG0..=opcodes::LOG4
+            | opcodes::CREATE
+            | opcodes::CALL
+            | opcodes::CALLCODE
+            | opcodes::DELEGATECALL
+            | opcodes::CREATE2
+            | opcodes::STATICCALL
+            | opcodes::SELFDESTRUCT => code[pc] = opcodes::INVALID,
+            _ => {}
+        }
+        pc += 1;
+    }
+}
@@ -25,6 +25,19 @@ impl std::fmt::Debug for EthAddress {
     }
 }
 
+#[cfg(feature = "json")]
+impl fil_actors_runtime::json::JsonCodec for EthAddress {
+    fn to_json(&self) -> fil_actors_runtime::json::Value {
+        fil_actors_runtime::json::Value::String(format!("0x{}", hex::encode(self.0)))
+    }
+
+    fn from_json(
+        value: &fil_actors_runtime::json::Value,
+    ) -> Result<Self, fil_actors_runtime::json::JsonError> {
+        <[u8; 20] as fil_actors_runtime::json::JsonCodec>::from_json(value).map(EthAddress)
+    }
+}
+
 impl From<EthAddress> for Address {
     fn from(addr: EthAddress) -> Self {
         From::from(&addr)
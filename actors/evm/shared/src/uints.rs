@@ -225,6 +225,21 @@ impl<'de> Deserialize<'de> for U256 {
     }
 }
 
+#[cfg(feature = "json")]
+impl fil_actors_runtime::json::JsonCodec for U256 {
+    fn to_json(&self) -> fil_actors_runtime::json::Value {
+        fil_actors_runtime::json::Value::String(format!("{:#x}", self))
+    }
+
+    fn from_json(
+        value: &fil_actors_runtime::json::Value,
+    ) -> Result<Self, fil_actors_runtime::json::JsonError> {
+        use fil_actors_runtime::json::JsonError;
+        let s = value.as_str().ok_or_else(|| JsonError::new("expected a hex string"))?;
+        U256::from_str_radix(s.strip_prefix("0x").unwrap_or(s), 16).map_err(JsonError::new)
+    }
+}
+
 fn zeroless_view(v: &impl AsRef<[u8]>) -> &[u8] {
     let v = v.as_ref();
     &v[v.iter().take_while(|&&b| b == 0).count()..]
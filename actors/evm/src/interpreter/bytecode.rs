@@ -35,6 +35,25 @@ impl Bytecode {
     pub fn valid_jump_destination(&self, offset: usize) -> bool {
         offset < self.jumpdest.len() && self.jumpdest[offset]
     }
+
+    /// Checks if the code pushes a four byte method selector, as a Solidity method
+    /// dispatcher does for each method the contract implements.
+    pub fn pushes_selector(&self, selector: &[u8; 4]) -> bool {
+        let mut i = 0;
+        while i < self.code.len() {
+            let op = self.code[i];
+            if op >= opcodes::PUSH1 && op <= opcodes::PUSH32 {
+                let end = i + (op - opcodes::PUSH1) as usize + 2;
+                if op == opcodes::PUSH4 && self.code.get(i + 1..end) == Some(&selector[..]) {
+                    return true;
+                }
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        false
+    }
 }
 
 impl Deref for Bytecode {
@@ -45,6 +45,16 @@ pub fn get_memory_region(
         .checked_add(size)
         .context_code(EVM_CONTRACT_ILLEGAL_MEMORY_ACCESS, "new memory size exceeds max u32")?;
 
+    // Without gas to bound it, memory could grow to 4GiB in a single instruction, exhausting the
+    // fuzzer's memory rather than finding bugs.
+    #[cfg(fuzzing)]
+    if new_size > crate::interpreter::FUZZING_MAX_MEMORY {
+        return Err(ActorError::unchecked(
+            EVM_CONTRACT_ILLEGAL_MEMORY_ACCESS,
+            "new memory size exceeds fuzzing limit".into(),
+        ));
+    }
+
     mem.grow(new_size as usize);
 
     Ok(Some(MemoryRegion {
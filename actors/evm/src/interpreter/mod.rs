@@ -12,11 +12,17 @@ pub mod test_util;
 
 pub use {
     bytecode::Bytecode,
-    execution::{execute, opcodes, ExecutionState},
+    execution::{execute, opcodes, ExecutionState, Machine},
     output::{Outcome, Output},
     system::System,
 };
 
+pub(crate) use system::{StateKamt, KAMT_CONFIG};
+
+/// The largest memory size, in bytes, allowed when fuzzing.
+#[cfg(fuzzing)]
+pub const FUZZING_MAX_MEMORY: u32 = 16 << 20;
+
 /// The kind of call-like instruction.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum CallKind {
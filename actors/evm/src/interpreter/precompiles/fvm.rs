@@ -1,8 +1,16 @@
+use std::borrow::Cow;
+
 use crate::{EVM_MAX_RESERVED_METHOD, EVM_WORD_SIZE};
+use cid::Cid;
 use fil_actors_evm_shared::uints::U256;
-use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::runtime::{DomainSeparationTag, Runtime};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Protocol;
+use fvm_shared::crypto::signature::{Signature, SignatureType};
+use fvm_shared::piece::{PaddedPieceSize, PieceInfo};
+use fvm_shared::sector::RegisteredSealProof;
 use fvm_shared::{address::Address, econ::TokenAmount, sys::SendFlags, METHOD_SEND};
+use num_traits::FromPrimitive;
 
 use crate::interpreter::{CallKind, System};
 
@@ -27,6 +35,47 @@ pub(super) fn lookup_delegated_address<RT: Runtime>(
     Ok(ab)
 }
 
+/// Looks up the actor's type, balance and code CID.
+///
+/// Returns (solidity ABI encoded):
+///
+/// ```text
+/// u32   actor_type (the builtin actor type, or 0 if the actor runs non-builtin code)
+/// u256  balance
+/// bytes code_cid
+/// ```
+///
+/// Empty array if the actor was not found, or `InvalidInput` if input was larger than 2^64.
+pub(super) fn get_actor_info<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    _: PrecompileContext,
+) -> PrecompileResult {
+    let mut id_bytes = ValueReader::new(input);
+    let id = id_bytes.read_value::<u64>()?;
+
+    let code_cid = match system.rt.get_actor_code_cid(&id) {
+        Some(cid) => cid,
+        None => return Ok(Vec::new()),
+    };
+    let actor_type = system.rt.resolve_builtin_actor_type(&code_cid).map_or(0, |t| t as u32);
+    let balance = system.rt.actor_balance(id).unwrap_or_default();
+    let code_cid = code_cid.to_bytes();
+
+    let mut output = Vec::with_capacity(5 * EVM_WORD_SIZE + code_cid.len());
+    output.extend_from_slice(&U256::from(actor_type).to_bytes());
+    output.extend_from_slice(&U256::from(&balance).to_bytes());
+    output.extend_from_slice(&U256::from(output.len() + EVM_WORD_SIZE).to_bytes());
+    output.extend_from_slice(&U256::from(code_cid.len()).to_bytes());
+    output.extend_from_slice(&code_cid);
+    // Pad out to the next increment of 32 bytes for solidity compatibility.
+    let offset = output.len() % EVM_WORD_SIZE;
+    if offset > 0 {
+        output.resize(output.len() - offset + EVM_WORD_SIZE, 0);
+    }
+    Ok(output)
+}
+
 /// Reads a FIL (i.e. f0xxx, f4xfxxx) encoded address
 /// Resolves a FIL encoded address into an ID address
 /// Returns BE encoded u256 (return will always be under 2^64).
@@ -220,3 +269,169 @@ pub(super) fn call_actor_shared<RT: Runtime>(
 
     Ok(output)
 }
+
+/// Reads solidity ABI encoded `bytes` starting at the given offset.
+fn read_bytes<'a>(
+    reader: &mut ValueReader<'a>,
+    offset: usize,
+) -> Result<Cow<'a, [u8]>, PrecompileError> {
+    reader.seek(offset);
+    let len: u32 = reader.read_value()?;
+    Ok(reader.read_padded(len.try_into()?))
+}
+
+/// Verifies a signature made by a Filecoin secp256k1 (f1) or BLS (f3) key address.
+///
+/// Parameters are encoded according to the solidity ABI, with no function selector:
+///
+/// ```text
+/// bytes signature
+/// bytes signer (f1 or f3 address)
+/// bytes message
+/// ```
+///
+/// The signature type is inferred from the signer's address protocol.
+/// Returns a BE encoded u256: 1 if the signature is valid, 0 otherwise.
+/// `InvalidInput` if the signer is not a secp256k1 or BLS address.
+pub(super) fn verify_signature<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    _: PrecompileContext,
+) -> PrecompileResult {
+    let mut input_params = ValueReader::new(input);
+
+    let signature_off: u32 = input_params.read_value()?;
+    let signer_off: u32 = input_params.read_value()?;
+    let message_off: u32 = input_params.read_value()?;
+
+    let signature = read_bytes(&mut input_params, signature_off.try_into()?)?.into_owned();
+    let signer = read_bytes(&mut input_params, signer_off.try_into()?)?;
+    let signer = Address::from_bytes(&signer).map_err(|_| PrecompileError::InvalidInput)?;
+    let message = read_bytes(&mut input_params, message_off.try_into()?)?;
+
+    let sig_type = match signer.protocol() {
+        Protocol::Secp256k1 => SignatureType::Secp256k1,
+        Protocol::BLS => SignatureType::BLS,
+        _ => return Err(PrecompileError::InvalidInput),
+    };
+    let signature = Signature { sig_type, bytes: signature };
+
+    let valid = system.rt.verify_signature(&signature, &signer, &message).is_ok();
+    Ok(U256::from(valid as u8).to_bytes().to_vec())
+}
+
+/// Hashes the input with blake2b-256, as used throughout Filecoin.
+/// Returns the 32 byte digest.
+pub(super) fn hash_blake2b<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    _: PrecompileContext,
+) -> PrecompileResult {
+    Ok(system.rt.hash_blake2b(input).to_vec())
+}
+
+/// Computes the unsealed sector CID (CommD) of a sector from its pieces.
+///
+/// Parameters are encoded according to the solidity ABI, with no function selector:
+///
+/// ```text
+/// i64   seal_proof_type
+/// (u64 size, bytes cid)[] pieces
+/// ```
+///
+/// where each piece's `size` is its padded size and `cid` is its binary encoded piece CID.
+/// Returns the binary encoded unsealed sector CID.
+/// `InvalidInput` if a piece CID fails to parse or the pieces are invalid for the proof type.
+pub(super) fn compute_unsealed_sector_cid<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    _: PrecompileContext,
+) -> PrecompileResult {
+    let mut input_params = ValueReader::new(input);
+
+    let proof_type: i64 = input_params.read_value()?;
+    let pieces_off: u32 = input_params.read_value()?;
+
+    input_params.seek(pieces_off.try_into()?);
+    let pieces_len: u32 = input_params.read_value()?;
+    // Every piece needs at least an offset word, so this bounds the loop below by the input.
+    if pieces_len as usize > input.len() / EVM_WORD_SIZE {
+        return Err(PrecompileError::InvalidInput);
+    }
+    // Tuple offsets are relative to the start of the array's contents, after its length.
+    let pieces_base =
+        (pieces_off as usize).checked_add(EVM_WORD_SIZE).ok_or(PrecompileError::InvalidInput)?;
+
+    let mut pieces = Vec::new();
+    for i in 0..pieces_len as usize {
+        input_params.seek(pieces_base + i * EVM_WORD_SIZE);
+        let piece_off: u32 = input_params.read_value()?;
+        let piece_start =
+            pieces_base.checked_add(piece_off as usize).ok_or(PrecompileError::InvalidInput)?;
+
+        input_params.seek(piece_start);
+        let size: u64 = input_params.read_value()?;
+        let cid_off: u32 = input_params.read_value()?;
+        let cid_start =
+            piece_start.checked_add(cid_off as usize).ok_or(PrecompileError::InvalidInput)?;
+        let cid = read_bytes(&mut input_params, cid_start)?;
+        let cid = Cid::try_from(&*cid).map_err(|_| PrecompileError::InvalidInput)?;
+
+        pieces.push(PieceInfo { size: PaddedPieceSize(size), cid });
+    }
+
+    let unsealed = system
+        .rt
+        .compute_unsealed_sector_cid(RegisteredSealProof::from(proof_type), &pieces)
+        .map_err(|e| {
+            log::debug!(target: "evm", "computing unsealed sector CID failed: {e}");
+            PrecompileError::InvalidInput
+        })?;
+    Ok(unsealed.to_bytes())
+}
+
+/// Draws ticket or beacon randomness from a (possibly past) epoch.
+///
+/// Parameters are encoded according to the solidity ABI, with no function selector:
+///
+/// ```text
+/// u64   source (0 for ticket randomness, 1 for beacon randomness)
+/// i64   personalization (a domain separation tag)
+/// i64   epoch
+/// bytes entropy
+/// ```
+///
+/// Returns the 32 bytes of randomness.
+/// `InvalidInput` if the source or personalization is unknown, or the epoch is in the future or
+/// further back than the policy's maximum randomness lookback.
+pub(super) fn get_randomness<RT: Runtime>(
+    system: &mut System<RT>,
+    input: &[u8],
+    _: PrecompileContext,
+) -> PrecompileResult {
+    let mut input_params = ValueReader::new(input);
+
+    let source: u64 = input_params.read_value()?;
+    let personalization: i64 = input_params.read_value()?;
+    let epoch: i64 = input_params.read_value()?;
+    let entropy_off: u32 = input_params.read_value()?;
+    let entropy = read_bytes(&mut input_params, entropy_off.try_into()?)?;
+
+    let tag =
+        DomainSeparationTag::from_i64(personalization).ok_or(PrecompileError::InvalidInput)?;
+
+    let curr_epoch = system.rt.curr_epoch();
+    if epoch < 0
+        || epoch > curr_epoch
+        || curr_epoch - epoch > system.rt.policy().max_randomness_lookback
+    {
+        return Err(PrecompileError::InvalidInput);
+    }
+
+    let randomness = match source {
+        0 => system.rt.get_randomness_from_tickets(tag, epoch, &entropy)?,
+        1 => system.rt.get_randomness_from_beacon(tag, epoch, &entropy)?,
+        _ => return Err(PrecompileError::InvalidInput),
+    };
+    Ok(randomness.to_vec())
+}
@@ -13,7 +13,10 @@ mod evm;
 mod fvm;
 
 use evm::{blake2f, ec_add, ec_mul, ec_pairing, ec_recover, identity, modexp, ripemd160, sha256};
-use fvm::{call_actor, call_actor_id, lookup_delegated_address, resolve_address};
+use fvm::{
+    call_actor, call_actor_id, compute_unsealed_sector_cid, get_actor_info, get_randomness,
+    hash_blake2b, lookup_delegated_address, resolve_address, verify_signature,
+};
 
 type PrecompileFn<RT> = fn(&mut System<RT>, &[u8], PrecompileContext) -> PrecompileResult;
 pub type PrecompileResult = Result<Vec<u8>, PrecompileError>;
@@ -41,12 +44,17 @@ pub struct Precompiles<RT>(PhantomData<RT>);
 
 impl<RT: Runtime> Precompiles<RT> {
     /// FEVM specific precompiles (0xfe prefix)
-    const NATIVE_PRECOMPILES: PrecompileTable<RT, 5> = PrecompileTable([
-        Some(resolve_address::<RT>),          // 0xfe00..01
-        Some(lookup_delegated_address::<RT>), // 0xfe00..02
-        Some(call_actor::<RT>),               // 0xfe00..03
-        None,                                 // 0xfe00..04 DISABLED
-        Some(call_actor_id::<RT>),            // 0xfe00..05
+    const NATIVE_PRECOMPILES: PrecompileTable<RT, 10> = PrecompileTable([
+        Some(resolve_address::<RT>),             // 0xfe00..01
+        Some(lookup_delegated_address::<RT>),    // 0xfe00..02
+        Some(call_actor::<RT>),                  // 0xfe00..03
+        None,                                    // 0xfe00..04 DISABLED
+        Some(call_actor_id::<RT>),               // 0xfe00..05
+        Some(verify_signature::<RT>),            // 0xfe00..06
+        Some(hash_blake2b::<RT>),                // 0xfe00..07
+        Some(compute_unsealed_sector_cid::<RT>), // 0xfe00..08
+        Some(get_actor_info::<RT>),              // 0xfe00..09
+        Some(get_randomness::<RT>),              // 0xfe00..0a
     ]);
 
     /// EVM specific precompiles
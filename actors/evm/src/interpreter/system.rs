@@ -44,7 +44,8 @@ use fvm_ipld_kamt::{AsHashedKey, Config as KamtConfig, Kamt};
 //
 // The following values have been set by looking at how the charts evolved
 // with the test contract. They might not be the best for other contracts.
-const KAMT_CONFIG: KamtConfig = KamtConfig { min_data_depth: 0, bit_width: 5, max_array_width: 1 };
+pub(crate) const KAMT_CONFIG: KamtConfig =
+    KamtConfig { min_data_depth: 0, bit_width: 5, max_array_width: 1 };
 
 pub struct StateHashAlgorithm;
 
@@ -330,17 +331,7 @@ impl<'r, RT: Runtime> System<'r, RT> {
     /// Set the bytecode.
     pub fn set_bytecode(&mut self, bytecode: &[u8]) -> Result<EvmBytecode, ActorError> {
         self.saved_state_root = None;
-        if bytecode.len() > MAX_CODE_SIZE {
-            return Err(ActorError::illegal_argument(format!(
-                "EVM byte code length ({}) is exceeding the maximum allowed of {MAX_CODE_SIZE}",
-                bytecode.len()
-            )));
-        } else if bytecode.first() == Some(&0xEF) {
-            // Reject code starting with 0xEF, EIP-3541
-            return Err(ActorError::illegal_argument(
-                "EIP-3541: Contract code starting with the 0xEF byte is disallowed.".into(),
-            ));
-        }
+        check_bytecode(bytecode)?;
 
         let code_hash = self.rt.hash(SupportedHashes::Keccak256, bytecode)[..]
             .try_into()
@@ -356,6 +347,33 @@ impl<'r, RT: Runtime> System<'r, RT> {
         Ok(bytecode)
     }
 
+    /// Set the bytecode to bytecode already in the blockstore, e.g. that of another contract,
+    /// without storing it again.
+    pub fn install_bytecode(&mut self, cid: &Cid) -> Result<EvmBytecode, ActorError> {
+        if cid.codec() != IPLD_RAW {
+            return Err(actor_error!(illegal_argument; "bytecode {} is not raw IPLD", cid));
+        }
+        let bytecode = self
+            .rt
+            .store()
+            .get(cid)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to read bytecode")?
+            .with_context_code(ExitCode::USR_NOT_FOUND, || format!("bytecode {} not found", cid))?;
+        if bytecode.is_empty() {
+            return Err(actor_error!(illegal_argument; "bytecode {} is empty", cid));
+        }
+        check_bytecode(&bytecode)?;
+
+        let code_hash = self.rt.hash(SupportedHashes::Keccak256, &bytecode)[..]
+            .try_into()
+            .context_code(ExitCode::USR_ASSERTION_FAILED, "expected a 32byte digest")?;
+
+        self.saved_state_root = None;
+        let bytecode = EvmBytecode::new(*cid, code_hash);
+        self.bytecode = Some(bytecode);
+        Ok(bytecode)
+    }
+
     /// Get value of a storage key.
     pub fn get_storage(&mut self, key: U256) -> Result<U256, ActorError> {
         Ok(self
@@ -447,3 +465,19 @@ impl<'r, RT: Runtime> System<'r, RT> {
         self.tombstone = Some(crate::current_tombstone(self.rt));
     }
 }
+
+/// Checks that bytecode may be deployed.
+fn check_bytecode(bytecode: &[u8]) -> Result<(), ActorError> {
+    if bytecode.len() > MAX_CODE_SIZE {
+        return Err(ActorError::illegal_argument(format!(
+            "EVM byte code length ({}) is exceeding the maximum allowed of {MAX_CODE_SIZE}",
+            bytecode.len()
+        )));
+    } else if bytecode.first() == Some(&0xEF) {
+        // Reject code starting with 0xEF, EIP-3541
+        return Err(ActorError::illegal_argument(
+            "EIP-3541: Contract code starting with the 0xEF byte is disallowed.".into(),
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,45 @@
+//! JSON representations of the EVM actor's method parameters and return values.
+
+use cid::Cid;
+use fil_actors_evm_shared::uints::U256;
+use fil_actors_runtime::json::{JsonCodec, JsonError, MethodCodec, Value};
+use fil_actors_runtime::{json_struct, method_codecs};
+use fvm_ipld_encoding::BytesDe;
+
+use crate::{
+    AuthenticateMessageParams, BytecodeHash, ConstructorParams, DelegateCallParams,
+    GetStorageAtParams, InstallBytecodeParams, Method, StorageSlot,
+};
+
+impl JsonCodec for BytecodeHash {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        <[u8; 32]>::from_json(value).map(BytecodeHash)
+    }
+}
+
+json_struct! {
+    ConstructorParams { creator, initcode }
+    DelegateCallParams { code, input, caller, value }
+    GetStorageAtParams { storage_key }
+    StorageSlot { key, value }
+    InstallBytecodeParams { bytecode, storage }
+    AuthenticateMessageParams { signature, message }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (ConstructorParams, ()),
+        Resurrect => (ConstructorParams, ()),
+        GetBytecode => ((), Option<Cid>),
+        GetBytecodeHash => ((), BytecodeHash),
+        GetStorageAt => (GetStorageAtParams, U256),
+        InvokeContractDelegate => (DelegateCallParams, BytesDe),
+        InstallBytecode => (InstallBytecodeParams, ()),
+        InvokeContract => (BytesDe, BytesDe),
+        AuthenticateMessage => (AuthenticateMessageParams, ()),
+    }
+}
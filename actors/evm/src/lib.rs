@@ -5,6 +5,7 @@ use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_ipld_encoding::{BytesDe, BytesSer};
 use fvm_shared::address::Address;
+use fvm_shared::crypto::hash::SupportedHashes;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 
@@ -15,15 +16,18 @@ use cid::Cid;
 use fil_actors_runtime::runtime::{ActorCode, Runtime};
 use fvm_shared::{MethodNum, METHOD_CONSTRUCTOR};
 use num_derive::FromPrimitive;
-use num_traits::FromPrimitive;
+use num_traits::{FromPrimitive, Zero};
 
 pub use types::*;
 
 #[doc(hidden)]
 pub mod ext;
 pub mod interpreter;
+#[cfg(feature = "json")]
+pub mod json;
 pub(crate) mod reader;
 mod state;
+pub mod testing;
 mod types;
 
 pub use state::*;
@@ -43,6 +47,11 @@ pub const EVM_CONTRACT_SELFDESTRUCT_FAILED: ExitCode = ExitCode::new(40);
 const EVM_MAX_RESERVED_METHOD: u64 = 1023;
 pub const NATIVE_METHOD_SIGNATURE: &str = "handle_filecoin_method(uint64,uint64,bytes)";
 pub const NATIVE_METHOD_SELECTOR: [u8; 4] = [0x86, 0x8e, 0x10, 0xc4];
+/// EIP-1271 signature validation method, invoked to answer `AuthenticateMessage`.
+pub const EIP1271_METHOD_SIGNATURE: &str = "isValidSignature(bytes32,bytes)";
+pub const EIP1271_METHOD_SELECTOR: [u8; 4] = [0x16, 0x26, 0xba, 0x7e];
+/// The value an EIP-1271 contract returns for a valid signature (its method selector).
+pub const EIP1271_MAGIC_VALUE: [u8; 4] = EIP1271_METHOD_SELECTOR;
 
 const EVM_WORD_SIZE: usize = 32;
 
@@ -55,6 +64,14 @@ fn test_method_selector() {
     assert_eq!(computed_selector, NATIVE_METHOD_SELECTOR);
 }
 
+#[test]
+fn test_eip1271_selector() {
+    use cid::multihash::MultihashDigest;
+    let hash = cid::multihash::Code::Keccak256.digest(EIP1271_METHOD_SIGNATURE.as_bytes());
+    let computed_selector = &hash.digest()[..4];
+    assert_eq!(computed_selector, EIP1271_METHOD_SELECTOR);
+}
+
 #[derive(FromPrimitive)]
 #[repr(u64)]
 pub enum Method {
@@ -64,7 +81,9 @@ pub enum Method {
     GetBytecodeHash = 4,
     GetStorageAt = 5,
     InvokeContractDelegate = 6,
+    InstallBytecode = 7,
     InvokeContract = frc42_dispatch::method_hash!("InvokeEVM"),
+    AuthenticateMessage = frc42_dispatch::method_hash!("AuthenticateMessage"),
 }
 
 pub struct EvmContractActor;
@@ -205,6 +224,36 @@ impl EvmContractActor {
         initialize_evm_contract(&mut System::resurrect(rt)?, params.creator, params.initcode.into())
     }
 
+    /// Installs existing bytecode and initial storage into a contract just created by the EAM
+    /// with no code, so that identical contracts can be deployed without running initcode.
+    pub fn install_bytecode<RT>(
+        rt: &mut RT,
+        params: InstallBytecodeParams,
+    ) -> Result<(), ActorError>
+    where
+        RT: Runtime,
+        RT::Blockstore: Clone,
+    {
+        rt.validate_immediate_caller_is(&[EAM_ACTOR_ADDR])?;
+
+        let mut system = System::load(rt)?;
+        if system.readonly {
+            return Err(actor_error!(read_only; "cannot install bytecode in read-only mode"));
+        }
+        let state: State = system.rt.state()?;
+        if state.bytecode_hash != BytecodeHash::EMPTY || state.nonce != 1 {
+            return Err(
+                actor_error!(forbidden; "can only install bytecode in a new empty contract"),
+            );
+        }
+
+        system.install_bytecode(&params.bytecode)?;
+        for slot in params.storage {
+            system.set_storage(slot.key, slot.value)?;
+        }
+        system.flush()
+    }
+
     pub fn invoke_contract_delegate<RT>(
         rt: &mut RT,
         params: DelegateCallParams,
@@ -258,6 +307,69 @@ impl EvmContractActor {
         handle_filecoin_method_output(&output)
     }
 
+    /// Authenticates a message by calling the contract's EIP-1271 `isValidSignature` method
+    /// with the keccak256 hash of the message, so that smart contract wallets can authenticate
+    /// Filecoin messages such as deal proposals. The call is read-only.
+    /// The message is authenticated only if the contract returns the EIP-1271 magic value.
+    /// Contracts without an `isValidSignature` method are instead passed the message in
+    /// `handle_filecoin_method`, which may authenticate it.
+    pub fn authenticate_message<RT>(
+        rt: &mut RT,
+        args: Option<IpldBlock>,
+    ) -> Result<Option<IpldBlock>, ActorError>
+    where
+        RT: Runtime,
+        RT::Blockstore: Clone,
+    {
+        let params: AuthenticateMessageParams = args
+            .as_ref()
+            .with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
+                "method expects arguments".to_string()
+            })?
+            .deserialize()?;
+        let hash = rt.hash(SupportedHashes::Keccak256, &params.message);
+
+        let mut system = System::load(rt)?;
+        let bytecode_cid = system
+            .get_bytecode()
+            .context_code(ExitCode::USR_FORBIDDEN, "contract has no code to authenticate with")?;
+        let implements_eip1271 = load_bytecode(system.rt.store(), &bytecode_cid)?
+            .map_or(false, |bytecode| bytecode.pushes_selector(&EIP1271_METHOD_SELECTOR));
+        if !implements_eip1271 {
+            drop(system);
+            return Self::handle_filecoin_method(rt, Method::AuthenticateMessage as u64, args);
+        }
+
+        system.rt.validate_immediate_caller_accept_any()?;
+        // Validating a signature mustn't change state, whether or not it succeeds.
+        system.readonly = true;
+        let caller = system.resolve_ethereum_address(&system.rt.message().caller()).unwrap();
+        let input = eip1271_input(&hash, &params.signature);
+        let output = match invoke_contract_inner(
+            &mut system,
+            input,
+            &bytecode_cid,
+            &caller,
+            TokenAmount::zero(),
+        ) {
+            Ok(output) => output,
+            Err(e) if e.exit_code() == EVM_CONTRACT_REVERTED => {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "isValidSignature reverted: {}",
+                    e.msg()
+                ));
+            }
+            Err(e) => return Err(e),
+        };
+        // The magic value is returned as an ABI-encoded bytes4, left-aligned in a word.
+        if output.len() >= EVM_WORD_SIZE && output[..4] == EIP1271_MAGIC_VALUE {
+            Ok(None)
+        } else {
+            Err(actor_error!(illegal_argument, "isValidSignature rejected the signature"))
+        }
+    }
+
     /// Returns the contract's EVM bytecode, or `None` if the contract has been deleted (has called
     /// SELFDESTRUCT).
     pub fn bytecode(rt: &mut impl Runtime) -> Result<Option<Cid>, ActorError> {
@@ -322,6 +434,26 @@ fn handle_filecoin_method_input(method: u64, codec: u64, params: &[u8]) -> Vec<u
     buf
 }
 
+/// Format "isValidSignature" input parameters.
+fn eip1271_input(hash: &[u8], signature: &[u8]) -> Vec<u8> {
+    let len = 4
+        + EVM_WORD_SIZE * 3 /* hash, offset and length of signature */
+        + (signature.len() + EVM_WORD_SIZE - 1) / EVM_WORD_SIZE * EVM_WORD_SIZE;
+    let mut buf = Vec::with_capacity(len);
+    buf.extend_from_slice(&EIP1271_METHOD_SELECTOR);
+    buf.extend_from_slice(hash);
+    for n in [EVM_WORD_SIZE as u64 * 2 /* start of signature */, signature.len() as u64] {
+        // Left-pad to 32 bytes, then be-encode the value.
+        let encoded = n.to_be_bytes();
+        buf.resize(buf.len() + (EVM_WORD_SIZE - encoded.len()), 0);
+        buf.extend_from_slice(&encoded);
+    }
+    // Extend with the signature, then right-pad with zeros.
+    buf.extend_from_slice(signature);
+    buf.resize(len, 0);
+    buf
+}
+
 /// Decode the response from "filecoin_native_method". We expect:
 ///
 /// 1. The exit code (u32).
@@ -437,6 +569,17 @@ impl ActorCode for EvmContractActor {
                 let value = Self::invoke_contract_delegate(rt, params)?;
                 Ok(IpldBlock::serialize_cbor(&BytesSer(&value))?)
             }
+            Some(Method::InstallBytecode) => {
+                Self::install_bytecode(
+                    rt,
+                    args.with_context_code(ExitCode::USR_ILLEGAL_ARGUMENT, || {
+                        "method expects arguments".to_string()
+                    })?
+                    .deserialize()?,
+                )?;
+                Ok(None)
+            }
+            Some(Method::AuthenticateMessage) => Self::authenticate_message(rt, args),
             Some(Method::Resurrect) => {
                 Self::resurrect(
                     rt,
@@ -0,0 +1,80 @@
+use cid::multihash::{Code, MultihashDigest};
+use fil_actors_runtime::{MessageAccumulator, EAM_ACTOR_ID};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_shared::address::{Address, Payload};
+
+use crate::interpreter::{StateKamt, KAMT_CONFIG};
+use crate::{BytecodeHash, State, Tombstone};
+
+/// A summary of a contract's state, for checks against other actors.
+#[derive(Debug)]
+pub struct StateSummary {
+    pub bytecode_hash: BytecodeHash,
+    pub tombstone: Option<Tombstone>,
+    pub storage_slots: u64,
+}
+
+/// Checks internal invariants of EVM contract state.
+pub fn check_state_invariants<BS: Blockstore>(
+    state: &State,
+    store: &BS,
+    delegated_address: Option<&Address>,
+) -> (StateSummary, MessageAccumulator) {
+    let acc = MessageAccumulator::default();
+
+    match delegated_address.map(|a| *a.payload()) {
+        Some(Payload::Delegated(da)) => acc.with_code("evm.delegated_address").require(
+            da.namespace() == EAM_ACTOR_ID,
+            format!("delegated address namespace {} is not the EAM", da.namespace()),
+        ),
+        Some(_) => acc.with_code("evm.delegated_address").add(format!(
+            "predictable address {} is not a delegated address",
+            delegated_address.unwrap()
+        )),
+        None => acc.with_code("evm.delegated_address").add("contract has no delegated address"),
+    }
+
+    // Bytecode is stored as a raw block and must hash to the recorded digest.
+    match store.get(&state.bytecode) {
+        Ok(Some(bytecode)) => {
+            let digest = Code::Keccak256.digest(&bytecode);
+            acc.with_code("evm.bytecode_hash").require(
+                digest.digest() == state.bytecode_hash.as_slice(),
+                format!(
+                    "bytecode hash {} does not match keccak of bytecode {}",
+                    state.bytecode_hash, state.bytecode
+                ),
+            );
+        }
+        Ok(None) => {
+            acc.with_code("evm.bytecode").add(format!("bytecode {} not found", state.bytecode))
+        }
+        Err(e) => acc
+            .with_code("evm.bytecode")
+            .add(format!("error loading bytecode {}: {e}", state.bytecode)),
+    }
+
+    let mut storage_slots = 0;
+    match StateKamt::load_with_config(&state.contract_state, store, KAMT_CONFIG.clone()) {
+        Ok(slots) => {
+            let ret = slots.for_each(|_, _| {
+                storage_slots += 1;
+                Ok(())
+            });
+            acc.with_code("evm.contract_state")
+                .require_no_error(ret, "error iterating contract state");
+        }
+        Err(e) => acc
+            .with_code("evm.contract_state")
+            .add(format!("error loading contract state {}: {e}", state.contract_state)),
+    }
+
+    (
+        StateSummary {
+            bytecode_hash: state.bytecode_hash,
+            tombstone: state.tombstone,
+            storage_slots,
+        },
+        acc,
+    )
+}
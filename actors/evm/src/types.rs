@@ -28,6 +28,28 @@ pub struct DelegateCallParams {
     pub value: TokenAmount,
 }
 
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct AuthenticateMessageParams {
+    #[serde(with = "strict_bytes")]
+    pub signature: Vec<u8>,
+    #[serde(with = "strict_bytes")]
+    pub message: Vec<u8>,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct StorageSlot {
+    pub key: U256,
+    pub value: U256,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct InstallBytecodeParams {
+    /// CID of the existing bytecode to install.
+    pub bytecode: Cid,
+    /// Storage slots to initialize, in place of running a constructor.
+    pub storage: Vec<StorageSlot>,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct GetStorageAtParams {
     pub storage_key: U256,
@@ -0,0 +1,201 @@
+mod asm;
+mod util;
+
+use fil_actor_evm as evm;
+use fil_actor_evm::{AuthenticateMessageParams, EVM_CONTRACT_REVERTED};
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::test_utils::MockRuntime;
+use fil_actors_runtime::ActorError;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::crypto::hash::SupportedHashes;
+use fvm_shared::error::ExitCode;
+
+const MESSAGE: &[u8] = b"deal proposal";
+
+/// A contract whose isValidSignature accepts a signature equal to the hash being validated,
+/// and rejects calls to any other method.
+fn eip1271_contract() -> Vec<u8> {
+    let body = r#"
+%dispatch_begin()
+%dispatch(0x1626ba7e, is_valid_signature)
+%dispatch_end()
+
+is_valid_signature:
+jumpdest
+push1 0x04
+calldataload # hash
+push1 0x64
+calldataload # first word of signature
+eq
+%push(valid)
+jumpi
+push1 0x20
+push1 0x00
+return       # zero word
+
+valid:
+jumpdest
+push1 0x00
+calldataload # selector, left-aligned
+%return_stack_word()
+"#;
+
+    asm::new_contract("eip1271", "", body).unwrap()
+}
+
+fn authenticate(rt: &mut MockRuntime, signature: Vec<u8>) -> Result<Option<IpldBlock>, ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<evm::EvmContractActor>(
+        evm::Method::AuthenticateMessage as u64,
+        IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+            signature,
+            message: MESSAGE.to_vec(),
+        })
+        .unwrap(),
+    );
+    rt.verify();
+    ret
+}
+
+#[test]
+fn test_authenticate_message() {
+    let mut rt = util::construct_and_verify(eip1271_contract());
+
+    let signature = rt.hash(SupportedHashes::Keccak256, MESSAGE);
+    assert!(authenticate(&mut rt, signature).unwrap().is_none());
+}
+
+#[test]
+fn test_authenticate_message_rejected() {
+    // The contract returns a zero word for an invalid signature.
+    let mut rt = util::construct_and_verify(eip1271_contract());
+    let mut signature = rt.hash(SupportedHashes::Keccak256, MESSAGE);
+    signature[0] ^= 0xff;
+    let err = authenticate(&mut rt, signature).unwrap_err();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+
+    // A contract implementing neither method reverts.
+    let mut rt = util::construct_and_verify(
+        asm::new_contract("empty", "", "%dispatch_begin()\n%dispatch_end()").unwrap(),
+    );
+    let signature = rt.hash(SupportedHashes::Keccak256, MESSAGE);
+    let err = authenticate(&mut rt, signature).unwrap_err();
+    assert_eq!(EVM_CONTRACT_REVERTED, err.exit_code());
+}
+
+/// A contract answering AuthenticateMessage through handle_filecoin_method, accepting any
+/// signature, and reverting for any other method.
+fn filecoin_method_contract() -> Vec<u8> {
+    let body = format!(
+        r#"
+%dispatch_begin()
+%dispatch(0x868e10c4, handle_filecoin_method)
+%dispatch_end()
+
+handle_filecoin_method:
+jumpdest
+push1 0x04
+calldataload # method
+%push(0x{:x})
+eq
+%push(authenticate)
+jumpi
+push1 0x00
+dup1
+revert
+
+authenticate:
+jumpdest
+stop
+"#,
+        evm::Method::AuthenticateMessage as u64
+    );
+
+    asm::new_contract("filecoin-method", "", &body).unwrap()
+}
+
+#[test]
+fn test_authenticate_message_falls_back_to_filecoin_method() {
+    let mut rt = util::construct_and_verify(filecoin_method_contract());
+
+    let signature = rt.hash(SupportedHashes::Keccak256, MESSAGE);
+    assert!(authenticate(&mut rt, signature).unwrap().is_none());
+}
+
+/// A contract whose isValidSignature runs the given code, and whose handle_filecoin_method
+/// accepts any AuthenticateMessage.
+fn wallet_contract(name: &str, is_valid_signature: &str) -> Vec<u8> {
+    let body = format!(
+        r#"
+%dispatch_begin()
+%dispatch(0x1626ba7e, is_valid_signature)
+%dispatch(0x868e10c4, handle_filecoin_method)
+%dispatch_end()
+
+is_valid_signature:
+jumpdest
+{}
+
+handle_filecoin_method:
+jumpdest
+stop
+"#,
+        is_valid_signature
+    );
+
+    asm::new_contract(name, "", &body).unwrap()
+}
+
+#[test]
+fn test_authenticate_message_rejected_without_fallback() {
+    // A non-magic answer is final, even though handle_filecoin_method would accept.
+    let mut rt = util::construct_and_verify(wallet_contract(
+        "rejecting",
+        r#"
+push4 0xffffffff
+push1 0xe0
+shl
+%return_stack_word()
+"#,
+    ));
+    let signature = rt.hash(SupportedHashes::Keccak256, MESSAGE);
+    let err = authenticate(&mut rt, signature).unwrap_err();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+}
+
+#[test]
+fn test_authenticate_message_reverted() {
+    let mut rt = util::construct_and_verify(wallet_contract(
+        "reverting",
+        r#"
+push1 0x00
+dup1
+revert
+"#,
+    ));
+    let signature = rt.hash(SupportedHashes::Keccak256, MESSAGE);
+    let err = authenticate(&mut rt, signature).unwrap_err();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+}
+
+#[test]
+fn test_authenticate_message_is_read_only() {
+    // The contract writes to storage before answering with the magic value.
+    let mut rt = util::construct_and_verify(wallet_contract(
+        "writing",
+        r#"
+push1 0x01
+push1 0x00
+sstore
+push4 0x1626ba7e
+push1 0xe0
+shl
+%return_stack_word()
+"#,
+    ));
+    let state_root = rt.get_state_root().unwrap();
+    let signature = rt.hash(SupportedHashes::Keccak256, MESSAGE);
+    let err = authenticate(&mut rt, signature).unwrap_err();
+    assert_eq!(ExitCode::USR_READ_ONLY, err.exit_code());
+    assert_eq!(state_root, rt.get_state_root().unwrap());
+}
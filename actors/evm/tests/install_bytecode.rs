@@ -0,0 +1,109 @@
+mod util;
+
+use cid::Cid;
+use fil_actor_evm as evm;
+use fil_actor_evm::{BytecodeHash, InstallBytecodeParams, StorageSlot};
+use fil_actors_evm_shared::uints::U256;
+use fil_actors_runtime::runtime::Runtime;
+use fil_actors_runtime::test_utils::{MockRuntime, EAM_ACTOR_CODE_ID};
+use fil_actors_runtime::{ActorError, EAM_ACTOR_ADDR};
+use fvm_ipld_blockstore::{Block, Blockstore};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::crypto::hash::SupportedHashes;
+use fvm_shared::error::ExitCode;
+use fvm_shared::IPLD_RAW;
+use multihash::{Code, MultihashDigest};
+
+/// Runtime bytecode returning the value of storage slot 0.
+const SLOAD_ZERO: &[u8] = &[
+    0x60, 0x00, // push1 0x00
+    0x54, // sload
+    0x60, 0x00, // push1 0x00
+    0x52, // mstore
+    0x60, 0x20, // push1 0x20
+    0x60, 0x00, // push1 0x00
+    0xf3, // return
+];
+
+fn put_bytecode(rt: &MockRuntime, bytecode: &[u8]) -> Cid {
+    rt.store.put(Code::Blake2b256, &Block::new(IPLD_RAW, bytecode)).unwrap()
+}
+
+fn install(
+    rt: &mut MockRuntime,
+    bytecode: Cid,
+    storage: Vec<StorageSlot>,
+) -> Result<Option<IpldBlock>, ActorError> {
+    rt.set_caller(*EAM_ACTOR_CODE_ID, EAM_ACTOR_ADDR);
+    rt.expect_validate_caller_addr(vec![EAM_ACTOR_ADDR]);
+    let ret = rt.call::<evm::EvmContractActor>(
+        evm::Method::InstallBytecode as u64,
+        IpldBlock::serialize_dag_cbor(&InstallBytecodeParams { bytecode, storage }).unwrap(),
+    );
+    rt.verify();
+    ret
+}
+
+#[test]
+fn test_install_bytecode() {
+    let mut rt = util::construct_and_verify(Vec::new());
+    let bytecode = put_bytecode(&rt, SLOAD_ZERO);
+
+    let slot = StorageSlot { key: U256::zero(), value: U256::from(0x42) };
+    install(&mut rt, bytecode, vec![slot]).unwrap();
+
+    let result = util::invoke_contract(&mut rt, &[]);
+    assert_eq!(U256::from_big_endian(&result), U256::from(0x42));
+
+    rt.expect_validate_caller_any();
+    let ret: Option<Cid> = rt
+        .call::<evm::EvmContractActor>(evm::Method::GetBytecode as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    assert_eq!(Some(bytecode), ret);
+
+    rt.expect_validate_caller_any();
+    let hash: BytecodeHash = rt
+        .call::<evm::EvmContractActor>(evm::Method::GetBytecodeHash as u64, None)
+        .unwrap()
+        .unwrap()
+        .deserialize()
+        .unwrap();
+    let expected: [u8; 32] = rt.hash(SupportedHashes::Keccak256, SLOAD_ZERO).try_into().unwrap();
+    assert_eq!(<[u8; 32]>::from(hash), expected);
+}
+
+#[test]
+fn test_install_bytecode_rejected() {
+    let mut rt = util::construct_and_verify(Vec::new());
+    let bytecode = put_bytecode(&rt, SLOAD_ZERO);
+
+    // Only the EAM may install bytecode.
+    rt.set_caller(*EAM_ACTOR_CODE_ID, Address::new_id(1234));
+    rt.expect_validate_caller_addr(vec![EAM_ACTOR_ADDR]);
+    let err = rt
+        .call::<evm::EvmContractActor>(
+            evm::Method::InstallBytecode as u64,
+            IpldBlock::serialize_dag_cbor(&InstallBytecodeParams { bytecode, storage: vec![] })
+                .unwrap(),
+        )
+        .unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+    rt.reset();
+
+    // Bytecode must exist and may not be empty.
+    let missing = Cid::new_v1(IPLD_RAW, Code::Blake2b256.digest(b"missing"));
+    let err = install(&mut rt, missing, vec![]).unwrap_err();
+    assert_eq!(ExitCode::USR_NOT_FOUND, err.exit_code());
+    let empty = put_bytecode(&rt, &[]);
+    let err = install(&mut rt, empty, vec![]).unwrap_err();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+
+    // Bytecode can only be installed once.
+    install(&mut rt, bytecode, vec![]).unwrap();
+    let err = install(&mut rt, bytecode, vec![]).unwrap_err();
+    assert_eq!(ExitCode::USR_FORBIDDEN, err.exit_code());
+}
@@ -1,10 +1,17 @@
 mod asm;
 
+use cid::Cid;
 use fil_actors_evm_shared::{address::EthAddress, uints::U256};
 use fil_actors_runtime::{
-    test_utils::{new_bls_addr, MockRuntime},
+    runtime::{builtins::Type, DomainSeparationTag, Primitives},
+    test_utils::{
+        make_piece_cid, new_bls_addr, ExpectedVerifySig, MockRuntime, MINER_ACTOR_CODE_ID,
+    },
     EAM_ACTOR_ID,
 };
+use fvm_shared::crypto::signature::{Signature, SignatureType};
+use fvm_shared::piece::{PaddedPieceSize, PieceInfo};
+use fvm_shared::sector::RegisteredSealProof;
 use fvm_shared::{address::Address as FILAddress, econ::TokenAmount, error::ExitCode, METHOD_SEND};
 
 mod util;
@@ -151,6 +158,56 @@ fn test_native_lookup_delegated_address() {
     test_lookup_address(&mut rt, FILAddress::new_id(11111), Vec::new());
 }
 
+#[test]
+fn test_native_get_actor_info() {
+    let mut rt = util::construct_and_verify(tester_bytecode());
+
+    // f0 10101 is a miner
+    let miner = FILAddress::new_id(10101);
+    rt.set_address_actor_type(miner, *MINER_ACTOR_CODE_ID);
+    rt.actor_balances.insert(miner.id().unwrap(), TokenAmount::from_whole(42));
+
+    // f0 10111 runs non-builtin code
+    let unknown = FILAddress::new_id(10111);
+    rt.set_address_actor_type(unknown, *util::DUMMY_ACTOR_CODE_ID);
+
+    fn expected_info(actor_type: u32, balance: TokenAmount, code: &Cid) -> Vec<u8> {
+        let code = code.to_bytes();
+        let mut expected = [
+            U256::from(actor_type).to_bytes().to_vec(),
+            U256::from(&balance).to_bytes().to_vec(),
+            U256::from(3 * 32).to_bytes().to_vec(),
+            U256::from(code.len()).to_bytes().to_vec(),
+            code,
+        ]
+        .concat();
+        expected.resize((expected.len() + 31) / 32 * 32, 0);
+        expected
+    }
+
+    fn test_actor_info(rt: &mut MockRuntime, id: FILAddress, expected: Vec<u8>) {
+        let test = PrecompileTest {
+            precompile_address: NativePrecompile::GetActorInfo.eth_address(),
+            output_size: 32,
+            expected_exit_code: PrecompileExit::Success,
+            gas_avaliable: 10_000_000_000,
+            call_op: util::PrecompileCallOpcode::StaticCall,
+            expected_return: expected,
+            input: id_to_vec(&id),
+        };
+
+        test.run_test(rt);
+    }
+
+    let miner_info =
+        expected_info(Type::Miner as u32, TokenAmount::from_whole(42), &MINER_ACTOR_CODE_ID);
+    test_actor_info(&mut rt, miner, miner_info);
+    let unknown_info = expected_info(0, TokenAmount::from_atto(0), &util::DUMMY_ACTOR_CODE_ID);
+    test_actor_info(&mut rt, unknown, unknown_info);
+    // not found
+    test_actor_info(&mut rt, FILAddress::new_id(11111), Vec::new());
+}
+
 #[test]
 fn test_resolve_delegated() {
     let bytecode = resolve_address_contract();
@@ -300,3 +357,202 @@ fn test_precompile_failure() {
     assert_eq!(&[1u8], result.as_slice());
     rt.reset();
 }
+
+/// Encodes `bytes` according to the solidity ABI: the length followed by the padded data.
+fn abi_bytes(data: &[u8]) -> Vec<u8> {
+    let mut out = U256::from(data.len()).to_bytes().to_vec();
+    out.extend_from_slice(data);
+    out.resize(32 + (data.len() + 31) / 32 * 32, 0);
+    out
+}
+
+#[test]
+fn test_native_verify_signature() {
+    let mut rt = util::construct_and_verify(tester_bytecode());
+
+    fn verify_input(signature: &[u8], signer: &FILAddress, message: &[u8]) -> Vec<u8> {
+        let signature = abi_bytes(signature);
+        let signer = abi_bytes(&signer.to_bytes());
+        let message = abi_bytes(message);
+        [
+            U256::from(3 * 32).to_bytes().to_vec(),
+            U256::from(3 * 32 + signature.len()).to_bytes().to_vec(),
+            U256::from(3 * 32 + signature.len() + signer.len()).to_bytes().to_vec(),
+            signature,
+            signer,
+            message,
+        ]
+        .concat()
+    }
+
+    let mut test = PrecompileTest {
+        precompile_address: util::NativePrecompile::VerifySignature.eth_address(),
+        output_size: 32,
+        expected_exit_code: PrecompileExit::Success,
+        gas_avaliable: 10_000_000_000,
+        call_op: util::PrecompileCallOpcode::StaticCall,
+        input: vec![],
+        expected_return: vec![],
+    };
+
+    let secp = FILAddress::new_secp256k1(&[3u8; 65]).unwrap();
+    let bls = new_bls_addr(1);
+    let message = b"attestation".to_vec();
+    for (signer, sig_type) in [(secp, SignatureType::Secp256k1), (bls, SignatureType::BLS)] {
+        let signature = vec![0xab; 65];
+        test.input = verify_input(&signature, &signer, &message);
+
+        rt.expect_verify_signature(ExpectedVerifySig {
+            sig: Signature { sig_type, bytes: signature.clone() },
+            signer,
+            plaintext: message.clone(),
+            result: Ok(()),
+        });
+        test.run_test_expecting(&mut rt, U256::from(1).to_bytes(), PrecompileExit::Success);
+
+        rt.expect_verify_signature(ExpectedVerifySig {
+            sig: Signature { sig_type, bytes: signature },
+            signer,
+            plaintext: message.clone(),
+            result: Err(anyhow::anyhow!("invalid signature")),
+        });
+        test.run_test_expecting(&mut rt, U256::zero().to_bytes(), PrecompileExit::Success);
+    }
+
+    // signer must be a key address
+    test.input = verify_input(&[0xab; 65], &FILAddress::new_id(1234), &message);
+    test.run_test_expecting(&mut rt, vec![], PrecompileExit::Reverted);
+}
+
+#[test]
+fn test_native_hash_blake2b() {
+    let mut rt = util::construct_and_verify(tester_bytecode());
+
+    let input = b"foo bar baz boxy".to_vec();
+    let expected = rt.hash_blake2b(&input).to_vec();
+    let test = PrecompileTest {
+        precompile_address: util::NativePrecompile::HashBlake2b.eth_address(),
+        output_size: 32,
+        expected_exit_code: PrecompileExit::Success,
+        gas_avaliable: 10_000_000_000,
+        call_op: util::PrecompileCallOpcode::StaticCall,
+        input,
+        expected_return: expected,
+    };
+    test.run_test(&mut rt);
+}
+
+#[test]
+fn test_native_compute_unsealed_sector_cid() {
+    let mut rt = util::construct_and_verify(tester_bytecode());
+
+    fn unsealed_input(proof_type: RegisteredSealProof, pieces: &[PieceInfo]) -> Vec<u8> {
+        let mut offsets = Vec::new();
+        let mut tuples = Vec::new();
+        for piece in pieces {
+            offsets.extend_from_slice(&U256::from(pieces.len() * 32 + tuples.len()).to_bytes());
+            tuples.extend_from_slice(&U256::from(piece.size.0).to_bytes());
+            tuples.extend_from_slice(&U256::from(2 * 32).to_bytes());
+            tuples.extend_from_slice(&abi_bytes(&piece.cid.to_bytes()));
+        }
+        [
+            U256::from(i64::from(proof_type) as u64).to_bytes().to_vec(),
+            U256::from(2 * 32).to_bytes().to_vec(),
+            U256::from(pieces.len()).to_bytes().to_vec(),
+            offsets,
+            tuples,
+        ]
+        .concat()
+    }
+
+    let proof_type = RegisteredSealProof::StackedDRG32GiBV1P1;
+    let pieces = vec![
+        PieceInfo { size: PaddedPieceSize(1 << 30), cid: make_piece_cid(b"piece-1") },
+        PieceInfo { size: PaddedPieceSize(1 << 30), cid: make_piece_cid(b"piece-2") },
+    ];
+    let unsealed = make_piece_cid(b"unsealed");
+
+    let mut test = PrecompileTest {
+        precompile_address: util::NativePrecompile::ComputeUnsealedSectorCid.eth_address(),
+        output_size: 64,
+        expected_exit_code: PrecompileExit::Success,
+        gas_avaliable: 10_000_000_000,
+        call_op: util::PrecompileCallOpcode::StaticCall,
+        input: unsealed_input(proof_type, &pieces),
+        expected_return: vec![],
+    };
+
+    rt.expect_compute_unsealed_sector_cid(proof_type, pieces.clone(), unsealed, ExitCode::OK);
+    test.run_test_expecting(&mut rt, unsealed.to_bytes(), PrecompileExit::Success);
+
+    // syscall failure reverts
+    rt.expect_compute_unsealed_sector_cid(
+        proof_type,
+        pieces.clone(),
+        unsealed,
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+    );
+    test.run_test_expecting(&mut rt, vec![], PrecompileExit::Reverted);
+
+    // invalid piece CIDs revert
+    let mut input = unsealed_input(proof_type, &pieces[..1]);
+    input.truncate(input.len() - abi_bytes(&pieces[0].cid.to_bytes()).len());
+    input.extend_from_slice(&abi_bytes(&[0xff; 3]));
+    test.input = input;
+    test.run_test_expecting(&mut rt, vec![], PrecompileExit::Reverted);
+}
+
+#[test]
+fn test_native_get_randomness() {
+    let mut rt = util::construct_and_verify(tester_bytecode());
+    rt.epoch = 10_000;
+    let lookback = rt.policy.max_randomness_lookback;
+
+    fn randomness_input(source: u64, tag: i64, epoch: i64, entropy: &[u8]) -> Vec<u8> {
+        [
+            U256::from(source).to_bytes().to_vec(),
+            U256::from(tag as u64).to_bytes().to_vec(),
+            U256::from(epoch as u64).to_bytes().to_vec(),
+            U256::from(4 * 32).to_bytes().to_vec(),
+            abi_bytes(entropy),
+        ]
+        .concat()
+    }
+
+    let mut test = PrecompileTest {
+        precompile_address: NativePrecompile::GetRandomness.eth_address(),
+        output_size: 32,
+        expected_exit_code: PrecompileExit::Success,
+        gas_avaliable: 10_000_000_000,
+        call_op: util::PrecompileCallOpcode::StaticCall,
+        input: vec![],
+        expected_return: vec![],
+    };
+
+    let tag = DomainSeparationTag::SealRandomness;
+    let entropy = b"lottery".to_vec();
+
+    // ticket randomness from the oldest permitted epoch
+    let epoch = rt.epoch - lookback;
+    rt.expect_get_randomness_from_tickets(tag, epoch, entropy.clone(), [0xaa; 32]);
+    test.input = randomness_input(0, tag as i64, epoch, &entropy);
+    test.run_test_expecting(&mut rt, [0xaa; 32], PrecompileExit::Success);
+
+    // beacon randomness from the current epoch
+    let epoch = rt.epoch;
+    rt.expect_get_randomness_from_beacon(tag, epoch, entropy.clone(), [0xbb; 32]);
+    test.input = randomness_input(1, tag as i64, epoch, &entropy);
+    test.run_test_expecting(&mut rt, [0xbb; 32], PrecompileExit::Success);
+
+    // future epochs, epochs beyond the lookback, unknown tags and unknown sources revert
+    let curr = rt.epoch;
+    for input in [
+        randomness_input(0, tag as i64, curr + 1, &entropy),
+        randomness_input(1, tag as i64, curr - lookback - 1, &entropy),
+        randomness_input(0, 0, curr, &entropy),
+        randomness_input(2, tag as i64, curr, &entropy),
+    ] {
+        test.input = input;
+        test.run_test_expecting(&mut rt, vec![], PrecompileExit::Reverted);
+    }
+}
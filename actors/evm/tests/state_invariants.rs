@@ -0,0 +1,67 @@
+use cid::Cid;
+use fil_actor_evm::testing::check_state_invariants;
+use fil_actor_evm::State;
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::EAM_ACTOR_ID;
+use fvm_shared::address::Address;
+use fvm_shared::IPLD_RAW;
+
+mod util;
+
+fn contract() -> (MockRuntime, Address) {
+    let bytecode = hex::decode(include_str!("contracts/simplecoin.hex")).unwrap();
+    let contract = Address::new_id(100);
+    let rt = util::init_construct_and_verify(bytecode, |rt| {
+        rt.actor_code_cids.insert(contract, *EVM_ACTOR_CODE_ID);
+        rt.set_origin(contract);
+    });
+    (rt, Address::new_delegated(EAM_ACTOR_ID, &util::CONTRACT_ADDRESS).unwrap())
+}
+
+fn check(rt: &MockRuntime, state: &State, delegated_address: Option<&Address>) -> Vec<String> {
+    let (_, acc) = check_state_invariants(state, &*rt.store, delegated_address);
+    acc.codes()
+}
+
+#[test]
+fn constructed_contract_passes() {
+    let (rt, delegated_address) = contract();
+    let state: State = rt.get_state();
+    let (summary, acc) = check_state_invariants(&state, &*rt.store, Some(&delegated_address));
+    acc.assert_empty();
+    assert_eq!(state.bytecode_hash, summary.bytecode_hash);
+    assert!(summary.tombstone.is_none());
+    // The constructor credits the creator's balance.
+    assert_eq!(1, summary.storage_slots);
+}
+
+#[test]
+fn requires_eam_delegated_address() {
+    let (rt, _) = contract();
+    let state: State = rt.get_state();
+
+    let foreign = Address::new_delegated(EAM_ACTOR_ID + 1, &util::CONTRACT_ADDRESS).unwrap();
+    assert_eq!(vec!["evm.delegated_address"], check(&rt, &state, Some(&foreign)));
+    assert_eq!(vec!["evm.delegated_address"], check(&rt, &state, Some(&Address::new_id(100))));
+    assert_eq!(vec!["evm.delegated_address"], check(&rt, &state, None));
+}
+
+#[test]
+fn requires_bytecode_matching_hash() {
+    let (rt, delegated_address) = contract();
+    let mut state: State = rt.get_state();
+    state.bytecode_hash = [0xff; 32].into();
+    assert_eq!(vec!["evm.bytecode_hash"], check(&rt, &state, Some(&delegated_address)));
+
+    let mut state: State = rt.get_state();
+    state.bytecode = Cid::new_v1(IPLD_RAW, *make_sealed_cid(b"missing").hash());
+    assert_eq!(vec!["evm.bytecode"], check(&rt, &state, Some(&delegated_address)));
+}
+
+#[test]
+fn requires_loadable_contract_state() {
+    let (rt, delegated_address) = contract();
+    let mut state: State = rt.get_state();
+    state.contract_state = make_sealed_cid(b"missing");
+    assert_eq!(vec!["evm.contract_state"], check(&rt, &state, Some(&delegated_address)));
+}
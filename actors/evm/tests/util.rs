@@ -114,6 +114,11 @@ pub enum NativePrecompile {
     CallActor = 3,
     GetActorType = 4,
     CallActorId = 5,
+    VerifySignature = 6,
+    HashBlake2b = 7,
+    ComputeUnsealedSectorCid = 8,
+    GetActorInfo = 9,
+    GetRandomness = 10,
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,21 @@
+//! JSON representations of the init actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs};
+
+use crate::{ConstructorParams, Exec4Params, Exec4Return, ExecParams, ExecReturn, Method};
+
+json_struct! {
+    ConstructorParams { network_name }
+    ExecParams { code_cid, constructor_params }
+    ExecReturn { id_address, robust_address }
+    Exec4Params { code_cid, constructor_params, subaddress }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (ConstructorParams, ()),
+        Exec => (ExecParams, ExecReturn),
+        Exec4 => (Exec4Params, Exec4Return),
+    }
+}
@@ -17,6 +17,8 @@ use num_derive::FromPrimitive;
 pub use self::state::State;
 pub use self::types::*;
 
+#[cfg(feature = "json")]
+pub mod json;
 mod state;
 pub mod testing;
 mod types;
@@ -176,7 +178,7 @@ impl ActorCode for Actor {
 fn can_exec(rt: &impl Runtime, caller: &Cid, exec: &Cid) -> bool {
     rt.resolve_builtin_actor_type(exec)
         .map(|typ| match typ {
-            Type::Multisig | Type::PaymentChannel => true,
+            Type::Multisig | Type::PaymentChannel | Type::PasskeyAccount => true,
             Type::Miner if rt.resolve_builtin_actor_type(caller) == Some(Type::Power) => true,
             _ => false,
         })
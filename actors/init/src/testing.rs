@@ -9,6 +9,7 @@ use fvm_shared::{
 
 use crate::State;
 
+#[derive(Debug)]
 pub struct StateSummary {
     pub ids_by_address: HashMap<Address, ActorID>,
     pub next_id: ActorID,
@@ -21,8 +22,9 @@ pub fn check_state_invariants<BS: Blockstore>(
 ) -> (StateSummary, MessageAccumulator) {
     let acc = MessageAccumulator::default();
 
-    acc.require(!state.network_name.is_empty(), "network name is empty");
-    acc.require(
+    acc.with_code("init.network_name")
+        .require(!state.network_name.is_empty(), "network name is empty");
+    acc.with_code("init.next_id").require(
         state.next_id >= FIRST_NON_SINGLETON_ADDR,
         format!("next id {} is too low", state.next_id),
     );
@@ -35,25 +37,27 @@ pub fn check_state_invariants<BS: Blockstore>(
         Ok(address_map) => {
             let ret = address_map.for_each(|key, actor_id| {
                 let key_address = Address::from_bytes(key)?;
+                let acc = acc.with_context("address", key_address);
 
-                acc.require(
+                acc.with_code("init.address_map.id_key").require(
                     key_address.protocol() != Protocol::ID,
                     format!("key {key_address} is an ID address"),
                 );
-                acc.require(
+                acc.with_code("init.address_map.singleton_id").require(
                     actor_id >= &FIRST_NON_SINGLETON_ADDR,
                     format!("unexpected singleton ID value {actor_id}"),
                 );
 
                 match key_address.protocol() {
                     Protocol::ID => {
-                        acc.add(format!("key {key_address} is an ID address"));
+                        acc.with_code("init.address_map.id_key")
+                            .add(format!("key {key_address} is an ID address"));
                     }
                     Protocol::Delegated => {
                         if let Some(duplicate) =
                             delegated_address_by_id.insert(*actor_id, key_address)
                         {
-                            acc.add(format!(
+                            acc.with_code("init.address_map.duplicate").add(format!(
                                 "duplicate mapping to ID {actor_id}: {key_address} {duplicate}"
                             ));
                         }
@@ -61,7 +65,7 @@ pub fn check_state_invariants<BS: Blockstore>(
                     _ => {
                         if let Some(duplicate) = stable_address_by_id.insert(*actor_id, key_address)
                         {
-                            acc.add(format!(
+                            acc.with_code("init.address_map.duplicate").add(format!(
                                 "duplicate mapping to ID {actor_id}: {key_address} {duplicate}"
                             ));
                         }
@@ -73,9 +77,9 @@ pub fn check_state_invariants<BS: Blockstore>(
                 Ok(())
             });
 
-            acc.require_no_error(ret, "error iterating address map");
+            acc.with_code("init.address_map").require_no_error(ret, "error iterating address map");
         }
-        Err(e) => acc.add(format!("error loading address map: {e}")),
+        Err(e) => acc.with_code("init.address_map").add(format!("error loading address map: {e}")),
     }
 
     (init_summary, acc)
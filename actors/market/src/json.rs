@@ -0,0 +1,116 @@
+//! JSON representations of the market actor's method parameters and return values.
+
+use fil_actors_runtime::json::{JsonCodec, JsonError, Map, MethodCodec, Value};
+use fil_actors_runtime::{json_struct, method_codecs};
+use fvm_shared::address::Address;
+
+use crate::{
+    ActivateDealsParams, ActivateDealsResult, ClientDealProposal, ComputeDataCommitmentParams,
+    ComputeDataCommitmentReturn, DealProposal, DealQueryParams, DealState, GetBalanceReturn,
+    GetDealActivationReturn, GetDealClientCollateralReturn, GetDealClientReturn,
+    GetDealDataCommitmentReturn, GetDealLabelReturn, GetDealProviderCollateralReturn,
+    GetDealProviderReturn, GetDealTermReturn, GetDealTotalPriceReturn, GetDealVerifiedReturn,
+    Label, MarketNotifyDealParams, Method, OnMinerSectorsTerminateParams,
+    PublishStorageDealsParams, PublishStorageDealsReturn, SectorDataSpec, SectorDealData,
+    SectorDeals, VerifiedDealInfo, VerifyDealsForActivationParams, VerifyDealsForActivationReturn,
+    WithdrawBalanceParams, WithdrawBalanceReturn,
+};
+
+/// Labels are `{"string": "..."}` or `{"bytes": "<hex>"}`.
+impl JsonCodec for Label {
+    fn to_json(&self) -> Value {
+        let (key, value) = match self {
+            Label::String(s) => ("string", s.to_json()),
+            Label::Bytes(b) => ("bytes", b.to_json()),
+        };
+        let mut map = Map::new();
+        map.insert(key.to_owned(), value);
+        Value::Object(map)
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let obj = value.as_object().ok_or_else(|| JsonError::new("expected a label object"))?;
+        match (obj.get("string"), obj.get("bytes")) {
+            (Some(s), None) => String::from_json(s).map(Label::String),
+            (None, Some(b)) => Vec::<u8>::from_json(b).map(Label::Bytes),
+            _ => Err(JsonError::new("label must have exactly one of \"string\" or \"bytes\"")),
+        }
+    }
+}
+
+json_struct! {
+    WithdrawBalanceParams { provider_or_client, amount }
+    transparent WithdrawBalanceReturn { amount_withdrawn }
+    GetBalanceReturn { balance, locked }
+    OnMinerSectorsTerminateParams { epoch, deal_ids }
+    PublishStorageDealsParams { deals }
+    PublishStorageDealsReturn { ids, valid_deals }
+    VerifyDealsForActivationParams { sectors }
+    SectorDeals { sector_type, sector_expiry, deal_ids }
+    VerifyDealsForActivationReturn { sectors }
+    SectorDealData { commd }
+    ActivateDealsParams { deal_ids, sector_expiry }
+    VerifiedDealInfo { client, allocation_id, data, size }
+    ActivateDealsResult { nonverified_deal_space, verified_infos }
+    ComputeDataCommitmentParams { inputs }
+    ComputeDataCommitmentReturn { commds }
+    SectorDataSpec { deal_ids, sector_type }
+    transparent DealQueryParams { id }
+    GetDealDataCommitmentReturn { data, size }
+    transparent GetDealClientReturn { client }
+    transparent GetDealProviderReturn { provider }
+    transparent GetDealLabelReturn { label }
+    GetDealTermReturn { start, duration }
+    transparent GetDealTotalPriceReturn { total_price }
+    transparent GetDealClientCollateralReturn { collateral }
+    transparent GetDealProviderCollateralReturn { collateral }
+    transparent GetDealVerifiedReturn { verified }
+    GetDealActivationReturn { activated, terminated }
+    MarketNotifyDealParams { proposal, deal_id }
+    DealProposal {
+        piece_cid,
+        piece_size,
+        verified_deal,
+        client,
+        provider,
+        label,
+        start_epoch,
+        end_epoch,
+        storage_price_per_epoch,
+        provider_collateral,
+        client_collateral,
+    }
+    ClientDealProposal { proposal, client_signature }
+    DealState { sector_start_epoch, last_updated_epoch, slash_epoch, verified_claim }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => ((), ()),
+        AddBalance => (Address, ()),
+        WithdrawBalance => (WithdrawBalanceParams, WithdrawBalanceReturn),
+        PublishStorageDeals => (PublishStorageDealsParams, PublishStorageDealsReturn),
+        VerifyDealsForActivation => (
+            VerifyDealsForActivationParams,
+            VerifyDealsForActivationReturn,
+        ),
+        ActivateDeals => (ActivateDealsParams, ActivateDealsResult),
+        OnMinerSectorsTerminate => (OnMinerSectorsTerminateParams, ()),
+        ComputeDataCommitment => (ComputeDataCommitmentParams, ComputeDataCommitmentReturn),
+        CronTick => ((), ()),
+        AddBalanceExported => (Address, ()),
+        WithdrawBalanceExported => (WithdrawBalanceParams, WithdrawBalanceReturn),
+        PublishStorageDealsExported => (PublishStorageDealsParams, PublishStorageDealsReturn),
+        GetBalanceExported => (Address, GetBalanceReturn),
+        GetDealDataCommitmentExported => (DealQueryParams, GetDealDataCommitmentReturn),
+        GetDealClientExported => (DealQueryParams, GetDealClientReturn),
+        GetDealProviderExported => (DealQueryParams, GetDealProviderReturn),
+        GetDealLabelExported => (DealQueryParams, GetDealLabelReturn),
+        GetDealTermExported => (DealQueryParams, GetDealTermReturn),
+        GetDealTotalPriceExported => (DealQueryParams, GetDealTotalPriceReturn),
+        GetDealClientCollateralExported => (DealQueryParams, GetDealClientCollateralReturn),
+        GetDealProviderCollateralExported => (DealQueryParams, GetDealProviderCollateralReturn),
+        GetDealVerifiedExported => (DealQueryParams, GetDealVerifiedReturn),
+        GetDealActivationExported => (DealQueryParams, GetDealActivationReturn),
+    }
+}
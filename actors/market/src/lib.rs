@@ -40,6 +40,7 @@ use fvm_ipld_encoding::{RawBytes, DAG_CBOR};
 use fvm_shared::sys::SendFlags;
 
 use crate::ext::verifreg::{AllocationID, AllocationRequest};
+use crate::reason::DealRejection;
 
 pub use self::deal::*;
 use self::policy::*;
@@ -50,7 +51,10 @@ pub use self::types::*;
 pub mod balance_table;
 #[doc(hidden)]
 pub mod ext;
+#[cfg(feature = "json")]
+pub mod json;
 pub mod policy;
+pub mod reason;
 pub mod testing;
 
 mod deal;
@@ -237,6 +241,8 @@ impl Actor {
 
         // Deals that passed `AuthenticateMessage` and other state-less checks.
         let mut validity_index: Vec<bool> = Vec::with_capacity(params.deals.len());
+        // Reasons for dropping the deals that failed, reported if all deals fail.
+        let mut rejections: Vec<DealRejection> = Vec::new();
 
         let baseline_power = request_current_baseline_power(rt)?;
         let (network_raw_power, _) = request_current_network_power(rt)?;
@@ -246,6 +252,10 @@ impl Actor {
             let valid = if let Err(e) = validate_deal(rt, deal, &network_raw_power, &baseline_power)
             {
                 info!("invalid deal {}: {}", di, e);
+                rejections.push(DealRejection {
+                    index: di as u64,
+                    code: e.reason_code().unwrap_or(reason::INVALID_DEAL),
+                });
                 false
             } else {
                 true
@@ -292,6 +302,8 @@ impl Actor {
                     "invalid deal {}: cannot publish deals from multiple providers in one batch",
                     di
                 );
+                rejections
+                    .push(DealRejection { index: di as u64, code: reason::MULTIPLE_PROVIDERS });
                 continue;
             }
             let client_id = match rt.resolve_address(&deal.proposal.client) {
@@ -301,6 +313,8 @@ impl Actor {
                         "invalid deal {}: failed to resolve proposal.client address {} for deal",
                         di, deal.proposal.client
                     );
+                    rejections
+                        .push(DealRejection { index: di as u64, code: reason::CLIENT_NOT_FOUND });
                     continue;
                 }
             };
@@ -315,6 +329,10 @@ impl Actor {
 
             if !client_balance_ok {
                 info!("invalid deal: {}: insufficient client funds to cover proposal cost", di);
+                rejections.push(DealRejection {
+                    index: di as u64,
+                    code: reason::INSUFFICIENT_CLIENT_FUNDS,
+                });
                 continue;
             }
 
@@ -328,6 +346,10 @@ impl Actor {
 
             if !provider_balance_ok {
                 info!("invalid deal: {}: insufficient provider funds to cover proposal cost", di);
+                rejections.push(DealRejection {
+                    index: di as u64,
+                    code: reason::INSUFFICIENT_PROVIDER_FUNDS,
+                });
                 continue;
             }
 
@@ -350,6 +372,7 @@ impl Actor {
             let duplicate_in_message = proposal_cid_lookup.contains(&pcid);
             if duplicate_in_state || duplicate_in_message {
                 info!("invalid deal {}: cannot publish duplicate deal proposal", di);
+                rejections.push(DealRejection { index: di as u64, code: reason::DUPLICATE_DEAL });
                 continue;
             }
 
@@ -368,6 +391,10 @@ impl Actor {
                     TokenAmount::from_whole(deal.proposal.piece_size.0 as i64);
                 if remaining_datacap < piece_datacap_required {
                     client_datacap_remaining.insert(client_id, remaining_datacap);
+                    rejections.push(DealRejection {
+                        index: di as u64,
+                        code: reason::INSUFFICIENT_DATACAP,
+                    });
                     continue; // Drop the deal
                 }
                 client_datacap_remaining
@@ -419,7 +446,9 @@ impl Actor {
             ));
         }
         if valid_deal_count == 0 {
-            return Err(actor_error!(illegal_argument, "All deal proposals invalid"));
+            rejections.sort_by_key(|r| r.index);
+            return Err(actor_error!(illegal_argument, "All deal proposals invalid")
+                .with_reason(reason::DEALS_REJECTED, rejections));
         }
 
         let mut new_deal_ids = Vec::with_capacity(valid_deals.len());
@@ -566,7 +595,8 @@ impl Actor {
                         illegal_argument,
                         "deal {} already activated",
                         deal_id
-                    ));
+                    )
+                    .with_reason(reason::DEAL_ALREADY_ACTIVATED, deal_id));
                 }
 
                 let proposal = st.get_proposal(rt.store(), deal_id)?;
@@ -1082,8 +1112,14 @@ pub fn validate_and_return_deal_space<BS: Blockstore>(
                 }
             })?;
 
-        validate_deal_can_activate(proposal, miner_addr, sector_expiry, sector_activation)
-            .with_context(|| format!("cannot activate deal {}", deal_id))?;
+        validate_deal_can_activate(
+            *deal_id,
+            proposal,
+            miner_addr,
+            sector_expiry,
+            sector_activation,
+        )
+        .with_context(|| format!("cannot activate deal {}", deal_id))?;
 
         if proposal.verified_deal {
             verified_deal_space += proposal.piece_size.0;
@@ -1201,6 +1237,7 @@ pub fn gen_rand_next_epoch(
 // Checks
 ////////////////////////////////////////////////////////////////////////////////
 fn validate_deal_can_activate(
+    deal_id: DealID,
     proposal: &DealProposal,
     miner_addr: &Address,
     sector_expiration: ChainEpoch,
@@ -1212,7 +1249,8 @@ fn validate_deal_can_activate(
             "proposal has provider {}, must be {}",
             proposal.provider,
             miner_addr
-        ));
+        )
+        .with_reason(reason::WRONG_PROVIDER, deal_id));
     };
 
     if curr_epoch > proposal.start_epoch {
@@ -1221,7 +1259,8 @@ fn validate_deal_can_activate(
             "proposal start epoch {} has already elapsed at {}",
             proposal.start_epoch,
             curr_epoch
-        ));
+        )
+        .with_reason(reason::ACTIVATION_AFTER_START, deal_id));
     };
 
     if proposal.end_epoch > sector_expiration {
@@ -1230,7 +1269,8 @@ fn validate_deal_can_activate(
             "proposal expiration {} exceeds sector expiration {}",
             proposal.end_epoch,
             sector_expiration
-        ));
+        )
+        .with_reason(reason::DEAL_EXCEEDS_SECTOR, deal_id));
     };
 
     Ok(())
@@ -1252,38 +1292,44 @@ fn validate_deal(
             "deal label can be at most {} bytes, is {}",
             detail::DEAL_MAX_LABEL_SIZE,
             proposal.label.len()
-        ));
+        )
+        .with_reason(reason::DEAL_LABEL_TOO_LONG, ()));
     }
 
-    proposal
-        .piece_size
-        .validate()
-        .map_err(|e| actor_error!(illegal_argument, "proposal piece size is invalid: {}", e))?;
+    proposal.piece_size.validate().map_err(|e| {
+        actor_error!(illegal_argument, "proposal piece size is invalid: {}", e)
+            .with_reason(reason::INVALID_PIECE_SIZE, ())
+    })?;
 
     // * we are skipping the check for if Cid is defined, but this shouldn't be possible
 
     if !is_piece_cid(&proposal.piece_cid) {
-        return Err(actor_error!(illegal_argument, "proposal PieceCID undefined"));
+        return Err(actor_error!(illegal_argument, "proposal PieceCID undefined")
+            .with_reason(reason::INVALID_PIECE_CID, ()));
     }
 
     if proposal.end_epoch <= proposal.start_epoch {
-        return Err(actor_error!(illegal_argument, "proposal end before proposal start"));
+        return Err(actor_error!(illegal_argument, "proposal end before proposal start")
+            .with_reason(reason::DEAL_END_BEFORE_START, ()));
     }
 
     if rt.curr_epoch() > proposal.start_epoch {
-        return Err(actor_error!(illegal_argument, "Deal start epoch has already elapsed."));
+        return Err(actor_error!(illegal_argument, "Deal start epoch has already elapsed.")
+            .with_reason(reason::DEAL_START_ELAPSED, ()));
     };
 
     let (min_dur, max_dur) = deal_duration_bounds(proposal.piece_size);
     if proposal.duration() < min_dur || proposal.duration() > max_dur {
-        return Err(actor_error!(illegal_argument, "Deal duration out of bounds."));
+        return Err(actor_error!(illegal_argument, "Deal duration out of bounds.")
+            .with_reason(reason::DEAL_DURATION_OUT_OF_BOUNDS, ()));
     };
 
     let (min_price, max_price) =
         deal_price_per_epoch_bounds(proposal.piece_size, proposal.duration());
     if proposal.storage_price_per_epoch < min_price || &proposal.storage_price_per_epoch > max_price
     {
-        return Err(actor_error!(illegal_argument, "Storage price out of bounds."));
+        return Err(actor_error!(illegal_argument, "Storage price out of bounds.")
+            .with_reason(reason::DEAL_PRICE_OUT_OF_BOUNDS, ()));
     };
 
     let (min_provider_collateral, max_provider_collateral) = deal_provider_collateral_bounds(
@@ -1296,7 +1342,8 @@ fn validate_deal(
     if proposal.provider_collateral < min_provider_collateral
         || proposal.provider_collateral > max_provider_collateral
     {
-        return Err(actor_error!(illegal_argument, "Provider collateral out of bounds."));
+        return Err(actor_error!(illegal_argument, "Provider collateral out of bounds.")
+            .with_reason(reason::PROVIDER_COLLATERAL_OUT_OF_BOUNDS, ()));
     };
 
     let (min_client_collateral, max_client_collateral) =
@@ -1304,7 +1351,8 @@ fn validate_deal(
     if proposal.client_collateral < min_client_collateral
         || proposal.client_collateral > max_client_collateral
     {
-        return Err(actor_error!(illegal_argument, "Client collateral out of bounds."));
+        return Err(actor_error!(illegal_argument, "Client collateral out of bounds.")
+            .with_reason(reason::CLIENT_COLLATERAL_OUT_OF_BOUNDS, ()));
     };
 
     Ok(())
@@ -1329,7 +1377,9 @@ fn deal_proposal_is_internally_valid(
         None,
         SendFlags::READ_ONLY,
     ))
-    .map_err(|e| e.wrap("proposal authentication failed"))?;
+    .map_err(|e| {
+        e.wrap("proposal authentication failed").with_reason(reason::INVALID_CLIENT_SIGNATURE, ())
+    })?;
     Ok(())
 }
 
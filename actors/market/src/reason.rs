@@ -0,0 +1,62 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Codes of the reasons attached to market actor errors, decoded with `ActorError::reason`.
+//! The type of each reason's detail is noted with its code. Codes of reasons for dropping a
+//! deal from a batch are reported in a `DealRejection`, without detail.
+
+use fvm_ipld_encoding::tuple::*;
+
+/// The deal label is too long. No detail.
+pub const DEAL_LABEL_TOO_LONG: u64 = 1;
+/// The deal piece size is invalid. No detail.
+pub const INVALID_PIECE_SIZE: u64 = 2;
+/// The deal piece CID is not a piece commitment. No detail.
+pub const INVALID_PIECE_CID: u64 = 3;
+/// The deal ends before it starts. No detail.
+pub const DEAL_END_BEFORE_START: u64 = 4;
+/// The deal start epoch has passed. No detail.
+pub const DEAL_START_ELAPSED: u64 = 5;
+/// The deal duration is out of bounds for its piece size. No detail.
+pub const DEAL_DURATION_OUT_OF_BOUNDS: u64 = 6;
+/// The deal storage price is out of bounds. No detail.
+pub const DEAL_PRICE_OUT_OF_BOUNDS: u64 = 7;
+/// The deal provider collateral is out of bounds. No detail.
+pub const PROVIDER_COLLATERAL_OUT_OF_BOUNDS: u64 = 8;
+/// The deal client collateral is out of bounds. No detail.
+pub const CLIENT_COLLATERAL_OUT_OF_BOUNDS: u64 = 9;
+/// The client signature on the deal proposal is invalid. No detail.
+pub const INVALID_CLIENT_SIGNATURE: u64 = 10;
+/// The deal is for a different provider than others in the batch. No detail.
+pub const MULTIPLE_PROVIDERS: u64 = 11;
+/// The deal client address cannot be resolved. No detail.
+pub const CLIENT_NOT_FOUND: u64 = 12;
+/// The client's escrow cannot cover the deal. No detail.
+pub const INSUFFICIENT_CLIENT_FUNDS: u64 = 13;
+/// The provider's escrow cannot cover the deal collateral. No detail.
+pub const INSUFFICIENT_PROVIDER_FUNDS: u64 = 14;
+/// The deal duplicates a pending deal or another in the batch. No detail.
+pub const DUPLICATE_DEAL: u64 = 15;
+/// The client's DataCap cannot cover the verified deal. No detail.
+pub const INSUFFICIENT_DATACAP: u64 = 16;
+/// No deals in a batch were valid. Detail: `Vec<DealRejection>`.
+pub const DEALS_REJECTED: u64 = 17;
+/// The deal to activate is for a different provider. Detail: the `DealID`.
+pub const WRONG_PROVIDER: u64 = 18;
+/// The deal to activate has passed its start epoch. Detail: the `DealID`.
+pub const ACTIVATION_AFTER_START: u64 = 19;
+/// The deal to activate ends after the sector expires. Detail: the `DealID`.
+pub const DEAL_EXCEEDS_SECTOR: u64 = 20;
+/// The deal to activate is already active. Detail: the `DealID`.
+pub const DEAL_ALREADY_ACTIVATED: u64 = 21;
+/// A deal is invalid for a reason without a specific code. No detail.
+pub const INVALID_DEAL: u64 = 22;
+/// An escrow balance cannot cover an amount to lock. Detail: `InsufficientBalance`.
+pub const INSUFFICIENT_ESCROW: u64 = 23;
+
+/// A deal proposal dropped from a batch, identified by its index in the batch.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct DealRejection {
+    pub index: u64,
+    pub code: u64,
+}
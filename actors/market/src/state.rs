@@ -7,7 +7,7 @@ use cid::Cid;
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{
     actor_error, make_empty_map, make_map_with_root_and_bitwidth, ActorError, Array, AsActorError,
-    Set, SetMultimap,
+    InsufficientBalance, Set, SetMultimap,
 };
 use fvm_ipld_blockstore::Blockstore;
 use fvm_ipld_encoding::tuple::*;
@@ -22,6 +22,7 @@ use num_traits::Zero;
 use std::collections::BTreeMap;
 
 use super::policy::*;
+use super::reason;
 use super::types::*;
 use super::{DealProposal, DealState, EX_DEAL_EXPIRED};
 
@@ -805,7 +806,11 @@ impl State {
             return Err(actor_error!(insufficient_funds;
                     "not enough balance to lock for addr{}: \
                     escrow balance {} < prev locked {} + amount {}",
-                    addr, escrow_balance, prev_locked, amount));
+                    addr, escrow_balance, prev_locked, amount)
+            .with_reason(
+                reason::INSUFFICIENT_ESCROW,
+                InsufficientBalance { required: &prev_locked + amount, available: escrow_balance },
+            ));
         }
 
         locked_table
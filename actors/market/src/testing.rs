@@ -24,7 +24,7 @@ use crate::{
 };
 use crate::{ext::verifreg::AllocationID, NO_ALLOCATION_ID};
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct DealSummary {
     pub provider: Address,
     pub start_epoch: ChainEpoch,
@@ -48,7 +48,7 @@ impl Default for DealSummary {
         }
     }
 }
-#[derive(Default, Clone)]
+#[derive(Debug, Default, Clone)]
 pub struct StateSummary {
     pub deals: BTreeMap<DealID, DealSummary>,
     pub claim_id_to_deal_id: BTreeMap<u64, DealID>,
@@ -69,21 +69,21 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
 ) -> (StateSummary, MessageAccumulator) {
     let acc = MessageAccumulator::default();
 
-    acc.require(
+    acc.with_code("market.client_locked_collateral.negative").require(
         !state.total_client_locked_collateral.is_negative(),
         format!(
             "negative total client locked collateral: {}",
             state.total_client_locked_collateral
         ),
     );
-    acc.require(
+    acc.with_code("market.provider_locked_collateral.negative").require(
         !state.total_provider_locked_collateral.is_negative(),
         format!(
             "negative total provider locked collateral: {}",
             state.total_provider_locked_collateral
         ),
     );
-    acc.require(
+    acc.with_code("market.client_storage_fee.negative").require(
         !state.total_client_storage_fee.is_negative(),
         format!("negative total client storage fee: {}", state.total_client_storage_fee),
     );
@@ -98,6 +98,7 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
     match DealArray::load(&state.proposals, store) {
         Ok(proposals) => {
             let ret = proposals.for_each(|deal_id, proposal| {
+                let acc = acc.with_context("deal", deal_id);
                 let proposal_cid = deal_cid(proposal)?;
 
                 if proposal.start_epoch >= current_epoch {
@@ -122,23 +123,23 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
                 total_proposal_collateral +=
                     &proposal.client_collateral + &proposal.provider_collateral;
 
-                acc.require(
+                acc.with_code("market.proposal.client_protocol").require(
                     proposal.client.protocol() == Protocol::ID,
                     "client address for deal {deal_id} is not an ID address",
                 );
-                acc.require(
+                acc.with_code("market.proposal.provider_protocol").require(
                     proposal.provider.protocol() == Protocol::ID,
                     "provider address for deal {deal_id} is not an ID address",
                 );
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating proposals");
+            acc.with_code("market.proposals").require_no_error(ret, "error iterating proposals");
         }
-        Err(e) => acc.add(format!("error loading proposals: {e}")),
+        Err(e) => acc.with_code("market.proposals").add(format!("error loading proposals: {e}")),
     };
 
     // next id should be higher than any existing deal
-    acc.require(
+    acc.with_code("market.next_id").require(
         state.next_id as i64 > max_deal_id,
         format!(
             "next id, {}, is not greater than highest id in proposals, {max_deal_id}",
@@ -153,8 +154,9 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
         Ok(pending_allocations_hamt) => {
             let ret = pending_allocations_hamt.for_each(|key, allocation_id| {
                 let deal_id: u64 = u64::decode_var(key.0.as_slice()).unwrap().0;
+                let acc = acc.with_context("deal", deal_id);
 
-                acc.require(
+                acc.with_code("market.pending_allocation.proposal").require(
                     proposal_stats.get(&deal_id).is_some(),
                     format!("pending deal allocation {} not found in proposals", deal_id),
                 );
@@ -163,9 +165,12 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
                 alloc_id_to_deal_id.insert(*allocation_id, deal_id);
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating pending allocations");
+            acc.with_code("market.pending_allocations")
+                .require_no_error(ret, "error iterating pending allocations");
         }
-        Err(e) => acc.add(format!("error loading pending allocations: {e}")),
+        Err(e) => acc
+            .with_code("market.pending_allocations")
+            .add(format!("error loading pending allocations: {e}")),
     };
 
     // deal states
@@ -174,18 +179,19 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
     match DealMetaArray::load(&state.states, store) {
         Ok(deal_states) => {
             let ret = deal_states.for_each(|deal_id, deal_state| {
-                acc.require(
+                let acc = acc.with_context("deal", deal_id);
+                acc.with_code("market.deal_state.sector_start").require(
                     deal_state.sector_start_epoch >= 0,
                     format!("deal {deal_id} state start epoch undefined: {:?}", deal_state),
                 );
-                acc.require(
+                acc.with_code("market.deal_state.last_updated").require(
                     deal_state.last_updated_epoch == EPOCH_UNDEFINED
                         || deal_state.last_updated_epoch >= deal_state.sector_start_epoch,
                     format!(
                         "deal {deal_id} state last updated before sector start: {deal_state:?}"
                     ),
                 );
-                acc.require(
+                acc.with_code("market.deal_state.last_updated").require(
                     deal_state.last_updated_epoch == EPOCH_UNDEFINED
                         || deal_state.last_updated_epoch <= current_epoch,
                     format!(
@@ -193,29 +199,35 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
                         deal_state.last_updated_epoch
                     ),
                 );
-                acc.require(deal_state.slash_epoch == EPOCH_UNDEFINED || deal_state.slash_epoch >= deal_state.sector_start_epoch, format!("deal {deal_id} state slashed before sector start: {deal_state:?}"));
-                acc.require(deal_state.slash_epoch == EPOCH_UNDEFINED || deal_state.slash_epoch <= current_epoch, format!("deal {deal_id} state slashed after current epoch {current_epoch}: {deal_state:?}"));
+                acc.with_code("market.deal_state.slash_epoch").require(deal_state.slash_epoch == EPOCH_UNDEFINED || deal_state.slash_epoch >= deal_state.sector_start_epoch, format!("deal {deal_id} state slashed before sector start: {deal_state:?}"));
+                acc.with_code("market.deal_state.slash_epoch").require(deal_state.slash_epoch == EPOCH_UNDEFINED || deal_state.slash_epoch <= current_epoch, format!("deal {deal_id} state slashed after current epoch {current_epoch}: {deal_state:?}"));
 
                 if let Some(stats) = proposal_stats.get_mut(&deal_id) {
                     stats.sector_start_epoch = deal_state.sector_start_epoch;
                     stats.last_update_epoch = deal_state.last_updated_epoch;
                     stats.slash_epoch = deal_state.slash_epoch;
                 } else {
-                    acc.add(format!("no deal proposal for deal state {deal_id}"));
+                    acc.with_code("market.deal_state.proposal").add(format!("no deal proposal for deal state {deal_id}"));
                 }
-                acc.require(!pending_allocations.contains_key(&deal_id), format!("deal {deal_id} has pending allocation"));
+                acc.with_code("market.deal_state.pending_allocation").require(!pending_allocations.contains_key(&deal_id), format!("deal {deal_id} has pending allocation"));
 
                 deal_state_count += 1;
 
-                if deal_state.verified_claim != NO_ALLOCATION_ID {
+                // The claim of a terminated deal may have been dropped along with its piece.
+                if deal_state.verified_claim != NO_ALLOCATION_ID
+                    && deal_state.slash_epoch == EPOCH_UNDEFINED
+                {
                     claim_id_to_deal_id.insert(deal_state.verified_claim, deal_id);
                 }
 
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating deal states");
+            acc.with_code("market.deal_states")
+                .require_no_error(ret, "error iterating deal states");
+        }
+        Err(e) => {
+            acc.with_code("market.deal_states").add(format!("error loading deal states: {e}"))
         }
-        Err(e) => acc.add(format!("error loading deal states: {e}")),
     };
 
     // pending proposals
@@ -229,14 +241,17 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
             let ret = pending_proposals.for_each(|key, _| {
                 let proposal_cid = Cid::try_from(key.0.to_owned())?;
 
-                acc.require(proposal_cids.contains(&proposal_cid), format!("pending proposal with cid {proposal_cid} not found within proposals {pending_proposals:?}"));
+                acc.with_code("market.pending_proposal.proposal").require(proposal_cids.contains(&proposal_cid), format!("pending proposal with cid {proposal_cid} not found within proposals {pending_proposals:?}"));
 
                 pending_proposal_count += 1;
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating pending proposals");
+            acc.with_code("market.pending_proposals")
+                .require_no_error(ret, "error iterating pending proposals");
         }
-        Err(e) => acc.add(format!("error loading pending proposals: {e}")),
+        Err(e) => acc
+            .with_code("market.pending_proposals")
+            .add(format!("error loading pending proposals: {e}")),
     };
 
     // escrow table and locked table
@@ -254,38 +269,43 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
 
                 // every entry in locked table should have a corresponding entry in escrow table that is at least as high
                 let escrow_amount = &escrow_table.get(&address)?;
-                acc.require(escrow_amount >= locked_amount, format!("locked funds for {address}, {locked_amount}, greater than escrow amount, {escrow_amount}"));
+                acc.with_code("market.locked.exceeds_escrow").require(escrow_amount >= locked_amount, format!("locked funds for {address}, {locked_amount}, greater than escrow amount, {escrow_amount}"));
 
                 lock_table_count += 1;
 
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating locked table");
+            acc.with_code("market.locked_table")
+                .require_no_error(ret, "error iterating locked table");
 
             // lockTable total should be sum of client and provider locked plus client storage fee
             let expected_lock_total = &state.total_provider_locked_collateral
                 + &state.total_client_locked_collateral
                 + &state.total_client_storage_fee;
-            acc.require(locked_total == expected_lock_total, format!("locked total, {locked_total}, does not sum to provider locked, {}, client locked, {}, and client storage fee, {}", state.total_provider_locked_collateral, state.total_client_locked_collateral, state.total_client_storage_fee));
+            acc.with_code("market.locked.total").require(locked_total == expected_lock_total, format!("locked total, {locked_total}, does not sum to provider locked, {}, client locked, {}, and client storage fee, {}", state.total_provider_locked_collateral, state.total_client_locked_collateral, state.total_client_storage_fee));
 
             // assert escrow <= actor balance
             // lock_table item <= escrow item and escrow_total <= balance implies lock_table total <= balance
             match escrow_table.total() {
                 Ok(escrow_total) => {
-                    acc.require(
+                    acc.with_code("market.escrow.exceeds_balance").require(
                         &escrow_total <= balance,
                         format!(
                             "escrow total, {escrow_total}, greater than actor balance, {balance}"
                         ),
                     );
-                    acc.require(escrow_total >= total_proposal_collateral, format!("escrow total, {escrow_total}, less than sum of proposal collateral, {total_proposal_collateral}"));
+                    acc.with_code("market.escrow.collateral").require(escrow_total >= total_proposal_collateral, format!("escrow total, {escrow_total}, less than sum of proposal collateral, {total_proposal_collateral}"));
                 }
-                Err(e) => acc.add(format!("error calculating escrow total: {e}")),
+                Err(e) => acc
+                    .with_code("market.escrow_table")
+                    .add(format!("error calculating escrow total: {e}")),
             }
         }
         (escrow_table, lock_table) => {
-            acc.require_no_error(escrow_table, "error loading escrow table");
-            acc.require_no_error(lock_table, "error loading locked table");
+            acc.with_code("market.escrow_table")
+                .require_no_error(escrow_table, "error loading escrow table");
+            acc.with_code("market.locked_table")
+                .require_no_error(lock_table, "error loading locked table");
         }
     };
 
@@ -300,18 +320,19 @@ pub fn check_state_invariants<BS: Blockstore + Debug>(
                 deal_op_epoch_count += 1;
 
                 deal_ops.for_each(epoch, |deal_id| {
-                    acc.require(proposal_stats.contains_key(&deal_id), format!("deal op found for deal id {deal_id} with missing proposal at epoch {epoch}"));
+                    let acc = acc.with_context("deal", deal_id).with_context("epoch", epoch);
+                    acc.with_code("market.deal_op.proposal").require(proposal_stats.contains_key(&deal_id), format!("deal op found for deal id {deal_id} with missing proposal at epoch {epoch}"));
                     expected_deal_ops.remove(&deal_id);
                     deal_op_count += 1;
                     Ok(())
                 }).map_err(|e| anyhow::anyhow!("error iterating deal ops for epoch {}: {}", epoch, e))
             });
-            acc.require_no_error(ret, "error iterating all deal ops");
+            acc.with_code("market.deal_ops").require_no_error(ret, "error iterating all deal ops");
         }
-        Err(e) => acc.add(format!("error loading deal ops: {e}")),
+        Err(e) => acc.with_code("market.deal_ops").add(format!("error loading deal ops: {e}")),
     };
 
-    acc.require(
+    acc.with_code("market.deal_op.missing").require(
         expected_deal_ops.is_empty(),
         format!("missing deal ops for proposals: {expected_deal_ops:?}"),
     );
@@ -803,7 +803,12 @@ pub fn assert_deal_deleted(rt: &mut MockRuntime, deal_id: DealID, p: DealProposa
     assert!(!pending_deals.contains_key(&BytesKey(p_cid.to_bytes())).unwrap());
 }
 
-pub fn assert_deal_failure<F>(add_funds: bool, post_setup: F, exit_code: ExitCode, sig_valid: bool)
+pub fn assert_deal_failure<F>(
+    add_funds: bool,
+    post_setup: F,
+    exit_code: ExitCode,
+    sig_valid: bool,
+) -> ActorError
 where
     F: FnOnce(&mut MockRuntime, &mut DealProposal),
 {
@@ -858,17 +863,16 @@ where
         deals: vec![ClientDealProposal { proposal: deal_proposal, client_signature: sig }],
     };
 
-    assert_eq!(
-        exit_code,
-        rt.call::<MarketActor>(
+    let err = rt
+        .call::<MarketActor>(
             Method::PublishStorageDeals as u64,
             IpldBlock::serialize_cbor(&params).unwrap(),
         )
-        .unwrap_err()
-        .exit_code()
-    );
+        .unwrap_err();
+    assert_eq!(exit_code, err.exit_code());
     rt.verify();
     check_state(&rt);
+    err
 }
 
 pub fn process_epoch(start_epoch: ChainEpoch, deal_id: DealID) -> ChainEpoch {
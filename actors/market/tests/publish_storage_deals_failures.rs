@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0, MIT
 
 use fil_actor_market::policy::deal_provider_collateral_bounds;
+use fil_actor_market::reason::{self, DealRejection};
 use fil_actor_market::{
     ext, Actor as MarketActor, ClientDealProposal, DealProposal, MarketNotifyDealParams, Method,
     PublishStorageDealsParams, PublishStorageDealsReturn, State, MARKET_NOTIFY_DEAL_METHOD,
@@ -9,6 +10,7 @@ use fil_actor_market::{
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::ActorError;
 use fvm_ipld_encoding::RawBytes;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::BigInt;
@@ -36,7 +38,8 @@ fn deal_end_after_deal_start() {
         d.start_epoch = 10;
         d.end_epoch = 9;
     };
-    assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, true);
+    let err = assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, true);
+    assert_deals_rejected(&err, reason::DEAL_END_BEFORE_START);
 }
 
 #[test]
@@ -44,7 +47,8 @@ fn current_epoch_greater_than_start_epoch() {
     let f = |rt: &mut MockRuntime, d: &mut DealProposal| {
         d.start_epoch = rt.epoch - 1;
     };
-    assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, true);
+    let err = assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, true);
+    assert_deals_rejected(&err, reason::DEAL_START_ELAPSED);
 }
 
 #[test]
@@ -130,7 +134,8 @@ fn client_does_not_have_enough_balance_for_collateral() {
         );
         add_provider_funds(rt, d.provider_collateral.clone(), &MinerAddresses::default());
     };
-    assert_deal_failure(false, f, ExitCode::USR_ILLEGAL_ARGUMENT, true);
+    let err = assert_deal_failure(false, f, ExitCode::USR_ILLEGAL_ARGUMENT, true);
+    assert_deals_rejected(&err, reason::INSUFFICIENT_CLIENT_FUNDS);
 }
 
 #[test]
@@ -165,7 +170,8 @@ fn unable_to_resolve_client_address() {
 #[test]
 fn signature_is_invalid() {
     let f = |_rt: &mut MockRuntime, _d: &mut DealProposal| {};
-    assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, false);
+    let err = assert_deal_failure(true, f, ExitCode::USR_ILLEGAL_ARGUMENT, false);
+    assert_deals_rejected(&err, reason::INVALID_CLIENT_SIGNATURE);
 }
 
 #[test]
@@ -560,3 +566,10 @@ fn fails_if_notify_deal_fails() {
     rt.verify();
     check_state(&rt);
 }
+
+/// Asserts that a batch of a single deal was rejected for the given reason.
+fn assert_deals_rejected(err: &ActorError, code: u64) {
+    let reason = err.reason::<Vec<DealRejection>>().unwrap();
+    assert_eq!(reason::DEALS_REJECTED, reason.code);
+    assert_eq!(vec![DealRejection { index: 0, code }], reason.detail);
+}
@@ -0,0 +1,130 @@
+//! Applies random sequences of operations to a deadline, checking its invariants after each.
+
+#![no_main]
+
+use std::collections::BTreeMap;
+
+use cid::multihash::Code;
+use fil_actor_miner::testing::check_deadline_state_invariants;
+use fil_actor_miner::{Deadline, Partition, PartitionSectorMap, PoStPartition};
+use fil_actor_miner_fuzz::{Harness, Op, PARTITION_SIZE, QUANT, SECTOR_SIZE};
+use fil_actors_runtime::MessageAccumulator;
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::CborStore;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut h = Harness::new();
+    let mut deadline = Deadline::new(&h.store).unwrap();
+    for op in ops {
+        // An error aborts the message, discarding any changes to the deadline.
+        let saved = h.store.put_cbor(&deadline, Code::Blake2b256).unwrap();
+        if apply(&mut h, &mut deadline, op).is_err() {
+            deadline = h.store.get_cbor(&saved).unwrap().unwrap();
+        }
+
+        let acc = MessageAccumulator::default();
+        check_deadline_state_invariants(
+            &deadline,
+            &h.store,
+            QUANT,
+            SECTOR_SIZE,
+            &h.sectors_map(),
+            &acc,
+        );
+        assert!(acc.is_empty(), "{}", acc.messages().join("\n"));
+    }
+});
+
+fn apply(h: &mut Harness, deadline: &mut Deadline, op: Op) -> anyhow::Result<()> {
+    match op {
+        Op::Add { expirations, proven } => {
+            let sectors = h.new_sectors(&expirations);
+            deadline.add_sectors(&h.store, PARTITION_SIZE, proven, &sectors, SECTOR_SIZE, QUANT)?;
+            h.store_sectors(sectors);
+        }
+        Op::Fault { sectors } => {
+            let mut partitions = to_sector_map(by_partition(h, deadline, &h.select(&sectors))?)?;
+            deadline.record_faults(
+                &h.store,
+                &h.load_sectors(),
+                SECTOR_SIZE,
+                QUANT,
+                h.fault_expiration(),
+                &mut partitions,
+            )?;
+        }
+        Op::DeclareRecovered { sectors } => {
+            let mut partitions = to_sector_map(by_partition(h, deadline, &h.select(&sectors))?)?;
+            deadline.declare_faults_recovered(
+                &h.store,
+                &h.load_sectors(),
+                SECTOR_SIZE,
+                &mut partitions,
+            )?;
+        }
+        Op::Prove { skipped } => {
+            let mut skipped = by_partition(h, deadline, &h.select(&skipped))?;
+            let mut posts: Vec<PoStPartition> = (0..deadline.partitions_amt(&h.store)?.count())
+                .map(|index| PoStPartition {
+                    index,
+                    skipped: skipped.remove(&index).unwrap_or_default(),
+                })
+                .collect();
+            deadline.record_proven_sectors(
+                &h.store,
+                &h.load_sectors(),
+                SECTOR_SIZE,
+                QUANT,
+                h.fault_expiration(),
+                &mut posts,
+            )?;
+            deadline.process_deadline_end(&h.store, QUANT, h.fault_expiration(), h.sectors_root)?;
+        }
+        Op::Terminate { sectors } => {
+            let mut partitions = to_sector_map(by_partition(h, deadline, &h.select(&sectors))?)?;
+            deadline.terminate_sectors(
+                &h.policy,
+                &h.store,
+                &h.load_sectors(),
+                h.epoch,
+                &mut partitions,
+                SECTOR_SIZE,
+                QUANT,
+            )?;
+        }
+        Op::Expire { epochs } => {
+            h.epoch += 1 + epochs as i64;
+            deadline.pop_expired_sectors(&h.store, h.epoch, QUANT)?;
+        }
+        Op::PopEarlyTerminations { max_partitions, max_sectors } => {
+            deadline.pop_early_terminations(&h.store, max_partitions as u64, max_sectors as u64)?;
+        }
+    }
+    Ok(())
+}
+
+/// Groups the selected sectors by the index of the partition holding them.
+fn by_partition(
+    h: &Harness,
+    deadline: &Deadline,
+    selected: &BitField,
+) -> anyhow::Result<BTreeMap<u64, BitField>> {
+    let mut partitions = BTreeMap::new();
+    deadline.partitions_amt(&h.store)?.for_each(|index, partition: &Partition| {
+        let sectors = &partition.sectors & selected;
+        if !sectors.is_empty() {
+            partitions.insert(index, sectors);
+        }
+        Ok(())
+    })?;
+    Ok(partitions)
+}
+
+fn to_sector_map(partitions: BTreeMap<u64, BitField>) -> anyhow::Result<PartitionSectorMap> {
+    let mut map = PartitionSectorMap::default();
+    for (index, sectors) in partitions {
+        map.add(index, sectors)?;
+    }
+    Ok(map)
+}
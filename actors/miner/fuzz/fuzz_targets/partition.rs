@@ -0,0 +1,92 @@
+//! Applies random sequences of operations to a partition, checking its invariants after each.
+
+#![no_main]
+
+use fil_actor_miner::testing::PartitionStateSummary;
+use fil_actor_miner::Partition;
+use fil_actor_miner_fuzz::{Harness, Op, QUANT, SECTOR_SIZE};
+use fil_actors_runtime::MessageAccumulator;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|ops: Vec<Op>| {
+    let mut h = Harness::new();
+    let mut partition = Partition::new(&h.store).unwrap();
+    for op in ops {
+        // An error aborts the message, discarding any changes to the partition.
+        let saved = partition.clone();
+        if apply(&mut h, &mut partition, op).is_err() {
+            partition = saved;
+        }
+
+        let acc = MessageAccumulator::default();
+        PartitionStateSummary::check_partition_state_invariants(
+            &partition,
+            &h.store,
+            QUANT,
+            SECTOR_SIZE,
+            &h.sectors_map(),
+            &acc,
+        );
+        assert!(acc.is_empty(), "{}", acc.messages().join("\n"));
+    }
+});
+
+fn apply(h: &mut Harness, partition: &mut Partition, op: Op) -> anyhow::Result<()> {
+    match op {
+        Op::Add { expirations, proven } => {
+            let sectors = h.new_sectors(&expirations);
+            partition.add_sectors(&h.store, proven, &sectors, SECTOR_SIZE, QUANT)?;
+            h.store_sectors(sectors);
+        }
+        Op::Fault { sectors } => {
+            partition.record_faults(
+                &h.store,
+                &h.load_sectors(),
+                &h.select(&sectors),
+                h.fault_expiration(),
+                SECTOR_SIZE,
+                QUANT,
+            )?;
+        }
+        Op::DeclareRecovered { sectors } => {
+            partition.declare_faults_recovered(
+                &h.load_sectors(),
+                SECTOR_SIZE,
+                &h.select(&sectors),
+            )?;
+        }
+        Op::Prove { skipped } => {
+            // As when the partition is proven in a Window PoSt.
+            let sectors = h.load_sectors();
+            partition.record_skipped_faults(
+                &h.store,
+                &sectors,
+                SECTOR_SIZE,
+                QUANT,
+                h.fault_expiration(),
+                &h.select(&skipped),
+            )?;
+            partition.recover_faults(&h.store, &sectors, SECTOR_SIZE, QUANT)?;
+            partition.activate_unproven();
+        }
+        Op::Terminate { sectors } => {
+            partition.terminate_sectors(
+                &h.policy,
+                &h.store,
+                &h.load_sectors(),
+                h.epoch,
+                &h.select(&sectors),
+                SECTOR_SIZE,
+                QUANT,
+            )?;
+        }
+        Op::Expire { epochs } => {
+            h.epoch += 1 + epochs as i64;
+            partition.pop_expired_sectors(&h.store, h.epoch, QUANT)?;
+        }
+        Op::PopEarlyTerminations { max_sectors, .. } => {
+            partition.pop_early_terminations(&h.store, max_sectors as u64)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Operations and setup shared by the fuzz targets for miner deadline and partition state.
+
+use cid::Cid;
+use fil_actor_miner::testing::SectorsMap;
+use fil_actor_miner::{SectorOnChainInfo, Sectors, SECTORS_AMT_BITWIDTH};
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::test_utils::make_sealed_cid;
+use fil_actors_runtime::Array;
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_blockstore::MemoryBlockstore;
+use fvm_shared::clock::{ChainEpoch, QuantSpec};
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::SectorSize;
+use libfuzzer_sys::arbitrary::{self, Arbitrary};
+
+pub const SECTOR_SIZE: SectorSize = SectorSize::_32GiB;
+pub const QUANT: QuantSpec = QuantSpec { unit: 4, offset: 1 };
+pub const PARTITION_SIZE: u64 = 4;
+/// The most sectors added by a single run, to keep runs fast.
+pub const MAX_SECTORS: usize = 512;
+
+/// An operation on a deadline or partition. Sectors are selected by their index among the
+/// sectors added so far, modulo the number added.
+#[derive(Arbitrary, Debug)]
+pub enum Op {
+    /// Adds new sectors, expiring the given numbers of epochs after the current one.
+    Add { expirations: Vec<u8>, proven: bool },
+    /// Declares the selected sectors faulty.
+    Fault { sectors: Vec<u16> },
+    /// Declares the selected sectors recovered.
+    DeclareRecovered { sectors: Vec<u16> },
+    /// Proves the sectors, except those selected to be skipped, recovering declared recoveries.
+    Prove { skipped: Vec<u16> },
+    /// Terminates the selected sectors.
+    Terminate { sectors: Vec<u16> },
+    /// Advances the epoch, then pops the sectors expiring by then.
+    Expire { epochs: u8 },
+    /// Pops early terminations, up to the given numbers of partitions and sectors.
+    PopEarlyTerminations { max_partitions: u8, max_sectors: u8 },
+}
+
+/// The sectors added so far, and the current epoch.
+pub struct Harness {
+    pub policy: Policy,
+    pub store: MemoryBlockstore,
+    pub sectors: Vec<SectorOnChainInfo>,
+    pub sectors_root: Cid,
+    pub epoch: ChainEpoch,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        let store = MemoryBlockstore::new();
+        let sectors_root =
+            Array::<SectorOnChainInfo, _>::new_with_bit_width(&store, SECTORS_AMT_BITWIDTH)
+                .flush()
+                .unwrap();
+        Self { policy: Policy::default(), store, sectors: Vec::new(), sectors_root, epoch: 0 }
+    }
+
+    /// Makes the next sectors, expiring after the given numbers of epochs, up to MAX_SECTORS in
+    /// total. The sectors must be stored once added.
+    pub fn new_sectors(&self, expirations: &[u8]) -> Vec<SectorOnChainInfo> {
+        let first = self.sectors.len();
+        expirations
+            .iter()
+            .take(MAX_SECTORS - first)
+            .enumerate()
+            .map(|(i, &expiration)| {
+                let sector_number = (first + i) as u64;
+                SectorOnChainInfo {
+                    sector_number,
+                    activation: self.epoch,
+                    expiration: self.epoch + 1 + expiration as ChainEpoch,
+                    initial_pledge: TokenAmount::from_atto(1000 + sector_number),
+                    sealed_cid: make_sealed_cid(format!("commR-{sector_number}").as_bytes()),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    /// Stores newly added sectors.
+    pub fn store_sectors(&mut self, sectors: Vec<SectorOnChainInfo>) {
+        let mut array = Sectors::load(&self.store, &self.sectors_root).unwrap();
+        array.store(sectors.clone()).unwrap();
+        self.sectors_root = array.amt.flush().unwrap();
+        self.sectors.extend(sectors);
+    }
+
+    pub fn load_sectors(&self) -> Sectors<'_, MemoryBlockstore> {
+        Sectors::load(&self.store, &self.sectors_root).unwrap()
+    }
+
+    pub fn sectors_map(&self) -> SectorsMap {
+        self.sectors.iter().map(|s| (s.sector_number, s.clone())).collect()
+    }
+
+    /// Selects the sectors with the given indices, modulo the number of sectors added.
+    pub fn select(&self, picks: &[u16]) -> BitField {
+        if self.sectors.is_empty() {
+            return BitField::new();
+        }
+        let count = self.sectors.len() as u64;
+        BitField::try_from_bits(picks.iter().map(|&pick| pick as u64 % count)).unwrap()
+    }
+
+    /// The epoch at which faults declared now would expire.
+    pub fn fault_expiration(&self) -> ChainEpoch {
+        self.epoch + self.policy.fault_max_age
+    }
+}
+
+impl Default for Harness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
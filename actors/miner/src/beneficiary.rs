@@ -1,11 +1,15 @@
+use fvm_ipld_encoding::repr::*;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
 
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
-use num_traits::Zero;
+use num_traits::{Signed, Zero};
 use std::ops::Sub;
 
+/// Maximum number of beneficiaries that may share the miner's withdrawals.
+pub const MAX_BENEFICIARIES: usize = 8;
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
 pub struct BeneficiaryTerm {
     /// The total amount the current beneficiary can withdraw. Monotonic, but reset when beneficiary changes.
@@ -70,3 +74,105 @@ impl PendingBeneficiaryChange {
         }
     }
 }
+
+/// Order in which a withdrawal is distributed across the beneficiaries of a split.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize_repr, Deserialize_repr)]
+#[repr(u8)]
+pub enum BeneficiaryPayout {
+    /// Beneficiaries are paid in list order, each up to its remaining quota.
+    Priority = 0,
+    /// Beneficiaries are paid in proportion to their remaining quota.
+    ProRata = 1,
+}
+
+/// A beneficiary's share of a proposed split.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct BeneficiaryShare {
+    pub beneficiary: Address,
+    pub quota: TokenAmount,
+    pub expiration: ChainEpoch,
+}
+
+/// A set of beneficiaries sharing the miner's withdrawals in place of a single beneficiary.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct BeneficiarySplit {
+    pub payout: BeneficiaryPayout,
+    pub beneficiaries: Vec<SplitBeneficiary>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct SplitBeneficiary {
+    pub beneficiary: Address,
+    pub term: BeneficiaryTerm,
+}
+
+impl BeneficiarySplit {
+    /// Whether the split includes the address.
+    pub fn contains(&self, addr: &Address) -> bool {
+        self.beneficiaries.iter().any(|b| &b.beneficiary == addr)
+    }
+
+    /// Get the total amount that the beneficiaries may still withdraw.
+    pub fn available(&self, cur: ChainEpoch) -> TokenAmount {
+        self.beneficiaries.iter().map(|b| b.term.available(cur)).sum()
+    }
+
+    /// Beneficiaries that still have quota available to withdraw.
+    pub fn effective(&self, cur: ChainEpoch) -> impl Iterator<Item = &SplitBeneficiary> {
+        self.beneficiaries.iter().filter(move |b| b.term.available(cur).is_positive())
+    }
+
+    /// Distributes an amount, which must not exceed the available total, across the beneficiaries
+    /// according to the payout order, and records it against their quotas.
+    /// Returns the non-zero amount owed to each beneficiary, in list order.
+    pub fn distribute(
+        &mut self,
+        amount: &TokenAmount,
+        cur: ChainEpoch,
+    ) -> Vec<(Address, TokenAmount)> {
+        let available: Vec<TokenAmount> =
+            self.beneficiaries.iter().map(|b| b.term.available(cur)).collect();
+        let mut shares = vec![TokenAmount::zero(); available.len()];
+        let mut remaining = amount.clone();
+
+        if self.payout == BeneficiaryPayout::ProRata {
+            let total: TokenAmount = available.iter().sum();
+            if total.is_positive() {
+                for (share, avail) in shares.iter_mut().zip(&available) {
+                    *share = TokenAmount::from_atto(amount.atto() * avail.atto() / total.atto());
+                    remaining -= &*share;
+                }
+            }
+        }
+
+        // Priority order, and any remainder left by rounding down pro-rata shares.
+        for (share, avail) in shares.iter_mut().zip(&available) {
+            if !remaining.is_positive() {
+                break;
+            }
+            let extra = std::cmp::min(avail - &*share, remaining.clone());
+            *share += &extra;
+            remaining -= extra;
+        }
+
+        self.beneficiaries
+            .iter_mut()
+            .zip(shares)
+            .filter(|(_, share)| share.is_positive())
+            .map(|(b, share)| {
+                b.term.used_quota += &share;
+                (b.beneficiary, share)
+            })
+            .collect()
+    }
+}
+
+/// A proposed change to the beneficiary split, awaiting approval from the nominated
+/// beneficiaries and those of the current split who still have quota available.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct PendingBeneficiarySplit {
+    pub payout: BeneficiaryPayout,
+    pub beneficiaries: Vec<BeneficiaryShare>,
+    /// Addresses whose approval is still outstanding.
+    pub pending_approvals: Vec<Address>,
+}
@@ -737,6 +737,133 @@ impl Deadline {
         Ok((live, dead, removed_power))
     }
 
+    /// MovePartitions removes the specified partitions from this deadline, shifting the
+    /// remaining ones to the left, and appends them to the destination deadline.
+    /// Faulty, recovering, unproven and terminated sectors move with their partition,
+    /// and each partition's expiration queue is re-quantized to the destination deadline.
+    ///
+    /// Returns an error if this deadline has early terminations pending, or if the destination
+    /// would exceed the maximum number of partitions per deadline.
+    #[allow(clippy::too_many_arguments)]
+    pub fn move_partitions<BS: Blockstore>(
+        &mut self,
+        policy: &Policy,
+        store: &BS,
+        dest: &mut Deadline,
+        to_move: &BitField,
+        sectors: &Sectors<'_, BS>,
+        sector_size: SectorSize,
+        orig_quant: QuantSpec,
+        dest_quant: QuantSpec,
+    ) -> anyhow::Result<()> {
+        let old_partitions =
+            self.partitions_amt(store).map_err(|e| e.downcast_wrap("failed to load partitions"))?;
+
+        let partition_count = old_partitions.count();
+        let to_move_set: BTreeSet<_> = to_move
+            .bounded_iter(partition_count)
+            .ok_or_else(|| actor_error!(illegal_argument; "partitions to move exceeds total"))?
+            .collect();
+
+        if let Some(&max_partition) = to_move_set.iter().max() {
+            if max_partition >= partition_count {
+                return Err(
+                    actor_error!(illegal_argument; "partition index {} out of range [0, {})", max_partition, partition_count).into()
+                );
+            }
+        } else {
+            // Nothing to do.
+            return Ok(());
+        }
+
+        // Pending early terminations are tracked by partition index, and are processed by
+        // the deadline that holds them.
+        if !self.early_terminations.is_empty() {
+            return Err(
+                actor_error!(illegal_argument; "cannot move partitions from deadline with early terminations").into(),
+            );
+        }
+
+        let mut dest_partitions = dest.partitions_amt(store)?;
+        let dest_partition_count = dest_partitions.count() + to_move_set.len() as u64;
+        if dest_partition_count > policy.max_partitions_per_deadline {
+            return Err(actor_error!(
+                illegal_argument,
+                "destination deadline would have {} partitions, limit {}",
+                dest_partition_count,
+                policy.max_partitions_per_deadline
+            )
+            .into());
+        }
+
+        let mut kept_partitions =
+            Array::<Partition, BS>::new_with_bit_width(store, DEADLINE_PARTITIONS_AMT_BITWIDTH);
+        let mut moved_partitions = Vec::<Partition>::with_capacity(to_move_set.len());
+
+        old_partitions
+            .for_each(|partition_idx, partition| {
+                if to_move_set.contains(&partition_idx) {
+                    moved_partitions.push(partition.clone());
+                } else {
+                    kept_partitions.set(kept_partitions.count(), partition.clone())?;
+                }
+                Ok(())
+            })
+            .map_err(|e| e.downcast_wrap("while moving partitions"))?;
+
+        self.partitions = kept_partitions
+            .flush()
+            .map_err(|e| e.downcast_wrap("failed to persist remaining partitions"))?;
+
+        let mut expiration_epochs = BitFieldQueue::new(store, &self.expirations_epochs, orig_quant)
+            .map_err(|e| e.downcast_wrap("failed to load expiration queue"))?;
+        expiration_epochs.cut(to_move).map_err(|e| {
+            e.downcast_wrap("failed cut moved partitions from deadline expiration queue")
+        })?;
+        self.expirations_epochs = expiration_epochs
+            .amt
+            .flush()
+            .map_err(|e| e.downcast_wrap("failed persist deadline expiration queue"))?;
+
+        let mut dest_expirations = Vec::<(ChainEpoch, u64)>::new();
+        for mut partition in moved_partitions {
+            let dest_idx = dest_partitions.count();
+            let epochs = partition
+                .requantize_expirations(store, sectors, sector_size, orig_quant, dest_quant)
+                .map_err(|e| {
+                    e.downcast_wrap(format!("failed to reschedule partition {}", dest_idx))
+                })?;
+            dest_expirations.extend(epochs.into_iter().map(|epoch| (epoch, dest_idx)));
+
+            let live_sectors = partition.live_sectors().len();
+            let total_sectors = partition.sectors.len();
+            self.live_sectors -= live_sectors;
+            self.total_sectors -= total_sectors;
+            self.faulty_power -= &partition.faulty_power;
+            dest.live_sectors += live_sectors;
+            dest.total_sectors += total_sectors;
+            dest.faulty_power += &partition.faulty_power;
+
+            dest_partitions.set(dest_idx, partition)?;
+        }
+        dest.partitions = dest_partitions
+            .flush()
+            .map_err(|e| e.downcast_wrap("failed to persist destination partitions"))?;
+
+        let mut dest_expiration_epochs =
+            BitFieldQueue::new(store, &dest.expirations_epochs, dest_quant)
+                .map_err(|e| e.downcast_wrap("failed to load destination expiration queue"))?;
+        dest_expiration_epochs
+            .add_many_to_queue_values(dest_expirations.iter().copied())
+            .map_err(|e| e.downcast_wrap("failed to add expirations for moved partitions"))?;
+        dest.expirations_epochs = dest_expiration_epochs
+            .amt
+            .flush()
+            .map_err(|e| e.downcast_wrap("failed persist destination expiration queue"))?;
+
+        Ok(())
+    }
+
     pub fn record_faults<BS: Blockstore>(
         &mut self,
         store: &BS,
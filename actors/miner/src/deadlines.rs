@@ -128,6 +128,37 @@ pub fn deadline_available_for_compaction(
         )
 }
 
+// Returns true if partitions may be moved from the origin to the destination deadline
+// in the current epoch. Partitions may be moved when:
+//
+// 1. The origin deadline may be compacted.
+// 2. The destination deadline is mutable.
+// 3. The destination deadline's next challenge window opens before the origin's, so that
+//    moved sectors are never left unproven for longer than a proving period.
+pub fn deadline_available_for_move(
+    policy: &Policy,
+    proving_period_start: ChainEpoch,
+    orig_deadline_idx: u64,
+    dest_deadline_idx: u64,
+    current_epoch: ChainEpoch,
+) -> bool {
+    if !deadline_available_for_compaction(
+        policy,
+        proving_period_start,
+        orig_deadline_idx,
+        current_epoch,
+    ) || !deadline_is_mutable(policy, proving_period_start, dest_deadline_idx, current_epoch)
+    {
+        return false;
+    }
+
+    let orig = new_deadline_info(policy, proving_period_start, orig_deadline_idx, current_epoch)
+        .next_not_elapsed();
+    let dest = new_deadline_info(policy, proving_period_start, dest_deadline_idx, current_epoch)
+        .next_not_elapsed();
+    dest.open < orig.open
+}
+
 // Determine current period start and deadline index directly from current epoch and
 // the offset implied by the proving period. This works correctly even for the state
 // of a miner actor without an active deadline cron
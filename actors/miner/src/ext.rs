@@ -24,6 +24,10 @@ pub mod market {
     pub const ACTIVATE_DEALS_METHOD: u64 = 6;
     pub const ON_MINER_SECTORS_TERMINATE_METHOD: u64 = 7;
     pub const COMPUTE_DATA_COMMITMENT_METHOD: u64 = 8;
+    pub const GET_DEAL_DATA_COMMITMENT_METHOD: u64 =
+        frc42_dispatch::method_hash!("GetDealDataCommitment");
+    pub const GET_DEAL_VERIFIED_METHOD: u64 = frc42_dispatch::method_hash!("GetDealVerified");
+    pub const GET_DEAL_TERM_METHOD: u64 = frc42_dispatch::method_hash!("GetDealTerm");
 
     #[derive(Serialize_tuple, Deserialize_tuple)]
     pub struct SectorDeals {
@@ -81,6 +85,30 @@ pub mod market {
         pub commds: Vec<Cid>,
     }
 
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    #[serde(transparent)]
+    pub struct DealQueryParams {
+        pub id: DealID,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct GetDealDataCommitmentReturn {
+        pub data: Cid,
+        pub size: PaddedPieceSize,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    #[serde(transparent)]
+    pub struct GetDealVerifiedReturn {
+        pub verified: bool,
+    }
+
+    #[derive(Serialize_tuple, Deserialize_tuple)]
+    pub struct GetDealTermReturn {
+        pub start: ChainEpoch,
+        pub duration: ChainEpoch,
+    }
+
     #[derive(Serialize_tuple, Deserialize_tuple)]
     pub struct OnMinerSectorsTerminateParams {
         pub epoch: ChainEpoch,
@@ -159,7 +187,9 @@ pub mod verifreg {
     use super::*;
 
     pub const GET_CLAIMS_METHOD: u64 = 10;
+    pub const GET_TRANSFERRED_CLAIMS_METHOD: u64 = 16;
     pub const CLAIM_ALLOCATIONS_METHOD: u64 = 9;
+    pub const REMOVE_SECTOR_CLAIMS_METHOD: u64 = 19;
 
     pub type ClaimID = u64;
     pub type AllocationID = u64;
@@ -216,4 +246,22 @@ pub mod verifreg {
         #[serde(with = "bigint_ser")]
         pub claimed_space: BigInt,
     }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct RemoveSectorClaimsParams {
+        pub sector: SectorNumber,
+        pub pieces: Vec<DroppedPiece>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct DroppedPiece {
+        pub data: Cid,
+        pub size: PaddedPieceSize,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct RemoveSectorClaimsReturn {
+        pub removed: Vec<ClaimID>,
+        pub results: BatchReturn,
+    }
 }
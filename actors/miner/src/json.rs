@@ -0,0 +1,267 @@
+//! JSON representations of the miner actor's method parameters and return values.
+
+use fil_actors_runtime::json::{JsonCodec, JsonError, MethodCodec, Value};
+use fil_actors_runtime::{json_struct, method_codecs};
+use fvm_ipld_bitfield::BitField;
+use fvm_shared::address::Address;
+
+use crate::{
+    ActiveBeneficiary, ApplyRewardParams, BeneficiaryPayout, BeneficiaryShare, BeneficiarySplit,
+    BeneficiaryTerm, ChangeBeneficiariesParams, ChangeBeneficiaryParams, ChangeMultiaddrsParams,
+    ChangePeerIDParams, ChangeWorkerAddressParams, CheckSectorProvenParams,
+    ClaimsTransferredParams, CompactCommD, CompactPartitionsParams, CompactSectorNumbersParams,
+    ConfirmSectorProofsParams, DataActivationNotification, DeclareFaultsParams,
+    DeclareFaultsRecoveredParams, DeferredCronEventParams, DisputeWindowedPoStParams,
+    DropSectorPiecesParams, ExpirationExtension, ExpirationExtension2, ExtendPreCommitsParams,
+    ExtendSectorExpiration2Params, ExtendSectorExpirationParams, FaultDeclaration,
+    GetAvailableBalanceReturn, GetBeneficiariesReturn, GetBeneficiaryReturn,
+    GetControlAddressesReturn, GetMultiaddrsReturn, GetOwnerReturn, GetPeerIDReturn,
+    GetSectorSizeReturn, GetVestingFundsReturn, IsControllingAddressParam,
+    IsControllingAddressReturn, Method, MinerConstructorParams, MovePartitionsParams,
+    PendingBeneficiaryChange, PendingBeneficiarySplit, PieceActivationManifest, PoStPartition,
+    PreCommitExtension, PreCommitSectorBatchParams, PreCommitSectorBatchParams2,
+    PreCommitSectorParams, ProveCommitAggregateParams, ProveCommitAggregateParams2,
+    ProveCommitSectorParams, ProveReplicaUpdatesParams, ProveReplicaUpdatesParams2,
+    ProveReplicaUpdatesParams3, RecoveryDeclaration, ReplicaUpdate, ReplicaUpdate2, ReplicaUpdate3,
+    ReportConsensusFaultParams, SectorActivationManifest, SectorClaim, SectorPreCommitInfo,
+    SplitBeneficiary, SubmitWindowedPoStParams, TerminateSectorsParams, TerminateSectorsReturn,
+    TerminationDeclaration, TransferredClaim, WithdrawBalanceParams, WithdrawBalanceReturn,
+};
+
+impl JsonCodec for CompactCommD {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        JsonCodec::from_json(value).map(CompactCommD)
+    }
+}
+
+/// Payout orders are `"priority"` or `"pro_rata"`.
+impl JsonCodec for BeneficiaryPayout {
+    fn to_json(&self) -> Value {
+        match self {
+            BeneficiaryPayout::Priority => "priority",
+            BeneficiaryPayout::ProRata => "pro_rata",
+        }
+        .to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match String::from_json(value)?.as_str() {
+            "priority" => Ok(BeneficiaryPayout::Priority),
+            "pro_rata" => Ok(BeneficiaryPayout::ProRata),
+            other => Err(JsonError(format!("unknown payout order {}", other))),
+        }
+    }
+}
+
+json_struct! {
+    MinerConstructorParams {
+        owner,
+        worker,
+        control_addresses,
+        window_post_proof_type,
+        peer_id,
+        multi_addresses,
+    }
+    GetControlAddressesReturn { owner, worker, control_addresses }
+    ChangeWorkerAddressParams { new_worker, new_control_addresses }
+    ChangePeerIDParams { new_id }
+    ChangeMultiaddrsParams { new_multi_addrs }
+    ConfirmSectorProofsParams {
+        sectors,
+        reward_smoothed,
+        reward_baseline_power,
+        quality_adj_power_smoothed,
+    }
+    DeferredCronEventParams { event_payload, reward_smoothed, quality_adj_power_smoothed }
+    PoStPartition { index, skipped }
+    SubmitWindowedPoStParams { deadline, partitions, proofs, chain_commit_epoch, chain_commit_rand }
+    ProveCommitSectorParams { sector_number, proof }
+    CheckSectorProvenParams { sector_number }
+    ExtendSectorExpirationParams { extensions }
+    ExpirationExtension { deadline, partition, sectors, new_expiration }
+    ExtendSectorExpiration2Params { extensions }
+    SectorClaim { sector_number, maintain_claims, drop_claims }
+    ExpirationExtension2 { deadline, partition, sectors, sectors_with_claims, new_expiration }
+    TerminateSectorsParams { terminations }
+    TerminationDeclaration { deadline, partition, sectors }
+    TerminateSectorsReturn { done }
+    DeclareFaultsParams { faults }
+    FaultDeclaration { deadline, partition, sectors }
+    DeclareFaultsRecoveredParams { recoveries }
+    RecoveryDeclaration { deadline, partition, sectors }
+    CompactPartitionsParams { deadline, partitions }
+    ExtendPreCommitsParams { extensions }
+    PreCommitExtension { sector_number, prove_commit_due }
+    MovePartitionsParams { orig_deadline, dest_deadline, partitions }
+    CompactSectorNumbersParams { mask_sector_numbers }
+    ReportConsensusFaultParams { header1, header2, header_extra }
+    WithdrawBalanceParams { amount_requested }
+    transparent WithdrawBalanceReturn { amount_withdrawn }
+    PreCommitSectorParams {
+        seal_proof,
+        sector_number,
+        sealed_cid,
+        seal_rand_epoch,
+        deal_ids,
+        expiration,
+        replace_capacity,
+        replace_sector_deadline,
+        replace_sector_partition,
+        replace_sector_number,
+    }
+    PreCommitSectorBatchParams { sectors }
+    PreCommitSectorBatchParams2 { sectors }
+    SectorPreCommitInfo {
+        seal_proof,
+        sector_number,
+        sealed_cid,
+        seal_rand_epoch,
+        deal_ids,
+        expiration,
+        unsealed_cid,
+    }
+    ApplyRewardParams { reward, penalty }
+    DisputeWindowedPoStParams { deadline, post_index }
+    ProveCommitAggregateParams { sector_numbers, aggregate_proof }
+    ProveCommitAggregateParams2 { sector_activations, aggregate_proof }
+    SectorActivationManifest { sector_number, pieces }
+    PieceActivationManifest { cid, size, notify }
+    DataActivationNotification { address, payload }
+    ReplicaUpdate {
+        sector_number,
+        deadline,
+        partition,
+        new_sealed_cid,
+        deals,
+        update_proof_type,
+        replica_proof,
+    }
+    ProveReplicaUpdatesParams { updates }
+    ReplicaUpdate2 {
+        sector_number,
+        deadline,
+        partition,
+        new_sealed_cid,
+        new_unsealed_cid,
+        deals,
+        update_proof_type,
+        replica_proof,
+    }
+    ProveReplicaUpdatesParams2 { updates }
+    ReplicaUpdate3 {
+        sector_number,
+        deadline,
+        partition,
+        new_sealed_cid,
+        new_unsealed_cid,
+        deals,
+        pieces,
+        update_proof_type,
+        replica_proof,
+    }
+    ProveReplicaUpdatesParams3 { updates }
+    DropSectorPiecesParams {
+        sector_number,
+        deadline,
+        partition,
+        new_sealed_cid,
+        new_unsealed_cid,
+        deals,
+        update_proof_type,
+        replica_proof,
+    }
+    ClaimsTransferredParams { claims, prior_transfers }
+    TransferredClaim { sector, size }
+    ChangeBeneficiaryParams { new_beneficiary, new_quota, new_expiration }
+    ChangeBeneficiariesParams { payout, beneficiaries }
+    BeneficiaryTerm { quota, used_quota, expiration }
+    PendingBeneficiaryChange {
+        new_beneficiary,
+        new_quota,
+        new_expiration,
+        approved_by_beneficiary,
+        approved_by_nominee,
+    }
+    BeneficiaryShare { beneficiary, quota, expiration }
+    BeneficiarySplit { payout, beneficiaries }
+    SplitBeneficiary { beneficiary, term }
+    PendingBeneficiarySplit { payout, beneficiaries, pending_approvals }
+    ActiveBeneficiary { beneficiary, term }
+    GetBeneficiaryReturn { active, proposed }
+    GetBeneficiariesReturn { split, proposed_split }
+    GetOwnerReturn { owner, proposed }
+    transparent IsControllingAddressParam { address }
+    transparent IsControllingAddressReturn { is_controlling }
+    transparent GetSectorSizeReturn { sector_size }
+    transparent GetAvailableBalanceReturn { available_balance }
+    GetVestingFundsReturn { vesting_funds }
+    GetPeerIDReturn { peer_id }
+    GetMultiaddrsReturn { multi_addrs }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (MinerConstructorParams, ()),
+        ControlAddresses => ((), GetControlAddressesReturn),
+        ChangeWorkerAddress => (ChangeWorkerAddressParams, ()),
+        ChangePeerID => (ChangePeerIDParams, ()),
+        SubmitWindowedPoSt => (SubmitWindowedPoStParams, ()),
+        PreCommitSector => (PreCommitSectorParams, ()),
+        ProveCommitSector => (ProveCommitSectorParams, ()),
+        ExtendSectorExpiration => (ExtendSectorExpirationParams, ()),
+        TerminateSectors => (TerminateSectorsParams, TerminateSectorsReturn),
+        DeclareFaults => (DeclareFaultsParams, ()),
+        DeclareFaultsRecovered => (DeclareFaultsRecoveredParams, ()),
+        OnDeferredCronEvent => (DeferredCronEventParams, ()),
+        CheckSectorProven => (CheckSectorProvenParams, ()),
+        ApplyRewards => (ApplyRewardParams, ()),
+        ReportConsensusFault => (ReportConsensusFaultParams, ()),
+        WithdrawBalance => (WithdrawBalanceParams, WithdrawBalanceReturn),
+        ConfirmSectorProofsValid => (ConfirmSectorProofsParams, ()),
+        ChangeMultiaddrs => (ChangeMultiaddrsParams, ()),
+        CompactPartitions => (CompactPartitionsParams, ()),
+        CompactSectorNumbers => (CompactSectorNumbersParams, ()),
+        ConfirmChangeWorkerAddress => ((), ()),
+        RepayDebt => ((), ()),
+        ChangeOwnerAddress => (Address, ()),
+        DisputeWindowedPoSt => (DisputeWindowedPoStParams, ()),
+        PreCommitSectorBatch => (PreCommitSectorBatchParams, ()),
+        ProveCommitAggregate => (ProveCommitAggregateParams, ()),
+        ProveReplicaUpdates => (ProveReplicaUpdatesParams, BitField),
+        PreCommitSectorBatch2 => (PreCommitSectorBatchParams2, ()),
+        ProveReplicaUpdates2 => (ProveReplicaUpdatesParams2, BitField),
+        ChangeBeneficiary => (ChangeBeneficiaryParams, ()),
+        GetBeneficiary => ((), GetBeneficiaryReturn),
+        ExtendSectorExpiration2 => (ExtendSectorExpiration2Params, ()),
+        ChangeBeneficiaries => (ChangeBeneficiariesParams, ()),
+        ProveCommitAggregate2 => (ProveCommitAggregateParams2, ()),
+        ProveReplicaUpdates3 => (ProveReplicaUpdatesParams3, BitField),
+        MovePartitions => (MovePartitionsParams, ()),
+        ExtendPreCommits => (ExtendPreCommitsParams, ()),
+        DropSectorPieces => (DropSectorPiecesParams, ()),
+        GetBeneficiaries => ((), GetBeneficiariesReturn),
+        ClaimsTransferred => (ClaimsTransferredParams, ()),
+        ChangeWorkerAddressExported => (ChangeWorkerAddressParams, ()),
+        ChangePeerIDExported => (ChangePeerIDParams, ()),
+        WithdrawBalanceExported => (WithdrawBalanceParams, WithdrawBalanceReturn),
+        ChangeMultiaddrsExported => (ChangeMultiaddrsParams, ()),
+        ConfirmChangeWorkerAddressExported => ((), ()),
+        RepayDebtExported => ((), ()),
+        ChangeOwnerAddressExported => (Address, ()),
+        ChangeBeneficiaryExported => (ChangeBeneficiaryParams, ()),
+        GetBeneficiaryExported => ((), GetBeneficiaryReturn),
+        ChangeBeneficiariesExported => (ChangeBeneficiariesParams, ()),
+        GetBeneficiariesExported => ((), GetBeneficiariesReturn),
+        GetOwnerExported => ((), GetOwnerReturn),
+        IsControllingAddressExported => (IsControllingAddressParam, IsControllingAddressReturn),
+        GetSectorSizeExported => ((), GetSectorSizeReturn),
+        GetAvailableBalanceExported => ((), GetAvailableBalanceReturn),
+        GetVestingFundsExported => ((), GetVestingFundsReturn),
+        GetPeerIDExported => ((), GetPeerIDReturn),
+        GetMultiaddrsExported => ((), GetMultiaddrsReturn),
+    }
+}
@@ -18,11 +18,12 @@ use fvm_shared::clock::ChainEpoch;
 use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::*;
+use fvm_shared::piece::PieceInfo;
 use fvm_shared::randomness::*;
 use fvm_shared::reward::ThisEpochRewardReturn;
 use fvm_shared::sector::*;
 use fvm_shared::smooth::FilterEstimate;
-use fvm_shared::{ActorID, METHOD_CONSTRUCTOR, METHOD_SEND};
+use fvm_shared::{ActorID, MethodNum, METHOD_CONSTRUCTOR, METHOD_SEND};
 use itertools::Itertools;
 use log::{error, info, warn};
 use multihash::Code::Blake2b256;
@@ -42,9 +43,9 @@ use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, DomainSeparationTag, Policy, Runtime};
 use fil_actors_runtime::{
     actor_dispatch, actor_error, deserialize_block, extract_send_result, ActorContext,
-    ActorDowncast, ActorError, BURNT_FUNDS_ACTOR_ADDR, INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR,
-    STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
-    VERIFIED_REGISTRY_ACTOR_ADDR,
+    ActorDowncast, ActorError, DealWeight, InsufficientBalance, BURNT_FUNDS_ACTOR_ADDR,
+    INIT_ACTOR_ADDR, REWARD_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR,
+    SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 pub use monies::*;
@@ -74,9 +75,12 @@ mod deadlines;
 mod expiration_queue;
 #[doc(hidden)]
 pub mod ext;
+#[cfg(feature = "json")]
+pub mod json;
 mod monies;
 mod partition_state;
 mod policy;
+pub mod reason;
 mod sector_map;
 mod sectors;
 mod state;
@@ -126,6 +130,14 @@ pub enum Method {
     ChangeBeneficiary = 30,
     GetBeneficiary = 31,
     ExtendSectorExpiration2 = 32,
+    ProveCommitAggregate2 = 33,
+    ProveReplicaUpdates3 = 34,
+    MovePartitions = 35,
+    ChangeBeneficiaries = 36,
+    ExtendPreCommits = 37,
+    DropSectorPieces = 38,
+    GetBeneficiaries = 39,
+    ClaimsTransferred = 40,
     // Method numbers derived from FRC-0042 standards
     ChangeWorkerAddressExported = frc42_dispatch::method_hash!("ChangeWorkerAddress"),
     ChangePeerIDExported = frc42_dispatch::method_hash!("ChangePeerID"),
@@ -136,6 +148,8 @@ pub enum Method {
     ChangeOwnerAddressExported = frc42_dispatch::method_hash!("ChangeOwnerAddress"),
     ChangeBeneficiaryExported = frc42_dispatch::method_hash!("ChangeBeneficiary"),
     GetBeneficiaryExported = frc42_dispatch::method_hash!("GetBeneficiary"),
+    ChangeBeneficiariesExported = frc42_dispatch::method_hash!("ChangeBeneficiaries"),
+    GetBeneficiariesExported = frc42_dispatch::method_hash!("GetBeneficiaries"),
     GetOwnerExported = frc42_dispatch::method_hash!("GetOwner"),
     IsControllingAddressExported = frc42_dispatch::method_hash!("IsControllingAddress"),
     GetSectorSizeExported = frc42_dispatch::method_hash!("GetSectorSize"),
@@ -147,6 +161,10 @@ pub enum Method {
 
 pub const ERR_BALANCE_INVARIANTS_BROKEN: ExitCode = ExitCode::new(1000);
 
+/// Method invoked on actors named in piece activation notifications, when sector content
+/// they are interested in is activated or updated.
+pub const SECTOR_CONTENT_CHANGED: MethodNum = frc42_dispatch::method_hash!("SectorContentChanged");
+
 /// Miner Actor
 /// here in order to update the Power Actor to v3.
 pub struct Actor;
@@ -405,6 +423,7 @@ impl Actor {
                 }
                 // Cancel pending beneficiary term change when the owner changes
                 info.pending_beneficiary_term = None;
+                info.pending_beneficiary_split = None;
 
                 // Set the new owner address
                 info.owner = pending_address;
@@ -763,7 +782,48 @@ impl Actor {
         rt: &mut impl Runtime,
         params: ProveCommitAggregateParams,
     ) -> Result<(), ActorError> {
-        let sector_numbers = params.sector_numbers.validate().map_err(|e| {
+        Self::prove_commit_aggregate_inner(
+            rt,
+            &params.sector_numbers,
+            params.aggregate_proof,
+            BTreeMap::new(),
+        )
+    }
+
+    /// As ProveCommitAggregate, but the sectors are described by activation manifests listing their pieces.
+    /// Once the sectors are activated, each actor named in a piece's notifications is sent
+    /// a SectorContentChanged message describing the pieces it was notified of.
+    fn prove_commit_aggregate2(
+        rt: &mut impl Runtime,
+        params: ProveCommitAggregateParams2,
+    ) -> Result<(), ActorError> {
+        let mut sector_numbers = BitField::new();
+        let mut manifests = BTreeMap::<SectorNumber, Vec<PieceActivationManifest>>::new();
+        for activation in params.sector_activations {
+            if sector_numbers.get(activation.sector_number) {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "duplicate sector number {}",
+                    activation.sector_number
+                )
+                .with_reason(reason::DUPLICATE_SECTOR_NUMBER, activation.sector_number));
+            }
+            sector_numbers.try_set(activation.sector_number).map_err(|_| {
+                actor_error!(illegal_argument, "invalid sector number {}", activation.sector_number)
+                    .with_reason(reason::INVALID_SECTOR_NUMBER, activation.sector_number)
+            })?;
+            manifests.insert(activation.sector_number, activation.pieces);
+        }
+        Self::prove_commit_aggregate_inner(rt, &sector_numbers, params.aggregate_proof, manifests)
+    }
+
+    fn prove_commit_aggregate_inner(
+        rt: &mut impl Runtime,
+        sector_numbers: &BitField,
+        aggregate_proof: Vec<u8>,
+        piece_manifests: BTreeMap<SectorNumber, Vec<PieceActivationManifest>>,
+    ) -> Result<(), ActorError> {
+        let sector_numbers = sector_numbers.validate().map_err(|e| {
             actor_error!(illegal_state, "Failed to validate bitfield for aggregated sectors: {}", e)
         })?;
         let agg_sectors_count = sector_numbers.len();
@@ -786,11 +846,11 @@ impl Actor {
                 ));
             }
 
-            if params.aggregate_proof.len() > policy.max_aggregated_proof_size {
+            if aggregate_proof.len() > policy.max_aggregated_proof_size {
                 return Err(actor_error!(
                     illegal_argument,
                     "sector prove-commit proof of size {} exceeds max size of {}",
-                    params.aggregate_proof.len(),
+                    aggregate_proof.len(),
                     policy.max_aggregated_proof_size
                 ));
             }
@@ -809,15 +869,13 @@ impl Actor {
         // validate each precommit
         let mut precommits_to_confirm = Vec::new();
         for (i, precommit) in precommits.iter().enumerate() {
-            let msd = max_prove_commit_duration(rt.policy(), precommit.info.seal_proof)
-                .ok_or_else(|| {
-                    actor_error!(
-                        illegal_state,
-                        "no max seal duration for proof type: {}",
-                        i64::from(precommit.info.seal_proof)
-                    )
-                })?;
-            let prove_commit_due = precommit.pre_commit_epoch + msd;
+            let prove_commit_due = prove_commit_due(rt.policy(), precommit).ok_or_else(|| {
+                actor_error!(
+                    illegal_state,
+                    "no max seal duration for proof type: {}",
+                    i64::from(precommit.info.seal_proof)
+                )
+            })?;
             if rt.curr_epoch() > prove_commit_due {
                 log::warn!(
                     "skipping commitment for sector {}, too late at {}, due {}",
@@ -862,7 +920,8 @@ impl Actor {
                     forbidden,
                     "too early to prove sector {}",
                     precommit.info.sector_number
-                ));
+                )
+                .with_reason(reason::PROVE_COMMIT_TOO_EARLY, precommit.info.sector_number));
             }
             let sv_info_randomness = rt.get_randomness_from_tickets(
                 DomainSeparationTag::SealRandomness,
@@ -877,6 +936,12 @@ impl Actor {
 
             let unsealed_cid = precommit.info.unsealed_cid.get_cid(precommit.info.seal_proof)?;
 
+            let pieces = piece_manifests
+                .get(&precommit.info.sector_number)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            verify_activated_pieces(rt, &precommit.info, pieces)?;
+
             let svi = AggregateSealVerifyInfo {
                 sector_number: precommit.info.sector_number,
                 randomness: Randomness(sv_info_randomness.into()),
@@ -898,7 +963,7 @@ impl Actor {
             miner: miner_actor_id,
             seal_proof,
             aggregate_proof: RegisteredAggregateProof::SnarkPackV2,
-            proof: params.aggregate_proof,
+            proof: aggregate_proof,
             infos: svis,
         })
         .map_err(|e| {
@@ -907,9 +972,10 @@ impl Actor {
 
         let rew = request_current_epoch_block_reward(rt)?;
         let pwr = request_current_total_power(rt)?;
-        confirm_sector_proofs_valid_internal(
+        let activated = confirm_sector_proofs_valid_internal(
             rt,
             precommits_to_confirm.clone(),
+            &piece_manifests,
             &rew.this_epoch_baseline_power,
             &rew.this_epoch_reward_smoothed,
             &pwr.quality_adj_power_smoothed,
@@ -929,10 +995,25 @@ impl Actor {
                 "remaining unlocked funds after prove-commit {} are insufficient to pay aggregation fee of {}",
                 unlocked_balance,
                 aggregate_fee
+            )
+            .with_reason(
+                reason::INSUFFICIENT_FUNDS_FOR_FEE,
+                InsufficientBalance { required: aggregate_fee, available: unlocked_balance },
             ));
         }
         burn_funds(rt, aggregate_fee)?;
         state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
+
+        let activations: Vec<_> = precommits_to_confirm
+            .iter()
+            .filter(|pc| activated.contains(&pc.info.sector_number))
+            .filter_map(|pc| {
+                piece_manifests
+                    .get(&pc.info.sector_number)
+                    .map(|pieces| (pc.info.sector_number, pc.info.expiration, pieces.as_slice()))
+            })
+            .collect();
+        notify_data_consumers(rt, &activations)?;
         Ok(())
     }
 
@@ -958,6 +1039,7 @@ impl Actor {
                 new_sealed_cid: ru.new_sealed_cid,
                 new_unsealed_cid: None,
                 deals: ru.deals,
+                pieces: vec![],
                 update_proof_type: ru.update_proof_type,
                 replica_proof: ru.replica_proof,
             })
@@ -985,6 +1067,38 @@ impl Actor {
                 new_sealed_cid: ru.new_sealed_cid,
                 new_unsealed_cid: Some(ru.new_unsealed_cid),
                 deals: ru.deals,
+                pieces: vec![],
+                update_proof_type: ru.update_proof_type,
+                replica_proof: ru.replica_proof,
+            })
+            .collect();
+        Self::prove_replica_updates_inner(rt, updates)
+    }
+
+    /// As ProveReplicaUpdates2, but each update may carry a manifest of the pieces in the new sector content.
+    /// An update with pieces need not have deals. After the updates are applied, each actor named in a
+    /// piece's notifications is sent a SectorContentChanged message describing the pieces it was notified of.
+    fn prove_replica_updates3<RT>(
+        rt: &mut RT,
+        params: ProveReplicaUpdatesParams3,
+    ) -> Result<BitField, ActorError>
+    where
+        // + Clone because we messed up and need to keep a copy around between transactions.
+        // https://github.com/filecoin-project/builtin-actors/issues/133
+        RT::Blockstore: Blockstore + Clone,
+        RT: Runtime,
+    {
+        let updates = params
+            .updates
+            .into_iter()
+            .map(|ru| ReplicaUpdateInner {
+                sector_number: ru.sector_number,
+                deadline: ru.deadline,
+                partition: ru.partition,
+                new_sealed_cid: ru.new_sealed_cid,
+                new_unsealed_cid: Some(ru.new_unsealed_cid),
+                deals: ru.deals,
+                pieces: ru.pieces,
                 update_proof_type: ru.update_proof_type,
                 replica_proof: ru.replica_proof,
             })
@@ -1058,8 +1172,21 @@ impl Actor {
                 continue;
             }
 
-            if update.deals.is_empty() {
-                info!("must have deals to update, skipping sector {}", update.sector_number,);
+            if update.deals.is_empty() && update.pieces.is_empty() {
+                info!(
+                    "must have deals or pieces to update, skipping sector {}",
+                    update.sector_number,
+                );
+                continue;
+            }
+
+            // The unsealed CID is computed from deals if there are any, so pieces alongside
+            // them would not be covered by the proof.
+            if !update.deals.is_empty() && !update.pieces.is_empty() {
+                info!(
+                    "cannot update with both deals and pieces, skipping sector {}",
+                    update.sector_number,
+                );
                 continue;
             }
 
@@ -1131,13 +1258,52 @@ impl Actor {
                 continue;
             }
 
+            if !update.pieces.is_empty() {
+                let declared_commd = match update.new_unsealed_cid {
+                    Some(commd) => commd,
+                    None => {
+                        info!(
+                            "unsealed CID must be declared with pieces, skipping sector {}",
+                            update.sector_number
+                        );
+                        continue;
+                    }
+                };
+                match pieces_match_unsealed_cid(
+                    rt,
+                    sector_info.seal_proof,
+                    &update.pieces,
+                    &declared_commd,
+                ) {
+                    Ok(true) => {}
+                    Ok(false) => {
+                        info!(
+                            "pieces do not match unsealed CID {}, skipping sector {}",
+                            declared_commd, update.sector_number
+                        );
+                        continue;
+                    }
+                    Err(e) => {
+                        info!(
+                            "failed to compute unsealed CID from pieces: {}, skipping sector {}",
+                            e.msg(),
+                            update.sector_number
+                        );
+                        continue;
+                    }
+                }
+            }
+
             let deal_spaces = match activate_deals_and_claim_allocations(
                 rt,
                 update.deals.clone(),
                 sector_info.expiration,
                 sector_info.sector_number,
             )? {
-                Some(deal_spaces) => deal_spaces,
+                Some(mut deal_spaces) => {
+                    deal_spaces.verified_deal_space += transferred_claim_space(&update.pieces);
+                    deal_spaces
+                }
                 None => {
                     info!(
                         "failed to activate deals on sector {}, skipping from replica update set",
@@ -1190,8 +1356,20 @@ impl Actor {
         let mut deadlines_to_load = Vec::<u64>::new();
         for (with_sector_info, deal_data) in validated_updates.iter().zip(deal_data.sectors.iter())
         {
-            let computed_commd = CompactCommD::new(deal_data.commd)
-                .get_cid(with_sector_info.sector_info.seal_proof)?;
+            let computed_commd = if with_sector_info.update.deals.is_empty() {
+                // Without deals, the content is described only by the pieces,
+                // which have been checked against the declared unsealed CID.
+                with_sector_info.update.new_unsealed_cid.ok_or_else(|| {
+                    actor_error!(
+                        illegal_state,
+                        "no unsealed CID for sector {}",
+                        with_sector_info.update.sector_number
+                    )
+                })?
+            } else {
+                CompactCommD::new(deal_data.commd)
+                    .get_cid(with_sector_info.sector_info.seal_proof)?
+            };
             if let Some(ref declared_commd) = with_sector_info.update.new_unsealed_cid {
                 if !declared_commd.eq(&computed_commd) {
                     info!(
@@ -1340,6 +1518,9 @@ impl Actor {
                                 deficit,
                                 unlocked_balance,
                                 with_details.sector_info.sector_number
+                            ).with_reason(
+                                reason::INSUFFICIENT_FUNDS_FOR_PLEDGE,
+                                InsufficientBalance { required: deficit, available: unlocked_balance },
                             ));
                         }
 
@@ -1452,119 +1633,606 @@ impl Actor {
         notify_pledge_changed(rt, &pledge_delta)?;
         request_update_power(rt, power_delta)?;
 
+        let activations: Vec<_> = validated_updates
+            .iter()
+            .map(|u| (u.update.sector_number, u.sector_info.expiration, u.update.pieces.as_slice()))
+            .collect();
+        notify_data_consumers(rt, &activations)?;
+
         Ok(succeeded_sectors)
     }
 
-    fn dispute_windowed_post(
+    /// Replaces the replica of a sector with one from which the pieces of some of its deals have been
+    /// removed, leaving the sector live with the rest of its content.
+    /// The new unsealed CID must be made up of the pieces of the remaining deals.
+    /// The dropped deals are terminated, their verified claims removed, and the sector's weight
+    /// reduced by what they would have contributed over the rest of their terms.
+    /// Verified deals whose claims were transferred to another provider no longer carry weight.
+    /// The miner is penalized only for the quality-adjusted power lost.
+    fn drop_sector_pieces(
         rt: &mut impl Runtime,
-        params: DisputeWindowedPoStParams,
+        params: DropSectorPiecesParams,
     ) -> Result<(), ActorError> {
-        rt.validate_immediate_caller_accept_any()?;
-        let reporter = rt.message().caller();
+        let curr_epoch = rt.curr_epoch();
+        let state: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &state)?;
+        rt.validate_immediate_caller_is(
+            info.control_addresses.iter().chain(&[info.owner, info.worker]),
+        )?;
 
-        {
-            let policy = rt.policy();
-            if params.deadline >= policy.wpost_period_deadlines {
+        let sector_number = params.sector_number;
+        if params.deals.is_empty() {
+            return Err(actor_error!(
+                illegal_argument,
+                "no deals to drop from sector {}",
+                sector_number
+            ));
+        }
+        if params.replica_proof.len() > 4096 {
+            return Err(actor_error!(
+                illegal_argument,
+                "update proof is too large ({})",
+                params.replica_proof.len()
+            ));
+        }
+        if params.deadline >= rt.policy().wpost_period_deadlines {
+            return Err(actor_error!(
+                illegal_argument,
+                "deadline {} not in range 0..{}",
+                params.deadline,
+                rt.policy().wpost_period_deadlines
+            ));
+        }
+        if !is_sealed_sector(&params.new_sealed_cid) {
+            return Err(actor_error!(illegal_argument, "new sealed CID had wrong prefix"));
+        }
+        if !is_unsealed_sector(&params.new_unsealed_cid) {
+            return Err(actor_error!(illegal_argument, "new unsealed CID had wrong prefix"));
+        }
+
+        // Deadlines are immutable while being proven.
+        if !deadline_is_mutable(
+            rt.policy(),
+            state.current_proving_period_start(rt.policy(), curr_epoch),
+            params.deadline,
+            curr_epoch,
+        ) {
+            return Err(actor_error!(
+                forbidden,
+                "cannot drop pieces from sector in immutable deadline {}",
+                params.deadline
+            ));
+        }
+        let active = state
+            .check_sector_active(
+                rt.policy(),
+                rt.store(),
+                params.deadline,
+                params.partition,
+                sector_number,
+                true,
+            )
+            .map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_ARGUMENT, "error checking sector health")
+            })?;
+        if !active {
+            return Err(actor_error!(forbidden, "sector {} is not active", sector_number)
+                .with_reason(reason::SECTOR_NOT_ACTIVE, sector_number));
+        }
+
+        let sector = state
+            .get_sector(rt.store(), sector_number)
+            .map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to load sector {}", sector_number),
+                )
+            })?
+            .ok_or_else(|| {
+                actor_error!(not_found, "no such sector {}", sector_number)
+                    .with_reason(reason::SECTOR_NOT_FOUND, sector_number)
+            })?;
+
+        let mut dropped = BitField::new();
+        for &deal_id in params.deals.iter() {
+            if dropped.get(deal_id) {
+                return Err(actor_error!(illegal_argument, "duplicate deal {}", deal_id));
+            }
+            if !sector.deal_ids.contains(&deal_id) {
                 return Err(actor_error!(
                     illegal_argument,
-                    "invalid deadline {} of {}",
-                    params.deadline,
-                    policy.wpost_period_deadlines
+                    "deal {} is not in sector {}",
+                    deal_id,
+                    sector_number
                 ));
             }
+            dropped.set(deal_id);
         }
-        let current_epoch = rt.curr_epoch();
 
-        // Note: these are going to be slightly inaccurate as time
-        // will have moved on from when the post was actually
-        // submitted.
-        //
-        // However, these are estimates _anyways_.
-        let epoch_reward = request_current_epoch_block_reward(rt)?;
-        let power_total = request_current_total_power(rt)?;
-
-        let (pledge_delta, mut to_burn, power_delta, to_reward) =
-            rt.transaction(|st: &mut State, rt| {
-                let policy = rt.policy();
-                let dl_info = st.deadline_info(policy, current_epoch);
-
-                if !deadline_available_for_optimistic_post_dispute(
-                    policy,
-                    dl_info.period_start,
-                    params.deadline,
-                    current_epoch,
-                ) {
-                    return Err(actor_error!(
-                        forbidden,
-                        "can only dispute window posts during the dispute window\
-                    ({} epochs after the challenge window closes)",
-                        policy.wpost_dispute_window
-                    ));
-                }
+        let update_proof_type = sector
+            .seal_proof
+            .registered_update_proof()
+            .map_err(|_| actor_error!(illegal_state, "couldn't load update proof type"))?;
+        if params.update_proof_type != update_proof_type {
+            return Err(actor_error!(
+                illegal_argument,
+                "unsupported update proof type {}",
+                i64::from(params.update_proof_type)
+            ));
+        }
 
-                let info = get_miner_info(rt.store(), st)?;
-                // --- check proof ---
+        let remaining_deals: Vec<DealID> =
+            sector.deal_ids.iter().copied().filter(|deal_id| !dropped.get(*deal_id)).collect();
+        let remaining_pieces = request_deal_pieces(rt, &remaining_deals)?;
+        let expected_unsealed_cid = if remaining_pieces.is_empty() {
+            CompactCommD::new(None).get_cid(sector.seal_proof)?
+        } else {
+            rt.compute_unsealed_sector_cid(sector.seal_proof, &remaining_pieces).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_ARGUMENT,
+                    "failed to compute unsealed sector CID",
+                )
+            })?
+        };
+        if params.new_unsealed_cid != expected_unsealed_cid {
+            return Err(actor_error!(
+                illegal_argument,
+                "new unsealed CID {} does not match remaining pieces of sector {}, expected {}",
+                params.new_unsealed_cid,
+                sector_number,
+                expected_unsealed_cid
+            ));
+        }
 
-                // Find the proving period start for the deadline in question.
-                let mut pp_start = dl_info.period_start;
-                if dl_info.index < params.deadline as u64 {
-                    pp_start -= policy.wpost_proving_period
-                }
-                let target_deadline =
-                    new_deadline_info(policy, pp_start, params.deadline, current_epoch);
-                // Load the target deadline
-                let mut deadlines_current = st
-                    .load_deadlines(rt.store())
-                    .map_err(|e| e.wrap("failed to load deadlines"))?;
+        rt.verify_replica_update(&ReplicaUpdateInfo {
+            update_proof_type,
+            new_sealed_cid: params.new_sealed_cid,
+            old_sealed_cid: sector.sealed_cid,
+            new_unsealed_cid: params.new_unsealed_cid,
+            proof: params.replica_proof.clone(),
+        })
+        .map_err(|e| {
+            e.downcast_default(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                format!("failed to verify replica proof for sector {}", sector_number),
+            )
+        })?;
 
-                let mut dl_current = deadlines_current
-                    .load_deadline(policy, rt.store(), params.deadline)
-                    .map_err(|e| {
-                        e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load deadline")
-                    })?;
+        let dropped_pieces = request_deal_pieces(rt, &params.deals)?;
+        let dropped_weights = request_remaining_deal_weights(
+            rt,
+            &params.deals,
+            &dropped_pieces,
+            curr_epoch,
+            sector.expiration,
+        )?;
+        let dropped_verified_deal_weight =
+            request_remove_sector_claims(rt, sector_number, dropped_weights.verified_pieces)?;
+        let reward_stats = request_current_epoch_block_reward(rt)?;
+        let power_total = request_current_total_power(rt)?;
 
-                // Take the post from the snapshot for dispute.
-                // This operation REMOVES the PoSt from the snapshot so
-                // it can't be disputed again. If this method fails,
-                // this operation must be rolled back.
-                let (partitions, proofs) =
-                    dl_current.take_post_proofs(rt.store(), params.post_index).map_err(|e| {
-                        e.downcast_default(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            "failed to load proof for dispute",
-                        )
-                    })?;
+        let (power_delta, pledge_delta, penalty) = rt.transaction(|state: &mut State, rt| {
+            let store = rt.store();
+            let policy = rt.policy();
 
-                // Load the partition info we need for the dispute.
-                let mut dispute_info = dl_current
-                    .load_partitions_for_dispute(rt.store(), partitions)
-                    .map_err(|e| {
-                        e.downcast_default(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            "failed to load partition for dispute",
-                        )
-                    })?;
+            let mut new_sector = sector.clone();
+            new_sector.sealed_cid = params.new_sealed_cid;
+            new_sector.sector_key_cid = sector.sector_key_cid.or(Some(sector.sealed_cid));
+            new_sector.deal_ids.retain(|deal_id| !dropped.get(*deal_id));
 
-                // This includes power that is no longer active (e.g., due to sector terminations).
-                // It must only be used for penalty calculations, not power adjustments.
-                let penalised_power = dispute_info.disputed_power.clone();
+            new_sector.deal_weight =
+                std::cmp::max(&sector.deal_weight - &dropped_weights.deal_weight, BigInt::zero());
+            new_sector.verified_deal_weight = std::cmp::max(
+                &sector.verified_deal_weight - &dropped_verified_deal_weight,
+                BigInt::zero(),
+            );
 
-                // Load sectors for the dispute.
-                let sectors =
-                    Sectors::load(rt.store(), &dl_current.sectors_snapshot).map_err(|e| {
-                        e.downcast_default(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            "failed to load sectors array",
-                        )
-                    })?;
-                let sector_infos = sectors
-                    .load_for_proof(&dispute_info.all_sector_nos, &dispute_info.ignored_sector_nos)
-                    .map_err(|e| {
-                        e.downcast_default(
-                            ExitCode::USR_ILLEGAL_STATE,
-                            "failed to load sectors to dispute window post",
-                        )
-                    })?;
+            let qa_power_before = qa_power_for_sector(info.sector_size, &sector);
+            let qa_power_after = qa_power_for_sector(info.sector_size, &new_sector);
+            let mut penalty = pledge_penalty_for_partial_termination(
+                &termination_penalty(
+                    info.sector_size,
+                    curr_epoch,
+                    &reward_stats.this_epoch_reward_smoothed,
+                    &power_total.quality_adj_power_smoothed,
+                    &[sector.clone()],
+                ),
+                &qa_power_before,
+                &(&qa_power_before - &qa_power_after),
+            );
+
+            let mut deadlines = state.load_deadlines(store)?;
+            let mut deadline =
+                deadlines.load_deadline(policy, store, params.deadline).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to load deadline {}", params.deadline),
+                    )
+                })?;
+            let mut partitions = deadline.partitions_amt(store).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to load partitions for deadline {}", params.deadline),
+                )
+            })?;
+            let mut partition = partitions
+                .get(params.partition)
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!(
+                            "failed to load deadline {} partition {}",
+                            params.deadline, params.partition
+                        ),
+                    )
+                })?
+                .cloned()
+                .ok_or_else(|| {
+                    actor_error!(
+                        not_found,
+                        "no such deadline {} partition {}",
+                        params.deadline,
+                        params.partition
+                    )
+                })?;
+
+            let quant = state.quant_spec_for_deadline(policy, params.deadline);
+            let (power_delta, mut pledge_delta) = partition
+                .replace_sectors(
+                    store,
+                    &[sector.clone()],
+                    &[new_sector.clone()],
+                    info.sector_size,
+                    quant,
+                )
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!(
+                            "failed to replace sector at deadline {} partition {}",
+                            params.deadline, params.partition
+                        ),
+                    )
+                })?;
+
+            partitions.set(params.partition, partition).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!(
+                        "failed to save deadline {} partition {}",
+                        params.deadline, params.partition
+                    ),
+                )
+            })?;
+            deadline.partitions = partitions.flush().map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to save partitions for deadline {}", params.deadline),
+                )
+            })?;
+            deadlines.update_deadline(policy, store, params.deadline, &deadline).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to save deadline {}", params.deadline),
+                )
+            })?;
+            state.save_deadlines(store, deadlines).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
+            })?;
+
+            state.put_sectors(store, vec![new_sector]).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to update sector info")
+            })?;
+
+            // Pay penalty, using unlocked pledge to pay down any outstanding fee debt.
+            state
+                .apply_penalty(&penalty)
+                .map_err(|e| actor_error!(illegal_state, "failed to apply penalty: {}", e))?;
+            let (penalty_from_vesting, penalty_from_balance) = state
+                .repay_partial_debt_in_priority_order(store, curr_epoch, &rt.current_balance())
+                .map_err(|e| {
+                    e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to repay penalty")
+                })?;
+            penalty = &penalty_from_vesting + penalty_from_balance;
+            pledge_delta -= penalty_from_vesting;
+
+            Ok((power_delta, pledge_delta, penalty))
+        })?;
+
+        burn_funds(rt, penalty)?;
+        notify_pledge_changed(rt, &pledge_delta)?;
+        request_update_power(rt, power_delta)?;
+        request_terminate_deals(rt, curr_epoch, params.deals)?;
+
+        let state: State = rt.state()?;
+        state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
+        Ok(())
+    }
+
+    /// Removes the verified weight of claims that the verified registry has transferred
+    /// from this miner's sectors to another provider, whose sector now carries that weight.
+    /// Sectors that are no longer live are ignored, having no power to remove.
+    /// Claims cannot be transferred from faulty or unproven sectors, whose power is not active.
+    fn claims_transferred(
+        rt: &mut impl Runtime,
+        params: ClaimsTransferredParams,
+    ) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&VERIFIED_REGISTRY_ACTOR_ADDR))?;
+
+        let mut space_by_sector = BTreeMap::<SectorNumber, u64>::new();
+        for claim in &params.claims {
+            *space_by_sector.entry(claim.sector).or_default() += claim.size.0;
+        }
+
+        let prior_space_by_sector: BTreeMap<SectorNumber, u64> =
+            params.prior_transfers.iter().map(|prior| (prior.sector, prior.size.0)).collect();
+
+        // Sectors with deals but without simple QA power had their verified weight set from
+        // their verified deals, and prorated by any extension since. A claim's share of the
+        // remaining weight is its share of the verified deals' space not yet transferred.
+        let state: State = rt.state()?;
+        let mut legacy_verified_space = BTreeMap::<SectorNumber, u64>::new();
+        for &sector_number in space_by_sector.keys() {
+            let sector = state.get_sector(rt.store(), sector_number).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to load sector {}", sector_number),
+                )
+            })?;
+            if let Some(sector) = sector.filter(|s| !s.simple_qa_power && !s.deal_ids.is_empty()) {
+                let prior_space = prior_space_by_sector.get(&sector_number).copied().unwrap_or(0);
+                let verified_space = request_verified_deal_space(rt, &sector.deal_ids)?;
+                legacy_verified_space
+                    .insert(sector_number, verified_space.saturating_sub(prior_space));
+            }
+        }
+
+        let power_delta = rt.transaction(|state: &mut State, rt| {
+            let info = get_miner_info(rt.store(), state)?;
+            let store = rt.store();
+            let policy = rt.policy();
+
+            let mut deadlines = state.load_deadlines(store)?;
+            let mut power_delta = PowerPair::zero();
+            let mut new_sectors = Vec::with_capacity(space_by_sector.len());
+            for (&sector_number, &space) in &space_by_sector {
+                let sector = match state.get_sector(store, sector_number).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to load sector {}", sector_number),
+                    )
+                })? {
+                    Some(sector) => sector,
+                    None => {
+                        info!("ignoring claims transferred from missing sector {}", sector_number);
+                        continue;
+                    }
+                };
+                let (dl_idx, p_idx) =
+                    state.find_sector(policy, store, sector_number).map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            format!("failed to find sector {}", sector_number),
+                        )
+                    })?;
+                let mut deadline = deadlines.load_deadline(policy, store, dl_idx).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to load deadline {}", dl_idx),
+                    )
+                })?;
+                let mut partitions = deadline.partitions_amt(store).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to load partitions for deadline {}", dl_idx),
+                    )
+                })?;
+                let mut partition = partitions
+                    .get(p_idx)
+                    .map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            format!("failed to load deadline {} partition {}", dl_idx, p_idx),
+                        )
+                    })?
+                    .cloned()
+                    .ok_or_else(|| {
+                        actor_error!(
+                            illegal_state,
+                            "no such deadline {} partition {}",
+                            dl_idx,
+                            p_idx
+                        )
+                    })?;
+
+                if partition.terminated.get(sector_number) {
+                    info!("ignoring claims transferred from terminated sector {}", sector_number);
+                    continue;
+                }
+                if !partition.active_sectors().get(sector_number) {
+                    return Err(actor_error!(
+                        forbidden,
+                        "cannot transfer claims from inactive sector {}",
+                        sector_number
+                    )
+                    .with_reason(reason::SECTOR_NOT_ACTIVE, sector_number));
+                }
+
+                let mut new_sector = sector.clone();
+                let claim_weight = match legacy_verified_space.get(&sector_number) {
+                    Some(&0) => DealWeight::zero(),
+                    Some(&verified_space) => (&sector.verified_deal_weight
+                        * std::cmp::min(space, verified_space))
+                    .div_floor(&BigInt::from(verified_space)),
+                    // The claim's space was verified for the sector's whole life.
+                    None => DealWeight::from(space) * (sector.expiration - sector.activation),
+                };
+                new_sector.verified_deal_weight =
+                    std::cmp::max(&sector.verified_deal_weight - claim_weight, BigInt::zero());
+
+                let quant = state.quant_spec_for_deadline(policy, dl_idx);
+                let (partition_power_delta, _) = partition
+                    .replace_sectors(
+                        store,
+                        &[sector],
+                        &[new_sector.clone()],
+                        info.sector_size,
+                        quant,
+                    )
+                    .map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            format!(
+                                "failed to replace sector at deadline {} partition {}",
+                                dl_idx, p_idx
+                            ),
+                        )
+                    })?;
+                power_delta += &partition_power_delta;
+
+                partitions.set(p_idx, partition).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to save deadline {} partition {}", dl_idx, p_idx),
+                    )
+                })?;
+                deadline.partitions = partitions.flush().map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to save partitions for deadline {}", dl_idx),
+                    )
+                })?;
+                deadlines.update_deadline(policy, store, dl_idx, &deadline).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to save deadline {}", dl_idx),
+                    )
+                })?;
+                new_sectors.push(new_sector);
+            }
+
+            state.save_deadlines(store, deadlines).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
+            })?;
+            state.put_sectors(store, new_sectors).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to update sector info")
+            })?;
+            Ok(power_delta)
+        })?;
+
+        request_update_power(rt, power_delta)
+    }
+
+    fn dispute_windowed_post(
+        rt: &mut impl Runtime,
+        params: DisputeWindowedPoStParams,
+    ) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let reporter = rt.message().caller();
+
+        {
+            let policy = rt.policy();
+            if params.deadline >= policy.wpost_period_deadlines {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "invalid deadline {} of {}",
+                    params.deadline,
+                    policy.wpost_period_deadlines
+                ));
+            }
+        }
+        let current_epoch = rt.curr_epoch();
+
+        // Note: these are going to be slightly inaccurate as time
+        // will have moved on from when the post was actually
+        // submitted.
+        //
+        // However, these are estimates _anyways_.
+        let epoch_reward = request_current_epoch_block_reward(rt)?;
+        let power_total = request_current_total_power(rt)?;
+
+        let (pledge_delta, mut to_burn, power_delta, to_reward) =
+            rt.transaction(|st: &mut State, rt| {
+                let policy = rt.policy();
+                let dl_info = st.deadline_info(policy, current_epoch);
+
+                if !deadline_available_for_optimistic_post_dispute(
+                    policy,
+                    dl_info.period_start,
+                    params.deadline,
+                    current_epoch,
+                ) {
+                    return Err(actor_error!(
+                        forbidden,
+                        "can only dispute window posts during the dispute window\
+                    ({} epochs after the challenge window closes)",
+                        policy.wpost_dispute_window
+                    ));
+                }
+
+                let info = get_miner_info(rt.store(), st)?;
+                // --- check proof ---
+
+                // Find the proving period start for the deadline in question.
+                let mut pp_start = dl_info.period_start;
+                if dl_info.index < params.deadline as u64 {
+                    pp_start -= policy.wpost_proving_period
+                }
+                let target_deadline =
+                    new_deadline_info(policy, pp_start, params.deadline, current_epoch);
+                // Load the target deadline
+                let mut deadlines_current = st
+                    .load_deadlines(rt.store())
+                    .map_err(|e| e.wrap("failed to load deadlines"))?;
+
+                let mut dl_current = deadlines_current
+                    .load_deadline(policy, rt.store(), params.deadline)
+                    .map_err(|e| {
+                        e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load deadline")
+                    })?;
+
+                // Take the post from the snapshot for dispute.
+                // This operation REMOVES the PoSt from the snapshot so
+                // it can't be disputed again. If this method fails,
+                // this operation must be rolled back.
+                let (partitions, proofs) =
+                    dl_current.take_post_proofs(rt.store(), params.post_index).map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            "failed to load proof for dispute",
+                        )
+                    })?;
+
+                // Load the partition info we need for the dispute.
+                let mut dispute_info = dl_current
+                    .load_partitions_for_dispute(rt.store(), partitions)
+                    .map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            "failed to load partition for dispute",
+                        )
+                    })?;
+
+                // This includes power that is no longer active (e.g., due to sector terminations).
+                // It must only be used for penalty calculations, not power adjustments.
+                let penalised_power = dispute_info.disputed_power.clone();
+
+                // Load sectors for the dispute.
+                let sectors =
+                    Sectors::load(rt.store(), &dl_current.sectors_snapshot).map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            "failed to load sectors array",
+                        )
+                    })?;
+                let sector_infos = sectors
+                    .load_for_proof(&dispute_info.all_sector_nos, &dispute_info.ignored_sector_nos)
+                    .map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            "failed to load sectors to dispute window post",
+                        )
+                    })?;
 
                 // Check proof, we fail if validation succeeds.
                 if verify_windowed_post(rt, target_deadline.challenge, &sector_infos, proofs)? {
@@ -1774,7 +2442,8 @@ impl Actor {
                     illegal_argument,
                     "duplicate sector number {}",
                     precommit.sector_number
-                ));
+                )
+                .with_reason(reason::DUPLICATE_SECTOR_NUMBER, precommit.sector_number));
             }
             sector_numbers.set(precommit.sector_number);
 
@@ -1790,7 +2459,8 @@ impl Actor {
                     illegal_argument,
                     "sector number {} out of range 0..(2^63-1)",
                     precommit.sector_number
-                ));
+                )
+                .with_reason(reason::INVALID_SECTOR_NUMBER, precommit.sector_number));
             }
             // Skip checking if CID is defined because it cannot be so in Rust
 
@@ -1927,7 +2597,9 @@ impl Actor {
                     None => CompactCommD::new(deal_data.commd),
                     Some(x) => x,
                 };
-                if commd.0 != deal_data.commd {
+                // A sector without deals may declare any CommD, describing content that will be
+                // listed in piece manifests at activation.
+                if !precommit.deal_ids.is_empty() && commd.0 != deal_data.commd {
                     return Err(actor_error!(illegal_argument, "computed {:?} and passed {:?} CommDs not equal",
                             deal_data.commd, commd));
                 }
@@ -1948,6 +2620,7 @@ impl Actor {
                     info: on_chain_precommit,
                     pre_commit_deposit: deposit_req.clone(),
                     pre_commit_epoch: curr_epoch,
+                    extension: 0,
                 });
 
                 total_deposit_required += &deposit_req;
@@ -1964,7 +2637,11 @@ impl Actor {
             }
             // Batch update actor state.
             if available_balance < total_deposit_required {
-                return Err(actor_error!(insufficient_funds, "insufficient funds {} for pre-commit deposit: {}", available_balance, total_deposit_required));
+                return Err(actor_error!(insufficient_funds, "insufficient funds {} for pre-commit deposit: {}", available_balance, total_deposit_required)
+                    .with_reason(
+                        reason::INSUFFICIENT_FUNDS_FOR_DEPOSIT,
+                        InsufficientBalance { required: total_deposit_required, available: available_balance },
+                    ));
             }
             state.add_pre_commit_deposit(&total_deposit_required)
                 .map_err(|e|
@@ -2004,6 +2681,198 @@ impl Actor {
         Ok(())
     }
 
+    /// Extends the proving deadline of pre-committed sectors beyond the maximum prove-commit duration.
+    /// The caller tops up each sector's pre-commit deposit in proportion to the added epochs.
+    /// The new deadline must still fall within the randomness lookback of the sector's seal
+    /// randomness epoch, and the sector's expiration must remain valid for activation at the new deadline.
+    fn extend_pre_commits(
+        rt: &mut impl Runtime,
+        params: ExtendPreCommitsParams,
+    ) -> Result<(), ActorError> {
+        let curr_epoch = rt.curr_epoch();
+        {
+            let policy = rt.policy();
+            if params.extensions.is_empty() {
+                return Err(actor_error!(illegal_argument, "batch empty"));
+            } else if params.extensions.len() > policy.pre_commit_sector_batch_max_size {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "batch of {} too large, max {}",
+                    params.extensions.len(),
+                    policy.pre_commit_sector_batch_max_size
+                ));
+            }
+        }
+        let mut sector_numbers = BitField::new();
+        for extension in params.extensions.iter() {
+            if sector_numbers.get(extension.sector_number) {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "duplicate sector number {}",
+                    extension.sector_number
+                )
+                .with_reason(reason::DUPLICATE_SECTOR_NUMBER, extension.sector_number));
+            }
+            sector_numbers.set(extension.sector_number);
+        }
+
+        let reward_stats = request_current_epoch_block_reward(rt)?;
+        let power_total = request_current_total_power(rt)?;
+
+        let mut fee_to_burn = TokenAmount::zero();
+        rt.transaction(|state: &mut State, rt| {
+            let available_balance =
+                state.get_available_balance(&rt.current_balance()).map_err(|e| {
+                    actor_error!(illegal_state, "failed to calculate available balance: {}", e)
+                })?;
+            fee_to_burn = repay_debts_or_abort(rt, state)?;
+
+            let info = get_miner_info(rt.store(), state)?;
+            rt.validate_immediate_caller_is(
+                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+            )?;
+            if consensus_fault_active(&info, curr_epoch) {
+                return Err(actor_error!(
+                    forbidden,
+                    "pre-commit extension not allowed during active consensus fault"
+                ));
+            }
+
+            let store = rt.store();
+            let policy = rt.policy();
+            let deposit_req = pre_commit_deposit_for_power(
+                &reward_stats.this_epoch_reward_smoothed,
+                &power_total.quality_adj_power_smoothed,
+                &qa_power_max(info.sector_size),
+            );
+
+            let mut updated = Vec::with_capacity(params.extensions.len());
+            let mut total_deposit_required = TokenAmount::zero();
+            let mut clean_up_events = Vec::with_capacity(params.extensions.len());
+            for extension in params.extensions {
+                let sector_number = extension.sector_number;
+                let mut precommit = state
+                    .get_precommitted_sector(store, sector_number)
+                    .map_err(|e| {
+                        e.downcast_default(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            format!("failed to load pre-committed sector {}", sector_number),
+                        )
+                    })?
+                    .ok_or_else(|| {
+                        actor_error!(not_found, "no pre-commited sector {}", sector_number)
+                            .with_reason(reason::PRECOMMIT_NOT_FOUND, sector_number)
+                    })?;
+
+                let seal_proof = precommit.info.seal_proof;
+                let msd = max_prove_commit_duration(policy, seal_proof).ok_or_else(|| {
+                    actor_error!(
+                        illegal_state,
+                        "no max seal duration set for proof type: {}",
+                        i64::from(seal_proof)
+                    )
+                })?;
+                let prev_due = precommit.pre_commit_epoch + msd + precommit.extension;
+                if curr_epoch > prev_due {
+                    return Err(actor_error!(
+                        forbidden,
+                        "pre-commit for sector {} expired at {}",
+                        sector_number,
+                        prev_due
+                    )
+                    .with_reason(reason::PRECOMMIT_EXPIRED, sector_number));
+                }
+                if extension.prove_commit_due <= prev_due {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "new prove-commit due {} for sector {} must be after {}",
+                        extension.prove_commit_due,
+                        sector_number,
+                        prev_due
+                    ));
+                }
+
+                // The seal randomness must still be within the lookback at the latest epoch
+                // the sector could have been pre-committed with an unextended deadline.
+                let max_due =
+                    precommit.info.seal_rand_epoch + policy.max_pre_commit_randomness_lookback + msd;
+                if extension.prove_commit_due > max_due {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "new prove-commit due {} for sector {} exceeds seal randomness lookback, max {}",
+                        extension.prove_commit_due,
+                        sector_number,
+                        max_due
+                    ));
+                }
+                validate_expiration(
+                    policy,
+                    curr_epoch,
+                    extension.prove_commit_due,
+                    precommit.info.expiration,
+                    seal_proof,
+                )?;
+
+                // Top up the deposit in proportion to the added epochs, rounding up.
+                let added_epochs = extension.prove_commit_due - prev_due;
+                let top_up = TokenAmount::from_atto(
+                    (deposit_req.atto() * added_epochs).div_ceil(&BigInt::from(msd)),
+                );
+                total_deposit_required += &top_up;
+
+                precommit.pre_commit_deposit += top_up;
+                precommit.extension = extension.prove_commit_due - precommit.pre_commit_epoch - msd;
+                clean_up_events.push((
+                    extension.prove_commit_due + policy.expired_pre_commit_clean_up_delay,
+                    sector_number,
+                ));
+                updated.push(precommit);
+            }
+
+            if available_balance < total_deposit_required {
+                return Err(actor_error!(
+                    insufficient_funds,
+                    "insufficient funds {} for pre-commit deposit top-up: {}",
+                    available_balance,
+                    total_deposit_required
+                )
+                .with_reason(
+                    reason::INSUFFICIENT_FUNDS_FOR_DEPOSIT,
+                    InsufficientBalance {
+                        required: total_deposit_required,
+                        available: available_balance,
+                    },
+                ));
+            }
+            state.add_pre_commit_deposit(&total_deposit_required).map_err(|e| {
+                actor_error!(
+                    illegal_state,
+                    "failed to add pre-commit deposit {}: {}",
+                    total_deposit_required,
+                    e
+                )
+            })?;
+            state.update_precommitted_sectors(store, updated).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    "failed to write pre-committed sectors",
+                )
+            })?;
+            state.add_pre_commit_clean_ups(policy, store, clean_up_events).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    "failed to add pre-commit expiry to queue",
+                )
+            })?;
+            Ok(())
+        })?;
+
+        burn_funds(rt, fee_to_burn)?;
+        let state: State = rt.state()?;
+        state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
+        Ok(())
+    }
+
     /// Checks state of the corresponding sector pre-commitment, then schedules the proof to be verified in bulk
     /// by the power actor.
     /// If valid, the power actor will call ConfirmSectorProofsValid at the end of the same epoch as this message.
@@ -2014,7 +2883,8 @@ impl Actor {
         rt.validate_immediate_caller_accept_any()?;
 
         if params.sector_number > MAX_SECTOR_NUMBER {
-            return Err(actor_error!(illegal_argument, "sector number greater than maximum"));
+            return Err(actor_error!(illegal_argument, "sector number greater than maximum")
+                .with_reason(reason::INVALID_SECTOR_NUMBER, params.sector_number));
         }
 
         let sector_number = params.sector_number;
@@ -2028,7 +2898,10 @@ impl Actor {
                     format!("failed to load pre-committed sector {}", sector_number),
                 )
             })?
-            .ok_or_else(|| actor_error!(not_found, "no pre-commited sector {}", sector_number))?;
+            .ok_or_else(|| {
+                actor_error!(not_found, "no pre-commited sector {}", sector_number)
+                    .with_reason(reason::PRECOMMIT_NOT_FOUND, sector_number)
+            })?;
 
         let max_proof_size = precommit.info.seal_proof.proof_size().map_err(|e| {
             actor_error!(
@@ -2047,15 +2920,13 @@ impl Actor {
             ));
         }
 
-        let msd =
-            max_prove_commit_duration(rt.policy(), precommit.info.seal_proof).ok_or_else(|| {
-                actor_error!(
-                    illegal_state,
-                    "no max seal duration set for proof type: {:?}",
-                    precommit.info.seal_proof
-                )
-            })?;
-        let prove_commit_due = precommit.pre_commit_epoch + msd;
+        let prove_commit_due = prove_commit_due(rt.policy(), &precommit).ok_or_else(|| {
+            actor_error!(
+                illegal_state,
+                "no max seal duration set for proof type: {:?}",
+                precommit.info.seal_proof
+            )
+        })?;
         if rt.curr_epoch() > prove_commit_due {
             return Err(actor_error!(
                 illegal_argument,
@@ -2066,6 +2937,8 @@ impl Actor {
             ));
         }
 
+        verify_activated_pieces(rt, &precommit.info, &[])?;
+
         let svi = get_verify_info(
             rt,
             SealVerifyParams {
@@ -2119,10 +2992,12 @@ impl Actor {
         confirm_sector_proofs_valid_internal(
             rt,
             precommited_sectors,
+            &BTreeMap::new(),
             &params.reward_baseline_power,
             &params.reward_smoothed,
             &params.quality_adj_power_smoothed,
-        )
+        )?;
+        Ok(())
     }
 
     fn check_sector_proven(
@@ -2132,7 +3007,8 @@ impl Actor {
         rt.validate_immediate_caller_accept_any()?;
 
         if params.sector_number > MAX_SECTOR_NUMBER {
-            return Err(actor_error!(illegal_argument, "sector number out of range"));
+            return Err(actor_error!(illegal_argument, "sector number out of range")
+                .with_reason(reason::INVALID_SECTOR_NUMBER, params.sector_number));
         }
 
         let st: State = rt.state()?;
@@ -2144,7 +3020,8 @@ impl Actor {
                 params.sector_number,
                 e
             )),
-            Ok(None) => Err(actor_error!(not_found, "sector {} not proven", params.sector_number)),
+            Ok(None) => Err(actor_error!(not_found, "sector {} not proven", params.sector_number)
+                .with_reason(reason::SECTOR_NOT_FOUND, params.sector_number)),
             Ok(Some(_sector)) => Ok(()),
         }
     }
@@ -2867,16 +3744,171 @@ impl Actor {
             if !deadline_available_for_compaction(
                 policy,
                 state.current_proving_period_start(policy, rt.curr_epoch()),
-                params_deadline,
+                params_deadline,
+                rt.curr_epoch(),
+            ) {
+                return Err(actor_error!(
+                    forbidden,
+                    "cannot compact deadline {} during its challenge window, \
+                    or the prior challenge window,
+                    or before {} epochs have passed since its last challenge window ended",
+                    params_deadline,
+                    policy.wpost_dispute_window
+                ));
+            }
+
+            let submission_partition_limit =
+                load_partitions_sectors_max(policy, info.window_post_partition_sectors);
+            if partition_count > submission_partition_limit {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "too many partitions {}, limit {}",
+                    partition_count,
+                    submission_partition_limit
+                ));
+            }
+
+            let quant = state.quant_spec_for_deadline(policy, params_deadline);
+            let mut deadlines =
+                state.load_deadlines(store).map_err(|e| e.wrap("failed to load deadlines"))?;
+
+            let mut deadline =
+                deadlines.load_deadline(policy, store, params_deadline).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to load deadline {}", params_deadline),
+                    )
+                })?;
+
+            let (live, dead, removed_power) =
+                deadline.remove_partitions(store, partitions, quant).map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to remove partitions from deadline {}", params_deadline),
+                    )
+                })?;
+
+            state.delete_sectors(store, &dead).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to delete dead sectors")
+            })?;
+
+            let sectors = state.load_sector_infos(store, &live).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load moved sectors")
+            })?;
+            let proven = true;
+            let added_power = deadline
+                .add_sectors(
+                    store,
+                    info.window_post_partition_sectors,
+                    proven,
+                    &sectors,
+                    info.sector_size,
+                    quant,
+                )
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        "failed to add back moved sectors",
+                    )
+                })?;
+
+            if removed_power != added_power {
+                return Err(actor_error!(
+                    illegal_state,
+                    "power changed when compacting partitions: was {:?}, is now {:?}",
+                    removed_power,
+                    added_power
+                ));
+            }
+
+            deadlines.update_deadline(policy, store, params_deadline, &deadline).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to update deadline {}", params_deadline),
+                )
+            })?;
+
+            state.save_deadlines(store, deadlines).map_err(|e| {
+                e.downcast_default(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to save deadline {}", params_deadline),
+                )
+            })?;
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Moves a number of partitions, with all their sectors, from one deadline to another.
+    /// The partitions are removed from the origin deadline, shifting the remaining ones,
+    /// and appended to the destination deadline. Faults, recoveries and terminated sectors
+    /// move with their partitions, and power is unchanged.
+    /// The destination deadline must be due for proof before the origin deadline, so that
+    /// no sector goes unproven for longer than a proving period.
+    /// May not be invoked if the origin deadline has any un-processed early terminations.
+    fn move_partitions(
+        rt: &mut impl Runtime,
+        params: MovePartitionsParams,
+    ) -> Result<(), ActorError> {
+        {
+            let policy = rt.policy();
+            if params.orig_deadline >= policy.wpost_period_deadlines {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "invalid origin deadline {}",
+                    params.orig_deadline
+                ));
+            }
+            if params.dest_deadline >= policy.wpost_period_deadlines {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "invalid destination deadline {}",
+                    params.dest_deadline
+                ));
+            }
+        }
+        if params.orig_deadline == params.dest_deadline {
+            return Err(actor_error!(
+                illegal_argument,
+                "origin and destination deadlines must differ, both are {}",
+                params.orig_deadline
+            ));
+        }
+
+        let partitions = params.partitions.validate().map_err(|e| {
+            actor_error!(illegal_argument, "failed to parse partitions bitfield: {}", e)
+        })?;
+        let partition_count = partitions.len();
+        if partition_count == 0 {
+            return Err(actor_error!(illegal_argument, "no partitions to move"));
+        }
+
+        rt.transaction(|state: &mut State, rt| {
+            let info = get_miner_info(rt.store(), state)?;
+
+            rt.validate_immediate_caller_is(
+                info.control_addresses.iter().chain(&[info.worker, info.owner]),
+            )?;
+
+            let store = rt.store();
+            let policy = rt.policy();
+
+            if !deadline_available_for_move(
+                policy,
+                state.current_proving_period_start(policy, rt.curr_epoch()),
+                params.orig_deadline,
+                params.dest_deadline,
                 rt.curr_epoch(),
             ) {
                 return Err(actor_error!(
                     forbidden,
-                    "cannot compact deadline {} during its challenge window, \
-                    or the prior challenge window,
-                    or before {} epochs have passed since its last challenge window ended",
-                    params_deadline,
-                    policy.wpost_dispute_window
+                    "cannot move partitions from deadline {} to deadline {}: both must be \
+                    outside their challenge windows, the origin's proofs must no longer be \
+                    disputable, and the destination must be due before the origin",
+                    params.orig_deadline,
+                    params.dest_deadline
                 ));
             }
 
@@ -2891,71 +3923,70 @@ impl Actor {
                 ));
             }
 
-            let quant = state.quant_spec_for_deadline(policy, params_deadline);
+            let orig_quant = state.quant_spec_for_deadline(policy, params.orig_deadline);
+            let dest_quant = state.quant_spec_for_deadline(policy, params.dest_deadline);
             let mut deadlines =
                 state.load_deadlines(store).map_err(|e| e.wrap("failed to load deadlines"))?;
 
-            let mut deadline =
-                deadlines.load_deadline(policy, store, params_deadline).map_err(|e| {
+            let mut orig_deadline =
+                deadlines.load_deadline(policy, store, params.orig_deadline).map_err(|e| {
                     e.downcast_default(
                         ExitCode::USR_ILLEGAL_STATE,
-                        format!("failed to load deadline {}", params_deadline),
+                        format!("failed to load deadline {}", params.orig_deadline),
                     )
                 })?;
-
-            let (live, dead, removed_power) =
-                deadline.remove_partitions(store, partitions, quant).map_err(|e| {
+            let mut dest_deadline =
+                deadlines.load_deadline(policy, store, params.dest_deadline).map_err(|e| {
                     e.downcast_default(
                         ExitCode::USR_ILLEGAL_STATE,
-                        format!("failed to remove partitions from deadline {}", params_deadline),
+                        format!("failed to load deadline {}", params.dest_deadline),
                     )
                 })?;
 
-            state.delete_sectors(store, &dead).map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to delete dead sectors")
+            let sectors = Sectors::load(store, &state.sectors).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load sectors")
             })?;
 
-            let sectors = state.load_sector_infos(store, &live).map_err(|e| {
-                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to load moved sectors")
-            })?;
-            let proven = true;
-            let added_power = deadline
-                .add_sectors(
+            orig_deadline
+                .move_partitions(
+                    policy,
                     store,
-                    info.window_post_partition_sectors,
-                    proven,
+                    &mut dest_deadline,
+                    partitions,
                     &sectors,
                     info.sector_size,
-                    quant,
+                    orig_quant,
+                    dest_quant,
                 )
                 .map_err(|e| {
                     e.downcast_default(
                         ExitCode::USR_ILLEGAL_STATE,
-                        "failed to add back moved sectors",
+                        format!(
+                            "failed to move partitions from deadline {} to deadline {}",
+                            params.orig_deadline, params.dest_deadline
+                        ),
                     )
                 })?;
 
-            if removed_power != added_power {
-                return Err(actor_error!(
-                    illegal_state,
-                    "power changed when compacting partitions: was {:?}, is now {:?}",
-                    removed_power,
-                    added_power
-                ));
-            }
-
-            deadlines.update_deadline(policy, store, params_deadline, &deadline).map_err(|e| {
-                e.downcast_default(
-                    ExitCode::USR_ILLEGAL_STATE,
-                    format!("failed to update deadline {}", params_deadline),
-                )
-            })?;
+            deadlines
+                .update_deadline(policy, store, params.orig_deadline, &orig_deadline)
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to update deadline {}", params.orig_deadline),
+                    )
+                })?;
+            deadlines
+                .update_deadline(policy, store, params.dest_deadline, &dest_deadline)
+                .map_err(|e| {
+                    e.downcast_default(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to update deadline {}", params.dest_deadline),
+                    )
+                })?;
 
             state.save_deadlines(store, deadlines).map_err(|e| {
-                e.downcast_default(
-                    ExitCode::USR_ILLEGAL_STATE,
-                    format!("failed to save deadline {}", params_deadline),
-                )
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save deadlines")
             })?;
 
             Ok(())
@@ -3047,6 +4078,10 @@ impl Actor {
                     "insufficient funds to lock, available: {}, requested: {}",
                     unlocked_balance,
                     reward_to_lock
+                )
+                .with_reason(
+                    reason::INSUFFICIENT_FUNDS_FOR_REWARD,
+                    InsufficientBalance { required: reward_to_lock, available: unlocked_balance },
                 ));
             }
 
@@ -3207,12 +4242,16 @@ impl Actor {
             ));
         }
 
-        let (info, amount_withdrawn, newly_vested, fee_to_burn, state) =
+        let (payouts, amount_withdrawn, newly_vested, fee_to_burn, state) =
             rt.transaction(|state: &mut State, rt| {
                 let mut info = get_miner_info(rt.store(), state)?;
 
-                // Only the owner or the beneficiary is allowed to withdraw the balance.
-                rt.validate_immediate_caller_is(&[info.owner, info.beneficiary])?;
+                // Only the owner or a beneficiary is allowed to withdraw the balance.
+                let mut withdrawers = vec![info.owner, info.beneficiary];
+                if let Some(split) = &info.beneficiary_split {
+                    withdrawers.extend(split.beneficiaries.iter().map(|b| b.beneficiary));
+                }
+                rt.validate_immediate_caller_is(&withdrawers)?;
 
                 // Ensure we don't have any pending terminations.
                 if !state.early_terminations.is_empty() {
@@ -3253,7 +4292,26 @@ impl Actor {
                         amount_withdrawn
                     ));
                 }
-                if info.beneficiary != info.owner {
+                if let Some(split) = info.beneficiary_split.as_mut() {
+                    let remaining_quota = split.available(rt.curr_epoch());
+                    if remaining_quota.is_zero() {
+                        return Err(actor_error!(
+                            forbidden,
+                            "beneficiary split expired or quotas depleted"
+                        ));
+                    }
+                    amount_withdrawn = std::cmp::min(amount_withdrawn, &remaining_quota);
+                    let payouts = split.distribute(amount_withdrawn, rt.curr_epoch());
+                    if amount_withdrawn.is_positive() {
+                        state.save_info(rt.store(), &info).map_err(|e| {
+                            e.downcast_default(
+                                ExitCode::USR_ILLEGAL_STATE,
+                                "failed to save miner info",
+                            )
+                        })?;
+                    }
+                    Ok((payouts, amount_withdrawn.clone(), newly_vested, fee_to_burn, state.clone()))
+                } else if info.beneficiary != info.owner {
                     // remaining_quota always zero and positive
                     let remaining_quota = info.beneficiary_term.available(rt.curr_epoch());
                     if remaining_quota.is_zero() {
@@ -3275,19 +4333,18 @@ impl Actor {
                             )
                         })?;
                     }
-                    Ok((info, amount_withdrawn.clone(), newly_vested, fee_to_burn, state.clone()))
+                    let payouts = vec![(info.beneficiary, amount_withdrawn.clone())];
+                    Ok((payouts, amount_withdrawn.clone(), newly_vested, fee_to_burn, state.clone()))
                 } else {
-                    Ok((info, amount_withdrawn.clone(), newly_vested, fee_to_burn, state.clone()))
+                    let payouts = vec![(info.beneficiary, amount_withdrawn.clone())];
+                    Ok((payouts, amount_withdrawn.clone(), newly_vested, fee_to_burn, state.clone()))
                 }
             })?;
 
-        if amount_withdrawn.is_positive() {
-            extract_send_result(rt.send_simple(
-                &info.beneficiary,
-                METHOD_SEND,
-                None,
-                amount_withdrawn.clone(),
-            ))?;
+        for (beneficiary, amount) in payouts {
+            if amount.is_positive() {
+                extract_send_result(rt.send_simple(&beneficiary, METHOD_SEND, None, amount))?;
+            }
         }
 
         burn_funds(rt, fee_to_burn)?;
@@ -3321,6 +4378,12 @@ impl Actor {
             if caller == info.owner {
                 // This is a ChangeBeneficiary proposal when the caller is Owner
                 if new_beneficiary != info.owner {
+                    if info.beneficiary_split.is_some() {
+                        return Err(actor_error!(
+                            forbidden,
+                            "cannot nominate a beneficiary while a beneficiary split is active"
+                        ));
+                    }
                     // When beneficiary is not owner, just check quota in params,
                     // Expiration maybe an expiration value, but wouldn't cause problem, just the new beneficiary never get any benefit
                     if !params.new_quota.is_positive() {
@@ -3445,6 +4508,170 @@ impl Actor {
         })
     }
 
+    /// Returns the active beneficiary split, if any, and any proposed change to it.
+    fn get_beneficiaries(rt: &mut impl Runtime) -> Result<GetBeneficiariesReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+        let info = get_miner_info(rt.store(), &st)?;
+
+        Ok(GetBeneficiariesReturn {
+            split: info.beneficiary_split,
+            proposed_split: info.pending_beneficiary_split,
+        })
+    }
+
+    /// Proposes or confirms a change to the set of beneficiaries sharing the miner's withdrawals.
+    /// A proposal must be submitted by the owner, and takes effect after approval of every nominated
+    /// beneficiary and every current split beneficiary that has time and quota remaining.
+    /// An empty proposal removes the split, returning withdrawals to the owner.
+    /// A split may not be proposed while a single beneficiary other than the owner is active.
+    fn change_beneficiaries(
+        rt: &mut impl Runtime,
+        params: ChangeBeneficiariesParams,
+    ) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let caller = rt.message().caller();
+
+        if params.beneficiaries.len() > MAX_BENEFICIARIES {
+            return Err(actor_error!(
+                illegal_argument,
+                "too many beneficiaries {}, limit {}",
+                params.beneficiaries.len(),
+                MAX_BENEFICIARIES
+            ));
+        }
+        let mut beneficiaries = Vec::with_capacity(params.beneficiaries.len());
+        for share in params.beneficiaries {
+            let beneficiary =
+                Address::new_id(rt.resolve_address(&share.beneficiary).ok_or_else(|| {
+                    actor_error!(
+                        illegal_argument,
+                        "unable to resolve address: {}",
+                        share.beneficiary
+                    )
+                })?);
+            beneficiaries.push(BeneficiaryShare { beneficiary, ..share });
+        }
+
+        rt.transaction(|state: &mut State, rt| {
+            let mut info = get_miner_info(rt.store(), state)?;
+            let curr_epoch = rt.curr_epoch();
+            if caller == info.owner {
+                // This is a ChangeBeneficiaries proposal when the caller is Owner
+                for (i, share) in beneficiaries.iter().enumerate() {
+                    if share.beneficiary == info.owner {
+                        return Err(actor_error!(
+                            illegal_argument,
+                            "owner {} cannot be a split beneficiary",
+                            info.owner
+                        ));
+                    }
+                    if !share.quota.is_positive() {
+                        return Err(actor_error!(
+                            illegal_argument,
+                            "beneficial quota {} of {} must bigger than zero",
+                            share.quota,
+                            share.beneficiary
+                        ));
+                    }
+                    if beneficiaries[..i].iter().any(|b| b.beneficiary == share.beneficiary) {
+                        return Err(actor_error!(
+                            illegal_argument,
+                            "duplicate beneficiary {}",
+                            share.beneficiary
+                        ));
+                    }
+                }
+                if info.beneficiary != info.owner
+                    && !info.beneficiary_term.available(curr_epoch).is_zero()
+                {
+                    return Err(actor_error!(
+                        forbidden,
+                        "cannot propose a beneficiary split while beneficiary {} is active",
+                        info.beneficiary
+                    ));
+                }
+
+                // Current beneficiaries with quota remaining and all nominees must approve.
+                let mut pending_approvals: Vec<Address> = info
+                    .beneficiary_split
+                    .iter()
+                    .flat_map(|split| split.effective(curr_epoch))
+                    .map(|b| b.beneficiary)
+                    .collect();
+                for share in &beneficiaries {
+                    if !pending_approvals.contains(&share.beneficiary) {
+                        pending_approvals.push(share.beneficiary);
+                    }
+                }
+                info.pending_beneficiary_split = Some(PendingBeneficiarySplit {
+                    payout: params.payout,
+                    beneficiaries,
+                    pending_approvals,
+                });
+            } else if let Some(pending) = info.pending_beneficiary_split.as_mut() {
+                if pending.payout != params.payout || pending.beneficiaries != beneficiaries {
+                    return Err(actor_error!(
+                        illegal_argument,
+                        "beneficiary split does not match the pending proposal"
+                    ));
+                }
+                let idx = pending.pending_approvals.iter().position(|a| *a == caller).ok_or_else(
+                    || {
+                        actor_error!(
+                            forbidden,
+                            "message caller {} is not required to approve the beneficiary split",
+                            caller
+                        )
+                    },
+                )?;
+                pending.pending_approvals.remove(idx);
+            } else {
+                return Err(actor_error!(forbidden, "No changeBeneficiaries proposal exists"));
+            }
+
+            let approved = info
+                .pending_beneficiary_split
+                .as_ref()
+                .map_or(false, |pending| pending.pending_approvals.is_empty());
+            if let Some(pending) = approved.then(|| info.pending_beneficiary_split.take()).flatten()
+            {
+                // approved by all nominees and effective current beneficiaries
+                let previous = info.beneficiary_split.take();
+                let beneficiaries: Vec<SplitBeneficiary> = pending
+                    .beneficiaries
+                    .into_iter()
+                    .map(|share| {
+                        // Continuing beneficiaries keep their used quota.
+                        let used_quota = previous
+                            .iter()
+                            .flat_map(|split| split.beneficiaries.iter())
+                            .find(|b| b.beneficiary == share.beneficiary)
+                            .map(|b| b.term.used_quota.clone())
+                            .unwrap_or_else(TokenAmount::zero);
+                        SplitBeneficiary {
+                            beneficiary: share.beneficiary,
+                            term: BeneficiaryTerm::new(share.quota, used_quota, share.expiration),
+                        }
+                    })
+                    .collect();
+                if !beneficiaries.is_empty() {
+                    // The split replaces any single beneficiary.
+                    info.beneficiary = info.owner;
+                    info.beneficiary_term = BeneficiaryTerm::default();
+                    info.pending_beneficiary_term = None;
+                    info.beneficiary_split =
+                        Some(BeneficiarySplit { payout: pending.payout, beneficiaries });
+                }
+            }
+
+            state.save_info(rt.store(), &info).map_err(|e| {
+                e.downcast_default(ExitCode::USR_ILLEGAL_STATE, "failed to save miner info")
+            })?;
+            Ok(())
+        })
+    }
+
     fn repay_debt(rt: &mut impl Runtime) -> Result<(), ActorError> {
         let (from_vesting, from_balance, state) = rt.transaction(|state: &mut State, rt| {
             let info = get_miner_info(rt.store(), state)?;
@@ -3535,6 +4762,8 @@ pub struct ReplicaUpdateInner {
     /// None means unknown
     pub new_unsealed_cid: Option<Cid>,
     pub deals: Vec<DealID>,
+    /// Empty for entry points that don't accept piece manifests
+    pub pieces: Vec<PieceActivationManifest>,
     pub update_proof_type: RegisteredUpdateProof,
     pub replica_proof: Vec<u8>,
 }
@@ -3637,7 +4866,7 @@ fn validate_extension_declarations(
                 .with_context(|| format!("failed to get claims for sector {}", sc.sector_number))?;
             let first_drop = sc.maintain_claims.len();
 
-            for (i, claim) in claims.iter().enumerate() {
+            for (i, (claim, transferred)) in claims.iter().enumerate() {
                 // check provider and sector matches
                 if claim.provider != rt.message().receiver().id().unwrap() {
                     return Err(actor_error!(illegal_argument, "failed to validate declaration sector={}, claim={}, expected claim provider to be {} but found {} ", sc.sector_number, all_claim_ids[i], rt.message().receiver().id().unwrap(), claim.provider));
@@ -3646,6 +4875,15 @@ fn validate_extension_declarations(
                     return Err(actor_error!(illegal_argument, "failed to validate declaration sector={}, claim={} expected claim sector number to be {} but found {} ", sc.sector_number, all_claim_ids[i], sc.sector_number, claim.sector));
                 }
 
+                // A claim transferred to another provider no longer carries weight in the
+                // sector, so it can only be dropped, which has no effect.
+                if *transferred {
+                    if i < first_drop {
+                        return Err(actor_error!(forbidden, "failed to validate declaration sector={}, claim={} cannot maintain a claim that has been transferred to another provider", sc.sector_number, sc.maintain_claims[i]));
+                    }
+                    continue;
+                }
+
                 // If we are not dropping check expiration does not exceed term max
                 let mut maintain_delta: u64 = 0;
                 if i < first_drop {
@@ -4337,6 +5575,122 @@ fn request_deal_data(
     ))?)
 }
 
+/// Requests the piece of each deal from the market actor.
+fn request_deal_pieces(
+    rt: &mut impl Runtime,
+    deal_ids: &[DealID],
+) -> Result<Vec<PieceInfo>, ActorError> {
+    deal_ids
+        .iter()
+        .map(|&id| {
+            let commitment: ext::market::GetDealDataCommitmentReturn =
+                deserialize_block(extract_send_result(rt.send_simple(
+                    &STORAGE_MARKET_ACTOR_ADDR,
+                    ext::market::GET_DEAL_DATA_COMMITMENT_METHOD,
+                    IpldBlock::serialize_cbor(&ext::market::DealQueryParams { id })?,
+                    TokenAmount::zero(),
+                ))?)?;
+            Ok(PieceInfo { cid: commitment.data, size: commitment.size })
+        })
+        .collect()
+}
+
+/// The weight that deals dropped from a sector would have contributed to it over the rest of
+/// their terms. Verified deals' weights are kept with their pieces.
+struct DroppedDealWeights {
+    deal_weight: DealWeight,
+    verified_pieces: Vec<(ext::verifreg::DroppedPiece, DealWeight)>,
+}
+
+/// Requests whether each deal is verified, and its term, from the market actor.
+/// A deal contributes its space for each epoch from now until the end of its term,
+/// or the sector's expiration if sooner.
+fn request_remaining_deal_weights(
+    rt: &mut impl Runtime,
+    deal_ids: &[DealID],
+    pieces: &[PieceInfo],
+    curr_epoch: ChainEpoch,
+    sector_expiration: ChainEpoch,
+) -> Result<DroppedDealWeights, ActorError> {
+    let mut weights =
+        DroppedDealWeights { deal_weight: DealWeight::zero(), verified_pieces: vec![] };
+    for (&id, piece) in deal_ids.iter().zip(pieces) {
+        let verified: ext::market::GetDealVerifiedReturn =
+            deserialize_block(extract_send_result(rt.send_simple(
+                &STORAGE_MARKET_ACTOR_ADDR,
+                ext::market::GET_DEAL_VERIFIED_METHOD,
+                IpldBlock::serialize_cbor(&ext::market::DealQueryParams { id })?,
+                TokenAmount::zero(),
+            ))?)?;
+        let term: ext::market::GetDealTermReturn =
+            deserialize_block(extract_send_result(rt.send_simple(
+                &STORAGE_MARKET_ACTOR_ADDR,
+                ext::market::GET_DEAL_TERM_METHOD,
+                IpldBlock::serialize_cbor(&ext::market::DealQueryParams { id })?,
+                TokenAmount::zero(),
+            ))?)?;
+        let end = std::cmp::min(term.start + term.duration, sector_expiration);
+        let weight = DealWeight::from(piece.size.0) * std::cmp::max(end - curr_epoch, 0);
+        if verified.verified {
+            weights
+                .verified_pieces
+                .push((ext::verifreg::DroppedPiece { data: piece.cid, size: piece.size }, weight));
+        } else {
+            weights.deal_weight += weight;
+        }
+    }
+    Ok(weights)
+}
+
+/// Requests the total size of the verified deals among a sector's deals from the market actor.
+fn request_verified_deal_space(
+    rt: &mut impl Runtime,
+    deal_ids: &[DealID],
+) -> Result<u64, ActorError> {
+    let pieces = request_deal_pieces(rt, deal_ids)?;
+    let mut space = 0;
+    for (&id, piece) in deal_ids.iter().zip(&pieces) {
+        let verified: ext::market::GetDealVerifiedReturn =
+            deserialize_block(extract_send_result(rt.send_simple(
+                &STORAGE_MARKET_ACTOR_ADDR,
+                ext::market::GET_DEAL_VERIFIED_METHOD,
+                IpldBlock::serialize_cbor(&ext::market::DealQueryParams { id })?,
+                TokenAmount::zero(),
+            ))?)?;
+        if verified.verified {
+            space += piece.size.0;
+        }
+    }
+    Ok(space)
+}
+
+/// Requests the verified registry to remove this provider's claims for pieces dropped from a sector.
+/// Returns the total weight of the pieces whose claims were removed.
+/// Pieces without a claim are ignored. A claim transferred away to another provider has
+/// already had its weight removed from the sector.
+fn request_remove_sector_claims(
+    rt: &mut impl Runtime,
+    sector: SectorNumber,
+    pieces: Vec<(ext::verifreg::DroppedPiece, DealWeight)>,
+) -> Result<DealWeight, ActorError> {
+    if pieces.is_empty() {
+        return Ok(DealWeight::zero());
+    }
+    let (pieces, weights): (Vec<_>, Vec<_>) = pieces.into_iter().unzip();
+    let params = ext::verifreg::RemoveSectorClaimsParams { sector, pieces };
+    let ret: ext::verifreg::RemoveSectorClaimsReturn = deserialize_block(
+        extract_send_result(rt.send_simple(
+            &VERIFIED_REGISTRY_ACTOR_ADDR,
+            ext::verifreg::REMOVE_SECTOR_CLAIMS_METHOD,
+            IpldBlock::serialize_cbor(&params)?,
+            TokenAmount::zero(),
+        ))
+        .map_err(|e| e.wrap(format!("failed to remove claims dropped from sector {}", sector)))?,
+    )?;
+    let indices: Vec<usize> = (0..weights.len()).collect();
+    Ok(ret.results.successes(&indices).into_iter().map(|i| &weights[i]).sum())
+}
+
 /// Requests the current epoch target block reward from the reward actor.
 /// return value includes reward, smoothed estimate of reward, and baseline power
 fn request_current_epoch_block_reward(
@@ -4428,25 +5782,52 @@ fn notify_pledge_changed(
     Ok(())
 }
 
+// Gets this provider's claims, falling back to the claims transferred away from it for those
+// it no longer holds. Each claim is returned with whether it has been transferred.
 fn get_claims(
     rt: &mut impl Runtime,
     ids: &Vec<ext::verifreg::ClaimID>,
-) -> Result<Vec<ext::verifreg::Claim>, ActorError> {
+) -> Result<Vec<(ext::verifreg::Claim, bool)>, ActorError> {
+    let claims_ret = send_get_claims(rt, ext::verifreg::GET_CLAIMS_METHOD, ids.clone())?;
+    let indices: Vec<usize> = (0..ids.len()).collect();
+    let mut claims: Vec<Option<(ext::verifreg::Claim, bool)>> = vec![None; ids.len()];
+    for (i, claim) in claims_ret.batch_info.successes(&indices).into_iter().zip(claims_ret.claims) {
+        claims[i] = Some((claim, false));
+    }
+
+    let missing: Vec<usize> =
+        claims_ret.batch_info.fail_codes.iter().map(|f| f.idx as usize).collect();
+    if !missing.is_empty() {
+        let missing_ids = missing.iter().map(|i| ids[*i]).collect();
+        let transferred_ret =
+            send_get_claims(rt, ext::verifreg::GET_TRANSFERRED_CLAIMS_METHOD, missing_ids)?;
+        for (i, claim) in
+            transferred_ret.batch_info.successes(&missing).into_iter().zip(transferred_ret.claims)
+        {
+            claims[i] = Some((claim, true));
+        }
+    }
+    claims
+        .into_iter()
+        .collect::<Option<_>>()
+        .ok_or_else(|| actor_error!(illegal_argument, "invalid claims"))
+}
+
+fn send_get_claims(
+    rt: &mut impl Runtime,
+    method: MethodNum,
+    claim_ids: Vec<ext::verifreg::ClaimID>,
+) -> Result<ext::verifreg::GetClaimsReturn, ActorError> {
     let params = ext::verifreg::GetClaimsParams {
         provider: rt.message().receiver().id().unwrap(),
-        claim_ids: ids.clone(),
+        claim_ids,
     };
-    let claims_ret: ext::verifreg::GetClaimsReturn =
-        deserialize_block(extract_send_result(rt.send_simple(
-            &VERIFIED_REGISTRY_ACTOR_ADDR,
-            ext::verifreg::GET_CLAIMS_METHOD as u64,
-            IpldBlock::serialize_cbor(&params)?,
-            TokenAmount::zero(),
-        ))?)?;
-    if (claims_ret.batch_info.success_count as usize) < ids.len() {
-        return Err(actor_error!(illegal_argument, "invalid claims"));
-    }
-    Ok(claims_ret.claims)
+    deserialize_block(extract_send_result(rt.send_simple(
+        &VERIFIED_REGISTRY_ACTOR_ADDR,
+        method,
+        IpldBlock::serialize_cbor(&params)?,
+        TokenAmount::zero(),
+    ))?)
 }
 
 /// Assigns proving period offset randomly in the range [0, WPoStProvingPeriod) by hashing
@@ -4699,10 +6080,11 @@ fn check_peer_info(
 fn confirm_sector_proofs_valid_internal(
     rt: &mut impl Runtime,
     pre_commits: Vec<SectorPreCommitOnChainInfo>,
+    piece_manifests: &BTreeMap<SectorNumber, Vec<PieceActivationManifest>>,
     this_epoch_baseline_power: &BigInt,
     this_epoch_reward_smoothed: &FilterEstimate,
     quality_adj_power_smoothed: &FilterEstimate,
-) -> Result<(), ActorError> {
+) -> Result<Vec<SectorNumber>, ActorError> {
     // get network stats from other actors
     let circulating_supply = rt.total_fil_circ_supply();
 
@@ -4726,7 +6108,12 @@ fn confirm_sector_proofs_valid_internal(
                 );
                 continue;
             }
-            Some(deal_spaces) => valid_pre_commits.push((pre_commit, deal_spaces)),
+            Some(mut deal_spaces) => {
+                if let Some(pieces) = piece_manifests.get(&pre_commit.info.sector_number) {
+                    deal_spaces.verified_deal_space += transferred_claim_space(pieces);
+                }
+                valid_pre_commits.push((pre_commit, deal_spaces))
+            }
         };
     }
 
@@ -4735,7 +6122,7 @@ fn confirm_sector_proofs_valid_internal(
         return Err(actor_error!(illegal_argument, "all prove commits failed to validate"));
     }
 
-    let (total_pledge, newly_vested) = rt.transaction(|state: &mut State, rt| {
+    let (total_pledge, newly_vested, activated) = rt.transaction(|state: &mut State, rt| {
         let policy = rt.policy();
         let store = rt.store();
         let info = get_miner_info(store, state)?;
@@ -4858,6 +6245,10 @@ fn confirm_sector_proofs_valid_internal(
                 "insufficient funds for aggregate initial pledge requirement {}, available: {}",
                 total_pledge,
                 unlocked_balance
+            )
+            .with_reason(
+                reason::INSUFFICIENT_FUNDS_FOR_PLEDGE,
+                InsufficientBalance { required: total_pledge, available: unlocked_balance },
             ));
         }
 
@@ -4867,12 +6258,130 @@ fn confirm_sector_proofs_valid_internal(
 
         state.check_balance_invariants(&rt.current_balance()).map_err(balance_invariants_broken)?;
 
-        Ok((total_pledge, newly_vested))
+        Ok((total_pledge, newly_vested, new_sector_numbers))
     })?;
 
     // Request pledge update for activated sector.
     notify_pledge_changed(rt, &(total_pledge - newly_vested))?;
 
+    Ok(activated)
+}
+
+// Checks a pre-committed sector's unsealed CID against the pieces it is activated with.
+// The CommD of a sector without deals is not checked at pre-commit, so unless the sector is
+// empty its content must be declared by pieces, which must make up the CommD.
+fn verify_activated_pieces(
+    rt: &impl Runtime,
+    precommit: &SectorPreCommitInfo,
+    pieces: &[PieceActivationManifest],
+) -> Result<(), ActorError> {
+    if pieces.is_empty() {
+        if precommit.deal_ids.is_empty() && precommit.unsealed_cid.0.is_some() {
+            return Err(actor_error!(
+                illegal_argument,
+                "sector {} without deals must declare pieces matching its unsealed CID",
+                precommit.sector_number
+            ));
+        }
+        return Ok(());
+    }
+    let unsealed_cid = precommit.unsealed_cid.get_cid(precommit.seal_proof)?;
+    if !pieces_match_unsealed_cid(rt, precommit.seal_proof, pieces, &unsealed_cid)? {
+        return Err(actor_error!(
+            illegal_argument,
+            "pieces do not match unsealed CID {} of sector {}",
+            unsealed_cid,
+            precommit.sector_number
+        ));
+    }
+    Ok(())
+}
+
+// Checks whether the pieces listed in a manifest make up the given unsealed CID.
+fn pieces_match_unsealed_cid(
+    rt: &impl Runtime,
+    seal_proof: RegisteredSealProof,
+    pieces: &[PieceActivationManifest],
+    unsealed_cid: &Cid,
+) -> Result<bool, ActorError> {
+    let piece_infos: Vec<PieceInfo> =
+        pieces.iter().map(|p| PieceInfo { size: p.size, cid: p.cid }).collect();
+    let computed = rt.compute_unsealed_sector_cid(seal_proof, &piece_infos).map_err(|e| {
+        e.downcast_default(ExitCode::USR_ILLEGAL_ARGUMENT, "failed to compute unsealed sector CID")
+    })?;
+    Ok(computed == *unsealed_cid)
+}
+
+// Computes the space of the pieces that notify the verified registry, each of which brings
+// a verified claim transferred from another provider into the sector.
+// The registry rejects any such piece for which it does not transfer a claim.
+fn transferred_claim_space(pieces: &[PieceActivationManifest]) -> BigInt {
+    pieces
+        .iter()
+        .filter(|p| p.notify.iter().any(|n| n.address == VERIFIED_REGISTRY_ACTOR_ADDR))
+        .map(|p| BigInt::from(p.size.0))
+        .sum()
+}
+
+// Sends SectorContentChanged notifications for activated pieces that requested them.
+// Notifications to the same actor are batched into a single message, in order of first appearance.
+// Aborts if any receiver fails or does not accept every piece it was notified of.
+fn notify_data_consumers(
+    rt: &mut impl Runtime,
+    activations: &[(SectorNumber, ChainEpoch, &[PieceActivationManifest])],
+) -> Result<(), ActorError> {
+    let mut notifications = Vec::<(Address, Vec<SectorChanges>)>::new();
+    for (sector_number, expiration, pieces) in activations {
+        for piece in pieces.iter() {
+            for notify in piece.notify.iter() {
+                let idx = match notifications.iter().position(|(addr, _)| *addr == notify.address) {
+                    Some(idx) => idx,
+                    None => {
+                        notifications.push((notify.address, vec![]));
+                        notifications.len() - 1
+                    }
+                };
+                let sectors = &mut notifications[idx].1;
+                let change = PieceChange {
+                    data: piece.cid,
+                    size: piece.size,
+                    payload: notify.payload.clone(),
+                };
+                match sectors.last_mut().filter(|last| last.sector == *sector_number) {
+                    Some(last) => last.added.push(change),
+                    None => sectors.push(SectorChanges {
+                        sector: *sector_number,
+                        minimum_commitment_epoch: *expiration,
+                        added: vec![change],
+                    }),
+                }
+            }
+        }
+    }
+
+    for (receiver, sectors) in notifications {
+        let piece_counts: Vec<usize> = sectors.iter().map(|s| s.added.len()).collect();
+        let ret = extract_send_result(rt.send_simple(
+            &receiver,
+            SECTOR_CONTENT_CHANGED,
+            IpldBlock::serialize_cbor(&SectorContentChangedParams { sectors })?,
+            TokenAmount::zero(),
+        ))
+        .with_context(|| format!("failed to notify {} of sector content changes", receiver))?;
+        let ret: SectorContentChangedReturn = deserialize_block(ret)?;
+
+        let all_accepted = ret.sectors.len() == piece_counts.len()
+            && ret.sectors.iter().zip(piece_counts.iter()).all(|(sector_ret, count)| {
+                sector_ret.added.len() == *count && sector_ret.added.iter().all(|p| p.accepted)
+            });
+        if !all_accepted {
+            return Err(actor_error!(
+                illegal_argument,
+                "sector content change notification rejected by {}",
+                receiver
+            ));
+        }
+    }
     Ok(())
 }
 
@@ -4996,7 +6505,17 @@ impl ActorCode for Actor {
         ChangeBeneficiaryExported => change_beneficiary,
         GetBeneficiary => get_beneficiary,
         GetBeneficiaryExported => get_beneficiary,
+        ChangeBeneficiaries => change_beneficiaries,
+        ChangeBeneficiariesExported => change_beneficiaries,
+        GetBeneficiaries => get_beneficiaries,
+        GetBeneficiariesExported => get_beneficiaries,
         ExtendSectorExpiration2 => extend_sector_expiration2,
+        ProveCommitAggregate2 => prove_commit_aggregate2,
+        ProveReplicaUpdates3 => prove_replica_updates3,
+        MovePartitions => move_partitions,
+        ExtendPreCommits => extend_pre_commits,
+        DropSectorPieces => drop_sector_pieces,
+        ClaimsTransferred => claims_transferred,
         GetOwnerExported => get_owner,
         IsControllingAddressExported => is_controlling_address,
         GetSectorSizeExported => get_sector_size,
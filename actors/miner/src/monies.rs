@@ -12,7 +12,7 @@ use fvm_shared::math::PRECISION;
 use fvm_shared::sector::StoragePower;
 use fvm_shared::smooth::{self, FilterEstimate};
 use lazy_static::lazy_static;
-use num_traits::Zero;
+use num_traits::{Signed, Zero};
 
 use super::{VestSpec, REWARD_VESTING_SPEC};
 use crate::detail::*;
@@ -209,6 +209,21 @@ pub fn pledge_penalty_for_termination(
     )
 }
 
+/// Penalty to locked pledge collateral for removing data from a sector that remains live.
+/// The penalty for terminating the whole sector is charged in proportion to the fraction of
+/// the sector's quality-adjusted power that is lost.
+pub fn pledge_penalty_for_partial_termination(
+    sector_termination_penalty: &TokenAmount,
+    qa_sector_power: &StoragePower,
+    qa_power_lost: &StoragePower,
+) -> TokenAmount {
+    if qa_sector_power.is_zero() || !qa_power_lost.is_positive() {
+        return TokenAmount::zero();
+    }
+    let lost = cmp::min(qa_power_lost, qa_sector_power);
+    TokenAmount::from_atto((sector_termination_penalty.atto() * lost).div_floor(qa_sector_power))
+}
+
 // The penalty for optimistically proving a sector with an invalid window PoSt.
 pub fn pledge_penalty_for_invalid_windowpost(
     reward_estimate: &FilterEstimate,
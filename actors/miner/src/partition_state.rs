@@ -399,6 +399,75 @@ impl Partition {
         Ok(sector_infos)
     }
 
+    /// Rebuilds the partition's expiration queue under a new quantization, as required
+    /// when the partition moves to a different deadline.
+    /// Live sectors are re-scheduled for on-time expiration, and faulty sectors keep their
+    /// early expiration where it still precedes the on-time one.
+    /// Returns the epochs of the rebuilt queue.
+    pub fn requantize_expirations<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        sectors: &Sectors<'_, BS>,
+        sector_size: SectorSize,
+        from: QuantSpec,
+        to: QuantSpec,
+    ) -> anyhow::Result<Vec<ChainEpoch>> {
+        let old_expirations = ExpirationQueue::new(store, &self.expirations_epochs, from)
+            .map_err(|e| e.downcast_wrap("failed to load sector expirations"))?;
+        let mut early_expirations = Vec::<(ChainEpoch, BitField)>::new();
+        old_expirations.amt.for_each(|epoch, expiration_set| {
+            if !expiration_set.early_sectors.is_empty() {
+                early_expirations.push((epoch as ChainEpoch, expiration_set.early_sectors.clone()));
+            }
+            Ok(())
+        })?;
+
+        let empty_expiration_array =
+            Array::<Cid, BS>::new_with_bit_width(store, PARTITION_EXPIRATION_AMT_BITWIDTH)
+                .flush()?;
+        let mut expirations = ExpirationQueue::new(store, &empty_expiration_array, to)
+            .map_err(|e| e.downcast_wrap("failed to create sector expirations"))?;
+
+        let live_infos = sectors.load_sector(&self.live_sectors())?;
+        expirations
+            .add_active_sectors(&live_infos, sector_size)
+            .map_err(|e| e.downcast_wrap("failed to add sector expirations"))?;
+
+        // Faulty sectors scheduled to expire early.
+        let mut early_sectors = Vec::<BitField>::with_capacity(early_expirations.len());
+        for (epoch, sector_numbers) in early_expirations {
+            let infos = sectors.load_sector(&sector_numbers)?;
+            expirations
+                .reschedule_as_faults(epoch, &infos, sector_size)
+                .map_err(|e| e.downcast_wrap("failed to reschedule early expirations"))?;
+            early_sectors.push(sector_numbers);
+        }
+
+        // Faulty sectors that were due to expire on time anyway.
+        let on_time_faults = &self.faults - &BitField::union(&early_sectors);
+        let on_time_fault_infos = sectors.load_sector(&on_time_faults)?;
+        if let Some(last_expiration) = on_time_fault_infos.iter().map(|s| s.expiration).max() {
+            expirations
+                .reschedule_as_faults(last_expiration, &on_time_fault_infos, sector_size)
+                .map_err(|e| e.downcast_wrap("failed to reschedule faulty expirations"))?;
+        }
+
+        let mut epochs = Vec::new();
+        expirations.amt.for_each(|epoch, _| {
+            epochs.push(epoch as ChainEpoch);
+            Ok(())
+        })?;
+        self.expirations_epochs = expirations
+            .amt
+            .flush()
+            .map_err(|e| e.downcast_wrap("failed to save sector expirations"))?;
+
+        // check invariants
+        self.validate_state()?;
+
+        Ok(epochs)
+    }
+
     /// Replaces a number of "old" sectors with new ones.
     /// The old sectors must not be faulty or terminated.
     /// If the same sector is both removed and added, this permits rescheduling *with a change in power*,
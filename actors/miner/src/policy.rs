@@ -16,7 +16,7 @@ use fvm_shared::sector::{
 };
 use lazy_static::lazy_static;
 
-use super::types::SectorOnChainInfo;
+use super::types::{SectorOnChainInfo, SectorPreCommitOnChainInfo};
 use super::{PowerPair, BASE_REWARD_FOR_DISPUTED_WINDOW_POST};
 
 /// Precision used for making QA power calculations
@@ -74,6 +74,15 @@ pub fn max_prove_commit_duration(
     }
 }
 
+/// Epoch by which a pre-committed sector must be proven, including any extension.
+pub fn prove_commit_due(
+    policy: &Policy,
+    precommit: &SectorPreCommitOnChainInfo,
+) -> Option<ChainEpoch> {
+    max_prove_commit_duration(policy, precommit.info.seal_proof)
+        .map(|msd| precommit.pre_commit_epoch + msd + precommit.extension)
+}
+
 /// Maximum duration to allow for the sealing process for seal algorithms.
 /// Dependent on algorithm and sector size
 pub fn seal_proof_sector_maximum_lifetime(proof: RegisteredSealProof) -> Option<ChainEpoch> {
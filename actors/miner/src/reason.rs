@@ -0,0 +1,30 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Codes of the reasons attached to miner actor errors, decoded with `ActorError::reason`.
+//! The type of each reason's detail is noted with its code.
+
+/// Unlocked funds cannot cover the pre-commit deposit. Detail: `InsufficientBalance`.
+pub const INSUFFICIENT_FUNDS_FOR_DEPOSIT: u64 = 1;
+/// Unlocked funds cannot cover the initial pledge. Detail: `InsufficientBalance`.
+pub const INSUFFICIENT_FUNDS_FOR_PLEDGE: u64 = 2;
+/// Unlocked funds cannot cover the aggregation network fee. Detail: `InsufficientBalance`.
+pub const INSUFFICIENT_FUNDS_FOR_FEE: u64 = 3;
+/// Unlocked funds cannot cover the reward to lock. Detail: `InsufficientBalance`.
+pub const INSUFFICIENT_FUNDS_FOR_REWARD: u64 = 4;
+/// Sector numbers have already been allocated. Detail: the colliding numbers, as a `BitField`.
+pub const SECTOR_NUMBERS_ALLOCATED: u64 = 5;
+/// A sector number appears more than once in the parameters. Detail: the `SectorNumber`.
+pub const DUPLICATE_SECTOR_NUMBER: u64 = 6;
+/// A sector number is out of range. Detail: the `SectorNumber`.
+pub const INVALID_SECTOR_NUMBER: u64 = 7;
+/// No sector is pre-committed with the number. Detail: the `SectorNumber`.
+pub const PRECOMMIT_NOT_FOUND: u64 = 8;
+/// The sector's pre-commit has expired. Detail: the `SectorNumber`.
+pub const PRECOMMIT_EXPIRED: u64 = 9;
+/// The sector cannot yet be proven. Detail: the `SectorNumber`.
+pub const PROVE_COMMIT_TOO_EARLY: u64 = 10;
+/// No sector is proven with the number. Detail: the `SectorNumber`.
+pub const SECTOR_NOT_FOUND: u64 = 11;
+/// The sector is faulty or terminated. Detail: the `SectorNumber`.
+pub const SECTOR_NOT_ACTIVE: u64 = 12;
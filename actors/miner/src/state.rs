@@ -31,6 +31,7 @@ use num_traits::Zero;
 use super::beneficiary::*;
 use super::deadlines::new_deadline_info;
 use super::policy::*;
+use super::reason;
 use super::types::*;
 use super::{
     assign_deadlines, deadline_is_mutable, new_deadline_info_from_offset_and_epoch,
@@ -266,7 +267,8 @@ impl State {
                     illegal_argument,
                     "sector numbers {:?} already allocated",
                     collisions
-                ));
+                )
+                .with_reason(reason::SECTOR_NUMBERS_ALLOCATED, &collisions));
             }
         }
         let new_allocation = &prior_allocation | sector_numbers;
@@ -307,6 +309,28 @@ impl State {
         Ok(())
     }
 
+    /// Stores updated pre-commitments, which must already exist.
+    pub fn update_precommitted_sectors<BS: Blockstore>(
+        &mut self,
+        store: &BS,
+        precommits: Vec<SectorPreCommitOnChainInfo>,
+    ) -> anyhow::Result<()> {
+        let mut precommitted =
+            make_map_with_root_and_bitwidth(&self.pre_committed_sectors, store, HAMT_BIT_WIDTH)?;
+        for precommit in precommits.into_iter() {
+            let sector_no = precommit.info.sector_number;
+            let prev = precommitted.set(u64_key(sector_no), precommit).map_err(|e| {
+                e.downcast_wrap(format!("failed to store precommitment for {:?}", sector_no))
+            })?;
+            if prev.is_none() {
+                return Err(anyhow!("sector {} not pre-commited", sector_no));
+            }
+        }
+
+        self.pre_committed_sectors = precommitted.flush()?;
+        Ok(())
+    }
+
     pub fn get_precommitted_sector<BS: Blockstore>(
         &self,
         store: &BS,
@@ -1062,6 +1086,16 @@ impl State {
                 None => continue,
             };
 
+            // Extended pre-commits are cleaned up by a later entry in the queue.
+            if sector.extension > 0 {
+                let due = prove_commit_due(policy, &sector).ok_or_else(|| {
+                    anyhow!("no max seal duration for proof type {:?}", sector.info.seal_proof)
+                })?;
+                if due + policy.expired_pre_commit_clean_up_delay > current_epoch {
+                    continue;
+                }
+            }
+
             // mark it for deletion
             precommits_to_delete.push(sector_number);
 
@@ -1261,6 +1295,12 @@ pub struct MinerInfo {
 
     /// A proposal new beneficiary message for this miner
     pub pending_beneficiary_term: Option<PendingBeneficiaryChange>,
+
+    /// Beneficiaries sharing withdrawals in place of the single beneficiary, if any.
+    pub beneficiary_split: Option<BeneficiarySplit>,
+
+    /// A proposed new beneficiary split for this miner
+    pub pending_beneficiary_split: Option<PendingBeneficiarySplit>,
 }
 
 impl MinerInfo {
@@ -1289,6 +1329,8 @@ impl MinerInfo {
             beneficiary: Address::new_id(owner),
             beneficiary_term: BeneficiaryTerm::default(),
             pending_beneficiary_term: None,
+            beneficiary_split: None,
+            pending_beneficiary_split: None,
             peer_id,
             multi_address,
             window_post_proof_type,
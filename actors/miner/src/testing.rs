@@ -1,6 +1,6 @@
 use crate::{
     power_for_sectors, BitFieldQueue, Deadline, ExpirationQueue, MinerInfo, Partition, PowerPair,
-    SectorOnChainInfo, SectorPreCommitOnChainInfo, Sectors, State,
+    SectorOnChainInfo, SectorPreCommitOnChainInfo, Sectors, State, MAX_BENEFICIARIES,
 };
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::{parse_uint_key, Map, MessageAccumulator};
@@ -36,7 +36,7 @@ pub fn check_state_invariants<BS: Blockstore>(
         }
         Err(e) => {
             // Stop here, it's too hard to make other useful checks.
-            acc.add(format!("error loading miner info: {e}"));
+            acc.with_code("miner.info").add(format!("error loading miner info: {e}"));
             return (miner_summary, acc);
         }
     };
@@ -48,16 +48,18 @@ pub fn check_state_invariants<BS: Blockstore>(
             if let Some(sectors) = allocated_sectors.bounded_iter(1 << 30) {
                 sectors.map(|i| i as SectorNumber).collect()
             } else {
-                acc.add("error expanding allocated sector bitfield");
+                acc.with_code("miner.allocated_sectors")
+                    .add("error expanding allocated sector bitfield");
                 BTreeSet::new()
             }
         }
         Ok(None) => {
-            acc.add("error loading allocated sector bitfield");
+            acc.with_code("miner.allocated_sectors").add("error loading allocated sector bitfield");
             BTreeSet::new()
         }
         Err(e) => {
-            acc.add(format!("error loading allocated sector bitfield: {e}"));
+            acc.with_code("miner.allocated_sectors")
+                .add(format!("error loading allocated sector bitfield: {e}"));
             BTreeSet::new()
         }
     };
@@ -68,8 +70,9 @@ pub fn check_state_invariants<BS: Blockstore>(
     match Sectors::load(&store, &state.sectors) {
         Ok(sectors) => {
             let ret = sectors.amt.for_each(|sector_number, sector| {
+                let acc = acc.with_context("sector", sector_number);
                 all_sectors.insert(sector_number, sector.clone());
-                acc.require(
+                acc.with_code("miner.sector.allocated").require(
                     allocated_sectors.contains(&sector_number),
                     format!(
                         "on chain sector's sector number has not been allocated {sector_number}"
@@ -84,19 +87,20 @@ pub fn check_state_invariants<BS: Blockstore>(
                         },
                     );
                 });
-                if !sector.deal_ids.is_empty() {
+                // Sectors may hold verified data without deals, from claims transferred into them.
+                if !sector.deal_ids.is_empty() || !sector.verified_deal_weight.is_zero() {
                     miner_summary.sectors_with_deals.insert(sector_number);
                 }
                 Ok(())
             });
 
-            acc.require_no_error(ret, "error iterating sectors");
+            acc.with_code("miner.sectors").require_no_error(ret, "error iterating sectors");
         }
-        Err(e) => acc.add(format!("error loading sectors: {e}")),
+        Err(e) => acc.with_code("miner.sectors").add(format!("error loading sectors: {e}")),
     };
 
     // check deadlines
-    acc.require(
+    acc.with_code("miner.current_deadline").require(
         state.current_deadline < policy.wpost_period_deadlines,
         format!(
             "current deadline index is greater than deadlines per period({}): {}",
@@ -107,7 +111,9 @@ pub fn check_state_invariants<BS: Blockstore>(
     match state.load_deadlines(store) {
         Ok(deadlines) => {
             let ret = deadlines.for_each(policy, store, |deadline_index, deadline| {
-                let acc = acc.with_prefix(format!("deadline {deadline_index}: "));
+                let acc = acc
+                    .with_prefix(format!("deadline {deadline_index}: "))
+                    .with_context("deadline", deadline_index);
                 let quant = state.quant_spec_for_deadline(policy, deadline_index);
                 let deadline_summary = check_deadline_state_invariants(
                     &deadline,
@@ -124,21 +130,23 @@ pub fn check_state_invariants<BS: Blockstore>(
                 Ok(())
             });
 
-            acc.require_no_error(ret, "error iterating deadlines");
+            acc.with_code("miner.deadlines").require_no_error(ret, "error iterating deadlines");
         }
         Err(e) => {
-            acc.add(format!("error loading deadlines: {e}"));
+            acc.with_code("miner.deadlines").add(format!("error loading deadlines: {e}"));
         }
     };
 
     (miner_summary, acc)
 }
 
+#[derive(Debug)]
 pub struct DealSummary {
     pub sector_start: ChainEpoch,
     pub sector_expiration: ChainEpoch,
 }
 
+#[derive(Debug)]
 pub struct StateSummary {
     pub live_power: PowerPair,
     pub active_power: PowerPair,
@@ -164,30 +172,30 @@ impl Default for StateSummary {
 }
 
 fn check_miner_info(info: MinerInfo, acc: &MessageAccumulator) {
-    acc.require(
+    acc.with_code("miner.info.owner_protocol").require(
         info.owner.protocol() == Protocol::ID,
         format!("owner address {} is not an ID address", info.owner),
     );
-    acc.require(
+    acc.with_code("miner.info.worker_protocol").require(
         info.worker.protocol() == Protocol::ID,
         format!("worker address {} is not an ID address", info.worker),
     );
     info.control_addresses.iter().for_each(|address| {
-        acc.require(
+        acc.with_code("miner.info.control_protocol").require(
             address.protocol() == Protocol::ID,
             format!("control address {} is not an ID address", address),
         )
     });
 
     if let Some(pending_worker_key) = info.pending_worker_key {
-        acc.require(
+        acc.with_code("miner.info.pending_worker_protocol").require(
             pending_worker_key.new_worker.protocol() == Protocol::ID,
             format!(
                 "pending worker address {} is not an ID address",
                 pending_worker_key.new_worker
             ),
         );
-        acc.require(
+        acc.with_code("miner.info.pending_worker_unchanged").require(
             pending_worker_key.new_worker != info.worker,
             format!(
                 "pending worker key {} is same as existing worker {}",
@@ -197,11 +205,11 @@ fn check_miner_info(info: MinerInfo, acc: &MessageAccumulator) {
     }
 
     if let Some(pending_owner_address) = info.pending_owner_address {
-        acc.require(
+        acc.with_code("miner.info.pending_owner_protocol").require(
             pending_owner_address.protocol() == Protocol::ID,
             format!("pending owner address {} is not an ID address", pending_owner_address),
         );
-        acc.require(
+        acc.with_code("miner.info.pending_owner_unchanged").require(
             pending_owner_address != info.owner,
             format!(
                 "pending owner address {} is same as existing owner {}",
@@ -210,12 +218,33 @@ fn check_miner_info(info: MinerInfo, acc: &MessageAccumulator) {
         );
     }
 
+    if let Some(split) = &info.beneficiary_split {
+        acc.with_code("miner.info.beneficiary_split_owner").require(
+            info.beneficiary == info.owner,
+            format!(
+                "beneficiary {} is not the owner while a beneficiary split is active",
+                info.beneficiary
+            ),
+        );
+        acc.with_code("miner.info.beneficiary_split_count").require(
+            split.beneficiaries.len() <= MAX_BENEFICIARIES,
+            format!("beneficiary split has {} beneficiaries", split.beneficiaries.len()),
+        );
+        split.beneficiaries.iter().for_each(|b| {
+            acc.with_code("miner.info.beneficiary_split_protocol").require(
+                b.beneficiary.protocol() == Protocol::ID,
+                format!("split beneficiary {} is not an ID address", b.beneficiary),
+            )
+        });
+    }
+
     if let RegisteredPoStProof::Invalid(id) = info.window_post_proof_type {
-        acc.add(format!("invalid Window PoSt proof type {id}"));
+        acc.with_code("miner.info.post_proof_type")
+            .add(format!("invalid Window PoSt proof type {id}"));
     } else {
         // safe to unwrap as we know it's valid at this point
         let sector_size = info.window_post_proof_type.sector_size().unwrap();
-        acc.require(
+        acc.with_code("miner.info.sector_size").require(
             info.sector_size == sector_size,
             format!(
                 "sector size {} is wrong for Window PoSt proof type {:?}: {}",
@@ -225,7 +254,7 @@ fn check_miner_info(info: MinerInfo, acc: &MessageAccumulator) {
 
         let partition_sectors =
             info.window_post_proof_type.window_post_partitions_sector().unwrap();
-        acc.require(info.window_post_partition_sectors == partition_sectors, format!("miner partition sectors {} does not match partition sectors {} for PoSt proof type {:?}", info.window_post_partition_sectors, partition_sectors, info.window_post_proof_type));
+        acc.with_code("miner.info.partition_sectors").require(info.window_post_partition_sectors == partition_sectors, format!("miner partition sectors {} does not match partition sectors {} for PoSt proof type {:?}", info.window_post_partition_sectors, partition_sectors, info.window_post_proof_type));
     }
 }
 
@@ -236,28 +265,28 @@ fn check_miner_balances<BS: Blockstore>(
     balance: &TokenAmount,
     acc: &MessageAccumulator,
 ) {
-    acc.require(
+    acc.with_code("miner.balance.negative").require(
         !balance.is_negative(),
         format!("miner actor balance is less than zero: {balance}"),
     );
-    acc.require(
+    acc.with_code("miner.locked_funds.negative").require(
         !state.locked_funds.is_negative(),
         format!("miner locked funds is less than zero: {}", state.locked_funds),
     );
-    acc.require(
+    acc.with_code("miner.precommit_deposits.negative").require(
         !state.pre_commit_deposits.is_negative(),
         format!("miner precommit deposit is less than zero: {}", state.pre_commit_deposits),
     );
-    acc.require(
+    acc.with_code("miner.initial_pledge.negative").require(
         !state.initial_pledge.is_negative(),
         format!("miner initial pledge is less than zero: {}", state.initial_pledge),
     );
-    acc.require(
+    acc.with_code("miner.fee_debt.negative").require(
         !state.fee_debt.is_negative(),
         format!("miner fee debt is less than zero: {}", state.fee_debt),
     );
 
-    acc.require(!(balance - &state.locked_funds - &state.pre_commit_deposits - &state.initial_pledge).is_negative(), format!("miner balance {balance} is less than sum of locked funds ({}), precommit deposit ({}) and initial pledge ({})", state.locked_funds, state.pre_commit_deposits, state.initial_pledge));
+    acc.with_code("miner.balance.insufficient").require(!(balance - &state.locked_funds - &state.pre_commit_deposits - &state.initial_pledge).is_negative(), format!("miner balance {balance} is less than sum of locked funds ({}), precommit deposit ({}) and initial pledge ({})", state.locked_funds, state.pre_commit_deposits, state.initial_pledge));
 
     // locked funds must be sum of vesting table and vesting table payments must be quantized
     let mut vesting_sum = TokenAmount::zero();
@@ -265,14 +294,14 @@ fn check_miner_balances<BS: Blockstore>(
         Ok(funds) => {
             let quant = state.quant_spec_every_deadline(policy);
             funds.funds.iter().for_each(|entry| {
-                acc.require(
+                acc.with_code("miner.vesting.amount").require(
                     entry.amount.is_positive(),
                     format!("non-positive amount in miner vesting table entry {entry:?}"),
                 );
                 vesting_sum += &entry.amount;
 
                 let quantized = quant.quantize_up(entry.epoch);
-                acc.require(
+                acc.with_code("miner.vesting.epoch").require(
                     entry.epoch == quantized,
                     format!(
                         "vesting table entry has non-quantized epoch {} (should be {quantized})",
@@ -282,11 +311,11 @@ fn check_miner_balances<BS: Blockstore>(
             });
         }
         Err(e) => {
-            acc.add(format!("error loading vesting funds: {e}"));
+            acc.with_code("miner.vesting").add(format!("error loading vesting funds: {e}"));
         }
     };
 
-    acc.require(
+    acc.with_code("miner.locked_funds.vesting_sum").require(
         state.locked_funds == vesting_sum,
         format!(
             "locked funds {} is not sum of vesting table entries {vesting_sum}",
@@ -296,7 +325,8 @@ fn check_miner_balances<BS: Blockstore>(
 
     // non zero funds implies that DeadlineCronActive is true
     if state.continue_deadline_cron() {
-        acc.require(state.deadline_cron_active, "DeadlineCronActive == false when IP+PCD+LF > 0");
+        acc.with_code("miner.deadline_cron_active")
+            .require(state.deadline_cron_active, "DeadlineCronActive == false when IP+PCD+LF > 0");
     }
 }
 
@@ -316,7 +346,7 @@ fn check_precommits<BS: Blockstore>(
             let ret = queue.amt.for_each(|epoch, expiration_bitfield| {
                 let epoch = epoch as ChainEpoch;
                 let quantized = quant.quantize_up(epoch);
-                acc.require(
+                acc.with_code("miner.precommit_cleanup.epoch").require(
                     quantized == epoch,
                     format!("pre-commit expiration {epoch} is not quantized"),
                 );
@@ -326,10 +356,12 @@ fn check_precommits<BS: Blockstore>(
                 });
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating pre-commit clean-up queue");
+            acc.with_code("miner.precommit_cleanup")
+                .require_no_error(ret, "error iterating pre-commit clean-up queue");
         }
         Err(e) => {
-            acc.add(format!("error loading pre-commit clean-up queue: {e}"));
+            acc.with_code("miner.precommit_cleanup")
+                .add(format!("error loading pre-commit clean-up queue: {e}"));
         }
     };
 
@@ -344,31 +376,35 @@ fn check_precommits<BS: Blockstore>(
                 let sector_number = match parse_uint_key(key) {
                     Ok(sector_number) => sector_number,
                     Err(e) => {
-                        acc.add(format!("error parsing pre-commit key as uint: {e}"));
+                        acc.with_code("miner.precommit.key")
+                            .add(format!("error parsing pre-commit key as uint: {e}"));
                         return Ok(());
                     }
                 };
+                let acc = acc.with_context("sector", sector_number);
 
-                acc.require(
+                acc.with_code("miner.precommit.allocated").require(
                     allocated_sectors.contains(&sector_number),
                     format!("pre-commited sector number has not been allocated {sector_number}"),
                 );
 
-                acc.require(
+                acc.with_code("miner.precommit.cleanup_epoch").require(
                     cleanup_epochs.contains_key(&sector_number),
                     format!("no clean-up epoch for pre-commit at {}", precommit.pre_commit_epoch),
                 );
                 precommit_total += &precommit.pre_commit_deposit;
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating pre-commited sectors");
+            acc.with_code("miner.precommits")
+                .require_no_error(ret, "error iterating pre-commited sectors");
         }
         Err(e) => {
-            acc.add(format!("error loading precommited_sectors: {e}"));
+            acc.with_code("miner.precommits")
+                .add(format!("error loading precommited_sectors: {e}"));
         }
     };
 
-    acc.require(state.pre_commit_deposits == precommit_total, format!("sum of pre-commit deposits {precommit_total} does not equal recorded pre-commit deposit {}", state.pre_commit_deposits));
+    acc.with_code("miner.precommit_deposits.sum").require(state.pre_commit_deposits == precommit_total, format!("sum of pre-commit deposits {precommit_total} does not equal recorded pre-commit deposit {}", state.pre_commit_deposits));
 }
 
 #[derive(Default)]
@@ -416,36 +452,66 @@ impl PartitionStateSummary {
         let active = partition.active_sectors();
 
         // live contains all live sectors
-        require_contains_all(&live, &active, acc, "live does not contain active");
+        require_contains_all(
+            &live,
+            &active,
+            &acc.with_code("miner.partition.live_active"),
+            "live does not contain active",
+        );
 
         // Live contains all faults.
-        require_contains_all(&live, &partition.faults, acc, "live does not contain faults");
+        require_contains_all(
+            &live,
+            &partition.faults,
+            &acc.with_code("miner.partition.live_faults"),
+            "live does not contain faults",
+        );
 
         // Live contains all unproven.
-        require_contains_all(&live, &partition.unproven, acc, "live does not contain unproven");
+        require_contains_all(
+            &live,
+            &partition.unproven,
+            &acc.with_code("miner.partition.live_unproven"),
+            "live does not contain unproven",
+        );
 
         // Active contains no faults
-        require_contains_none(&active, &partition.faults, acc, "active includes faults");
+        require_contains_none(
+            &active,
+            &partition.faults,
+            &acc.with_code("miner.partition.active_faults"),
+            "active includes faults",
+        );
 
         // Active contains no unproven
-        require_contains_none(&active, &partition.unproven, acc, "active includes unproven");
+        require_contains_none(
+            &active,
+            &partition.unproven,
+            &acc.with_code("miner.partition.active_unproven"),
+            "active includes unproven",
+        );
 
         // Faults contains all recoveries.
         require_contains_all(
             &partition.faults,
             &partition.recoveries,
-            acc,
+            &acc.with_code("miner.partition.faults_recoveries"),
             "faults do not contain recoveries",
         );
 
         // Live contains no terminated sectors
-        require_contains_none(&live, &partition.terminated, acc, "live includes terminations");
+        require_contains_none(
+            &live,
+            &partition.terminated,
+            &acc.with_code("miner.partition.live_terminated"),
+            "live includes terminations",
+        );
 
         // Unproven contains no faults
         require_contains_none(
             &partition.faults,
             &partition.unproven,
-            acc,
+            &acc.with_code("miner.partition.unproven_faults"),
             "unproven includes faults",
         );
 
@@ -453,7 +519,7 @@ impl PartitionStateSummary {
         require_contains_all(
             &partition.sectors,
             &partition.terminated,
-            acc,
+            &acc.with_code("miner.partition.sectors_terminated"),
             "sectors do not contain terminations",
         );
 
@@ -466,12 +532,13 @@ impl PartitionStateSummary {
         if missing.is_empty() {
             live_power =
                 power_for_sectors(sector_size, &live_sectors.values().cloned().collect::<Vec<_>>());
-            acc.require(
+            acc.with_code("miner.partition.live_power").require(
                 partition.live_power == live_power,
                 format!("live power was {:?}, expected {:?}", partition.live_power, live_power),
             );
         } else {
-            acc.add(format!("live sectors missing from all sectors: {missing:?}"));
+            acc.with_code("miner.partition.live_sectors")
+                .add(format!("live sectors missing from all sectors: {missing:?}"));
         }
 
         let (unproven_sectors, missing) = select_sectors_map(sectors_map, &partition.unproven);
@@ -480,7 +547,7 @@ impl PartitionStateSummary {
                 sector_size,
                 &unproven_sectors.values().cloned().collect::<Vec<_>>(),
             );
-            acc.require(
+            acc.with_code("miner.partition.unproven_power").require(
                 partition.unproven_power == unproven_power,
                 format!(
                     "unproven power power was {:?}, expected {:?}",
@@ -488,7 +555,8 @@ impl PartitionStateSummary {
                 ),
             );
         } else {
-            acc.add(format!("unproven sectors missing from all sectors: {missing:?}"));
+            acc.with_code("miner.partition.unproven_sectors")
+                .add(format!("unproven sectors missing from all sectors: {missing:?}"));
         }
 
         let (faulty_sectors, missing) = select_sectors_map(sectors_map, &partition.faults);
@@ -497,7 +565,7 @@ impl PartitionStateSummary {
                 sector_size,
                 &faulty_sectors.values().cloned().collect::<Vec<_>>(),
             );
-            acc.require(
+            acc.with_code("miner.partition.faulty_power").require(
                 partition.faulty_power == faulty_power,
                 format!(
                     "faulty power power was {:?}, expected {:?}",
@@ -505,7 +573,8 @@ impl PartitionStateSummary {
                 ),
             );
         } else {
-            acc.add(format!("faulty sectors missing from all sectors: {missing:?}"));
+            acc.with_code("miner.partition.faulty_sectors")
+                .add(format!("faulty sectors missing from all sectors: {missing:?}"));
         }
 
         let (recovering_sectors, missing) = select_sectors_map(sectors_map, &partition.recoveries);
@@ -514,7 +583,7 @@ impl PartitionStateSummary {
                 sector_size,
                 &recovering_sectors.values().cloned().collect::<Vec<_>>(),
             );
-            acc.require(
+            acc.with_code("miner.partition.recovering_power").require(
                 partition.recovering_power == recovering_power,
                 format!(
                     "recovering power power was {:?}, expected {:?}",
@@ -522,12 +591,13 @@ impl PartitionStateSummary {
                 ),
             );
         } else {
-            acc.add(format!("recovering sectors missing from all sectors: {missing:?}"));
+            acc.with_code("miner.partition.recovering_sectors")
+                .add(format!("recovering sectors missing from all sectors: {missing:?}"));
         }
 
         let active_power = &live_power - &faulty_power - unproven_power;
         let partition_active_power = partition.active_power();
-        acc.require(
+        acc.with_code("miner.partition.active_power").require(
             partition_active_power == active_power,
             format!("active power was {active_power:?}, expected {:?}", partition_active_power),
         );
@@ -549,10 +619,16 @@ impl PartitionStateSummary {
                 // check the queue is compatible with partition fields
                 let queue_sectors =
                     BitField::union([&queue_summary.on_time_sectors, &queue_summary.early_sectors]);
-                require_equal(&live, &queue_sectors, acc, "live does not equal all expirations");
+                require_equal(
+                    &live,
+                    &queue_sectors,
+                    &acc.with_code("miner.partition.live_expirations"),
+                    "live does not equal all expirations",
+                );
             }
             Err(err) => {
-                acc.add(format!("error loading expiration_queue: {err}"));
+                acc.with_code("miner.partition.expiration_queue")
+                    .add(format!("error loading expiration_queue: {err}"));
             }
         };
 
@@ -561,7 +637,8 @@ impl PartitionStateSummary {
             match BitFieldQueue::new(store, &partition.early_terminated, NO_QUANTIZATION) {
                 Ok(queue) => check_early_termination_queue(queue, &partition.terminated, acc),
                 Err(err) => {
-                    acc.add(format!("error loading early termination queue: {err}"));
+                    acc.with_code("miner.partition.early_termination_queue")
+                        .add(format!("error loading early termination queue: {err}"));
                     0
                 }
             };
@@ -617,44 +694,46 @@ impl ExpirationQueueStateSummary {
 
         let ret = expiration_queue.amt.for_each(|epoch, expiration_set| {
             let epoch = epoch as i64;
-            let acc = acc.with_prefix(format!("expiration epoch {epoch}: "));
+            let acc = acc.with_prefix(format!("expiration epoch {epoch}: ")).with_context("epoch", epoch);
             let quant_up = quant.quantize_up(epoch);
-            acc.require(quant_up == epoch, format!("expiration queue key {epoch} is not quantized, expected {quant_up}"));
+            acc.with_code("miner.expiration.quantized").require(quant_up == epoch, format!("expiration queue key {epoch} is not quantized, expected {quant_up}"));
 
             expiration_epochs.push(epoch);
 
             let mut on_time_sectors_pledge = TokenAmount::zero();
             for sector_number in expiration_set.on_time_sectors.iter() {
+                let acc = acc.with_context("sector", sector_number);
                 // check sectors are present only once
                 if !seen_sectors.insert(sector_number) {
-                    acc.add(format!("sector {sector_number} in expiration queue twice"));
+                    acc.with_code("miner.expiration.duplicate").add(format!("sector {sector_number} in expiration queue twice"));
                 }
 
                 // check expiring sectors are still alive
                 if let Some(sector) = live_sectors.get(&sector_number) {
                     let target = quant.quantize_up(sector.expiration);
-                    acc.require(epoch == target, format!("invalid expiration {epoch} for sector {sector_number}, expected {target}"));
+                    acc.with_code("miner.expiration.on_time_epoch").require(epoch == target, format!("invalid expiration {epoch} for sector {sector_number}, expected {target}"));
                     on_time_sectors_pledge += sector.initial_pledge.clone();
                 } else {
-                    acc.add(format!("on time expiration sector {sector_number} isn't live"));
+                    acc.with_code("miner.expiration.not_live").add(format!("on time expiration sector {sector_number} isn't live"));
                 }
             }
 
             for sector_number in expiration_set.early_sectors.iter() {
+                let acc = acc.with_context("sector", sector_number);
                 // check sectors are present only once
                 if !seen_sectors.insert(sector_number) {
-                    acc.add(format!("sector {sector_number} in expiration queue twice"));
+                    acc.with_code("miner.expiration.duplicate").add(format!("sector {sector_number} in expiration queue twice"));
                 }
 
                 // check early sectors are faulty
-                acc.require(partition_faults.get(sector_number), format!("sector {sector_number} expiring early but not faulty"));
+                acc.with_code("miner.expiration.early_not_faulty").require(partition_faults.get(sector_number), format!("sector {sector_number} expiring early but not faulty"));
 
                 // check expiring sectors are still alive
                 if let Some(sector) = live_sectors.get(&sector_number) {
                     let target = quant.quantize_up(sector.expiration);
-                    acc.require(epoch < target, format!("invalid early expiration {epoch} for sector {sector_number}, expected < {target}"));
+                    acc.with_code("miner.expiration.early_epoch").require(epoch < target, format!("invalid early expiration {epoch} for sector {sector_number}, expected < {target}"));
                 } else {
-                    acc.add(format!("on time expiration sector {sector_number} isn't live"));
+                    acc.with_code("miner.expiration.not_live").add(format!("on time expiration sector {sector_number} isn't live"));
                 }
             }
 
@@ -663,19 +742,19 @@ impl ExpirationQueueStateSummary {
             let all = BitField::union([&expiration_set.on_time_sectors, &expiration_set.early_sectors]);
             let all_active = &all - partition_faults;
             let (active_sectors, missing) = select_sectors_map(live_sectors, &all_active);
-            acc.require(missing.is_empty(), format!("active sectors missing from live: {missing:?}"));
+            acc.with_code("miner.expiration.active_sectors").require(missing.is_empty(), format!("active sectors missing from live: {missing:?}"));
 
             let all_faulty = &all & partition_faults;
             let (faulty_sectors, missing) = select_sectors_map(live_sectors, &all_faulty);
-            acc.require(missing.is_empty(), format!("faulty sectors missing from live: {missing:?}"));
+            acc.with_code("miner.expiration.faulty_sectors").require(missing.is_empty(), format!("faulty sectors missing from live: {missing:?}"));
 
             let active_sectors_power = power_for_sectors(sector_size, &active_sectors.values().cloned().collect::<Vec<_>>());
-            acc.require(expiration_set.active_power == active_sectors_power, format!("active power recorded {:?} doesn't match computed {active_sectors_power:?}", expiration_set.active_power));
+            acc.with_code("miner.expiration.active_power").require(expiration_set.active_power == active_sectors_power, format!("active power recorded {:?} doesn't match computed {active_sectors_power:?}", expiration_set.active_power));
 
             let faulty_sectors_power = power_for_sectors(sector_size, &faulty_sectors.values().cloned().collect::<Vec<_>>());
-            acc.require(expiration_set.faulty_power == faulty_sectors_power, format!("faulty power recorded {:?} doesn't match computed {faulty_sectors_power:?}", expiration_set.faulty_power));
+            acc.with_code("miner.expiration.faulty_power").require(expiration_set.faulty_power == faulty_sectors_power, format!("faulty power recorded {:?} doesn't match computed {faulty_sectors_power:?}", expiration_set.faulty_power));
 
-            acc.require(expiration_set.on_time_pledge == on_time_sectors_pledge, format!("on time pledge recorded {} doesn't match computed: {on_time_sectors_pledge}", expiration_set.on_time_pledge));
+            acc.with_code("miner.expiration.on_time_pledge").require(expiration_set.on_time_pledge == on_time_sectors_pledge, format!("on time pledge recorded {} doesn't match computed: {on_time_sectors_pledge}", expiration_set.on_time_pledge));
 
             all_on_time.push(expiration_set.on_time_sectors.clone());
             all_early.push(expiration_set.early_sectors.clone());
@@ -685,7 +764,8 @@ impl ExpirationQueueStateSummary {
 
             Ok(())
         });
-        acc.require_no_error(ret, "error iterating early termination bitfield");
+        acc.with_code("miner.expiration_queue")
+            .require_no_error(ret, "error iterating early termination bitfield");
 
         let union_on_time = BitField::union(&all_on_time);
         let union_early = BitField::union(&all_early);
@@ -712,20 +792,26 @@ fn check_early_termination_queue<BS: Blockstore>(
     let mut seen_bitfield = BitField::new();
 
     let iter_result = early_queue.amt.for_each(|epoch, bitfield| {
-        let acc = acc.with_prefix(format!("early termination epoch {epoch}: "));
+        let acc = acc
+            .with_prefix(format!("early termination epoch {epoch}: "))
+            .with_context("epoch", epoch);
         for i in bitfield.iter() {
-            acc.require(!seen.contains(&i), format!("sector {i} in early termination queue twice"));
+            acc.with_code("miner.early_termination.duplicate").require(
+                !seen.contains(&i),
+                format!("sector {i} in early termination queue twice"),
+            );
             seen.insert(i);
             seen_bitfield.set(i);
         }
         Ok(())
     });
 
-    acc.require_no_error(iter_result, "error iterating early termination bitfield");
+    acc.with_code("miner.early_termination_queue")
+        .require_no_error(iter_result, "error iterating early termination bitfield");
     require_contains_all(
         terminated,
         &seen_bitfield,
-        acc,
+        &acc.with_code("miner.early_termination.terminated"),
         "terminated sectors missing early termination entry",
     );
 
@@ -789,7 +875,8 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
         Ok(partitions) => partitions,
         Err(e) => {
             // Hard to do any useful checks.
-            acc.add(format!("error loading partitions: {e}"));
+            acc.with_code("miner.deadline.partitions")
+                .add(format!("error loading partitions: {e}"));
             return DeadlineStateSummary::default();
         }
     };
@@ -812,7 +899,7 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
     partitions
         .for_each(|index, partition| {
             // check sequential partitions
-            acc.require(
+            acc.with_code("miner.deadline.partition_index").require(
                 index == partition_count,
                 format!(
                     "Non-sequential partitions, expected index {partition_count}, found {index}"
@@ -820,7 +907,8 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
             );
             partition_count += 1;
 
-            let acc = acc.with_prefix(format!("partition {index}"));
+            let acc =
+                acc.with_prefix(format!("partition {index}")).with_context("partition", index);
             let summary = PartitionStateSummary::check_partition_state_invariants(
                 partition,
                 store,
@@ -830,7 +918,7 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
                 &acc,
             );
 
-            acc.require(
+            acc.with_code("miner.deadline.duplicate_sector").require(
                 !all_sectors.contains_any(&summary.all_sectors),
                 format!("duplicate sector in partition {index}"),
             );
@@ -859,11 +947,11 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
 
     // Check invariants on partitions proven
     if let Some(last_proof) = deadline.partitions_posted.last() {
-        acc.require(
+        acc.with_code("miner.deadline.proven_partition_count").require(
             partition_count > last_proof,
             format!("expected at least {} partitions, found {partition_count}", last_proof + 1),
         );
-        acc.require(
+        acc.with_code("miner.deadline.proven_live_sectors").require(
             deadline.live_sectors > 0,
             "expected at least one live sector when partitions have been proven",
         );
@@ -874,29 +962,33 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
     match deadline.partitions_snapshot_amt(store) {
         Ok(partition_snapshot) => {
             let ret = partition_snapshot.for_each(|i, partition| {
-                let acc = acc.with_prefix(format!("partition snapshot {i}"));
-                acc.require(
+                let acc =
+                    acc.with_prefix(format!("partition snapshot {i}")).with_context("partition", i);
+                acc.with_code("miner.deadline.snapshot.recovering_power").require(
                     partition.recovering_power.is_zero(),
                     "snapshot partition has recovering power",
                 );
-                acc.require(
+                acc.with_code("miner.deadline.snapshot.recoveries").require(
                     partition.recoveries.is_empty(),
                     "snapshot partition has pending recoveries",
                 );
-                acc.require(
+                acc.with_code("miner.deadline.snapshot.unproven_power").require(
                     partition.unproven_power.is_zero(),
                     "snapshot partition has unproven power",
                 );
-                acc.require(
+                acc.with_code("miner.deadline.snapshot.unproven").require(
                     partition.unproven.is_empty(),
                     "snapshot partition has unproven sectors",
                 );
 
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating partitions snapshot");
+            acc.with_code("miner.deadline.partitions_snapshot")
+                .require_no_error(ret, "error iterating partitions snapshot");
         }
-        Err(e) => acc.add(format!("error loading partitions snapshot: {e}")),
+        Err(e) => acc
+            .with_code("miner.deadline.partitions_snapshot")
+            .add(format!("error loading partitions snapshot: {e}")),
     };
 
     // Check that we don't have any proofs proving partitions that are not in the snapshot.
@@ -906,24 +998,27 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
                 let ret = proofs_snapshot.for_each(|_, proof| {
                     for partition in proof.partitions.iter() {
                         match partitions_snapshot.get(partition) {
-                            Ok(snapshot) => acc.require(
+                            Ok(snapshot) => acc.with_code("miner.deadline.proof_partition").require(
                                 snapshot.is_some(),
                                 format!("failed to find partition {partition} for recorded proof in the snapshot"),
                             ),
-                            Err(e) => acc.add(format!("error loading partition snapshot: {e}")),
+                            Err(e) => acc.with_code("miner.deadline.proofs_snapshot").add(format!("error loading partition snapshot: {e}")),
                         }
                     }
                     Ok(())
                 });
-                acc.require_no_error(ret, "error iterating proofs snapshot");
+                acc.with_code("miner.deadline.proofs_snapshot")
+                    .require_no_error(ret, "error iterating proofs snapshot");
             }
         }
-        Err(e) => acc.add(format!("error loading proofs snapshot: {e}")),
+        Err(e) => acc
+            .with_code("miner.deadline.proofs_snapshot")
+            .add(format!("error loading proofs snapshot: {e}")),
     };
 
     // check memoized sector and power values
     let live_sectors = BitField::union(&all_live_sectors);
-    acc.require(
+    acc.with_code("miner.deadline.live_sectors").require(
         deadline.live_sectors == live_sectors.len(),
         format!(
             "deadline live sectors {} != partitions count {}",
@@ -932,7 +1027,7 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
         ),
     );
 
-    acc.require(
+    acc.with_code("miner.deadline.total_sectors").require(
         deadline.total_sectors == all_sectors.len(),
         format!(
             "deadline total sectors {} != partitions count {}",
@@ -946,7 +1041,7 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
     let unproven_sectors = BitField::union(&all_unproven_sectors);
     let terminated_sectors = BitField::union(&all_terminated_sectors);
 
-    acc.require(
+    acc.with_code("miner.deadline.faulty_power").require(
         deadline.faulty_power == all_faulty_power,
         format!(
             "deadline faulty power {:?} != partitions total {all_faulty_power:?}",
@@ -962,24 +1057,28 @@ pub fn check_deadline_state_invariants<BS: Blockstore>(
                 match expiration_queue.amt.get(epoch as u64) {
                     Ok(expiration_bitfield) if expiration_bitfield.is_some() => {
                         for partition in expiring_idx {
-                            acc.require(expiration_bitfield.unwrap().get(partition), format!("expected partition {partition} to be present in deadline expiration queue at epoch {epoch}"));
+                            acc.with_code("miner.deadline.expiration_partition").require(expiration_bitfield.unwrap().get(partition), format!("expected partition {partition} to be present in deadline expiration queue at epoch {epoch}"));
                         }
                     }
-                    Ok(_) => acc.add(format!(
+                    Ok(_) => acc.with_code("miner.deadline.expiration_entry").add(format!(
                         "expected to find partition expiration entry at epoch {epoch}"
                     )),
-                    Err(e) => acc.add(format!("error fetching expiration bitfield: {e}")),
+                    Err(e) => acc
+                        .with_code("miner.deadline.expiration_queue")
+                        .add(format!("error fetching expiration bitfield: {e}")),
                 }
             }
         }
-        Err(e) => acc.add(format!("error loading expiration queue: {e}")),
+        Err(e) => acc
+            .with_code("miner.deadline.expiration_queue")
+            .add(format!("error loading expiration queue: {e}")),
     }
 
     // Validate the early termination queue contains exactly the partitions with early terminations.
     require_equal(
         &partitions_with_early_terminations,
         &deadline.early_terminations,
-        acc,
+        &acc.with_code("miner.deadline.early_terminations"),
         "deadline early terminations doesn't match expected partitions",
     );
 
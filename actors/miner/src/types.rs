@@ -4,12 +4,13 @@
 use cid::Cid;
 use fvm_ipld_bitfield::BitField;
 use fvm_ipld_encoding::tuple::*;
-use fvm_ipld_encoding::{strict_bytes, BytesDe};
+use fvm_ipld_encoding::{strict_bytes, BytesDe, RawBytes};
 use fvm_shared::address::Address;
 use fvm_shared::bigint::bigint_ser;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::deal::DealID;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::piece::PaddedPieceSize;
 use fvm_shared::randomness::Randomness;
 use fvm_shared::sector::{
     PoStProof, RegisteredPoStProof, RegisteredSealProof, RegisteredUpdateProof, SectorNumber,
@@ -237,6 +238,25 @@ pub struct CompactPartitionsParams {
     pub partitions: BitField,
 }
 
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ExtendPreCommitsParams {
+    pub extensions: Vec<PreCommitExtension>,
+}
+
+#[derive(Debug, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct PreCommitExtension {
+    pub sector_number: SectorNumber,
+    /// The new epoch by which the sector must be proven.
+    pub prove_commit_due: ChainEpoch,
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct MovePartitionsParams {
+    pub orig_deadline: u64,
+    pub dest_deadline: u64,
+    pub partitions: BitField,
+}
+
 #[derive(Serialize_tuple, Deserialize_tuple)]
 pub struct CompactSectorNumbersParams {
     pub mask_sector_numbers: BitField,
@@ -318,6 +338,9 @@ pub struct SectorPreCommitOnChainInfo {
     pub info: SectorPreCommitInfo,
     pub pre_commit_deposit: TokenAmount,
     pub pre_commit_epoch: ChainEpoch,
+    /// Epochs by which the deadline for proving the sector has been extended
+    /// beyond the seal proof's maximum prove-commit duration.
+    pub extension: ChainEpoch,
 }
 
 /// Information stored on-chain for a proven sector.
@@ -381,6 +404,40 @@ pub struct ProveCommitAggregateParams {
     pub aggregate_proof: Vec<u8>,
 }
 
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ProveCommitAggregateParams2 {
+    pub sector_activations: Vec<SectorActivationManifest>,
+    #[serde(with = "strict_bytes")]
+    pub aggregate_proof: Vec<u8>,
+}
+
+/// Describes the pieces committed to a sector being activated.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SectorActivationManifest {
+    pub sector_number: SectorNumber,
+    /// Pieces comprising the sector content, in order.
+    /// If non-empty, they must match the sector's unsealed CID.
+    pub pieces: Vec<PieceActivationManifest>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct PieceActivationManifest {
+    /// Piece data commitment.
+    pub cid: Cid,
+    /// Piece size.
+    pub size: PaddedPieceSize,
+    /// Notifications to be sent to other actors after the piece is activated.
+    pub notify: Vec<DataActivationNotification>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct DataActivationNotification {
+    /// Actor to be notified, via its SectorContentChanged method.
+    pub address: Address,
+    /// Opaque data to be delivered with the notification.
+    pub payload: RawBytes,
+}
+
 #[derive(Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct ReplicaUpdate {
     pub sector_number: SectorNumber,
@@ -416,6 +473,112 @@ pub struct ProveReplicaUpdatesParams2 {
     pub updates: Vec<ReplicaUpdate2>,
 }
 
+#[derive(Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ReplicaUpdate3 {
+    pub sector_number: SectorNumber,
+    pub deadline: u64,
+    pub partition: u64,
+    pub new_sealed_cid: Cid,
+    pub new_unsealed_cid: Cid,
+    pub deals: Vec<DealID>,
+    /// Pieces comprising the new sector content, in order.
+    /// If non-empty, they must match the new unsealed CID, and the update may carry no deals.
+    pub pieces: Vec<PieceActivationManifest>,
+    pub update_proof_type: RegisteredUpdateProof,
+    #[serde(with = "strict_bytes")]
+    pub replica_proof: Vec<u8>,
+}
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ProveReplicaUpdatesParams3 {
+    pub updates: Vec<ReplicaUpdate3>,
+}
+
+/// Replaces a sector's replica with one from which the pieces of some of its deals
+/// have been removed. The sector remains live with the rest of its content.
+#[derive(Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct DropSectorPiecesParams {
+    pub sector_number: SectorNumber,
+    pub deadline: u64,
+    pub partition: u64,
+    pub new_sealed_cid: Cid,
+    pub new_unsealed_cid: Cid,
+    /// Deals whose pieces are removed from the sector. These deals are terminated.
+    pub deals: Vec<DealID>,
+    pub update_proof_type: RegisteredUpdateProof,
+    #[serde(with = "strict_bytes")]
+    pub replica_proof: Vec<u8>,
+}
+
+/// Verified claims that the verified registry has transferred from this miner's sectors
+/// to another provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ClaimsTransferredParams {
+    pub claims: Vec<TransferredClaim>,
+    /// The space of claims transferred from the same sectors by earlier notifications,
+    /// one entry per sector that has any.
+    pub prior_transfers: Vec<TransferredClaim>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct TransferredClaim {
+    /// The sector that held the claim.
+    pub sector: SectorNumber,
+    /// The claim's space, no longer verified in the sector.
+    pub size: PaddedPieceSize,
+}
+
+/// Parameters of the SectorContentChanged method, which the miner invokes on
+/// actors named in piece activation notifications.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct SectorContentChangedParams {
+    pub sectors: Vec<SectorChanges>,
+}
+
+/// Description of new content committed to a sector.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct SectorChanges {
+    /// The sector number.
+    pub sector: SectorNumber,
+    /// The minimum epoch until which the data is committed to the sector.
+    /// Note the sector may later be extended without necessarily another notification.
+    pub minimum_commitment_epoch: ChainEpoch,
+    /// Information about the pieces added to the sector.
+    pub added: Vec<PieceChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct PieceChange {
+    /// Piece data commitment.
+    pub data: Cid,
+    /// Piece size.
+    pub size: PaddedPieceSize,
+    /// The payload supplied in the piece's activation notification.
+    pub payload: RawBytes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct SectorContentChangedReturn {
+    /// One result per sector in the parameters, in order.
+    pub sectors: Vec<SectorReturn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct SectorReturn {
+    /// One result per piece added to the sector, in order.
+    pub added: Vec<PieceReturn>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+#[serde(transparent)]
+pub struct PieceReturn {
+    /// Whether the receiver accepted the piece.
+    pub accepted: bool,
+}
+
 #[derive(Debug, Clone, Serialize_tuple, Deserialize_tuple)]
 pub struct ChangeBeneficiaryParams {
     pub new_beneficiary: Address,
@@ -433,6 +596,12 @@ impl ChangeBeneficiaryParams {
     }
 }
 
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct ChangeBeneficiariesParams {
+    pub payout: BeneficiaryPayout,
+    pub beneficiaries: Vec<BeneficiaryShare>,
+}
+
 #[derive(Debug, Serialize_tuple, Deserialize_tuple)]
 pub struct ActiveBeneficiary {
     pub beneficiary: Address,
@@ -445,6 +614,12 @@ pub struct GetBeneficiaryReturn {
     pub proposed: Option<PendingBeneficiaryChange>,
 }
 
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct GetBeneficiariesReturn {
+    pub split: Option<BeneficiarySplit>,
+    pub proposed_split: Option<PendingBeneficiarySplit>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize_tuple, Deserialize_tuple)]
 pub struct GetOwnerReturn {
     pub owner: Address,
@@ -83,6 +83,7 @@ fn funds_vest() {
     let (_, acc) = check_state_invariants(rt.policy(), &st, rt.store(), &rt.get_balance());
     assert_eq!(1, acc.len());
     assert!(acc.messages().first().unwrap().contains("DeadlineCronActive == false"));
+    assert_eq!(acc.codes(), vec!["miner.deadline_cron_active"]);
 }
 
 #[test]
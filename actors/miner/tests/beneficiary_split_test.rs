@@ -0,0 +1,234 @@
+use fil_actor_miner::{
+    Actor, BeneficiaryPayout, BeneficiaryShare, ChangeBeneficiariesParams, Method,
+    WithdrawBalanceParams, WithdrawBalanceReturn, MAX_BENEFICIARIES,
+};
+use fil_actors_runtime::test_utils::{
+    expect_abort_contains_message, MockRuntime, ACCOUNT_ACTOR_CODE_ID,
+};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::{address::Address, econ::TokenAmount, error::ExitCode, METHOD_SEND};
+
+mod util;
+use util::*;
+
+const EXPIRATION: ChainEpoch = 1000;
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let big_balance = 20u128.pow(23);
+    let period_offset = 100;
+
+    let h = ActorHarness::new(period_offset);
+    let mut rt = h.new_runtime();
+    h.construct_and_verify(&mut rt);
+    rt.balance.replace(TokenAmount::from_atto(big_balance));
+
+    (h, rt)
+}
+
+fn share(id: u64, quota: u64) -> BeneficiaryShare {
+    BeneficiaryShare {
+        beneficiary: Address::new_id(id),
+        quota: TokenAmount::from_atto(quota),
+        expiration: EXPIRATION,
+    }
+}
+
+// Proposes the split as the owner and approves it as each nominee.
+fn install_split(h: &ActorHarness, rt: &mut MockRuntime, params: &ChangeBeneficiariesParams) {
+    h.change_beneficiaries(rt, h.owner, params).unwrap();
+    for share in &params.beneficiaries {
+        h.change_beneficiaries(rt, share.beneficiary, params).unwrap();
+    }
+}
+
+fn withdraw(
+    h: &ActorHarness,
+    rt: &mut MockRuntime,
+    split: &[Address],
+    requested: u64,
+    expected_payouts: &[(Address, u64)],
+) {
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.owner);
+    let mut withdrawers = vec![h.owner, h.owner];
+    withdrawers.extend_from_slice(split);
+    rt.expect_validate_caller_addr(withdrawers);
+    for (beneficiary, amount) in expected_payouts {
+        rt.expect_send_simple(
+            *beneficiary,
+            METHOD_SEND,
+            None,
+            TokenAmount::from_atto(*amount),
+            None,
+            ExitCode::OK,
+        );
+    }
+
+    let ret = rt
+        .call::<Actor>(
+            Method::WithdrawBalance as u64,
+            IpldBlock::serialize_cbor(&WithdrawBalanceParams {
+                amount_requested: TokenAmount::from_atto(requested),
+            })
+            .unwrap(),
+        )
+        .unwrap()
+        .unwrap()
+        .deserialize::<WithdrawBalanceReturn>()
+        .unwrap();
+    rt.verify();
+
+    let total: u64 = expected_payouts.iter().map(|(_, amount)| amount).sum();
+    assert_eq!(TokenAmount::from_atto(total), ret.amount_withdrawn);
+}
+
+#[test]
+fn split_takes_effect_after_all_nominees_approve() {
+    let (mut h, mut rt) = setup();
+    let params = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::Priority,
+        beneficiaries: vec![share(999, 100), share(1000, 100)],
+    };
+
+    h.change_beneficiaries(&mut rt, h.owner, &params).unwrap();
+    h.change_beneficiaries(&mut rt, Address::new_id(999), &params).unwrap();
+    let ret = h.get_beneficiaries(&mut rt).unwrap();
+    assert!(ret.split.is_none());
+    assert_eq!(vec![Address::new_id(1000)], ret.proposed_split.unwrap().pending_approvals);
+
+    h.change_beneficiaries(&mut rt, Address::new_id(1000), &params).unwrap();
+    let ret = h.get_beneficiaries(&mut rt).unwrap();
+    assert!(ret.proposed_split.is_none());
+    assert_eq!(h.owner, h.get_beneficiary(&mut rt).unwrap().active.beneficiary);
+    let split = ret.split.unwrap();
+    assert_eq!(BeneficiaryPayout::Priority, split.payout);
+    assert_eq!(2, split.beneficiaries.len());
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn priority_split_pays_in_order() {
+    let (h, mut rt) = setup();
+    let (a, b) = (Address::new_id(999), Address::new_id(1000));
+    let params = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::Priority,
+        beneficiaries: vec![share(999, 100), share(1000, 100)],
+    };
+    install_split(&h, &mut rt, &params);
+
+    withdraw(&h, &mut rt, &[a, b], 150, &[(a, 100), (b, 50)]);
+    // Quotas cap the amount withdrawn.
+    withdraw(&h, &mut rt, &[a, b], 150, &[(b, 50)]);
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn pro_rata_split_pays_in_proportion_to_remaining_quota() {
+    let (h, mut rt) = setup();
+    let (a, b) = (Address::new_id(999), Address::new_id(1000));
+    let params = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::ProRata,
+        beneficiaries: vec![share(999, 300), share(1000, 100)],
+    };
+    install_split(&h, &mut rt, &params);
+
+    // The rounding remainder goes to the first beneficiary.
+    withdraw(&h, &mut rt, &[a, b], 201, &[(a, 151), (b, 50)]);
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn current_split_beneficiaries_must_approve_replacement() {
+    let (mut h, mut rt) = setup();
+    let params = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::Priority,
+        beneficiaries: vec![share(999, 100)],
+    };
+    install_split(&h, &mut rt, &params);
+
+    let replacement = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::Priority,
+        beneficiaries: vec![share(1000, 100)],
+    };
+    h.change_beneficiaries(&mut rt, h.owner, &replacement).unwrap();
+    h.change_beneficiaries(&mut rt, Address::new_id(1000), &replacement).unwrap();
+    let ret = h.get_beneficiaries(&mut rt).unwrap();
+    assert_eq!(Address::new_id(999), ret.split.unwrap().beneficiaries[0].beneficiary);
+
+    h.change_beneficiaries(&mut rt, Address::new_id(999), &replacement).unwrap();
+    let ret = h.get_beneficiaries(&mut rt).unwrap();
+    assert_eq!(Address::new_id(1000), ret.split.unwrap().beneficiaries[0].beneficiary);
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn empty_split_returns_withdrawals_to_owner() {
+    let (mut h, mut rt) = setup();
+    let params = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::Priority,
+        beneficiaries: vec![share(999, 100)],
+    };
+    install_split(&h, &mut rt, &params);
+
+    // Once the split expires, the owner can remove it without approval.
+    rt.set_epoch(EXPIRATION);
+    let clear =
+        ChangeBeneficiariesParams { payout: BeneficiaryPayout::Priority, beneficiaries: vec![] };
+    h.change_beneficiaries(&mut rt, h.owner, &clear).unwrap();
+    let ret = h.get_beneficiaries(&mut rt).unwrap();
+    assert!(ret.split.is_none());
+    assert!(ret.proposed_split.is_none());
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_nominate_single_beneficiary_while_split_active() {
+    let (mut h, mut rt) = setup();
+    let params = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::Priority,
+        beneficiaries: vec![share(999, 100)],
+    };
+    install_split(&h, &mut rt, &params);
+
+    let change =
+        BeneficiaryChange::new(Address::new_id(1000), TokenAmount::from_atto(100), EXPIRATION);
+    let ret = h.change_beneficiary(&mut rt, h.owner, &change, None);
+    expect_abort_contains_message(ExitCode::USR_FORBIDDEN, "beneficiary split is active", ret);
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_propose_invalid_split() {
+    let (h, mut rt) = setup();
+
+    let too_many = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::ProRata,
+        beneficiaries: (0..=MAX_BENEFICIARIES as u64).map(|i| share(999 + i, 100)).collect(),
+    };
+    let ret = h.change_beneficiaries(&mut rt, h.owner, &too_many);
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "too many beneficiaries", ret);
+    rt.reset();
+
+    let duplicate = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::ProRata,
+        beneficiaries: vec![share(999, 100), share(999, 200)],
+    };
+    let ret = h.change_beneficiaries(&mut rt, h.owner, &duplicate);
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "duplicate beneficiary", ret);
+    rt.reset();
+
+    let zero_quota = ChangeBeneficiariesParams {
+        payout: BeneficiaryPayout::ProRata,
+        beneficiaries: vec![share(999, 0)],
+    };
+    let ret = h.change_beneficiaries(&mut rt, h.owner, &zero_quota);
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "must bigger than zero", ret);
+
+    h.check_state(&rt);
+}
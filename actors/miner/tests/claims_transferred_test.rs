@@ -0,0 +1,241 @@
+use fil_actor_market::{
+    DealQueryParams, GetDealDataCommitmentReturn, GetDealVerifiedReturn, Method as MarketMethod,
+};
+use fil_actor_miner::{
+    power_for_sector, Actor, ClaimsTransferredParams, ExpirationExtension,
+    ExtendSectorExpirationParams, Method, PowerPair, SectorOnChainInfo, State, TransferredClaim,
+};
+use fil_actor_power::{Method as PowerMethod, UpdateClaimedPowerParams};
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{
+    DealWeight, STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::bigint::{BigInt, Integer, Zero};
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::piece::PaddedPieceSize;
+
+mod util;
+use util::*;
+
+const PERIOD_OFFSET: ChainEpoch = 100;
+
+// Commits and proves a sector holding two verified deals, each filling a quarter of the sector.
+fn setup() -> (ActorHarness, MockRuntime, SectorOnChainInfo) {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&mut rt);
+
+    let quarter = h.sector_size as u64 / 4;
+    let mut cfg = ProveCommitConfig::empty();
+    cfg.add_verified_deals(
+        h.next_sector_no,
+        vec![test_verified_deal(quarter), test_verified_deal(quarter)],
+    );
+    let sector = h
+        .commit_and_prove_sectors_with_cfgs(
+            &mut rt,
+            1,
+            DEFAULT_SECTOR_EXPIRATION,
+            vec![vec![10, 20]],
+            true,
+            cfg,
+        )
+        .remove(0);
+    h.advance_and_submit_posts(&mut rt, &[sector.clone()]);
+
+    (h, rt, sector)
+}
+
+fn transferred(sector: &SectorOnChainInfo, size: u64) -> TransferredClaim {
+    TransferredClaim { sector: sector.sector_number, size: PaddedPieceSize(size) }
+}
+
+#[test]
+fn removes_weight_and_power_of_transferred_claims() {
+    let (h, mut rt, sector) = setup();
+    let quarter = h.sector_size as u64 / 4;
+
+    let mut expected = sector.clone();
+    expected.verified_deal_weight = &sector.verified_deal_weight
+        - DealWeight::from(quarter) * (sector.expiration - sector.activation);
+    let power_delta =
+        power_for_sector(h.sector_size, &expected) - power_for_sector(h.sector_size, &sector);
+    assert!(power_delta.qa < BigInt::zero());
+    assert!(power_delta.raw.is_zero());
+    h.claims_transferred(&mut rt, vec![transferred(&sector, quarter)], &power_delta).unwrap();
+
+    let updated = h.get_sector(&rt, sector.sector_number);
+    assert_eq!(expected, updated);
+    let (_, partition) = h.find_sector(&rt, sector.sector_number);
+    assert_eq!(power_for_sector(h.sector_size, &updated), partition.live_power);
+    h.check_state(&rt);
+
+    // The remaining claim is transferred, along with a claim in a sector that doesn't exist.
+    let mut expected = updated.clone();
+    expected.verified_deal_weight = BigInt::zero();
+    let power_delta =
+        power_for_sector(h.sector_size, &expected) - power_for_sector(h.sector_size, &updated);
+    let mut missing = transferred(&sector, quarter);
+    missing.sector += 100;
+    h.claims_transferred(&mut rt, vec![missing, transferred(&sector, quarter)], &power_delta)
+        .unwrap();
+    assert_eq!(expected, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
+
+#[test]
+fn prorates_weight_of_claims_in_legacy_extended_sector() {
+    let (h, mut rt, sector) = setup();
+    let quarter = h.sector_size as u64 / 4;
+
+    // Make the sector a legacy one, whose weights are prorated when it is extended.
+    let mut st: State = rt.get_state();
+    let mut legacy = sector.clone();
+    legacy.simple_qa_power = false;
+    st.put_sectors(rt.store(), vec![legacy.clone()]).unwrap();
+    rt.replace_state(&st);
+
+    let (dlidx, pidx) = st.find_sector(rt.policy(), rt.store(), sector.sector_number).unwrap();
+    let new_expiration = sector.expiration + 42 * rt.policy.wpost_proving_period;
+    let mut extended = legacy.clone();
+    extended.expiration = new_expiration;
+    extended.deal_weight = (&legacy.deal_weight * (legacy.expiration - rt.epoch))
+        .div_floor(&BigInt::from(legacy.expiration - legacy.activation));
+    extended.verified_deal_weight = (&legacy.verified_deal_weight * (legacy.expiration - rt.epoch))
+        .div_floor(&BigInt::from(legacy.expiration - legacy.activation));
+    let power_delta =
+        power_for_sector(h.sector_size, &extended) - power_for_sector(h.sector_size, &legacy);
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(h.caller_addrs());
+    rt.expect_send_simple(
+        STORAGE_POWER_ACTOR_ADDR,
+        PowerMethod::UpdateClaimedPower as u64,
+        IpldBlock::serialize_cbor(&UpdateClaimedPowerParams {
+            raw_byte_delta: power_delta.raw,
+            quality_adjusted_delta: power_delta.qa,
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+    let params = ExtendSectorExpirationParams {
+        extensions: vec![ExpirationExtension {
+            deadline: dlidx,
+            partition: pidx,
+            sectors: make_bitfield(&[sector.sector_number]),
+            new_expiration,
+        }],
+    };
+    rt.call::<Actor>(
+        Method::ExtendSectorExpiration as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+    assert_eq!(extended, h.get_sector(&rt, sector.sector_number));
+
+    // The first claim takes half the prorated weight, its share of the verified space.
+    expect_verified_deal_queries(&mut rt, quarter);
+    let mut expected = extended.clone();
+    expected.verified_deal_weight = &extended.verified_deal_weight
+        - (&extended.verified_deal_weight * quarter).div_floor(&BigInt::from(2 * quarter));
+    let power_delta =
+        power_for_sector(h.sector_size, &expected) - power_for_sector(h.sector_size, &extended);
+    h.claims_transferred(&mut rt, vec![transferred(&sector, quarter)], &power_delta).unwrap();
+    let updated = h.get_sector(&rt, sector.sector_number);
+    assert_eq!(expected, updated);
+    h.check_state(&rt);
+
+    // The second claim takes the rest, being all the verified space still held.
+    expect_verified_deal_queries(&mut rt, quarter);
+    let mut expected = updated.clone();
+    expected.verified_deal_weight = BigInt::zero();
+    let power_delta =
+        power_for_sector(h.sector_size, &expected) - power_for_sector(h.sector_size, &updated);
+    h.claims_transferred_after(
+        &mut rt,
+        vec![transferred(&sector, quarter)],
+        vec![transferred(&sector, quarter)],
+        &power_delta,
+    )
+    .unwrap();
+    assert_eq!(expected, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
+
+// Expects the market actor to be queried for the pieces of the sector's two verified deals.
+fn expect_verified_deal_queries(rt: &mut MockRuntime, size: u64) {
+    for id in [10, 20] {
+        rt.expect_send_simple(
+            STORAGE_MARKET_ACTOR_ADDR,
+            MarketMethod::GetDealDataCommitmentExported as u64,
+            IpldBlock::serialize_cbor(&DealQueryParams { id }).unwrap(),
+            TokenAmount::zero(),
+            IpldBlock::serialize_cbor(&GetDealDataCommitmentReturn {
+                data: make_piece_cid(b"verified"),
+                size: PaddedPieceSize(size),
+            })
+            .unwrap(),
+            ExitCode::OK,
+        );
+    }
+    for id in [10, 20] {
+        rt.expect_send_simple(
+            STORAGE_MARKET_ACTOR_ADDR,
+            MarketMethod::GetDealVerifiedExported as u64,
+            IpldBlock::serialize_cbor(&DealQueryParams { id }).unwrap(),
+            TokenAmount::zero(),
+            IpldBlock::serialize_cbor(&GetDealVerifiedReturn { verified: true }).unwrap(),
+            ExitCode::OK,
+        );
+    }
+}
+
+#[test]
+fn ignores_claims_in_missing_sectors() {
+    let (h, mut rt, sector) = setup();
+    let mut missing = transferred(&sector, h.sector_size as u64 / 4);
+    missing.sector += 100;
+    h.claims_transferred(&mut rt, vec![missing], &PowerPair::zero()).unwrap();
+    assert_eq!(sector, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
+
+#[test]
+fn rejects_callers_other_than_verified_registry() {
+    let (h, mut rt, sector) = setup();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
+    let params = ClaimsTransferredParams {
+        claims: vec![transferred(&sector, h.sector_size as u64 / 4)],
+        prior_transfers: vec![],
+    };
+    let result = rt.call::<Actor>(
+        Method::ClaimsTransferred as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    expect_abort(ExitCode::USR_FORBIDDEN, result);
+    rt.reset();
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_for_faulty_sector() {
+    let (h, mut rt, sector) = setup();
+    h.declare_faults(&mut rt, &[sector.clone()]);
+
+    let result = h.claims_transferred(
+        &mut rt,
+        vec![transferred(&sector, h.sector_size as u64 / 4)],
+        &PowerPair::zero(),
+    );
+    expect_abort_contains_message(ExitCode::USR_FORBIDDEN, "inactive sector", result);
+    rt.reset();
+    assert_eq!(sector, h.get_sector(&rt, sector.sector_number));
+    h.check_state(&rt);
+}
@@ -335,6 +335,50 @@ fn cannot_remove_partitions_with_early_terminations() {
     assert!(deadline.remove_partitions(store, &bitfield_from_slice(&[0]), QUANT_SPEC).is_err());
 }
 
+#[test]
+fn cannot_move_partitions_past_max_partitions_per_deadline() {
+    let (_, rt) = setup();
+    let store = rt.store();
+    let policy = Policy { max_partitions_per_deadline: 3, ..Policy::default() };
+
+    let mut orig = Deadline::new(store).unwrap();
+    add_sectors(&rt, &mut orig, true);
+    let mut dest = Deadline::new(store).unwrap();
+    dest.add_sectors(store, PARTITION_SIZE, false, &extra_sectors(), SECTOR_SIZE, QUANT_SPEC)
+        .unwrap();
+    let sectors = sectors_arr(store, all_sectors());
+
+    // The destination already has one partition, so it can take two more but not three.
+    let err = orig
+        .move_partitions(
+            &policy,
+            store,
+            &mut dest,
+            &bitfield_from_slice(&[0, 1, 2]),
+            &sectors,
+            SECTOR_SIZE,
+            QUANT_SPEC,
+            QUANT_SPEC,
+        )
+        .unwrap_err();
+    let err = err.downcast::<ActorError>().unwrap();
+    assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+
+    orig.move_partitions(
+        &policy,
+        store,
+        &mut dest,
+        &bitfield_from_slice(&[0, 1]),
+        &sectors,
+        SECTOR_SIZE,
+        QUANT_SPEC,
+        QUANT_SPEC,
+    )
+    .unwrap();
+    assert_eq!(1, orig.partitions_amt(store).unwrap().count());
+    assert_eq!(3, dest.partitions_amt(store).unwrap().count());
+}
+
 #[test]
 fn can_pop_early_terminations_in_multiple_steps() {
     let (_, rt) = setup();
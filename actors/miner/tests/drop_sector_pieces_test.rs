@@ -0,0 +1,389 @@
+use cid::Cid;
+use fil_actor_market::{
+    DealQueryParams, GetDealDataCommitmentReturn, GetDealTermReturn, GetDealVerifiedReturn,
+    Method as MarketMethod, OnMinerSectorsTerminateParams,
+};
+use fil_actor_miner::ext::verifreg::{
+    DroppedPiece, RemoveSectorClaimsParams, RemoveSectorClaimsReturn, REMOVE_SECTOR_CLAIMS_METHOD,
+};
+use fil_actor_miner::{
+    pledge_penalty_for_partial_termination, pledge_penalty_for_termination, power_for_sector,
+    qa_power_for_sector, Actor, DropSectorPiecesParams, Method, SectorOnChainInfo, State,
+    TransferredClaim,
+};
+use fil_actor_power::{Method as PowerMethod, UpdateClaimedPowerParams};
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{
+    ActorError, BatchReturnGen, BURNT_FUNDS_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR,
+    STORAGE_POWER_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
+};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::bigint::{BigInt, Zero};
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::deal::DealID;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::piece::{PaddedPieceSize, PieceInfo};
+use fvm_shared::sector::ReplicaUpdateInfo;
+use fvm_shared::METHOD_SEND;
+
+mod util;
+use util::*;
+
+const PERIOD_OFFSET: ChainEpoch = 100;
+const UNVERIFIED_DEAL: DealID = 10;
+const VERIFIED_DEAL: DealID = 20;
+const CLAIM_ID: u64 = 30;
+
+// Commits and proves a sector holding an unverified and a verified deal, each filling half the sector.
+fn setup() -> (ActorHarness, MockRuntime, SectorOnChainInfo) {
+    let mut h = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+    h.construct_and_verify(&mut rt);
+
+    let half = h.sector_size as u64 / 2;
+    let mut cfg = ProveCommitConfig::empty();
+    cfg.deal_space.insert(h.next_sector_no, BigInt::from(half));
+    cfg.add_verified_deals(h.next_sector_no, vec![test_verified_deal(half)]);
+    let sector = h
+        .commit_and_prove_sectors_with_cfgs(
+            &mut rt,
+            1,
+            DEFAULT_SECTOR_EXPIRATION,
+            vec![vec![UNVERIFIED_DEAL, VERIFIED_DEAL]],
+            true,
+            cfg,
+        )
+        .remove(0);
+    h.advance_and_submit_posts(&mut rt, &[sector.clone()]);
+
+    (h, rt, sector)
+}
+
+fn make_params(
+    rt: &MockRuntime,
+    sector: &SectorOnChainInfo,
+    deals: Vec<DealID>,
+) -> DropSectorPiecesParams {
+    let state: State = rt.get_state();
+    let (deadline, partition) =
+        state.find_sector(&rt.policy, &rt.store, sector.sector_number).unwrap();
+    DropSectorPiecesParams {
+        sector_number: sector.sector_number,
+        deadline,
+        partition,
+        new_sealed_cid: make_sealed_cid(b"dropped"),
+        new_unsealed_cid: make_piece_cid(b"dropped"),
+        deals,
+        update_proof_type: sector.seal_proof.registered_update_proof().unwrap(),
+        replica_proof: vec![],
+    }
+}
+
+// Expects the remaining unverified deal's piece to be queried, and to make up the given CommD.
+fn expect_remaining_pieces(h: &ActorHarness, rt: &mut MockRuntime, unsealed_cid: Cid) {
+    let half = h.sector_size as u64 / 2;
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(h.caller_addrs());
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::GetDealDataCommitmentExported as u64,
+        IpldBlock::serialize_cbor(&DealQueryParams { id: UNVERIFIED_DEAL }).unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&GetDealDataCommitmentReturn {
+            data: make_piece_cid(b"unverified"),
+            size: PaddedPieceSize(half),
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    rt.expect_compute_unsealed_sector_cid(
+        h.seal_proof_type,
+        vec![PieceInfo { size: PaddedPieceSize(half), cid: make_piece_cid(b"unverified") }],
+        unsealed_cid,
+        ExitCode::OK,
+    );
+}
+
+// Drops the verified deal, whose term ends at deal_end, from the sector, expecting a penalty
+// for the lost power unless the deal's claim was transferred to another provider.
+// Returns the sector's expected verified deal weight afterwards.
+fn drop_verified_deal(
+    h: &ActorHarness,
+    rt: &mut MockRuntime,
+    sector: &SectorOnChainInfo,
+    deal_end: ChainEpoch,
+    claim_transferred: bool,
+) -> Result<BigInt, ActorError> {
+    let params = make_params(rt, sector, vec![VERIFIED_DEAL]);
+    expect_remaining_pieces(h, rt, params.new_unsealed_cid);
+    rt.expect_replica_verify(
+        ReplicaUpdateInfo {
+            update_proof_type: params.update_proof_type,
+            new_sealed_cid: params.new_sealed_cid,
+            old_sealed_cid: sector.sealed_cid,
+            new_unsealed_cid: params.new_unsealed_cid,
+            proof: vec![],
+        },
+        Ok(()),
+    );
+
+    let half = h.sector_size as u64 / 2;
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::GetDealDataCommitmentExported as u64,
+        IpldBlock::serialize_cbor(&DealQueryParams { id: VERIFIED_DEAL }).unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&GetDealDataCommitmentReturn {
+            data: make_piece_cid(b"verified"),
+            size: PaddedPieceSize(half),
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::GetDealVerifiedExported as u64,
+        IpldBlock::serialize_cbor(&DealQueryParams { id: VERIFIED_DEAL }).unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&GetDealVerifiedReturn { verified: true }).unwrap(),
+        ExitCode::OK,
+    );
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::GetDealTermExported as u64,
+        IpldBlock::serialize_cbor(&DealQueryParams { id: VERIFIED_DEAL }).unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&GetDealTermReturn {
+            start: sector.activation,
+            duration: deal_end - sector.activation,
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    // A transferred claim is no longer held by the miner.
+    let mut results = BatchReturnGen::new(1);
+    if claim_transferred {
+        results.add_fail(ExitCode::USR_NOT_FOUND);
+    } else {
+        results.add_success();
+    }
+    rt.expect_send_simple(
+        VERIFIED_REGISTRY_ACTOR_ADDR,
+        REMOVE_SECTOR_CLAIMS_METHOD,
+        IpldBlock::serialize_cbor(&RemoveSectorClaimsParams {
+            sector: sector.sector_number,
+            pieces: vec![DroppedPiece {
+                data: make_piece_cid(b"verified"),
+                size: PaddedPieceSize(half),
+            }],
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        IpldBlock::serialize_cbor(&RemoveSectorClaimsReturn {
+            removed: if claim_transferred { vec![] } else { vec![CLAIM_ID] },
+            results: results.gen(),
+        })
+        .unwrap(),
+        ExitCode::OK,
+    );
+    h.expect_query_network_info(rt);
+
+    // The deal would have contributed its space for the rest of its term, unless its claim
+    // was transferred, which already removed its weight.
+    let mut expected = sector.clone();
+    if !claim_transferred {
+        expected.verified_deal_weight =
+            &sector.verified_deal_weight - BigInt::from(half) * (deal_end - rt.epoch);
+    }
+    let qa_before = qa_power_for_sector(h.sector_size, sector);
+    let qa_lost = &qa_before - qa_power_for_sector(h.sector_size, &expected);
+    let penalty = pledge_penalty_for_partial_termination(
+        &pledge_penalty_for_termination(
+            &sector.expected_day_reward,
+            rt.epoch - sector.activation,
+            &sector.expected_storage_pledge,
+            &h.epoch_qa_power_smooth,
+            &qa_before,
+            &h.epoch_reward_smooth,
+            &sector.replaced_day_reward,
+            sector.replaced_sector_age,
+        ),
+        &qa_before,
+        &qa_lost,
+    );
+    assert_eq!(!claim_transferred, penalty.is_positive());
+    if !claim_transferred {
+        rt.expect_send_simple(
+            BURNT_FUNDS_ACTOR_ADDR,
+            METHOD_SEND,
+            None,
+            penalty,
+            None,
+            ExitCode::OK,
+        );
+        rt.expect_send_simple(
+            STORAGE_POWER_ACTOR_ADDR,
+            PowerMethod::UpdateClaimedPower as u64,
+            IpldBlock::serialize_cbor(&UpdateClaimedPowerParams {
+                raw_byte_delta: BigInt::zero(),
+                quality_adjusted_delta: -qa_lost,
+            })
+            .unwrap(),
+            TokenAmount::zero(),
+            None,
+            ExitCode::OK,
+        );
+    }
+    rt.expect_send_simple(
+        STORAGE_MARKET_ACTOR_ADDR,
+        MarketMethod::OnMinerSectorsTerminate as u64,
+        IpldBlock::serialize_cbor(&OnMinerSectorsTerminateParams {
+            epoch: rt.epoch,
+            deal_ids: vec![VERIFIED_DEAL],
+        })
+        .unwrap(),
+        TokenAmount::zero(),
+        None,
+        ExitCode::OK,
+    );
+
+    rt.call::<Actor>(Method::DropSectorPieces as u64, IpldBlock::serialize_cbor(&params).unwrap())?;
+    rt.verify();
+    Ok(expected.verified_deal_weight)
+}
+
+#[test]
+fn drops_verified_deal_and_keeps_sector_live() {
+    let (h, mut rt, sector) = setup();
+    let verified_deal_weight =
+        drop_verified_deal(&h, &mut rt, &sector, sector.expiration, false).unwrap();
+
+    let updated = h.get_sector(&rt, sector.sector_number);
+    assert_eq!(vec![UNVERIFIED_DEAL], updated.deal_ids);
+    assert_eq!(sector.deal_weight, updated.deal_weight);
+    // The weight the deal contributed before it was dropped remains.
+    assert_eq!(verified_deal_weight, updated.verified_deal_weight);
+    assert!(verified_deal_weight > BigInt::zero());
+    assert!(verified_deal_weight < sector.verified_deal_weight);
+    assert_eq!(make_sealed_cid(b"dropped"), updated.sealed_cid);
+    assert_eq!(Some(sector.sealed_cid), updated.sector_key_cid);
+    assert_eq!(sector.expiration, updated.expiration);
+    assert_eq!(sector.initial_pledge, updated.initial_pledge);
+
+    let (_, partition) = h.find_sector(&rt, sector.sector_number);
+    assert!(partition.active_sectors().get(sector.sector_number));
+    assert_eq!(qa_power_for_sector(h.sector_size, &updated), partition.live_power.qa);
+    h.check_state(&rt);
+}
+
+#[test]
+fn drops_weight_for_rest_of_deal_term() {
+    let (h, mut rt, sector) = setup();
+    let deal_end = rt.epoch + 1000;
+    assert!(deal_end < sector.expiration);
+    let verified_deal_weight = drop_verified_deal(&h, &mut rt, &sector, deal_end, false).unwrap();
+
+    let updated = h.get_sector(&rt, sector.sector_number);
+    let half = h.sector_size as u64 / 2;
+    assert_eq!(&sector.verified_deal_weight - BigInt::from(half) * 1000, verified_deal_weight);
+    assert_eq!(verified_deal_weight, updated.verified_deal_weight);
+    h.check_state(&rt);
+}
+
+#[test]
+fn drops_deal_with_transferred_claim_without_penalty() {
+    let (h, mut rt, sector) = setup();
+
+    // The claim's transfer to another provider removes its weight and power.
+    let half = h.sector_size as u64 / 2;
+    let mut transferred = sector.clone();
+    transferred.verified_deal_weight = BigInt::zero();
+    let power_delta =
+        power_for_sector(h.sector_size, &transferred) - power_for_sector(h.sector_size, &sector);
+    h.claims_transferred(
+        &mut rt,
+        vec![TransferredClaim { sector: sector.sector_number, size: PaddedPieceSize(half) }],
+        &power_delta,
+    )
+    .unwrap();
+    let sector = h.get_sector(&rt, sector.sector_number);
+    assert_eq!(BigInt::zero(), sector.verified_deal_weight);
+
+    // Dropping the piece then removes no further weight.
+    let verified_deal_weight =
+        drop_verified_deal(&h, &mut rt, &sector, sector.expiration, true).unwrap();
+    assert_eq!(BigInt::zero(), verified_deal_weight);
+    let updated = h.get_sector(&rt, sector.sector_number);
+    assert_eq!(vec![UNVERIFIED_DEAL], updated.deal_ids);
+    assert_eq!(sector.deal_weight, updated.deal_weight);
+    assert_eq!(BigInt::zero(), updated.verified_deal_weight);
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_drop_with_unsealed_cid_not_matching_remaining_pieces() {
+    let (h, mut rt, sector) = setup();
+    let params = make_params(&rt, &sector, vec![VERIFIED_DEAL]);
+    expect_remaining_pieces(&h, &mut rt, make_piece_cid(b"remaining"));
+    let result = rt.call::<Actor>(
+        Method::DropSectorPieces as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "does not match remaining pieces",
+        result,
+    );
+    rt.reset();
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_drop_deal_not_in_sector() {
+    let (h, mut rt, sector) = setup();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(h.caller_addrs());
+    let result = rt.call::<Actor>(
+        Method::DropSectorPieces as u64,
+        IpldBlock::serialize_cbor(&make_params(&rt, &sector, vec![99])).unwrap(),
+    );
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "is not in sector", result);
+    rt.reset();
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_drop_no_deals() {
+    let (h, mut rt, sector) = setup();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(h.caller_addrs());
+    let result = rt.call::<Actor>(
+        Method::DropSectorPieces as u64,
+        IpldBlock::serialize_cbor(&make_params(&rt, &sector, vec![])).unwrap(),
+    );
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "no deals to drop", result);
+    rt.reset();
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_drop_from_faulty_sector() {
+    let (h, mut rt, sector) = setup();
+    h.declare_faults(&mut rt, &[sector.clone()]);
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, h.worker);
+    rt.expect_validate_caller_addr(h.caller_addrs());
+    let result = rt.call::<Actor>(
+        Method::DropSectorPieces as u64,
+        IpldBlock::serialize_cbor(&make_params(&rt, &sector, vec![VERIFIED_DEAL])).unwrap(),
+    );
+    expect_abort_contains_message(ExitCode::USR_FORBIDDEN, "is not active", result);
+    rt.reset();
+
+    h.check_state(&rt);
+}
@@ -0,0 +1,138 @@
+use fil_actor_miner::{
+    max_prove_commit_duration, ExtendPreCommitsParams, PreCommitExtension,
+    SectorPreCommitOnChainInfo, State,
+};
+use fil_actors_runtime::test_utils::*;
+use fvm_shared::bigint::{BigInt, Integer};
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::sector::SectorNumber;
+
+mod util;
+use util::*;
+
+const PERIOD_OFFSET: ChainEpoch = 100;
+const SECTOR_NUMBER: SectorNumber = 100;
+
+fn setup() -> (ActorHarness, MockRuntime, SectorPreCommitOnChainInfo) {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = h.new_runtime();
+    rt.set_balance(BIG_BALANCE.clone());
+
+    let precommit_epoch = PERIOD_OFFSET + 1;
+    rt.set_epoch(precommit_epoch);
+    h.construct_and_verify(&mut rt);
+
+    let expiration = h.deadline(&rt).period_end()
+        + DEFAULT_SECTOR_EXPIRATION as i64 * rt.policy.wpost_proving_period;
+    let params = h.make_pre_commit_params(SECTOR_NUMBER, precommit_epoch - 1, expiration, vec![]);
+    let precommit = h.pre_commit_sector_and_get(&mut rt, params, PreCommitConfig::empty(), true);
+
+    (h, rt, precommit)
+}
+
+fn extend(due: ChainEpoch) -> ExtendPreCommitsParams {
+    ExtendPreCommitsParams {
+        extensions: vec![PreCommitExtension {
+            sector_number: SECTOR_NUMBER,
+            prove_commit_due: due,
+        }],
+    }
+}
+
+#[test]
+fn extends_prove_commit_due_and_tops_up_deposit() {
+    let (h, mut rt, precommit) = setup();
+    let msd = max_prove_commit_duration(&rt.policy, h.seal_proof_type).unwrap();
+    let due = precommit.pre_commit_epoch + msd;
+
+    rt.set_epoch(due - 10);
+    h.extend_pre_commits(&mut rt, extend(due + 100)).unwrap();
+
+    let extended = h.get_precommit(&mut rt, SECTOR_NUMBER);
+    assert_eq!(100, extended.extension);
+    let top_up = TokenAmount::from_atto(
+        (precommit.pre_commit_deposit.atto() * 100).div_ceil(&BigInt::from(msd)),
+    );
+    assert_eq!(&precommit.pre_commit_deposit + &top_up, extended.pre_commit_deposit);
+    let st: State = rt.get_state();
+    assert_eq!(extended.pre_commit_deposit, st.pre_commit_deposits);
+
+    // The clean-up is rescheduled after the new deadline.
+    let expirations = h.collect_precommit_expirations(&rt, &st);
+    let quant = st.quant_spec_every_deadline(&rt.policy);
+    let new_clean_up = quant.quantize_up(due + 100 + rt.policy.expired_pre_commit_clean_up_delay);
+    assert_eq!(Some(&vec![SECTOR_NUMBER]), expirations.get(&new_clean_up));
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn extended_pre_commit_survives_original_clean_up() {
+    let (h, mut rt, precommit) = setup();
+    let msd = max_prove_commit_duration(&rt.policy, h.seal_proof_type).unwrap();
+    let due = precommit.pre_commit_epoch + msd;
+    let extended_due = due + 2 * rt.policy.wpost_challenge_window;
+    h.extend_pre_commits(&mut rt, extend(extended_due)).unwrap();
+
+    // Run cron past the original clean-up epoch without burning the deposit.
+    let clean_up_epoch = due + rt.policy.expired_pre_commit_clean_up_delay;
+    let mut dlinfo = h.deadline(&rt);
+    while dlinfo.open <= clean_up_epoch {
+        dlinfo = h.advance_deadline(&mut rt, CronConfig::empty());
+    }
+    assert!(h.has_precommit(&rt, SECTOR_NUMBER));
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_extend_beyond_randomness_lookback() {
+    let (h, mut rt, precommit) = setup();
+    let msd = max_prove_commit_duration(&rt.policy, h.seal_proof_type).unwrap();
+    let max_due =
+        precommit.info.seal_rand_epoch + rt.policy.max_pre_commit_randomness_lookback + msd;
+
+    let result = h.extend_pre_commits(&mut rt, extend(max_due + 1));
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "exceeds seal randomness lookback",
+        result,
+    );
+    rt.reset();
+
+    h.extend_pre_commits(&mut rt, extend(max_due)).unwrap();
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_shorten_or_extend_expired_pre_commit() {
+    let (h, mut rt, precommit) = setup();
+    let msd = max_prove_commit_duration(&rt.policy, h.seal_proof_type).unwrap();
+    let due = precommit.pre_commit_epoch + msd;
+
+    let result = h.extend_pre_commits(&mut rt, extend(due));
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "must be after", result);
+    rt.reset();
+
+    rt.set_epoch(due + 1);
+    let result = h.extend_pre_commits(&mut rt, extend(due + 100));
+    expect_abort_contains_message(ExitCode::USR_FORBIDDEN, "expired", result);
+    rt.reset();
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_extend_missing_pre_commit() {
+    let (h, mut rt, _) = setup();
+    let params = ExtendPreCommitsParams {
+        extensions: vec![PreCommitExtension { sector_number: 1, prove_commit_due: 10_000 }],
+    };
+    let result = h.extend_pre_commits(&mut rt, params);
+    expect_abort(ExitCode::USR_NOT_FOUND, result);
+    rt.reset();
+
+    h.check_state(&rt);
+}
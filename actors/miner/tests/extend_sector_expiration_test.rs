@@ -3,7 +3,7 @@ use fil_actor_miner::ext::verifreg::Claim as FILPlusClaim;
 use fil_actor_miner::{
     power_for_sector, seal_proof_sector_maximum_lifetime, ExpirationExtension,
     ExpirationExtension2, ExtendSectorExpiration2Params, ExtendSectorExpirationParams,
-    PoStPartition, SectorClaim, SectorOnChainInfo, State,
+    PoStPartition, SectorClaim, SectorOnChainInfo, State, TransferredClaim,
 };
 use fil_actors_runtime::DealWeight;
 use fil_actors_runtime::{
@@ -735,6 +735,117 @@ fn extend_expiration2_drop_claims() {
     );
 }
 
+#[test]
+fn extend_expiration2_drop_transferred_claims() {
+    let (mut h, mut rt) = setup();
+    let verified_deals = vec![
+        test_verified_deal(h.sector_size as u64 / 2),
+        test_verified_deal(h.sector_size as u64 / 2),
+    ];
+    let old_sector = commit_sector_verified_deals(&verified_deals, &mut h, &mut rt);
+    h.advance_and_submit_posts(&mut rt, &vec![old_sector.clone()]);
+
+    let state: State = rt.get_state();
+    let (deadline_index, partition_index) =
+        state.find_sector(rt.policy(), rt.store(), old_sector.sector_number).unwrap();
+
+    let new_expiration = old_sector.expiration + 42 * rt.policy().wpost_proving_period;
+    let claim_ids = vec![400, 500];
+    let client = Address::new_id(3000).id().unwrap();
+    let claim0 = make_claim(
+        claim_ids[0],
+        &old_sector,
+        client,
+        h.receiver.id().unwrap(),
+        new_expiration,
+        &verified_deals[0],
+        rt.policy.minimum_verified_allocation_term,
+    );
+    // The claim as it was before being transferred to another provider.
+    let claim1 = make_claim(
+        claim_ids[1],
+        &old_sector,
+        client,
+        h.receiver.id().unwrap(),
+        new_expiration,
+        &verified_deals[1],
+        rt.policy.minimum_verified_allocation_term,
+    );
+    let mut claims = HashMap::new();
+    claims.insert(claim_ids[0], Ok(claim0));
+    claims.insert(claim_ids[1], Err(actor_error!(not_found, "claim not found")));
+    let mut transferred = HashMap::new();
+    transferred.insert(claim_ids[1], claim1);
+
+    // The transfer removes the claim's weight from the sector.
+    let mut transferred_sector = old_sector.clone();
+    transferred_sector.verified_deal_weight = &old_sector.verified_deal_weight
+        - DealWeight::from(verified_deals[1].size.0)
+            * (old_sector.expiration - old_sector.activation);
+    let power_delta = power_for_sector(h.sector_size, &transferred_sector)
+        - power_for_sector(h.sector_size, &old_sector);
+    h.claims_transferred(
+        &mut rt,
+        vec![TransferredClaim { sector: old_sector.sector_number, size: verified_deals[1].size }],
+        &power_delta,
+    )
+    .unwrap();
+
+    let make_params = |maintain_claims, drop_claims| ExtendSectorExpiration2Params {
+        extensions: vec![ExpirationExtension2 {
+            deadline: deadline_index,
+            partition: partition_index,
+            sectors: BitField::new(),
+            new_expiration,
+            sectors_with_claims: vec![SectorClaim {
+                sector_number: old_sector.sector_number,
+                maintain_claims,
+                drop_claims,
+            }],
+        }],
+    };
+
+    // A transferred claim can't be maintained.
+    expect_abort_contains_message(
+        ExitCode::USR_FORBIDDEN,
+        "transferred to another provider",
+        h.extend_sectors2_with_transferred(
+            &mut rt,
+            make_params(claim_ids.clone(), vec![]),
+            claims.clone(),
+            transferred.clone(),
+        ),
+    );
+    rt.reset();
+
+    // A transferred claim can be dropped before the end of the sector's life.
+    assert!(
+        old_sector.expiration - rt.epoch > rt.policy().end_of_life_claim_drop_period,
+        "test requires an epoch before the end of life claim drop period"
+    );
+    h.extend_sectors2_with_transferred(
+        &mut rt,
+        make_params(vec![claim_ids[0]], vec![claim_ids[1]]),
+        claims,
+        transferred,
+    )
+    .unwrap();
+    check_for_expiration(
+        &mut h,
+        &mut rt,
+        new_expiration,
+        old_sector.sector_number,
+        deadline_index,
+        partition_index,
+    );
+    assert_sector_verified_space(
+        &mut h,
+        &mut rt,
+        old_sector.sector_number,
+        verified_deals[0].size.0,
+    );
+}
+
 #[test]
 fn update_expiration_legacy_fails_on_new_sector_with_deals() {
     let (mut h, mut rt) = setup();
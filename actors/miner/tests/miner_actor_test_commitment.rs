@@ -1,9 +1,11 @@
 use fil_actor_miner::{
-    max_prove_commit_duration, pre_commit_deposit_for_power, qa_power_max, DeadlineInfo, State,
-    VestSpec,
+    max_prove_commit_duration, pre_commit_deposit_for_power, qa_power_max, reason, DeadlineInfo,
+    State, VestSpec,
 };
 use fil_actors_runtime::network::EPOCHS_IN_DAY;
 use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::InsufficientBalance;
+use fvm_ipld_bitfield::BitField;
 use fvm_shared::address::Address;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::consensus::{ConsensusFault, ConsensusFaultType};
@@ -113,7 +115,7 @@ mod miner_actor_test_commitment {
         h.set_proof_type(RegisteredSealProof::StackedDRG64GiBV1);
         let mut rt = h.new_runtime();
 
-        rt.set_balance(insufficient_balance);
+        rt.set_balance(insufficient_balance.clone());
         rt.set_received(TokenAmount::zero());
 
         let precommit_epoch = period_offset + 1;
@@ -126,10 +128,14 @@ mod miner_actor_test_commitment {
 
         let precommit_params = h.make_pre_commit_params(101, challenge_epoch, expiration, vec![]);
 
-        expect_abort(
-            ExitCode::USR_INSUFFICIENT_FUNDS,
-            h.pre_commit_sector(&mut rt, precommit_params, util::PreCommitConfig::default(), true),
-        );
+        let err = h
+            .pre_commit_sector(&mut rt, precommit_params, util::PreCommitConfig::default(), true)
+            .unwrap_err();
+        assert_eq!(ExitCode::USR_INSUFFICIENT_FUNDS, err.exit_code());
+        let reason = err.reason::<InsufficientBalance>().unwrap();
+        assert_eq!(reason::INSUFFICIENT_FUNDS_FOR_DEPOSIT, reason.code);
+        assert_eq!(insufficient_balance, reason.detail.available);
+        assert!(reason.detail.required > reason.detail.available);
         rt.reset();
         h.check_state(&rt);
     }
@@ -207,6 +213,9 @@ mod miner_actor_test_commitment {
                 util::PreCommitConfig::default(),
                 false,
             );
+            let reason = ret.as_ref().unwrap_err().reason::<BitField>().unwrap();
+            assert_eq!(reason::SECTOR_NUMBERS_ALLOCATED, reason.code);
+            assert_eq!(BitField::try_from_bits([101]).unwrap(), reason.detail);
             expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "already allocated", ret);
             rt.reset();
         }
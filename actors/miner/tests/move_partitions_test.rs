@@ -0,0 +1,164 @@
+use fil_actor_miner::{deadline_available_for_move, SectorOnChainInfo, State};
+use fil_actors_runtime::{
+    runtime::RuntimePolicy,
+    test_utils::{expect_abort, expect_abort_contains_message, MockRuntime},
+};
+use fvm_shared::{clock::ChainEpoch, econ::TokenAmount, error::ExitCode};
+
+mod util;
+use fvm_shared::bigint::Zero;
+use util::*;
+
+const PERIOD_OFFSET: ChainEpoch = 100;
+
+fn setup() -> (ActorHarness, MockRuntime) {
+    let h = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = h.new_runtime();
+    h.construct_and_verify(&mut rt);
+    rt.balance.replace(BIG_BALANCE.clone());
+
+    (h, rt)
+}
+
+// Commits and proves four sectors into partition 0 of deadline 0, then waits out the
+// dispute window for that deadline's proofs.
+fn setup_proven_partition(h: &mut ActorHarness, rt: &mut MockRuntime) -> Vec<SectorOnChainInfo> {
+    rt.set_epoch(200);
+    let sectors_info = h.commit_and_prove_sectors(
+        rt,
+        4,
+        DEFAULT_SECTOR_EXPIRATION,
+        vec![vec![10], vec![20], vec![30], vec![40]],
+        true,
+    );
+    h.advance_and_submit_posts(rt, &sectors_info);
+
+    rt.set_epoch(rt.epoch + 100);
+    h.apply_rewards(rt, BIG_REWARDS.clone(), TokenAmount::zero());
+    let target_epoch = rt.epoch + rt.policy().wpost_dispute_window;
+    h.advance_to_epoch_with_cron(rt, target_epoch);
+
+    sectors_info
+}
+
+fn assert_sectors_moved(
+    rt: &MockRuntime,
+    sectors_info: &[SectorOnChainInfo],
+    expected_deadline: u64,
+    expected_partition: u64,
+) {
+    let state: State = rt.get_state();
+    for sector in sectors_info {
+        let (deadline, pid) =
+            state.find_sector(&rt.policy, &rt.store, sector.sector_number).unwrap();
+        assert_eq!(expected_deadline, deadline);
+        assert_eq!(expected_partition, pid);
+    }
+}
+
+#[test]
+fn moves_partition_to_deadline_due_sooner() {
+    let (mut h, mut rt) = setup();
+    let sectors_info = setup_proven_partition(&mut h, &mut rt);
+
+    let dest_deadline = rt.policy().wpost_period_deadlines - 1;
+    let state: State = rt.get_state();
+    assert!(deadline_available_for_move(
+        rt.policy(),
+        state.current_proving_period_start(rt.policy(), rt.epoch),
+        0,
+        dest_deadline,
+        rt.epoch,
+    ));
+
+    h.move_partitions(&mut rt, 0, dest_deadline, bitfield_from_slice(&[0])).unwrap();
+    assert_sectors_moved(&rt, &sectors_info, dest_deadline, 0);
+
+    let orig = h.get_deadline(&rt, 0);
+    assert_eq!(0, orig.live_sectors);
+    assert_eq!(0, orig.total_sectors);
+    let dest = h.get_deadline(&rt, dest_deadline);
+    assert_eq!(4, dest.live_sectors);
+    assert_eq!(4, dest.total_sectors);
+    h.check_state(&rt);
+
+    // The moved sectors are now proven at their new deadline.
+    h.advance_and_submit_posts(&mut rt, &sectors_info);
+    h.check_state(&rt);
+}
+
+#[test]
+fn moving_faulty_partition_preserves_faults() {
+    let (mut h, mut rt) = setup();
+    let sectors_info = setup_proven_partition(&mut h, &mut rt);
+    h.declare_faults(&mut rt, &sectors_info);
+    let faulty_power = h.get_deadline(&rt, 0).faulty_power;
+    assert!(!faulty_power.is_zero());
+
+    let dest_deadline = rt.policy().wpost_period_deadlines - 1;
+    h.move_partitions(&mut rt, 0, dest_deadline, bitfield_from_slice(&[0])).unwrap();
+    assert_sectors_moved(&rt, &sectors_info, dest_deadline, 0);
+
+    assert!(h.get_deadline(&rt, 0).faulty_power.is_zero());
+    assert_eq!(faulty_power, h.get_deadline(&rt, dest_deadline).faulty_power);
+    let (_, partition) = h.get_deadline_and_partition(&rt, dest_deadline, 0);
+    assert_eq!(sectors_info.len() as u64, partition.faults.len());
+    assert_eq!(faulty_power, partition.faulty_power);
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_move_to_deadline_due_after_origin() {
+    let (mut h, mut rt) = setup();
+    setup_proven_partition(&mut h, &mut rt);
+
+    let result = h.move_partitions(&mut rt, 0, 1, bitfield_from_slice(&[0]));
+    expect_abort(ExitCode::USR_FORBIDDEN, result);
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_move_while_proofs_can_be_disputed() {
+    let (mut h, mut rt) = setup();
+    rt.set_epoch(200);
+    let sectors_info = h.commit_and_prove_sectors(
+        &mut rt,
+        4,
+        DEFAULT_SECTOR_EXPIRATION,
+        vec![vec![10], vec![20], vec![30], vec![40]],
+        true,
+    );
+    h.advance_and_submit_posts(&mut rt, &sectors_info);
+
+    let dest_deadline = rt.policy().wpost_period_deadlines - 1;
+    let result = h.move_partitions(&mut rt, 0, dest_deadline, bitfield_from_slice(&[0]));
+    expect_abort(ExitCode::USR_FORBIDDEN, result);
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_move_within_same_deadline() {
+    let (h, mut rt) = setup();
+    let result = h.move_partitions(&mut rt, 3, 3, bitfield_from_slice(&[0]));
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "origin and destination deadlines must differ",
+        result,
+    );
+
+    h.check_state(&rt);
+}
+
+#[test]
+fn fails_to_move_missing_partition() {
+    let (mut h, mut rt) = setup();
+    setup_proven_partition(&mut h, &mut rt);
+
+    let dest_deadline = rt.policy().wpost_period_deadlines - 1;
+    let result = h.move_partitions(&mut rt, 0, dest_deadline, bitfield_from_slice(&[1]));
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "out of range", result);
+
+    h.check_state(&rt);
+}
@@ -79,5 +79,10 @@ fn new_pre_commit_on_chain(
         expiration: 1,
         ..SectorPreCommitInfo::default()
     };
-    SectorPreCommitOnChainInfo { info, pre_commit_deposit: deposit, pre_commit_epoch: epoch }
+    SectorPreCommitOnChainInfo {
+        info,
+        pre_commit_deposit: deposit,
+        pre_commit_epoch: epoch,
+        extension: 0,
+    }
 }
@@ -0,0 +1,395 @@
+use fil_actor_miner::{
+    DataActivationNotification, Method, PieceActivationManifest, PieceChange,
+    PreCommitSectorBatchParams2, ProveCommitAggregateParams2, SectorActivationManifest,
+    SectorChanges, SectorContentChangedParams, SectorPreCommitOnChainInfo,
+};
+use fil_actors_runtime::runtime::DomainSeparationTag;
+use fil_actors_runtime::test_utils::{
+    expect_abort_contains_message, make_piece_cid, MockRuntime, ACCOUNT_ACTOR_CODE_ID,
+};
+use fil_actors_runtime::VERIFIED_REGISTRY_ACTOR_ADDR;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::RawBytes;
+use fvm_shared::address::Address;
+use fvm_shared::bigint::BigInt;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::piece::{PaddedPieceSize, PieceInfo};
+use num_traits::Zero;
+
+mod util;
+use util::*;
+
+// an expiration ~10 days greater than effective min expiration taking into account 30 days max
+// between pre and prove commit
+const DEFAULT_SECTOR_EXPIRATION: ChainEpoch = 220;
+
+const PERIOD_OFFSET: ChainEpoch = 100;
+
+fn precommit_sectors(
+    actor: &ActorHarness,
+    rt: &mut MockRuntime,
+    count: u64,
+) -> (Vec<SectorPreCommitOnChainInfo>, ChainEpoch) {
+    rt.add_balance(BIG_BALANCE.clone());
+    let precommit_epoch = PERIOD_OFFSET + 1;
+    rt.set_epoch(precommit_epoch);
+    actor.construct_and_verify(rt);
+    let dl_info = actor.deadline(rt);
+    let expiration =
+        dl_info.period_end() + rt.policy.wpost_proving_period * DEFAULT_SECTOR_EXPIRATION;
+
+    let precommits = (0..count)
+        .map(|i| {
+            let params = actor.make_pre_commit_params(i, precommit_epoch - 1, expiration, vec![i]);
+            let config = PreCommitConfig::new(Some(make_piece_cid(format!("{}", i).as_bytes())));
+            actor.pre_commit_sector_and_get(rt, params, config, i == 0)
+        })
+        .collect();
+    rt.set_epoch(precommit_epoch + rt.policy.pre_commit_challenge_delay + 1);
+    (precommits, expiration)
+}
+
+// Pre-commits sectors without deals, each declaring an unsealed CID for content to be
+// described by pieces at activation.
+// The harness must pre-commit with PreCommitSectorBatch2 for sectors to declare unsealed CIDs.
+fn precommit_sectors_without_deals(
+    actor: &ActorHarness,
+    rt: &mut MockRuntime,
+    count: u64,
+) -> Vec<SectorPreCommitOnChainInfo> {
+    rt.add_balance(BIG_BALANCE.clone());
+    let precommit_epoch = PERIOD_OFFSET + 1;
+    rt.set_epoch(precommit_epoch);
+    actor.construct_and_verify(rt);
+    let dl_info = actor.deadline(rt);
+    let expiration =
+        dl_info.period_end() + rt.policy.wpost_proving_period * DEFAULT_SECTOR_EXPIRATION;
+
+    let precommits = (0..count)
+        .map(|i| {
+            let unsealed_cid = make_piece_cid(format!("content-{}", i).as_bytes());
+            let sector = actor.make_pre_commit_params_v2(
+                i,
+                precommit_epoch - 1,
+                expiration,
+                vec![],
+                Some(unsealed_cid),
+            );
+            let params = PreCommitSectorBatchParams2 { sectors: vec![sector] };
+            actor.pre_commit_sector_batch_v2(rt, params, i == 0, &TokenAmount::zero()).unwrap();
+            rt.verify();
+            actor.get_precommit(rt, i)
+        })
+        .collect();
+    rt.set_epoch(precommit_epoch + rt.policy.pre_commit_challenge_delay + 1);
+    precommits
+}
+
+fn v2_harness() -> ActorHarness {
+    ActorHarness::new_with_options(HarnessOptions {
+        proving_period_offset: PERIOD_OFFSET,
+        use_v2_pre_commit_and_replica_update: true,
+    })
+}
+
+fn piece_manifest(
+    pc: &SectorPreCommitOnChainInfo,
+    notify: Vec<Address>,
+) -> PieceActivationManifest {
+    PieceActivationManifest {
+        cid: make_piece_cid(format!("piece-{}", pc.info.sector_number).as_bytes()),
+        size: PaddedPieceSize(32 << 30),
+        notify: notify
+            .into_iter()
+            .map(|address| DataActivationNotification {
+                address,
+                payload: RawBytes::new(vec![pc.info.sector_number as u8]),
+            })
+            .collect(),
+    }
+}
+
+fn expect_compute_commd(rt: &MockRuntime, pc: &SectorPreCommitOnChainInfo, commd: cid::Cid) {
+    let piece = piece_manifest(pc, vec![]);
+    rt.expect_compute_unsealed_sector_cid(
+        pc.info.seal_proof,
+        vec![PieceInfo { size: piece.size, cid: piece.cid }],
+        commd,
+        ExitCode::OK,
+    );
+}
+
+#[test]
+fn notifies_each_receiver_once_after_activation() {
+    let actor = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = actor.new_runtime();
+    let (precommits, expiration) = precommit_sectors(&actor, &mut rt, 4);
+
+    let client_a = Address::new_id(5000);
+    let client_b = Address::new_id(5001);
+
+    // Every sector notifies A, only the first also notifies B.
+    let sector_activations: Vec<_> = precommits
+        .iter()
+        .map(|pc| {
+            let notify =
+                if pc.info.sector_number == 0 { vec![client_a, client_b] } else { vec![client_a] };
+            SectorActivationManifest {
+                sector_number: pc.info.sector_number,
+                pieces: vec![piece_manifest(pc, notify)],
+            }
+        })
+        .collect();
+    for pc in &precommits {
+        expect_compute_commd(&rt, pc, pc.info.unsealed_cid.get_cid(pc.info.seal_proof).unwrap());
+    }
+
+    let changes = |pc: &SectorPreCommitOnChainInfo| {
+        let piece = piece_manifest(pc, vec![]);
+        SectorChanges {
+            sector: pc.info.sector_number,
+            minimum_commitment_epoch: expiration,
+            added: vec![PieceChange {
+                data: piece.cid,
+                size: piece.size,
+                payload: RawBytes::new(vec![pc.info.sector_number as u8]),
+            }],
+        }
+    };
+    let notifications = vec![
+        (
+            client_a,
+            SectorContentChangedParams { sectors: precommits.iter().map(changes).collect() },
+        ),
+        (client_b, SectorContentChangedParams { sectors: vec![changes(&precommits[0])] }),
+    ];
+
+    let params = ProveCommitAggregateParams2 { sector_activations, aggregate_proof: vec![0; 1024] };
+    actor
+        .prove_commit_aggregate_sector2(
+            &mut rt,
+            ProveCommitConfig::empty(),
+            precommits.clone(),
+            params,
+            &TokenAmount::zero(),
+            notifications,
+        )
+        .unwrap();
+
+    for pc in &precommits {
+        assert!(!actor.has_precommit(&rt, pc.info.sector_number));
+        assert_eq!(expiration, actor.get_sector(&rt, pc.info.sector_number).expiration);
+    }
+    actor.check_state(&rt);
+}
+
+#[test]
+fn pieces_notifying_verified_registry_count_as_verified_space() {
+    let actor = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = actor.new_runtime();
+    let (precommits, expiration) = precommit_sectors(&actor, &mut rt, 4);
+
+    // Each sector takes over a verified claim for its piece from another provider.
+    let sector_activations: Vec<_> = precommits
+        .iter()
+        .map(|pc| SectorActivationManifest {
+            sector_number: pc.info.sector_number,
+            pieces: vec![piece_manifest(pc, vec![VERIFIED_REGISTRY_ACTOR_ADDR])],
+        })
+        .collect();
+    let mut config = ProveCommitConfig::empty();
+    for pc in &precommits {
+        expect_compute_commd(&rt, pc, pc.info.unsealed_cid.get_cid(pc.info.seal_proof).unwrap());
+        let piece = piece_manifest(pc, vec![]);
+        config.transferred_claim_space.insert(pc.info.sector_number, BigInt::from(piece.size.0));
+    }
+    let changes = precommits
+        .iter()
+        .map(|pc| {
+            let piece = piece_manifest(pc, vec![]);
+            SectorChanges {
+                sector: pc.info.sector_number,
+                minimum_commitment_epoch: expiration,
+                added: vec![PieceChange {
+                    data: piece.cid,
+                    size: piece.size,
+                    payload: RawBytes::new(vec![pc.info.sector_number as u8]),
+                }],
+            }
+        })
+        .collect();
+
+    let activation = rt.epoch;
+    let params = ProveCommitAggregateParams2 { sector_activations, aggregate_proof: vec![0; 1024] };
+    actor
+        .prove_commit_aggregate_sector2(
+            &mut rt,
+            config,
+            precommits.clone(),
+            params,
+            &TokenAmount::zero(),
+            vec![(VERIFIED_REGISTRY_ACTOR_ADDR, SectorContentChangedParams { sectors: changes })],
+        )
+        .unwrap();
+
+    for pc in &precommits {
+        let sector = actor.get_sector(&rt, pc.info.sector_number);
+        assert_eq!(
+            BigInt::from(32u64 << 30) * (expiration - activation),
+            sector.verified_deal_weight
+        );
+    }
+    actor.check_state(&rt);
+}
+
+#[test]
+fn rejects_pieces_not_matching_unsealed_cid() {
+    let actor = ActorHarness::new(PERIOD_OFFSET);
+    let mut rt = actor.new_runtime();
+    let (precommits, _) = precommit_sectors(&actor, &mut rt, 4);
+
+    let sector_activations: Vec<_> = precommits
+        .iter()
+        .map(|pc| SectorActivationManifest {
+            sector_number: pc.info.sector_number,
+            pieces: vec![piece_manifest(pc, vec![Address::new_id(5000)])],
+        })
+        .collect();
+
+    // The first sector's pieces compute to a different CommD.
+    let pc = &precommits[0];
+    expect_compute_commd(&rt, pc, make_piece_cid(b"wrong"));
+    let receiver_bytes = RawBytes::serialize(actor.receiver).unwrap();
+    rt.expect_get_randomness_from_tickets(
+        DomainSeparationTag::SealRandomness,
+        pc.info.seal_rand_epoch,
+        receiver_bytes.to_vec(),
+        TEST_RANDOMNESS_ARRAY_FROM_ONE,
+    );
+    rt.expect_get_randomness_from_beacon(
+        DomainSeparationTag::InteractiveSealChallengeSeed,
+        pc.pre_commit_epoch + rt.policy.pre_commit_challenge_delay,
+        receiver_bytes.to_vec(),
+        TEST_RANDOMNESS_ARRAY_FROM_TWO,
+    );
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, actor.worker);
+    rt.expect_validate_caller_addr(actor.caller_addrs());
+    let params = ProveCommitAggregateParams2 { sector_activations, aggregate_proof: vec![0; 1024] };
+    let result = rt.call::<fil_actor_miner::Actor>(
+        Method::ProveCommitAggregate2 as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "pieces do not match", result);
+    rt.reset();
+    actor.check_state(&rt);
+}
+
+#[test]
+fn activates_sectors_without_deals_with_matching_pieces() {
+    let actor = v2_harness();
+    let mut rt = actor.new_runtime();
+    let precommits = precommit_sectors_without_deals(&actor, &mut rt, 4);
+
+    let sector_activations: Vec<_> = precommits
+        .iter()
+        .map(|pc| SectorActivationManifest {
+            sector_number: pc.info.sector_number,
+            pieces: vec![piece_manifest(pc, vec![])],
+        })
+        .collect();
+    for pc in &precommits {
+        expect_compute_commd(&rt, pc, pc.info.unsealed_cid.get_cid(pc.info.seal_proof).unwrap());
+    }
+
+    let params = ProveCommitAggregateParams2 { sector_activations, aggregate_proof: vec![0; 1024] };
+    actor
+        .prove_commit_aggregate_sector2(
+            &mut rt,
+            ProveCommitConfig::empty(),
+            precommits.clone(),
+            params,
+            &TokenAmount::zero(),
+            vec![],
+        )
+        .unwrap();
+
+    for pc in &precommits {
+        assert!(!actor.has_precommit(&rt, pc.info.sector_number));
+        actor.get_sector(&rt, pc.info.sector_number);
+    }
+    actor.check_state(&rt);
+}
+
+#[test]
+fn rejects_aggregate_activation_of_sector_without_deals_or_pieces() {
+    let actor = v2_harness();
+    let mut rt = actor.new_runtime();
+    let precommits = precommit_sectors_without_deals(&actor, &mut rt, 4);
+
+    // The first sector declares no pieces, so nothing vouches for its unsealed CID.
+    let sector_activations: Vec<_> = precommits
+        .iter()
+        .map(|pc| SectorActivationManifest {
+            sector_number: pc.info.sector_number,
+            pieces: if pc.info.sector_number == 0 {
+                vec![]
+            } else {
+                vec![piece_manifest(pc, vec![])]
+            },
+        })
+        .collect();
+    let pc = &precommits[0];
+    let receiver_bytes = RawBytes::serialize(actor.receiver).unwrap();
+    rt.expect_get_randomness_from_tickets(
+        DomainSeparationTag::SealRandomness,
+        pc.info.seal_rand_epoch,
+        receiver_bytes.to_vec(),
+        TEST_RANDOMNESS_ARRAY_FROM_ONE,
+    );
+    rt.expect_get_randomness_from_beacon(
+        DomainSeparationTag::InteractiveSealChallengeSeed,
+        pc.pre_commit_epoch + rt.policy.pre_commit_challenge_delay,
+        receiver_bytes.to_vec(),
+        TEST_RANDOMNESS_ARRAY_FROM_TWO,
+    );
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, actor.worker);
+    rt.expect_validate_caller_addr(actor.caller_addrs());
+    let params = ProveCommitAggregateParams2 { sector_activations, aggregate_proof: vec![0; 1024] };
+    let result = rt.call::<fil_actor_miner::Actor>(
+        Method::ProveCommitAggregate2 as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "without deals must declare pieces",
+        result,
+    );
+    rt.reset();
+    actor.check_state(&rt);
+}
+
+#[test]
+fn rejects_prove_commit_of_sector_without_deals_or_pieces() {
+    let actor = v2_harness();
+    let mut rt = actor.new_runtime();
+    let precommits = precommit_sectors_without_deals(&actor, &mut rt, 1);
+
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, actor.worker);
+    rt.expect_validate_caller_any();
+    let params = actor.make_prove_commit_params(precommits[0].info.sector_number);
+    let result = rt.call::<fil_actor_miner::Actor>(
+        Method::ProveCommitSector as u64,
+        IpldBlock::serialize_cbor(&params).unwrap(),
+    );
+    expect_abort_contains_message(
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "without deals must declare pieces",
+        result,
+    );
+    rt.reset();
+    actor.check_state(&rt);
+}
@@ -16,21 +16,24 @@ use fil_actor_miner::{
     initial_pledge_for_power, locked_reward_from_reward, max_prove_commit_duration,
     new_deadline_info_from_offset_and_epoch, pledge_penalty_for_continued_fault, power_for_sectors,
     qa_power_for_sector, qa_power_for_weight, reward_for_consensus_slash_report, ActiveBeneficiary,
-    Actor, ApplyRewardParams, BeneficiaryTerm, BitFieldQueue, ChangeBeneficiaryParams,
-    ChangeMultiaddrsParams, ChangePeerIDParams, ChangeWorkerAddressParams, CheckSectorProvenParams,
-    CompactCommD, CompactPartitionsParams, CompactSectorNumbersParams, ConfirmSectorProofsParams,
-    CronEventPayload, Deadline, DeadlineInfo, Deadlines, DeclareFaultsParams,
-    DeclareFaultsRecoveredParams, DeferredCronEventParams, DisputeWindowedPoStParams,
-    ExpirationQueue, ExpirationSet, ExtendSectorExpiration2Params, ExtendSectorExpirationParams,
-    FaultDeclaration, GetAvailableBalanceReturn, GetBeneficiaryReturn, GetControlAddressesReturn,
-    GetMultiaddrsReturn, GetPeerIDReturn, Method, MinerConstructorParams as ConstructorParams,
-    MinerInfo, Partition, PendingBeneficiaryChange, PoStPartition, PowerPair,
-    PreCommitSectorBatchParams, PreCommitSectorBatchParams2, PreCommitSectorParams,
-    ProveCommitSectorParams, RecoveryDeclaration, ReportConsensusFaultParams, SectorOnChainInfo,
-    SectorPreCommitInfo, SectorPreCommitOnChainInfo, Sectors, State, SubmitWindowedPoStParams,
-    TerminateSectorsParams, TerminationDeclaration, VestingFunds, WindowedPoSt,
+    Actor, ApplyRewardParams, BeneficiaryTerm, BitFieldQueue, ChangeBeneficiariesParams,
+    ChangeBeneficiaryParams, ChangeMultiaddrsParams, ChangePeerIDParams, ChangeWorkerAddressParams,
+    CheckSectorProvenParams, ClaimsTransferredParams, CompactCommD, CompactPartitionsParams,
+    CompactSectorNumbersParams, ConfirmSectorProofsParams, CronEventPayload, Deadline,
+    DeadlineInfo, Deadlines, DeclareFaultsParams, DeclareFaultsRecoveredParams,
+    DeferredCronEventParams, DisputeWindowedPoStParams, ExpirationQueue, ExpirationSet,
+    ExtendPreCommitsParams, ExtendSectorExpiration2Params, ExtendSectorExpirationParams,
+    FaultDeclaration, GetAvailableBalanceReturn, GetBeneficiariesReturn, GetBeneficiaryReturn,
+    GetControlAddressesReturn, GetMultiaddrsReturn, GetPeerIDReturn, Method,
+    MinerConstructorParams as ConstructorParams, MinerInfo, MovePartitionsParams, Partition,
+    PendingBeneficiaryChange, PieceReturn, PoStPartition, PowerPair, PreCommitSectorBatchParams,
+    PreCommitSectorBatchParams2, PreCommitSectorParams, ProveCommitAggregateParams2,
+    ProveCommitSectorParams, RecoveryDeclaration, ReportConsensusFaultParams,
+    SectorContentChangedParams, SectorContentChangedReturn, SectorOnChainInfo, SectorPreCommitInfo,
+    SectorPreCommitOnChainInfo, SectorReturn, Sectors, State, SubmitWindowedPoStParams,
+    TerminateSectorsParams, TerminationDeclaration, TransferredClaim, VestingFunds, WindowedPoSt,
     WithdrawBalanceParams, WithdrawBalanceReturn, CRON_EVENT_PROVING_DEADLINE,
-    SECTORS_AMT_BITWIDTH,
+    SECTORS_AMT_BITWIDTH, SECTOR_CONTENT_CHANGED,
 };
 use fil_actor_miner::{Method as MinerMethod, ProveCommitAggregateParams};
 use fil_actor_power::{
@@ -821,6 +824,35 @@ impl ActorHarness {
         self.get_precommit(rt, params.sector_number)
     }
 
+    pub fn extend_pre_commits(
+        &self,
+        rt: &mut MockRuntime,
+        params: ExtendPreCommitsParams,
+    ) -> Result<(), ActorError> {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
+        rt.expect_validate_caller_addr(self.caller_addrs());
+        self.expect_query_network_info(rt);
+
+        let state = self.get_state(rt);
+        if state.fee_debt.is_positive() {
+            rt.expect_send_simple(
+                BURNT_FUNDS_ACTOR_ADDR,
+                METHOD_SEND,
+                None,
+                state.fee_debt.clone(),
+                None,
+                ExitCode::OK,
+            );
+        }
+
+        rt.call::<Actor>(
+            Method::ExtendPreCommits as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+        rt.verify();
+        Ok(())
+    }
+
     pub fn has_precommit(&self, rt: &MockRuntime, sector_number: SectorNumber) -> bool {
         let state = self.get_state(rt);
         state.get_precommitted_sector(&rt.store, sector_number).unwrap().is_some()
@@ -940,6 +972,52 @@ impl ActorHarness {
         precommits: Vec<SectorPreCommitOnChainInfo>,
         params: ProveCommitAggregateParams,
         base_fee: &TokenAmount,
+    ) -> Result<(), ActorError> {
+        self.prove_commit_aggregate_sector_inner(
+            rt,
+            config,
+            precommits,
+            MinerMethod::ProveCommitAggregate as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+            &params.aggregate_proof,
+            base_fee,
+            vec![],
+        )
+    }
+
+    // Expects the notifications to be accepted by their receivers.
+    // Expectations for computing the sectors' unsealed CIDs from the pieces must be set by the caller.
+    pub fn prove_commit_aggregate_sector2(
+        &self,
+        rt: &mut MockRuntime,
+        config: ProveCommitConfig,
+        precommits: Vec<SectorPreCommitOnChainInfo>,
+        params: ProveCommitAggregateParams2,
+        base_fee: &TokenAmount,
+        notifications: Vec<(Address, SectorContentChangedParams)>,
+    ) -> Result<(), ActorError> {
+        self.prove_commit_aggregate_sector_inner(
+            rt,
+            config,
+            precommits,
+            MinerMethod::ProveCommitAggregate2 as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+            &params.aggregate_proof,
+            base_fee,
+            notifications,
+        )
+    }
+
+    fn prove_commit_aggregate_sector_inner(
+        &self,
+        rt: &mut MockRuntime,
+        config: ProveCommitConfig,
+        precommits: Vec<SectorPreCommitOnChainInfo>,
+        method: MethodNum,
+        params: Option<IpldBlock>,
+        aggregate_proof: &[u8],
+        base_fee: &TokenAmount,
+        notifications: Vec<(Address, SectorContentChangedParams)>,
     ) -> Result<(), ActorError> {
         let comm_ds: Vec<_> = precommits
             .iter()
@@ -987,7 +1065,7 @@ impl ActorHarness {
                 unsealed_cid: comm_ds[i],
             })
         }
-        rt.expect_aggregate_verify_seals(svis, params.aggregate_proof.clone(), Ok(()));
+        rt.expect_aggregate_verify_seals(svis, aggregate_proof.to_vec(), Ok(()));
 
         // confirm sector proofs valid
         self.confirm_sector_proofs_valid_internal(rt, config, &precommits);
@@ -1004,13 +1082,30 @@ impl ActorHarness {
             ExitCode::OK,
         );
 
+        for (receiver, notification) in notifications {
+            let ret = SectorContentChangedReturn {
+                sectors: notification
+                    .sectors
+                    .iter()
+                    .map(|sector| SectorReturn {
+                        added: vec![PieceReturn { accepted: true }; sector.added.len()],
+                    })
+                    .collect(),
+            };
+            rt.expect_send_simple(
+                receiver,
+                SECTOR_CONTENT_CHANGED,
+                IpldBlock::serialize_cbor(&notification).unwrap(),
+                TokenAmount::zero(),
+                IpldBlock::serialize_cbor(&ret).unwrap(),
+                ExitCode::OK,
+            );
+        }
+
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
         let addrs = self.caller_addrs().clone();
         rt.expect_validate_caller_addr(addrs);
-        rt.call::<Actor>(
-            MinerMethod::ProveCommitAggregate as u64,
-            IpldBlock::serialize_cbor(&params).unwrap(),
-        )?;
+        rt.call::<Actor>(method, params)?;
         rt.verify();
 
         Ok(())
@@ -1135,7 +1230,10 @@ impl ActorHarness {
             let mut expected_raw_power = BigInt::from(0);
 
             for pc in valid_pcs {
-                let spaces = cfg.deal_spaces(&pc.info.sector_number);
+                let mut spaces = cfg.deal_spaces(&pc.info.sector_number);
+                if let Some(space) = cfg.transferred_claim_space.get(&pc.info.sector_number) {
+                    spaces.verified_deal_space += space;
+                }
 
                 let duration = pc.info.expiration - rt.epoch;
                 let deal_weight = spaces.deal_space * duration;
@@ -1800,6 +1898,51 @@ impl ActorHarness {
         PowerPair { raw: expected_raw_delta, qa: expected_qa_delta }
     }
 
+    // Invokes ClaimsTransferred as the verified registry, expecting the miner's power to
+    // change by the given delta.
+    pub fn claims_transferred(
+        &self,
+        rt: &mut MockRuntime,
+        claims: Vec<TransferredClaim>,
+        expected_power_delta: &PowerPair,
+    ) -> Result<(), ActorError> {
+        self.claims_transferred_after(rt, claims, vec![], expected_power_delta)
+    }
+
+    // As claims_transferred, with the space of claims transferred from the same sectors
+    // by earlier notifications.
+    pub fn claims_transferred_after(
+        &self,
+        rt: &mut MockRuntime,
+        claims: Vec<TransferredClaim>,
+        prior_transfers: Vec<TransferredClaim>,
+        expected_power_delta: &PowerPair,
+    ) -> Result<(), ActorError> {
+        rt.set_caller(*VERIFREG_ACTOR_CODE_ID, VERIFIED_REGISTRY_ACTOR_ADDR);
+        rt.expect_validate_caller_addr(vec![VERIFIED_REGISTRY_ACTOR_ADDR]);
+        if !expected_power_delta.is_zero() {
+            let claim = UpdateClaimedPowerParams {
+                raw_byte_delta: expected_power_delta.raw.clone(),
+                quality_adjusted_delta: expected_power_delta.qa.clone(),
+            };
+            rt.expect_send_simple(
+                STORAGE_POWER_ACTOR_ADDR,
+                PowerMethod::UpdateClaimedPower as u64,
+                IpldBlock::serialize_cbor(&claim).unwrap(),
+                TokenAmount::zero(),
+                None,
+                ExitCode::OK,
+            );
+        }
+        rt.call::<Actor>(
+            Method::ClaimsTransferred as u64,
+            IpldBlock::serialize_cbor(&ClaimsTransferredParams { claims, prior_transfers })
+                .unwrap(),
+        )?;
+        rt.verify();
+        Ok(())
+    }
+
     pub fn declare_recoveries(
         &self,
         rt: &mut MockRuntime,
@@ -2350,6 +2493,22 @@ impl ActorHarness {
         Ok(ret)
     }
 
+    pub fn change_beneficiaries(
+        &self,
+        rt: &mut MockRuntime,
+        caller: Address,
+        params: &ChangeBeneficiariesParams,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.expect_validate_caller_any();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, caller);
+        let ret = rt.call::<Actor>(
+            Method::ChangeBeneficiaries as u64,
+            IpldBlock::serialize_cbor(params).unwrap(),
+        )?;
+        rt.verify();
+        Ok(ret)
+    }
+
     pub fn get_beneficiary(
         &mut self,
         rt: &mut MockRuntime,
@@ -2360,6 +2519,16 @@ impl ActorHarness {
         Ok(ret.unwrap().deserialize::<GetBeneficiaryReturn>().unwrap())
     }
 
+    pub fn get_beneficiaries(
+        &self,
+        rt: &mut MockRuntime,
+    ) -> Result<GetBeneficiariesReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let ret = rt.call::<Actor>(Method::GetBeneficiaries as u64, None)?;
+        rt.verify();
+        Ok(ret.unwrap().deserialize::<GetBeneficiariesReturn>().unwrap())
+    }
+
     // extend sectors without verified deals using either legacy or updated sector extension
     pub fn extend_sectors_versioned(
         &self,
@@ -2422,10 +2591,22 @@ impl ActorHarness {
     }
 
     pub fn extend_sectors2(
+        &self,
+        rt: &mut MockRuntime,
+        params: ExtendSectorExpiration2Params,
+        expected_claims: HashMap<ClaimID, Result<FILPlusClaim, ActorError>>,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        self.extend_sectors2_with_transferred(rt, params, expected_claims, HashMap::new())
+    }
+
+    // As extend_sectors2, with claims that have been transferred away from the miner,
+    // which are looked up for those not found among the miner's claims.
+    pub fn extend_sectors2_with_transferred(
         &self,
         rt: &mut MockRuntime,
         mut params: ExtendSectorExpiration2Params,
         expected_claims: HashMap<ClaimID, Result<FILPlusClaim, ActorError>>,
+        transferred_claims: HashMap<ClaimID, FILPlusClaim>,
     ) -> Result<Option<IpldBlock>, ActorError> {
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
         rt.expect_validate_caller_addr(self.caller_addrs());
@@ -2438,6 +2619,7 @@ impl ActorHarness {
                 let mut all_claim_ids = sc.maintain_claims.clone();
                 all_claim_ids.append(&mut sc.drop_claims.clone());
                 let mut batch_gen = BatchReturnGen::new(all_claim_ids.len());
+                let mut missing_ids = Vec::new();
                 for claim_id in &all_claim_ids {
                     match expected_claims.get(&claim_id).unwrap().clone() {
                         Ok(claim) => {
@@ -2446,6 +2628,7 @@ impl ActorHarness {
                         }
                         Err(ae) => {
                             batch_gen.add_fail(ae.exit_code());
+                            missing_ids.push(*claim_id);
                         }
                     }
                 }
@@ -2466,6 +2649,36 @@ impl ActorHarness {
                     .unwrap(),
                     ExitCode::OK,
                 );
+
+                if !missing_ids.is_empty() {
+                    let mut transferred = Vec::new();
+                    let mut batch_gen = BatchReturnGen::new(missing_ids.len());
+                    for claim_id in &missing_ids {
+                        match transferred_claims.get(claim_id) {
+                            Some(claim) => {
+                                batch_gen.add_success();
+                                transferred.push(claim.clone());
+                            }
+                            None => batch_gen.add_fail(ExitCode::USR_NOT_FOUND),
+                        }
+                    }
+                    rt.expect_send_simple(
+                        VERIFIED_REGISTRY_ACTOR_ADDR,
+                        fil_actor_miner::ext::verifreg::GET_TRANSFERRED_CLAIMS_METHOD,
+                        IpldBlock::serialize_cbor(&GetClaimsParams {
+                            provider: self.receiver.id().unwrap(),
+                            claim_ids: missing_ids,
+                        })
+                        .unwrap(),
+                        TokenAmount::zero(),
+                        IpldBlock::serialize_cbor(&GetClaimsReturn {
+                            batch_info: batch_gen.gen(),
+                            claims: transferred,
+                        })
+                        .unwrap(),
+                        ExitCode::OK,
+                    );
+                }
             }
         }
 
@@ -2481,6 +2694,7 @@ impl ActorHarness {
             for sector_claim in &extension.sectors_with_claims {
                 let mut dropped_space = BigInt::zero();
                 for drop in &sector_claim.drop_claims {
+                    // Transferred claims no longer carry weight in the sector.
                     dropped_space += match expected_claims.get(&drop).unwrap() {
                         Ok(claim) => BigInt::from(claim.size.0),
                         Err(_) => BigInt::zero(),
@@ -2542,6 +2756,26 @@ impl ActorHarness {
         Ok(())
     }
 
+    pub fn move_partitions(
+        &self,
+        rt: &mut MockRuntime,
+        orig_deadline: u64,
+        dest_deadline: u64,
+        partitions: BitField,
+    ) -> Result<(), ActorError> {
+        let params = MovePartitionsParams { orig_deadline, dest_deadline, partitions };
+
+        rt.expect_validate_caller_addr(self.caller_addrs());
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, self.worker);
+
+        rt.call::<Actor>(
+            Method::MovePartitions as u64,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+        rt.verify();
+        Ok(())
+    }
+
     pub fn get_info(&self, rt: &MockRuntime) -> MinerInfo {
         let state: State = rt.get_state();
         state.get_info(rt.store()).unwrap()
@@ -2642,6 +2876,8 @@ pub struct ProveCommitConfig {
     pub claim_allocs_exit: HashMap<SectorNumber, ExitCode>,
     pub deal_space: HashMap<SectorNumber, BigInt>,
     pub verified_deal_infos: HashMap<SectorNumber, Vec<VerifiedDealInfo>>,
+    // Space of verified claims transferred into each sector by its piece manifests.
+    pub transferred_claim_space: HashMap<SectorNumber, BigInt>,
 }
 
 #[allow(dead_code)]
@@ -2663,6 +2899,7 @@ impl ProveCommitConfig {
             claim_allocs_exit: HashMap::new(),
             deal_space: HashMap::new(),
             verified_deal_infos: HashMap::new(),
+            transferred_claim_space: HashMap::new(),
         }
     }
 
@@ -0,0 +1,51 @@
+//! JSON representations of the multisig actor's method parameters and return values.
+
+use fil_actors_runtime::json::{JsonCodec, JsonError, MethodCodec, Value};
+use fil_actors_runtime::{json_struct, method_codecs};
+use fvm_actor_utils::receiver::UniversalReceiverParams;
+
+use crate::{
+    AddSignerParams, ApproveReturn, ChangeNumApprovalsThresholdParams, ConstructorParams,
+    LockBalanceParams, Method, ProposeParams, ProposeReturn, RemoveSignerParams, SwapSignerParams,
+    Transaction, TxnID, TxnIDParams,
+};
+
+impl JsonCodec for TxnID {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        i64::from_json(value).map(TxnID)
+    }
+}
+
+json_struct! {
+    ConstructorParams { signers, num_approvals_threshold, unlock_duration, start_epoch }
+    ProposeParams { to, value, method, params }
+    ProposeReturn { txn_id, applied, code, ret }
+    TxnIDParams { id, proposal_hash }
+    ApproveReturn { applied, code, ret }
+    AddSignerParams { signer, increase }
+    RemoveSignerParams { signer, decrease }
+    SwapSignerParams { from, to }
+    ChangeNumApprovalsThresholdParams { new_threshold }
+    LockBalanceParams { start_epoch, unlock_duration, amount }
+    Transaction { to, value, method, params, approved }
+    remote UniversalReceiverParamsJson(UniversalReceiverParams) { type_, payload }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (ConstructorParams, ()),
+        Propose => (ProposeParams, ProposeReturn),
+        Approve => (TxnIDParams, ApproveReturn),
+        Cancel => (TxnIDParams, ()),
+        AddSigner => (AddSignerParams, ()),
+        RemoveSigner => (RemoveSignerParams, ()),
+        SwapSigner => (SwapSignerParams, ()),
+        ChangeNumApprovalsThreshold => (ChangeNumApprovalsThresholdParams, ()),
+        LockBalance => (LockBalanceParams, ()),
+        UniversalReceiverHook => (UniversalReceiverParamsJson, ()),
+    }
+}
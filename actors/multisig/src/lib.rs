@@ -26,6 +26,8 @@ pub use self::types::*;
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+#[cfg(feature = "json")]
+pub mod json;
 mod state;
 pub mod testing;
 mod types;
@@ -8,6 +8,7 @@ use integer_encoding::VarInt;
 
 use crate::{State, Transaction, TxnID, SIGNERS_MAX};
 
+#[derive(Debug)]
 pub struct StateSummary {
     pub pending_tx_count: u64,
     pub num_approvals_threshold: u64,
@@ -22,11 +23,11 @@ pub fn check_state_invariants<BS: Blockstore>(
     let acc = MessageAccumulator::default();
 
     // assert invariants involving signers
-    acc.require(
+    acc.with_code("multisig.signers.max").require(
         state.signers.len() <= SIGNERS_MAX,
         format!("multisig has too many signers: {}", state.signers.len()),
     );
-    acc.require(
+    acc.with_code("multisig.signers.threshold").require(
         state.signers.len() as u64 >= state.num_approvals_threshold,
         format!(
             "multisig has insufficient signers to meet threshold ({} < {})",
@@ -37,11 +38,11 @@ pub fn check_state_invariants<BS: Blockstore>(
 
     // See https://github.com/filecoin-project/specs-actors/issues/1185
     if state.unlock_duration == 0 {
-        acc.require(
+        acc.with_code("multisig.unlock.start_epoch").require(
             state.start_epoch == 0,
             format!("non-zero start epoch {} with zero unlock duration", state.start_epoch),
         );
-        acc.require(
+        acc.with_code("multisig.unlock.initial_balance").require(
             state.initial_balance.is_zero(),
             format!("non-zero locked balance {} with zero unlock duration", state.initial_balance),
         );
@@ -67,22 +68,23 @@ pub fn check_state_invariants<BS: Blockstore>(
                     max_tx_id = tx_id;
                 }
 
+                let acc = acc.with_context("txn", tx_id.0);
                 let mut seen_approvals = HashSet::<&Address>::new();
                 transaction.approved.iter().for_each(|approval| {
-                    acc.require(
+                    acc.with_code("multisig.txn.approver").require(
                         signers.contains(approval),
                         format!(
                             "approval {approval} for transaction {tx_id} is not in signers list"
                         ),
                     );
 
-                    acc.require(
+                    acc.with_code("multisig.txn.duplicate_approval").require(
                         !seen_approvals.contains(approval),
                         format!("duplicate approval {approval} for transaction {tx_id}"),
                     );
                     seen_approvals.insert(approval);
                 });
-                acc.require((seen_approvals.len() as u64) < state.num_approvals_threshold,
+                acc.with_code("multisig.txn.threshold_met").require((seen_approvals.len() as u64) < state.num_approvals_threshold,
                     format!("number of approvals ({}) meets the approvals threshold ({}), transaction should not be pending",
                     seen_approvals.len(), state.num_approvals_threshold));
 
@@ -91,12 +93,12 @@ pub fn check_state_invariants<BS: Blockstore>(
                 Ok(())
             });
 
-            acc.require_no_error(ret, "error iterating transactions");
+            acc.with_code("multisig.txns").require_no_error(ret, "error iterating transactions");
         }
-        Err(e) => acc.add(format!("error loading transactions: {e}")),
+        Err(e) => acc.with_code("multisig.txns").add(format!("error loading transactions: {e}")),
     };
 
-    acc.require(
+    acc.with_code("multisig.next_txn_id").require(
         state.next_tx_id > max_tx_id,
         format!("next transaction id {} is not greater than pending ids", state.next_tx_id),
     );
@@ -0,0 +1,22 @@
+//! JSON representations of the passkey account actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs};
+
+use crate::types::{AuthenticateMessageParams, ConstructorParams};
+use crate::Method;
+
+json_struct! {
+    ConstructorParams { public_key, rp_id_hash }
+}
+
+json_struct! {
+    AuthenticateMessageParams { signature, message }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (ConstructorParams, ()),
+        AuthenticateMessageExported => (AuthenticateMessageParams, ()),
+    }
+}
@@ -0,0 +1,141 @@
+#[cfg(feature = "json")]
+pub mod json;
+mod state;
+pub mod testing;
+pub mod types;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::crypto::hash::SupportedHashes::Sha256;
+use fvm_shared::{MethodNum, METHOD_CONSTRUCTOR};
+use num_derive::FromPrimitive;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+
+use crate::types::{AuthenticateMessageParams, ConstructorParams, WebAuthnAssertion};
+use fil_actors_runtime::runtime::{ActorCode, Runtime};
+use fil_actors_runtime::{
+    actor_dispatch, actor_error, ActorError, FIRST_EXPORTED_METHOD_NUMBER, INIT_ACTOR_ADDR,
+};
+
+pub use self::state::State;
+
+#[cfg(feature = "fil-actor")]
+fil_actors_runtime::wasm_trampoline!(PasskeyAccountActor);
+
+/// Length of the SHA-256 relying party ID hash at the start of the authenticator data.
+pub const RP_ID_HASH_LEN: usize = 32;
+/// Minimum length of authenticator data: the RP ID hash, a flags byte and a 4-byte counter.
+pub const MIN_AUTHENTICATOR_DATA_LEN: usize = RP_ID_HASH_LEN + 1 + 4;
+/// Authenticator data flag indicating the user was present.
+pub const FLAG_USER_PRESENT: u8 = 0x01;
+
+/// Passkey Account actor methods.
+#[derive(FromPrimitive)]
+#[repr(u64)]
+pub enum Method {
+    Constructor = METHOD_CONSTRUCTOR,
+    AuthenticateMessageExported = frc42_dispatch::method_hash!("AuthenticateMessage"),
+}
+
+/// Passkey Account actor.
+/// Authenticates messages with WebAuthn assertions from a P-256 (secp256r1) passkey credential.
+pub struct PasskeyAccountActor;
+
+impl PasskeyAccountActor {
+    /// Passkey Account actor constructor, called via the init actor's Exec.
+    pub fn constructor(rt: &mut impl Runtime, params: ConstructorParams) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_is(std::iter::once(&INIT_ACTOR_ADDR))?;
+
+        VerifyingKey::from_sec1_bytes(&params.public_key)
+            .map_err(|_| actor_error!(illegal_argument; "invalid P-256 public key"))?;
+        if params.rp_id_hash.len() != RP_ID_HASH_LEN {
+            return Err(actor_error!(illegal_argument;
+                "relying party ID hash must be {} bytes, got {}",
+                RP_ID_HASH_LEN, params.rp_id_hash.len()));
+        }
+
+        rt.create(&State { public_key: params.public_key, rp_id_hash: params.rp_id_hash })?;
+        Ok(())
+    }
+
+    /// Authenticates whether the provided signature is valid for the provided message.
+    /// The signature must be a CBOR-encoded WebAuthn assertion whose challenge is the
+    /// blake2b-256 hash of the message.
+    /// Errors with USR_ILLEGAL_ARGUMENT if the authentication is invalid.
+    pub fn authenticate_message(
+        rt: &mut impl Runtime,
+        params: AuthenticateMessageParams,
+    ) -> Result<(), ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let st: State = rt.state()?;
+
+        let assertion: WebAuthnAssertion = fvm_ipld_encoding::from_slice(&params.signature)
+            .map_err(|e| actor_error!(illegal_argument; "invalid webauthn assertion: {}", e))?;
+
+        let auth_data = &assertion.authenticator_data;
+        if auth_data.len() < MIN_AUTHENTICATOR_DATA_LEN {
+            return Err(actor_error!(illegal_argument;
+                "authenticator data must be at least {} bytes, got {}",
+                MIN_AUTHENTICATOR_DATA_LEN, auth_data.len()));
+        }
+        if auth_data[..RP_ID_HASH_LEN] != st.rp_id_hash[..] {
+            return Err(
+                actor_error!(illegal_argument; "assertion is for a different relying party"),
+            );
+        }
+        if auth_data[RP_ID_HASH_LEN] & FLAG_USER_PRESENT == 0 {
+            return Err(
+                actor_error!(illegal_argument; "assertion does not indicate user presence"),
+            );
+        }
+
+        // The user agent serializes the type and challenge first and without whitespace,
+        // so the client data can be checked against an expected prefix without parsing it.
+        let challenge = rt.hash_blake2b(&params.message);
+        let expected_prefix = format!(
+            r#"{{"type":"webauthn.get","challenge":"{}""#,
+            URL_SAFE_NO_PAD.encode(challenge)
+        );
+        if !assertion.client_data_json.starts_with(expected_prefix.as_bytes()) {
+            return Err(actor_error!(illegal_argument; "client data does not match message"));
+        }
+
+        // The authenticator signs the authenticator data followed by the client data hash.
+        let mut signed = assertion.authenticator_data.clone();
+        signed.extend_from_slice(&rt.hash(Sha256, &assertion.client_data_json));
+
+        let signature = Signature::from_der(&assertion.signature)
+            .map_err(|_| actor_error!(illegal_argument; "invalid signature encoding"))?;
+        let key = VerifyingKey::from_sec1_bytes(&st.public_key)
+            .map_err(|_| actor_error!(illegal_state; "invalid stored public key"))?;
+        key.verify(&signed, &signature)
+            .map_err(|_| actor_error!(illegal_argument; "invalid signature"))?;
+
+        Ok(())
+    }
+
+    // Always succeeds, accepting any transfers.
+    pub fn fallback(
+        rt: &mut impl Runtime,
+        method: MethodNum,
+        _: Option<IpldBlock>,
+    ) -> Result<Option<IpldBlock>, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if method >= FIRST_EXPORTED_METHOD_NUMBER {
+            Ok(None)
+        } else {
+            Err(actor_error!(unhandled_message; "invalid method: {}", method))
+        }
+    }
+}
+
+impl ActorCode for PasskeyAccountActor {
+    type Methods = Method;
+    actor_dispatch! {
+        Constructor => constructor,
+        AuthenticateMessageExported => authenticate_message,
+        _ => fallback [raw],
+    }
+}
@@ -0,0 +1,13 @@
+use fvm_ipld_encoding::strict_bytes;
+use fvm_ipld_encoding::tuple::*;
+
+/// State includes the passkey credential the actor authenticates against.
+#[derive(Serialize_tuple, Deserialize_tuple, Debug, Clone)]
+pub struct State {
+    /// SEC1-encoded P-256 public key of the passkey credential.
+    #[serde(with = "strict_bytes")]
+    pub public_key: Vec<u8>,
+    /// SHA-256 hash of the WebAuthn relying party ID the credential is scoped to.
+    #[serde(with = "strict_bytes")]
+    pub rp_id_hash: Vec<u8>,
+}
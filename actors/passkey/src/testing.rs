@@ -0,0 +1,18 @@
+use fil_actors_runtime::MessageAccumulator;
+use p256::ecdsa::VerifyingKey;
+
+use crate::{State, RP_ID_HASH_LEN};
+
+/// Checks internal invariants of passkey account state.
+pub fn check_state_invariants(state: &State) -> MessageAccumulator {
+    let acc = MessageAccumulator::default();
+    acc.with_code("passkey.public_key").require(
+        VerifyingKey::from_sec1_bytes(&state.public_key).is_ok(),
+        format!("public key {:?} is not a valid P-256 point", state.public_key),
+    );
+    acc.with_code("passkey.rp_id_hash").require(
+        state.rp_id_hash.len() == RP_ID_HASH_LEN,
+        format!("relying party ID hash has length {}", state.rp_id_hash.len()),
+    );
+    acc
+}
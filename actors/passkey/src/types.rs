@@ -0,0 +1,35 @@
+use fvm_ipld_encoding::strict_bytes;
+use fvm_ipld_encoding::tuple::*;
+
+#[derive(Debug, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct ConstructorParams {
+    /// SEC1-encoded P-256 public key of the passkey credential.
+    #[serde(with = "strict_bytes")]
+    pub public_key: Vec<u8>,
+    /// SHA-256 hash of the WebAuthn relying party ID the credential is scoped to.
+    #[serde(with = "strict_bytes")]
+    pub rp_id_hash: Vec<u8>,
+}
+
+#[derive(Debug, Serialize_tuple, Deserialize_tuple)]
+pub struct AuthenticateMessageParams {
+    #[serde(with = "strict_bytes")]
+    pub signature: Vec<u8>,
+    #[serde(with = "strict_bytes")]
+    pub message: Vec<u8>,
+}
+
+/// A WebAuthn assertion over a message, CBOR-encoded as the signature bytes
+/// passed to `AuthenticateMessage`.
+#[derive(Debug, Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct WebAuthnAssertion {
+    /// The authenticator data returned by the authenticator.
+    #[serde(with = "strict_bytes")]
+    pub authenticator_data: Vec<u8>,
+    /// The client data JSON serialized by the user agent, containing the challenge.
+    #[serde(with = "strict_bytes")]
+    pub client_data_json: Vec<u8>,
+    /// DER-encoded ECDSA signature over the authenticator data and client data hash.
+    #[serde(with = "strict_bytes")]
+    pub signature: Vec<u8>,
+}
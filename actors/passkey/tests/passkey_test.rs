@@ -0,0 +1,138 @@
+mod util;
+
+use crate::util::*;
+use fil_actor_passkey::types::{AuthenticateMessageParams, ConstructorParams};
+use fil_actor_passkey::{Method, PasskeyAccountActor, State, FLAG_USER_PRESENT};
+use fil_actors_runtime::test_utils::{
+    expect_abort_contains_message, MockRuntime, ACCOUNT_ACTOR_CODE_ID, INIT_ACTOR_CODE_ID,
+};
+use fil_actors_runtime::{ActorError, INIT_ACTOR_ADDR};
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::error::ExitCode;
+use fvm_shared::MethodNum;
+use p256::ecdsa::SigningKey;
+
+const MESSAGE: &[u8] = b"deal proposal";
+
+fn authenticate(rt: &mut MockRuntime, signature: Vec<u8>) -> Result<Option<IpldBlock>, ActorError> {
+    rt.expect_validate_caller_any();
+    let ret = rt.call::<PasskeyAccountActor>(
+        Method::AuthenticateMessageExported as MethodNum,
+        IpldBlock::serialize_cbor(&AuthenticateMessageParams {
+            signature,
+            message: MESSAGE.to_vec(),
+        })
+        .unwrap(),
+    );
+    rt.verify();
+    ret
+}
+
+#[test]
+fn construction() {
+    let rt = setup();
+    let st: State = rt.get_state();
+    assert_eq!(public_key(&signing_key()), st.public_key);
+    assert_eq!(rp_id_hash("example.com"), st.rp_id_hash);
+}
+
+#[test]
+fn construction_fails() {
+    let construct = |caller: Address, params: ConstructorParams, code: ExitCode, msg: &str| {
+        let mut rt = new_runtime();
+        rt.set_caller(*INIT_ACTOR_CODE_ID, caller);
+        rt.expect_validate_caller_addr(vec![INIT_ACTOR_ADDR]);
+        expect_abort_contains_message(
+            code,
+            msg,
+            rt.call::<PasskeyAccountActor>(
+                Method::Constructor as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            ),
+        );
+        rt.verify();
+    };
+    let valid = ConstructorParams {
+        public_key: public_key(&signing_key()),
+        rp_id_hash: rp_id_hash("example.com"),
+    };
+
+    construct(Address::new_id(1234), valid.clone(), ExitCode::USR_FORBIDDEN, "");
+    construct(
+        INIT_ACTOR_ADDR,
+        ConstructorParams { public_key: vec![4; 65], ..valid.clone() },
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "invalid P-256 public key",
+    );
+    construct(
+        INIT_ACTOR_ADDR,
+        ConstructorParams { rp_id_hash: vec![1; 20], ..valid },
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+        "relying party ID hash must be 32 bytes",
+    );
+}
+
+#[test]
+fn authenticates_assertion() {
+    let mut rt = setup();
+    rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(1234));
+    let signature = sign_assertion(
+        &signing_key(),
+        authenticator_data("example.com", FLAG_USER_PRESENT),
+        client_data_json(MESSAGE),
+    );
+    assert!(authenticate(&mut rt, signature).unwrap().is_none());
+}
+
+#[test]
+fn rejects_invalid_assertions() {
+    let mut rt = setup();
+    let key = signing_key();
+    let check = |rt: &mut MockRuntime, signature: Vec<u8>, msg: &str| {
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            msg,
+            authenticate(rt, signature),
+        );
+    };
+
+    check(&mut rt, vec![0xde, 0xad], "invalid webauthn assertion");
+    check(
+        &mut rt,
+        sign_assertion(&key, vec![0; 36], client_data_json(MESSAGE)),
+        "authenticator data must be at least 37 bytes",
+    );
+    check(
+        &mut rt,
+        sign_assertion(
+            &key,
+            authenticator_data("evil.com", FLAG_USER_PRESENT),
+            client_data_json(MESSAGE),
+        ),
+        "different relying party",
+    );
+    check(
+        &mut rt,
+        sign_assertion(&key, authenticator_data("example.com", 0), client_data_json(MESSAGE)),
+        "user presence",
+    );
+    check(
+        &mut rt,
+        sign_assertion(
+            &key,
+            authenticator_data("example.com", FLAG_USER_PRESENT),
+            client_data_json(b"another message"),
+        ),
+        "client data does not match message",
+    );
+    check(
+        &mut rt,
+        sign_assertion(
+            &SigningKey::from_bytes(&[8u8; 32]).unwrap(),
+            authenticator_data("example.com", FLAG_USER_PRESENT),
+            client_data_json(MESSAGE),
+        ),
+        "invalid signature",
+    );
+}
@@ -0,0 +1,96 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use fil_actor_passkey::types::{ConstructorParams, WebAuthnAssertion};
+use fil_actor_passkey::{testing, Method, PasskeyAccountActor, State};
+use fil_actors_runtime::test_utils::{hash, MockRuntime, INIT_ACTOR_CODE_ID};
+use fil_actors_runtime::INIT_ACTOR_ADDR;
+use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::address::Address;
+use fvm_shared::crypto::hash::SupportedHashes;
+use fvm_shared::MethodNum;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{SigningKey, VerifyingKey};
+
+pub const PASSKEY: Address = Address::new_id(1000);
+
+pub fn new_runtime() -> MockRuntime {
+    MockRuntime {
+        receiver: PASSKEY,
+        caller: INIT_ACTOR_ADDR,
+        caller_type: *INIT_ACTOR_CODE_ID,
+        ..Default::default()
+    }
+}
+
+pub fn signing_key() -> SigningKey {
+    SigningKey::from_bytes(&[7u8; 32]).unwrap()
+}
+
+pub fn public_key(key: &SigningKey) -> Vec<u8> {
+    VerifyingKey::from(key).to_encoded_point(false).as_bytes().to_vec()
+}
+
+pub fn rp_id_hash(rp_id: &str) -> Vec<u8> {
+    sha256(rp_id.as_bytes())
+}
+
+pub fn sha256(data: &[u8]) -> Vec<u8> {
+    let (digest, len) = hash(SupportedHashes::Sha256, data);
+    digest[..len].to_vec()
+}
+
+#[allow(dead_code)]
+pub fn setup() -> MockRuntime {
+    let mut rt = new_runtime();
+    rt.expect_validate_caller_addr(vec![INIT_ACTOR_ADDR]);
+    rt.call::<PasskeyAccountActor>(
+        Method::Constructor as MethodNum,
+        IpldBlock::serialize_cbor(&ConstructorParams {
+            public_key: public_key(&signing_key()),
+            rp_id_hash: rp_id_hash("example.com"),
+        })
+        .unwrap(),
+    )
+    .unwrap();
+    rt.verify();
+    testing::check_state_invariants(&rt.get_state::<State>()).assert_empty();
+    rt
+}
+
+/// Builds authenticator data for a relying party with the given flags.
+#[allow(dead_code)]
+pub fn authenticator_data(rp_id: &str, flags: u8) -> Vec<u8> {
+    let mut data = rp_id_hash(rp_id);
+    data.push(flags);
+    data.extend_from_slice(&[0, 0, 0, 1]);
+    data
+}
+
+/// Builds client data JSON for an assertion over a message, as serialized by a user agent.
+#[allow(dead_code)]
+pub fn client_data_json(message: &[u8]) -> Vec<u8> {
+    let (digest, _) = hash(SupportedHashes::Blake2b256, message);
+    format!(
+        r#"{{"type":"webauthn.get","challenge":"{}","origin":"https://example.com","crossOrigin":false}}"#,
+        URL_SAFE_NO_PAD.encode(&digest[..32])
+    )
+    .into_bytes()
+}
+
+/// Signs an assertion with the given key, returning the encoded signature bytes.
+#[allow(dead_code)]
+pub fn sign_assertion(
+    key: &SigningKey,
+    authenticator_data: Vec<u8>,
+    client_data_json: Vec<u8>,
+) -> Vec<u8> {
+    let mut signed = authenticator_data.clone();
+    signed.extend_from_slice(&sha256(&client_data_json));
+    let signature: p256::ecdsa::Signature = key.sign(&signed);
+    fvm_ipld_encoding::to_vec(&WebAuthnAssertion {
+        authenticator_data,
+        client_data_json,
+        signature: signature.to_der().as_bytes().to_vec(),
+    })
+    .unwrap()
+}
@@ -0,0 +1,39 @@
+//! JSON representations of the payment channel actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs};
+
+use crate::{
+    ConstructorParams, Merge, Method, ModVerifyParams, PaymentVerifyParams, SignedVoucher,
+    UpdateChannelStateParams,
+};
+
+json_struct! {
+    ConstructorParams { from, to }
+    UpdateChannelStateParams { sv, secret }
+    SignedVoucher {
+        channel_addr,
+        time_lock_min,
+        time_lock_max,
+        secret_pre_image,
+        extra,
+        lane,
+        nonce,
+        amount,
+        min_settle_height,
+        merges,
+        signature,
+    }
+    ModVerifyParams { actor, method, data }
+    Merge { lane, nonce }
+    PaymentVerifyParams { extra, proof }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (ConstructorParams, ()),
+        UpdateChannelState => (UpdateChannelStateParams, ()),
+        Settle => ((), ()),
+        Collect => ((), ()),
+    }
+}
@@ -26,6 +26,8 @@ pub use self::types::*;
 fil_actors_runtime::wasm_trampoline!(Actor);
 
 pub mod ext;
+#[cfg(feature = "json")]
+pub mod json;
 mod state;
 pub mod testing;
 mod types;
@@ -7,6 +7,7 @@ use num_traits::Zero;
 
 use crate::{LaneState, State};
 
+#[derive(Debug)]
 pub struct StateSummary {
     pub redeemed: TokenAmount,
 }
@@ -20,15 +21,15 @@ pub fn check_state_invariants<BS: Blockstore>(
     let acc = MessageAccumulator::default();
     let mut redeemed = TokenAmount::zero();
 
-    acc.require(
+    acc.with_code("paych.from_protocol").require(
         state.from.protocol() == Protocol::ID,
         format!("from address is not ID address {}", state.from),
     );
-    acc.require(
+    acc.with_code("paych.to_protocol").require(
         state.to.protocol() == Protocol::ID,
         format!("to address is not ID address {}", state.to),
     );
-    acc.require(
+    acc.with_code("paych.settling_at").require(
         state.settling_at >= state.min_settle_height,
         format!(
             "channel is setting at epoch {} before min settle height {}",
@@ -39,19 +40,20 @@ pub fn check_state_invariants<BS: Blockstore>(
     match Amt::<LaneState, _>::load(&state.lane_states, store) {
         Ok(lanes) => {
             let ret = lanes.for_each(|i, lane| {
-                acc.require(
+                let acc = acc.with_context("lane", i);
+                acc.with_code("paych.lane.redeemed").require(
                     lane.redeemed.is_positive(),
                     format!("lane {i} redeemed is not greater than zero {}", lane.redeemed),
                 );
                 redeemed += &lane.redeemed;
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating lanes");
+            acc.with_code("paych.lanes").require_no_error(ret, "error iterating lanes");
         }
-        Err(e) => acc.add(format!("error loading lanes: {e}")),
+        Err(e) => acc.with_code("paych.lanes").add(format!("error loading lanes: {e}")),
     }
 
-    acc.require(
+    acc.with_code("paych.to_send").require(
         balance >= &state.to_send,
         format!("channel has insufficient funds to send ({} < {})", balance, state.to_send),
     );
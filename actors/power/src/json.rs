@@ -0,0 +1,48 @@
+//! JSON representations of the power actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs};
+use fvm_shared::sector::SealVerifyInfo;
+
+use crate::{
+    CreateMinerParams, CreateMinerReturn, CurrentTotalPowerReturn, EnrollCronEventParams, Method,
+    MinerConsensusCountReturn, MinerCountReturn, MinerRawPowerParams, MinerRawPowerReturn,
+    NetworkRawPowerReturn, UpdateClaimedPowerParams, UpdatePledgeTotalParams,
+};
+
+json_struct! {
+    CreateMinerParams { owner, worker, window_post_proof_type, peer, multiaddrs }
+    CreateMinerReturn { id_address, robust_address }
+    UpdateClaimedPowerParams { raw_byte_delta, quality_adjusted_delta }
+    EnrollCronEventParams { event_epoch, payload }
+    transparent UpdatePledgeTotalParams { pledge_delta }
+    CurrentTotalPowerReturn {
+        raw_byte_power,
+        quality_adj_power,
+        pledge_collateral,
+        quality_adj_power_smoothed,
+    }
+    transparent NetworkRawPowerReturn { raw_byte_power }
+    transparent MinerRawPowerParams { miner }
+    MinerRawPowerReturn { raw_byte_power, meets_consensus_minimum }
+    transparent MinerCountReturn { miner_count }
+    transparent MinerConsensusCountReturn { miner_consensus_count }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => ((), ()),
+        CreateMiner => (CreateMinerParams, CreateMinerReturn),
+        UpdateClaimedPower => (UpdateClaimedPowerParams, ()),
+        EnrollCronEvent => (EnrollCronEventParams, ()),
+        OnEpochTickEnd => ((), ()),
+        UpdatePledgeTotal => (UpdatePledgeTotalParams, ()),
+        SubmitPoRepForBulkVerify => (SealVerifyInfo, ()),
+        CurrentTotalPower => ((), CurrentTotalPowerReturn),
+        CreateMinerExported => (CreateMinerParams, CreateMinerReturn),
+        NetworkRawPowerExported => ((), NetworkRawPowerReturn),
+        MinerRawPowerExported => (MinerRawPowerParams, MinerRawPowerReturn),
+        MinerCountExported => ((), MinerCountReturn),
+        MinerConsensusCountExported => ((), MinerConsensusCountReturn),
+    }
+}
@@ -35,6 +35,8 @@ fil_actors_runtime::wasm_trampoline!(Actor);
 
 #[doc(hidden)]
 pub mod ext;
+#[cfg(feature = "json")]
+pub mod json;
 mod policy;
 mod state;
 pub mod testing;
@@ -17,6 +17,7 @@ use crate::{
     PROOF_VALIDATION_BATCH_AMT_BITWIDTH,
 };
 
+#[derive(Debug)]
 pub struct MinerCronEvent {
     pub epoch: ChainEpoch,
     pub payload: RawBytes,
@@ -26,6 +27,7 @@ type CronEventsByAddress = HashMap<Address, Vec<MinerCronEvent>>;
 type ClaimsByAddress = HashMap<Address, Claim>;
 type ProofsByAddress = HashMap<Address, SealVerifyInfo>;
 
+#[derive(Debug)]
 pub struct StateSummary {
     pub crons: CronEventsByAddress,
     pub claims: ClaimsByAddress,
@@ -41,45 +43,45 @@ pub fn check_state_invariants<BS: Blockstore>(
     let acc = MessageAccumulator::default();
 
     // basic invariants around recorded power
-    acc.require(
+    acc.with_code("power.total_raw_power.negative").require(
         !state.total_raw_byte_power.is_negative(),
         format!("total raw power is negative {}", state.total_raw_byte_power),
     );
-    acc.require(
+    acc.with_code("power.total_qa_power.negative").require(
         !state.total_quality_adj_power.is_negative(),
         format!("total qa power is negative {}", state.total_quality_adj_power),
     );
-    acc.require(
+    acc.with_code("power.committed_raw_power.negative").require(
         !state.total_bytes_committed.is_negative(),
         format!("total raw power committed is negative {}", state.total_bytes_committed),
     );
-    acc.require(
+    acc.with_code("power.committed_qa_power.negative").require(
         !state.total_qa_bytes_committed.is_negative(),
         format!("total qa power committed is negative {}", state.total_qa_bytes_committed),
     );
 
-    acc.require(
+    acc.with_code("power.total_raw_power.exceeds_qa").require(
         state.total_raw_byte_power <= state.total_quality_adj_power,
         format!(
             "total raw power {} is greater than total quality adjusted power {}",
             state.total_raw_byte_power, state.total_quality_adj_power
         ),
     );
-    acc.require(
+    acc.with_code("power.committed_raw_power.exceeds_qa").require(
         state.total_bytes_committed <= state.total_qa_bytes_committed,
         format!(
             "committed raw power {} is greater than committed quality adjusted power {}",
             state.total_bytes_committed, state.total_qa_bytes_committed
         ),
     );
-    acc.require(
+    acc.with_code("power.total_raw_power.exceeds_committed").require(
         state.total_raw_byte_power <= state.total_bytes_committed,
         format!(
             "total raw power {} is greater than raw power committed {}",
             state.total_raw_byte_power, state.total_bytes_committed
         ),
     );
-    acc.require(
+    acc.with_code("power.total_qa_power.exceeds_committed").require(
         state.total_quality_adj_power <= state.total_qa_bytes_committed,
         format!(
             "total qa power {} is greater than qa power committed {}",
@@ -112,12 +114,14 @@ fn check_cron_invariants<BS: Blockstore>(
                 let epoch = match parse_uint_key(key) {
                     Ok(key) => key,
                     Err(e) => {
-                        acc.add(format!("non-int key in cron array: {e}"));
+                        acc.with_code("power.cron.epoch_key")
+                            .add(format!("non-int key in cron array: {e}"));
                         return Ok(());
                     }
                 } as i64;
+                let acc = acc.with_context("epoch", epoch);
 
-                acc.require(
+                acc.with_code("power.cron.epoch").require(
                     epoch >= state.first_cron_epoch,
                     format!(
                         "cron event at epoch {epoch} before first_cron_epoch {}",
@@ -135,9 +139,11 @@ fn check_cron_invariants<BS: Blockstore>(
                         anyhow::anyhow!("error iterating cron events for epoch {}: {}", epoch, e)
                     })
             });
-            acc.require_no_error(ret, "error iterating cron tasks");
+            acc.with_code("power.cron_queue").require_no_error(ret, "error iterating cron tasks");
+        }
+        Err(e) => {
+            acc.with_code("power.cron_queue").add(format!("error loading cron event queue: {e}"))
         }
-        Err(e) => acc.add(format!("error loading cron event queue: {e}")),
     }
 
     cron_events_by_address
@@ -160,6 +166,7 @@ fn check_claims_invariants<BS: Blockstore>(
         Ok(claims) => {
             let ret = claims.for_each(|key, claim| {
                 let address = Address::from_bytes(key)?;
+                let acc = acc.with_context("miner", address);
                 claims_by_address.insert(address, claim.clone());
 
                 committed_raw_power += &claim.raw_byte_power;
@@ -169,7 +176,7 @@ fn check_claims_invariants<BS: Blockstore>(
                     match consensus_miner_min_power(policy, claim.window_post_proof_type) {
                         Ok(power) => power,
                         Err(e) => {
-                            acc.add(format!(
+                            acc.with_code("power.claim.min_power").add(format!(
                                 "could not get consensus miner min power for miner {address}: {e}"
                             ));
                             // noted above
@@ -185,22 +192,22 @@ fn check_claims_invariants<BS: Blockstore>(
 
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating power claims");
+            acc.with_code("power.claims").require_no_error(ret, "error iterating power claims");
         }
-        Err(e) => acc.add(format!("error loading power claims: {e}")),
+        Err(e) => acc.with_code("power.claims").add(format!("error loading power claims: {e}")),
     };
 
-    acc.require(committed_raw_power == state.total_bytes_committed, format!("sum of raw power in claims {committed_raw_power} does not match recorded bytes committed {}", state.total_bytes_committed));
-    acc.require(committed_qa_power == state.total_qa_bytes_committed, format!("sum of qa power in claims {committed_qa_power} does not match recorded qa power committed {}", state.total_qa_bytes_committed));
-    acc.require(claims_with_sufficient_power_count == state.miner_above_min_power_count, format!("claims with sufficient power {claims_with_sufficient_power_count} does not match miner_above_min_power_count {}", state.miner_above_min_power_count));
-    acc.require(
+    acc.with_code("power.claims.committed_raw_power").require(committed_raw_power == state.total_bytes_committed, format!("sum of raw power in claims {committed_raw_power} does not match recorded bytes committed {}", state.total_bytes_committed));
+    acc.with_code("power.claims.committed_qa_power").require(committed_qa_power == state.total_qa_bytes_committed, format!("sum of qa power in claims {committed_qa_power} does not match recorded qa power committed {}", state.total_qa_bytes_committed));
+    acc.with_code("power.claims.above_min_power_count").require(claims_with_sufficient_power_count == state.miner_above_min_power_count, format!("claims with sufficient power {claims_with_sufficient_power_count} does not match miner_above_min_power_count {}", state.miner_above_min_power_count));
+    acc.with_code("power.claims.raw_power").require(
         state.total_raw_byte_power == raw_power,
         format!(
             "recorded raw power {} does not match raw power in claims {raw_power}",
             state.total_raw_byte_power
         ),
     );
-    acc.require(
+    acc.with_code("power.claims.qa_power").require(
         state.total_quality_adj_power == qa_power,
         format!(
             "recorded qa power {} does not match qa power in claims {qa_power}",
@@ -230,19 +237,20 @@ fn check_proofs_invariants<BS: Blockstore>(
         Ok(queue) => {
             let ret = queue.for_all::<_, SealVerifyInfo>(|key, infos| {
                 let address = Address::from_bytes(key)?;
+                let acc = acc.with_context("miner", address);
                 let claim = if let Some(claim) = claims.get(&address) {
                     claim
                 } else {
-                    acc.add(format!("miner {address} has proofs awaiting validation but no claim"));
+                    acc.with_code("power.proof.no_claim").add(format!("miner {address} has proofs awaiting validation but no claim"));
                     return Ok(())
                 };
 
                 let ret = infos.for_each(|_, info| {
                     match info.registered_proof.registered_window_post_proof() {
                         Ok(sector_window_post_proof_type) => {
-                            acc.require(claim.window_post_proof_type == sector_window_post_proof_type, format!("miner submitted proof with proof type {:?} different from claim {:?}", sector_window_post_proof_type, claim.window_post_proof_type));
+                            acc.with_code("power.proof.proof_type").require(claim.window_post_proof_type == sector_window_post_proof_type, format!("miner submitted proof with proof type {:?} different from claim {:?}", sector_window_post_proof_type, claim.window_post_proof_type));
                         },
-                        Err(e) => acc.add(format!("Invalid PoSt proof: {e}"))
+                        Err(e) => acc.with_code("power.proof.proof_type").add(format!("Invalid PoSt proof: {e}"))
                     }
                     proofs_by_address.insert(address, info.clone());
                     Ok(())
@@ -252,12 +260,15 @@ fn check_proofs_invariants<BS: Blockstore>(
                     return ret.map_err(|e| anyhow::anyhow!("error iterating proof validation batch for address {}: {}", address, e));
                 }
 
-                acc.require(proofs_by_address.len() as u64 <= MAX_MINER_PROVE_COMMITS_PER_EPOCH, format!("miner {address} has submitted too many proofs ({}) for batch verification", proofs_by_address.len()));
+                acc.with_code("power.proof.count").require(proofs_by_address.len() as u64 <= MAX_MINER_PROVE_COMMITS_PER_EPOCH, format!("miner {address} has submitted too many proofs ({}) for batch verification", proofs_by_address.len()));
                 Ok(())
             });
-            acc.require_no_error(ret, "error iterating proof validation queue");
+            acc.with_code("power.proof_queue")
+                .require_no_error(ret, "error iterating proof validation queue");
         }
-        Err(e) => acc.add(format!("error loading proof validation queue: {e}")),
+        Err(e) => acc
+            .with_code("power.proof_queue")
+            .add(format!("error loading proof validation queue: {e}")),
     }
 
     proofs_by_address
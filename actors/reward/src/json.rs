@@ -0,0 +1,19 @@
+//! JSON representations of the reward actor's method parameters and return values.
+
+use fil_actors_runtime::json::{BigIntParam, MethodCodec};
+use fil_actors_runtime::{json_struct, method_codecs};
+
+use crate::{AwardBlockRewardParams, Method, ThisEpochRewardReturn};
+
+json_struct! {
+    AwardBlockRewardParams { miner, penalty, gas_reward, win_count }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (Option<BigIntParam>, ()),
+        AwardBlockReward => (AwardBlockRewardParams, ()),
+        ThisEpochReward => ((), ThisEpochRewardReturn),
+        UpdateNetworkKPI => (Option<BigIntParam>, ()),
+    }
+}
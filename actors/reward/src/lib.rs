@@ -23,6 +23,8 @@ pub use self::types::*;
 fil_actors_runtime::wasm_trampoline!(Actor);
 
 pub(crate) mod expneg;
+#[cfg(feature = "json")]
+pub mod json;
 mod logic;
 mod state;
 pub mod testing;
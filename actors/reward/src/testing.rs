@@ -3,7 +3,7 @@ use fil_actors_runtime::MessageAccumulator;
 use fvm_shared::{clock::ChainEpoch, econ::TokenAmount};
 use num_traits::Signed;
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 pub struct StateSummary {}
 
 pub fn check_state_invariants(
@@ -16,7 +16,7 @@ pub fn check_state_invariants(
     let storage_mining_allocation_check = TokenAmount::from_whole(1_100_000_000);
 
     // Can't assert equality because anyone can send funds to reward actor (and already have on mainnet)
-    acc.require(
+    acc.with_code("reward.total_reward").require(
         &state.total_storage_power_reward + balance >= storage_mining_allocation_check,
         format!(
             "reward given {} + reward left {} < storage mining allocation {}",
@@ -24,7 +24,7 @@ pub fn check_state_invariants(
         ),
     );
 
-    acc.require(
+    acc.with_code("reward.epoch").require(
         state.epoch == prior_epoch + 1,
         format!(
             "reward state epoch {} does not match prior_epoch+1 {}",
@@ -32,7 +32,7 @@ pub fn check_state_invariants(
             prior_epoch + 1
         ),
     );
-    acc.require(
+    acc.with_code("reward.effective_network_time").require(
         state.effective_network_time <= state.epoch,
         format!(
             "effective network time {} greater than state epoch {}",
@@ -40,14 +40,14 @@ pub fn check_state_invariants(
         ),
     );
 
-    acc.require(
+    acc.with_code("reward.cumsum_realized").require(
         state.cumsum_realized <= state.cumsum_baseline,
         format!(
             "cumsum realized {} > cumsum baseline {}",
             state.cumsum_realized, state.cumsum_baseline
         ),
     );
-    acc.require(
+    acc.with_code("reward.cumsum_realized").require(
         !state.cumsum_realized.is_negative(),
         format!("cumsum realized negative ({})", state.cumsum_realized),
     );
@@ -56,7 +56,7 @@ pub fn check_state_invariants(
     // because of rounding issues explained and tracked in https://github.com/filecoin-project/builtin-actors/issues/459
     // we settled on this workaround.
     let next_epoch_baseline_power = baseline_power_from_prev(&state.this_epoch_baseline_power);
-    acc.require(
+    acc.with_code("reward.effective_baseline_power").require(
         state.effective_baseline_power <= next_epoch_baseline_power,
         format!(
             "effective baseline power ({}) > next_epoch_baseline_power ({})",
@@ -0,0 +1,12 @@
+//! JSON representations of the system actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::method_codecs;
+
+use crate::Method;
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => ((), ()),
+    }
+}
@@ -16,6 +16,9 @@ use fil_actors_runtime::{
 #[cfg(feature = "fil-actor")]
 fil_actors_runtime::wasm_trampoline!(Actor);
 
+#[cfg(feature = "json")]
+pub mod json;
+
 /// System actor methods.
 #[derive(FromPrimitive)]
 #[repr(u64)]
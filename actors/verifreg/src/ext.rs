@@ -20,6 +20,7 @@ pub mod account {
 
 pub mod datacap {
     use super::*;
+    use fvm_shared::clock::ChainEpoch;
     use fvm_shared::econ::TokenAmount;
 
     #[repr(u64)]
@@ -29,6 +30,7 @@ pub mod datacap {
         Balance = frc42_dispatch::method_hash!("Balance"),
         Transfer = frc42_dispatch::method_hash!("Transfer"),
         Burn = frc42_dispatch::method_hash!("Burn"),
+        MintExpiring = frc42_dispatch::method_hash!("MintExpiring"),
     }
 
     #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
@@ -38,9 +40,76 @@ pub mod datacap {
         pub operators: Vec<Address>,
     }
 
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct MintExpiringParams {
+        pub to: Address,
+        pub amount: TokenAmount,
+        pub operators: Vec<Address>,
+        pub expiration: ChainEpoch,
+    }
+
     #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
     pub struct DestroyParams {
         pub owner: Address,
         pub amount: TokenAmount,
     }
 }
+
+pub mod miner {
+    use super::*;
+    use cid::Cid;
+    use fvm_ipld_encoding::RawBytes;
+    use fvm_shared::clock::ChainEpoch;
+    use fvm_shared::piece::PaddedPieceSize;
+    use fvm_shared::sector::SectorNumber;
+
+    pub const CLAIMS_TRANSFERRED_METHOD: u64 = 40;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct ClaimsTransferredParams {
+        pub claims: Vec<TransferredClaim>,
+        pub prior_transfers: Vec<TransferredClaim>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct TransferredClaim {
+        pub sector: SectorNumber,
+        pub size: PaddedPieceSize,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct SectorContentChangedParams {
+        pub sectors: Vec<SectorChanges>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct SectorChanges {
+        pub sector: SectorNumber,
+        pub minimum_commitment_epoch: ChainEpoch,
+        pub added: Vec<PieceChange>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct PieceChange {
+        pub data: Cid,
+        pub size: PaddedPieceSize,
+        pub payload: RawBytes,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    pub struct SectorContentChangedReturn {
+        pub sectors: Vec<SectorReturn>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    #[serde(transparent)]
+    pub struct SectorReturn {
+        pub added: Vec<PieceReturn>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+    #[serde(transparent)]
+    pub struct PieceReturn {
+        pub accepted: bool,
+    }
+}
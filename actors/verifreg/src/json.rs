@@ -0,0 +1,116 @@
+//! JSON representations of the verified registry actor's method parameters and return values.
+
+use fil_actors_runtime::json::MethodCodec;
+use fil_actors_runtime::{json_struct, method_codecs, BatchReturn};
+use fvm_actor_utils::receiver::UniversalReceiverParams;
+use fvm_shared::address::Address;
+
+use crate::ext::miner::{
+    PieceChange, PieceReturn, SectorChanges, SectorContentChangedParams,
+    SectorContentChangedReturn, SectorReturn,
+};
+use crate::state::{Claim, ReplicaSet};
+use crate::{
+    Allocation, AllocationRequest, AllocationRequests, AllocationRequests2, AllocationsResponse,
+    CancelAllocationsParams, CancelAllocationsReturn, ClaimAllocationsParams,
+    ClaimAllocationsReturn, ClaimExtensionRequest, ClaimTerm, ClaimTransferProposal,
+    ClaimTransferRequest, DroppedPiece, ExpiringVerifierParams, ExtendClaimTermsParams,
+    GetAllocationsParams, GetAllocationsReturn, GetClaimsParams, GetClaimsReturn,
+    ListAllocationsParams, ListAllocationsReturn, Method, RemoveDataCapParams,
+    RemoveDataCapRequest, RemoveDataCapReturn, RemoveExpiredAllocationsParams,
+    RemoveExpiredAllocationsReturn, RemoveExpiredClaimsParams, RemoveExpiredClaimsReturn,
+    RemoveSectorClaimsParams, RemoveSectorClaimsReturn, ReplicatedAllocationRequest,
+    SectorAllocationClaim, VerifierParams,
+};
+
+json_struct! {
+    VerifierParams { address, allowance }
+    ExpiringVerifierParams { address, allowance, expiration }
+    RemoveDataCapParams {
+        verified_client_to_remove,
+        data_cap_amount_to_remove,
+        verifier_request_1,
+        verifier_request_2,
+    }
+    RemoveDataCapRequest { verifier, signature }
+    RemoveDataCapReturn { verified_client, data_cap_removed }
+    RemoveExpiredAllocationsParams { client, allocation_ids }
+    RemoveExpiredAllocationsReturn { considered, results, datacap_recovered }
+    SectorAllocationClaim { client, allocation_id, data, size, sector, sector_expiry }
+    ClaimAllocationsParams { sectors, all_or_nothing }
+    ClaimAllocationsReturn { batch_info, claimed_space }
+    ClaimTerm { provider, claim_id, term_max }
+    ExtendClaimTermsParams { terms }
+    AllocationRequest { provider, data, size, term_min, term_max, expiration }
+    ClaimExtensionRequest { provider, claim, term_max }
+    ReplicatedAllocationRequest { providers, replicas, data, size, term_min, term_max, expiration }
+    AllocationRequests { allocations, extensions }
+    AllocationRequests2 { allocations, extensions, replicated }
+    AllocationsResponse { allocation_results, extension_results, new_allocations }
+    GetClaimsParams { provider, claim_ids }
+    GetClaimsReturn { batch_info, claims }
+    RemoveExpiredClaimsParams { provider, claim_ids }
+    RemoveExpiredClaimsReturn { considered, results }
+    RemoveSectorClaimsParams { sector, pieces }
+    DroppedPiece { data, size }
+    RemoveSectorClaimsReturn { removed, results }
+    GetAllocationsParams { client, allocation_ids }
+    GetAllocationsReturn { batch_info, allocations }
+    ListAllocationsParams { client, cursor, limit }
+    ListAllocationsReturn { allocation_ids, allocations, next_cursor }
+    CancelAllocationsParams { allocation_ids }
+    CancelAllocationsReturn { results, expiration }
+    ClaimTransferRequest { provider, claim_id, expiration, signature }
+    ClaimTransferProposal { provider, claim_id, new_provider, expiration }
+    SectorContentChangedParams { sectors }
+    SectorChanges { sector, minimum_commitment_epoch, added }
+    PieceChange { data, size, payload }
+    SectorContentChangedReturn { sectors }
+    transparent SectorReturn { added }
+    transparent PieceReturn { accepted }
+    Claim { provider, client, data, size, term_min, term_max, term_start, sector }
+    ReplicaSet { providers, first, count, claimed_by }
+    Allocation { client, provider, data, size, term_min, term_max, expiration, replicas }
+    remote UniversalReceiverParamsJson(UniversalReceiverParams) { type_, payload }
+}
+
+pub fn methods() -> Vec<MethodCodec> {
+    method_codecs! {
+        Constructor => (Address, ()),
+        AddVerifier => (VerifierParams, ()),
+        RemoveVerifier => (Address, ()),
+        AddVerifiedClient => (VerifierParams, ()),
+        RemoveVerifiedClientDataCap => (RemoveDataCapParams, RemoveDataCapReturn),
+        RemoveExpiredAllocations => (
+            RemoveExpiredAllocationsParams,
+            RemoveExpiredAllocationsReturn,
+        ),
+        ClaimAllocations => (ClaimAllocationsParams, ClaimAllocationsReturn),
+        GetClaims => (GetClaimsParams, GetClaimsReturn),
+        ExtendClaimTerms => (ExtendClaimTermsParams, BatchReturn),
+        RemoveExpiredClaims => (RemoveExpiredClaimsParams, RemoveExpiredClaimsReturn),
+        GetAllocations => (GetAllocationsParams, GetAllocationsReturn),
+        ListAllocations => (ListAllocationsParams, ListAllocationsReturn),
+        CancelAllocations => (CancelAllocationsParams, CancelAllocationsReturn),
+        GetTransferredClaims => (GetClaimsParams, GetClaimsReturn),
+        AddSubVerifier => (ExpiringVerifierParams, ()),
+        AddExpiringVerifiedClient => (ExpiringVerifierParams, ()),
+        RemoveSectorClaims => (RemoveSectorClaimsParams, RemoveSectorClaimsReturn),
+        UniversalReceiverHook => (UniversalReceiverParamsJson, AllocationsResponse),
+        AddVerifiedClientExported => (VerifierParams, ()),
+        RemoveExpiredAllocationsExported => (
+            RemoveExpiredAllocationsParams,
+            RemoveExpiredAllocationsReturn,
+        ),
+        GetClaimsExported => (GetClaimsParams, GetClaimsReturn),
+        ExtendClaimTermsExported => (ExtendClaimTermsParams, BatchReturn),
+        RemoveExpiredClaimsExported => (RemoveExpiredClaimsParams, RemoveExpiredClaimsReturn),
+        GetAllocationsExported => (GetAllocationsParams, GetAllocationsReturn),
+        ListAllocationsExported => (ListAllocationsParams, ListAllocationsReturn),
+        CancelAllocationsExported => (CancelAllocationsParams, CancelAllocationsReturn),
+        GetTransferredClaimsExported => (GetClaimsParams, GetClaimsReturn),
+        AddSubVerifierExported => (ExpiringVerifierParams, ()),
+        AddExpiringVerifiedClientExported => (ExpiringVerifierParams, ()),
+        SectorContentChanged => (SectorContentChangedParams, SectorContentChangedReturn),
+    }
+}
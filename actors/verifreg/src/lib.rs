@@ -1,6 +1,8 @@
 // Copyright 2019-2022 ChainSafe Systems
 // SPDX-License-Identifier: Apache-2.0, MIT
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use frc46_token::receiver::{FRC46TokenReceived, FRC46_TOKEN_TYPE};
 use frc46_token::token::types::{BurnParams, TransferParams};
 use frc46_token::token::TOKEN_PRECISION;
@@ -14,6 +16,8 @@ use fvm_shared::bigint::BigInt;
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
+use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::sector::SectorNumber;
 use fvm_shared::{ActorID, HAMT_BIT_WIDTH, METHOD_CONSTRUCTOR};
 use log::info;
 use num_derive::FromPrimitive;
@@ -24,19 +28,22 @@ use fil_actors_runtime::runtime::builtins::Type;
 use fil_actors_runtime::runtime::{ActorCode, Policy, Runtime};
 use fil_actors_runtime::{
     actor_dispatch, actor_error, deserialize_block, extract_send_result,
-    make_map_with_root_and_bitwidth, resolve_to_actor_id, ActorDowncast, ActorError, BatchReturn,
-    Map, DATACAP_TOKEN_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
-    VERIFIED_REGISTRY_ACTOR_ADDR,
+    make_map_with_root_and_bitwidth, parse_uint_key, resolve_to_actor_id, ActorDowncast,
+    ActorError, BatchReturn, Map, MapMap, DATACAP_TOKEN_ACTOR_ADDR, STORAGE_MARKET_ACTOR_ADDR,
+    SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use fil_actors_runtime::{ActorContext, AsActorError, BatchReturnGen};
 use fvm_ipld_encoding::ipld_block::IpldBlock;
 use fvm_shared::sys::SendFlags;
 
-use crate::ext::datacap::{DestroyParams, MintParams};
+use crate::ext::datacap::{DestroyParams, MintExpiringParams, MintParams};
+use crate::reason::InsufficientDataCap;
 
 pub use self::state::Allocation;
 pub use self::state::Claim;
+pub use self::state::ReplicaSet;
 pub use self::state::State;
+pub use self::state::VerifierInfo;
 pub use self::types::*;
 
 #[cfg(feature = "fil-actor")]
@@ -44,6 +51,9 @@ fil_actors_runtime::wasm_trampoline!(Actor);
 
 pub mod expiration;
 pub mod ext;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod reason;
 pub mod state;
 pub mod testing;
 pub mod types;
@@ -64,12 +74,26 @@ pub enum Method {
     GetClaims = 10,
     ExtendClaimTerms = 11,
     RemoveExpiredClaims = 12,
+    GetAllocations = 13,
+    ListAllocations = 14,
+    CancelAllocations = 15,
+    GetTransferredClaims = 16,
+    AddSubVerifier = 17,
+    AddExpiringVerifiedClient = 18,
+    RemoveSectorClaims = 19,
     // Method numbers derived from FRC-0042 standards
     AddVerifiedClientExported = frc42_dispatch::method_hash!("AddVerifiedClient"),
     RemoveExpiredAllocationsExported = frc42_dispatch::method_hash!("RemoveExpiredAllocations"),
     GetClaimsExported = frc42_dispatch::method_hash!("GetClaims"),
     ExtendClaimTermsExported = frc42_dispatch::method_hash!("ExtendClaimTerms"),
     RemoveExpiredClaimsExported = frc42_dispatch::method_hash!("RemoveExpiredClaims"),
+    GetAllocationsExported = frc42_dispatch::method_hash!("GetAllocations"),
+    ListAllocationsExported = frc42_dispatch::method_hash!("ListAllocations"),
+    CancelAllocationsExported = frc42_dispatch::method_hash!("CancelAllocations"),
+    GetTransferredClaimsExported = frc42_dispatch::method_hash!("GetTransferredClaims"),
+    AddSubVerifierExported = frc42_dispatch::method_hash!("AddSubVerifier"),
+    AddExpiringVerifiedClientExported = frc42_dispatch::method_hash!("AddExpiringVerifiedClient"),
+    SectorContentChanged = frc42_dispatch::method_hash!("SectorContentChanged"),
     UniversalReceiverHook = frc42_dispatch::method_hash!("Receive"),
 }
 
@@ -128,9 +152,15 @@ impl Actor {
         }
 
         // Store the new verifier and allowance (over-writing).
+        // A verifier added by the root key has no parent and its allowance doesn't expire.
         rt.transaction(|st: &mut State, rt| {
             st.put_verifier(rt.store(), &verifier, &params.allowance)
-                .context("failed to add verifier")
+                .context("failed to add verifier")?;
+            st.put_verifier_info(
+                rt.store(),
+                &verifier,
+                VerifierInfo { parent: None, expiration: None },
+            )
         })
     }
 
@@ -142,13 +172,131 @@ impl Actor {
         rt.validate_immediate_caller_is(std::iter::once(&state.root_key))?;
 
         rt.transaction(|st: &mut State, rt| {
-            st.remove_verifier(rt.store(), &verifier).context("failed to remove verifier")
+            st.remove_verifier(rt.store(), &verifier).context("failed to remove verifier")?;
+            st.put_verifier_info(
+                rt.store(),
+                &verifier,
+                VerifierInfo { parent: None, expiration: None },
+            )
+        })
+    }
+
+    /// Adds a verifier with an allowance delegated from the caller's own, recording the caller
+    /// as its parent.
+    /// The sub-verifier's allowance expires no later than the caller's.
+    /// The root key may also call this method, to add a verifier with an expiring allowance
+    /// without spending any allowance of its own.
+    pub fn add_sub_verifier(
+        rt: &mut impl Runtime,
+        params: AddSubVerifierParams,
+    ) -> Result<(), ActorError> {
+        // The caller will be verified by checking the verifiers table below.
+        rt.validate_immediate_caller_accept_any()?;
+
+        if params.allowance < rt.policy().minimum_verified_allocation_size {
+            return Err(actor_error!(
+                illegal_argument,
+                "allowance {} below minimum deal size for add sub-verifier {}",
+                params.allowance,
+                params.address
+            ));
+        }
+
+        let verifier = resolve_to_actor_id(rt, &params.address)?;
+        let verifier = Address::new_id(verifier);
+        let caller = rt.message().caller();
+
+        let st: State = rt.state()?;
+        if verifier == st.root_key {
+            return Err(actor_error!(illegal_argument, "Rootkey cannot be added as verifier"));
+        }
+        if verifier == caller {
+            return Err(actor_error!(illegal_argument, "verifier cannot add itself"));
+        }
+
+        // The root key delegates no allowance of its own, so has no expiration to inherit.
+        let (parent, parent_cap, parent_expiration) = if caller == st.root_key {
+            (None, None, None)
+        } else {
+            let cap = st.get_verifier_cap(rt.store(), &caller)?.ok_or_else(|| {
+                actor_error!(not_found, "caller {} is not a verifier", caller)
+                    .with_reason(reason::NOT_A_VERIFIER, caller)
+            })?;
+            let expiration = check_verifier_active(rt, &st, &caller)?;
+            (Some(caller), Some(cap), expiration)
+        };
+
+        // Disallow overwriting an existing verifier, which may have a different parent.
+        if st.get_verifier_cap(rt.store(), &verifier)?.is_some() {
+            return Err(actor_error!(illegal_argument, "{} is already a verifier", verifier));
+        }
+
+        // Disallow existing clients as verifiers.
+        let token_balance = balance(rt, &verifier)?;
+        if token_balance.is_positive() {
+            return Err(actor_error!(
+                illegal_argument,
+                "verified client {} cannot become a verifier",
+                verifier
+            ));
+        }
+
+        let expiration = match params.expiration {
+            Some(expiration) => {
+                validate_expiration(rt.curr_epoch(), expiration, parent_expiration)?;
+                Some(expiration)
+            }
+            None => parent_expiration,
+        };
+
+        if let Some(parent_cap) = &parent_cap {
+            if *parent_cap < params.allowance {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "delegate more DataCap {} to sub-verifier than allocated {}",
+                    params.allowance,
+                    parent_cap
+                )
+                .with_reason(
+                    reason::INSUFFICIENT_ALLOWANCE,
+                    InsufficientDataCap {
+                        required: params.allowance.clone(),
+                        available: parent_cap.clone(),
+                    },
+                ));
+            }
+        }
+
+        rt.transaction(|st: &mut State, rt| {
+            if let (Some(parent), Some(parent_cap)) = (&parent, &parent_cap) {
+                st.put_verifier(rt.store(), parent, &(parent_cap - &params.allowance))
+                    .context("failed to update verifier allowance")?;
+            }
+            st.put_verifier(rt.store(), &verifier, &params.allowance)
+                .context("failed to add sub-verifier")?;
+            st.put_verifier_info(rt.store(), &verifier, VerifierInfo { parent, expiration })
         })
     }
 
     pub fn add_verified_client(
         rt: &mut impl Runtime,
         params: AddVerifiedClientParams,
+    ) -> Result<(), ActorError> {
+        Self::add_expiring_verified_client(
+            rt,
+            AddExpiringVerifiedClientParams {
+                address: params.address,
+                allowance: params.allowance,
+                expiration: None,
+            },
+        )
+    }
+
+    /// Grants DataCap to a client from the calling verifier's allowance.
+    /// If an expiration is specified, the DataCap lapses if the client hasn't spent it by then.
+    pub fn add_expiring_verified_client(
+        rt: &mut impl Runtime,
+        params: AddExpiringVerifiedClientParams,
     ) -> Result<(), ActorError> {
         // The caller will be verified by checking table below
         rt.validate_immediate_caller_accept_any()?;
@@ -172,9 +320,14 @@ impl Actor {
 
         // Validate caller is one of the verifiers, i.e. has an allowance (even if zero).
         let verifier = rt.message().caller();
-        let verifier_cap = st
-            .get_verifier_cap(rt.store(), &verifier)?
-            .ok_or_else(|| actor_error!(not_found, "caller {} is not a verifier", verifier))?;
+        let verifier_cap = st.get_verifier_cap(rt.store(), &verifier)?.ok_or_else(|| {
+            actor_error!(not_found, "caller {} is not a verifier", verifier)
+                .with_reason(reason::NOT_A_VERIFIER, verifier)
+        })?;
+        check_verifier_active(rt, &st, &verifier)?;
+        if let Some(expiration) = params.expiration {
+            validate_expiration(rt.curr_epoch(), expiration, None)?;
+        }
 
         // Disallow existing verifiers as clients.
         if st.get_verifier_cap(rt.store(), &client)?.is_some() {
@@ -192,6 +345,10 @@ impl Actor {
                 "add more DataCap {} for client than allocated {}",
                 params.allowance,
                 verifier_cap
+            )
+            .with_reason(
+                reason::INSUFFICIENT_ALLOWANCE,
+                InsufficientDataCap { required: params.allowance, available: verifier_cap },
             ));
         }
 
@@ -204,7 +361,7 @@ impl Actor {
 
         // Credit client token allowance.
         let operators = vec![STORAGE_MARKET_ACTOR_ADDR];
-        mint(rt, &client, &params.allowance, operators).context(format!(
+        mint(rt, &client, &params.allowance, operators, params.expiration).context(format!(
             "failed to mint {} data cap to client {}",
             &params.allowance, client
         ))?;
@@ -243,11 +400,13 @@ impl Actor {
             }
 
             if !is_verifier(rt, st, verifier_1)? {
-                return Err(actor_error!(not_found, "{} is not a verifier", verifier_1));
+                return Err(actor_error!(not_found, "{} is not a verifier", verifier_1)
+                    .with_reason(reason::NOT_A_VERIFIER, verifier_1));
             }
 
             if !is_verifier(rt, st, verifier_2)? {
-                return Err(actor_error!(not_found, "{} is not a verifier", verifier_2));
+                return Err(actor_error!(not_found, "{} is not a verifier", verifier_2)
+                    .with_reason(reason::NOT_A_VERIFIER, verifier_2));
             }
 
             // validate signatures
@@ -413,6 +572,17 @@ impl Actor {
                     continue;
                 }
 
+                // A provider may claim at most one replica of a set.
+                if alloc.replicas.as_ref().map_or(false, |set| set.claimed_by.contains(&provider)) {
+                    ret_gen.add_fail(ExitCode::USR_FORBIDDEN);
+                    info!(
+                        "provider {} already claimed a replica of allocation {}",
+                        provider, claim_alloc.allocation_id,
+                    );
+                    continue;
+                }
+                let replicas = alloc.replicas.clone();
+
                 let new_claim = Claim {
                     provider,
                     client: alloc.client,
@@ -445,6 +615,24 @@ impl Actor {
                     format!("failed to remove allocation {}", claim_alloc.allocation_id),
                 )?;
 
+                // Record the provider with the set's unclaimed replicas.
+                if let Some(set) = replicas {
+                    for id in set.first..set.first + set.count {
+                        let mut replica =
+                            match state::get_allocation(&mut allocs, claim_alloc.client, id)? {
+                                Some(replica) => replica.clone(),
+                                None => continue,
+                            };
+                        if let Some(replica_set) = replica.replicas.as_mut() {
+                            replica_set.claimed_by.push(provider);
+                        }
+                        allocs.put(claim_alloc.client, id, replica).context_code(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            format!("failed to write allocation {}", id),
+                        )?;
+                    }
+                }
+
                 datacap_claimed += DataCap::from(claim_alloc.size.0);
                 ret_gen.add_success();
             }
@@ -459,7 +647,8 @@ impl Actor {
                 illegal_argument,
                 "all or nothing call contained failures: {}",
                 batch_info.to_string()
-            ));
+            )
+            .with_reason(reason::CLAIMS_FAILED, batch_info));
         }
 
         // Burn the datacap tokens from verified registry's own balance.
@@ -495,6 +684,140 @@ impl Actor {
         Ok(GetClaimsReturn { batch_info: batch_gen.gen(), claims })
     }
 
+    // get claims transferred away from a provider, as they were before the transfer
+    pub fn get_transferred_claims(
+        rt: &mut impl Runtime,
+        params: GetTransferredClaimsParams,
+    ) -> Result<GetTransferredClaimsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let mut batch_gen = BatchReturnGen::new(params.claim_ids.len());
+        let st: State = rt.state()?;
+        let mut st_claims = st.load_transferred_claims(rt.store())?;
+        let mut claims = Vec::new();
+        for id in params.claim_ids {
+            match state::get_claim(&mut st_claims, params.provider, id)? {
+                None => {
+                    batch_gen.add_fail(ExitCode::USR_NOT_FOUND);
+                    info!("no transferred claim {} for provider {}", id, params.provider);
+                }
+                Some(claim) => {
+                    batch_gen.add_success();
+                    claims.push(claim.clone());
+                }
+            };
+        }
+
+        Ok(GetTransferredClaimsReturn { batch_info: batch_gen.gen(), claims })
+    }
+
+    // get allocations for a client
+    pub fn get_allocations(
+        rt: &mut impl Runtime,
+        params: GetAllocationsParams,
+    ) -> Result<GetAllocationsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        let mut batch_gen = BatchReturnGen::new(params.allocation_ids.len());
+        let st: State = rt.state()?;
+        let mut st_allocs = st.load_allocs(rt.store())?;
+        let mut allocations = Vec::new();
+        for id in params.allocation_ids {
+            match state::get_allocation(&mut st_allocs, params.client, id)? {
+                None => {
+                    batch_gen.add_fail(ExitCode::USR_NOT_FOUND);
+                    info!("no allocation {} for client {}", id, params.client);
+                }
+                Some(alloc) => {
+                    batch_gen.add_success();
+                    allocations.push(alloc.clone());
+                }
+            };
+        }
+
+        Ok(GetAllocationsReturn { batch_info: batch_gen.gen(), allocations })
+    }
+
+    /// Lists a page of a client's allocations, in ascending order of id.
+    /// Allocations from the cursor are listed up to the limit, along with the cursor
+    /// from which to list the rest.
+    pub fn list_allocations(
+        rt: &mut impl Runtime,
+        params: ListAllocationsParams,
+    ) -> Result<ListAllocationsReturn, ActorError> {
+        rt.validate_immediate_caller_accept_any()?;
+        if params.limit == 0 || params.limit > LIST_ALLOCATIONS_MAX_LIMIT {
+            return Err(actor_error!(
+                illegal_argument,
+                "list allocations limit {} must be between 1 and {}",
+                params.limit,
+                LIST_ALLOCATIONS_MAX_LIMIT
+            ));
+        }
+        let st: State = rt.state()?;
+        let mut st_allocs = st.load_allocs(rt.store())?;
+        let mut found = Vec::<(AllocationID, Allocation)>::new();
+        st_allocs
+            .for_each(params.client, |key, alloc| {
+                let id = parse_uint_key(key)
+                    .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to parse uint key")?;
+                if id >= params.cursor {
+                    found.push((id, alloc.clone()));
+                }
+                Ok(())
+            })
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate over allocations")?;
+        found.sort_by_key(|(id, _)| *id);
+
+        let next_cursor = found.get(params.limit as usize).map(|(id, _)| *id);
+        found.truncate(params.limit as usize);
+        let (allocation_ids, allocations) = found.into_iter().unzip();
+        Ok(ListAllocationsReturn { allocation_ids, allocations, next_cursor })
+    }
+
+    /// Cancels some of the caller's allocations, bringing their expiration forward to the end
+    /// of the policy's cancellation grace period.
+    /// A provider that has begun sealing the data may still claim an allocation during the
+    /// grace period. After it, the client may recover the DataCap with RemoveExpiredAllocations.
+    /// Allocations already expiring before the end of the grace period are left unchanged.
+    pub fn cancel_allocations(
+        rt: &mut impl Runtime,
+        params: CancelAllocationsParams,
+    ) -> Result<CancelAllocationsReturn, ActorError> {
+        // Permissions are checked per-allocation, as only the caller's are considered.
+        rt.validate_immediate_caller_accept_any()?;
+        let client = rt.message().caller().id().unwrap();
+        if params.allocation_ids.is_empty() {
+            return Err(actor_error!(illegal_argument, "cancel allocations called with no ids"));
+        }
+        let expiration = rt.curr_epoch() + rt.policy().verified_allocation_cancellation_grace;
+        let mut batch_gen = BatchReturnGen::new(params.allocation_ids.len());
+        rt.transaction(|st: &mut State, rt| {
+            let mut allocs = st.load_allocs(rt.store())?;
+            for id in params.allocation_ids {
+                let alloc = match state::get_allocation(&mut allocs, client, id)? {
+                    None => {
+                        batch_gen.add_fail(ExitCode::USR_NOT_FOUND);
+                        info!("no allocation {} for client {}", id, client);
+                        continue;
+                    }
+                    Some(alloc) => alloc,
+                };
+                if alloc.expiration > expiration {
+                    let cancelled = Allocation { expiration, ..alloc.clone() };
+                    allocs.put(client, id, cancelled).context_code(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to cancel allocation {}", id),
+                    )?;
+                }
+                batch_gen.add_success();
+            }
+            st.save_allocs(&mut allocs)?;
+            Ok(())
+        })
+        .context("state transaction failed")?;
+
+        Ok(CancelAllocationsReturn { results: batch_gen.gen(), expiration })
+    }
+
     /// Extends the maximum term of some claims up to the largest value they could have been
     /// originally allocated.
     /// Callable only by the claims' client.
@@ -601,6 +924,17 @@ impl Actor {
             }
 
             st.save_claims(&mut claims)?;
+
+            // Records of claims transferred away from the provider are no longer needed
+            // once they expire.
+            let mut transferred = st.load_transferred_claims(rt.store())?;
+            for id in expiration::find_expired(&mut transferred, params.provider, curr_epoch)? {
+                transferred.remove(params.provider, id).context_code(
+                    ExitCode::USR_ILLEGAL_STATE,
+                    format!("failed to remove transferred claim {}", id),
+                )?;
+            }
+            st.save_transferred_claims(&mut transferred)?;
             Ok(())
         })
         .context("state transaction failed")?;
@@ -608,6 +942,208 @@ impl Actor {
         Ok(RemoveExpiredClaimsReturn { considered, results: batch_ret })
     }
 
+    // Called by a storage provider actor when it drops pieces from a live sector, to remove
+    // its claims for the pieces' data in that sector. Each piece removes at most one claim,
+    // matching its data and size. A piece for which the provider holds no such claim,
+    // such as one whose claim has been transferred away, fails with NOT_FOUND.
+    pub fn remove_sector_claims(
+        rt: &mut impl Runtime,
+        params: RemoveSectorClaimsParams,
+    ) -> Result<RemoveSectorClaimsReturn, ActorError> {
+        rt.validate_immediate_caller_type(std::iter::once(&Type::Miner))?;
+        let provider = rt.message().caller().id().unwrap();
+        let mut batch_gen = BatchReturnGen::new(params.pieces.len());
+        let mut removed = Vec::<ClaimID>::new();
+        rt.transaction(|st: &mut State, rt| {
+            let mut claims = st.load_claims(rt.store())?;
+            let mut in_sector = Vec::<(ClaimID, Claim)>::new();
+            claims
+                .for_each(provider, |key, claim| {
+                    if claim.sector == params.sector {
+                        let id = parse_uint_key(key).context_code(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            "failed to parse uint key",
+                        )?;
+                        in_sector.push((id, claim.clone()));
+                    }
+                    Ok(())
+                })
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to iterate over claims")?;
+
+            for piece in &params.pieces {
+                let found = in_sector
+                    .iter()
+                    .position(|(_, claim)| claim.data == piece.data && claim.size == piece.size);
+                match found {
+                    Some(idx) => {
+                        let (id, _) = in_sector.swap_remove(idx);
+                        claims.remove(provider, id).context_code(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            format!("failed to remove claim {}", id),
+                        )?;
+                        removed.push(id);
+                        batch_gen.add_success();
+                    }
+                    None => {
+                        info!(
+                            "no claim for piece {} in sector {} of provider {}",
+                            piece.data, params.sector, provider
+                        );
+                        batch_gen.add_fail(ExitCode::USR_NOT_FOUND);
+                    }
+                }
+            }
+            st.save_claims(&mut claims)?;
+            Ok(())
+        })
+        .context("state transaction failed")?;
+
+        Ok(RemoveSectorClaimsReturn { removed, results: batch_gen.gen() })
+    }
+
+    // Called by a storage provider actor when it activates pieces in a sector, with the pieces
+    // whose activation notifications name the registry.
+    // Each such piece's payload requests the transfer of an existing claim for the same data,
+    // held by another provider, to the new sector of the calling provider. The transfer must
+    // be approved by the claim's client, by signature. The claim keeps its remaining term.
+    // The previous provider's claim is removed, and kept as a transferred claim until it
+    // expires, so that the previous provider may drop it from its sector without penalty.
+    // Each previous provider is notified of the claims transferred from its sectors, so that
+    // it stops counting their verified power, which the new provider's sectors now carry.
+    // A piece is accepted only if its claim is transferred.
+    pub fn sector_content_changed(
+        rt: &mut impl Runtime,
+        params: ext::miner::SectorContentChangedParams,
+    ) -> Result<ext::miner::SectorContentChangedReturn, ActorError> {
+        rt.validate_immediate_caller_type(std::iter::once(&Type::Miner))?;
+        let new_provider = rt.message().caller().id().unwrap();
+        let curr_epoch = rt.curr_epoch();
+
+        // Validate the requests against current state, authenticating each with the client.
+        let st: State = rt.state()?;
+        let mut claims = st.load_claims(rt.store())?;
+        let mut seen = BTreeSet::<(ActorID, ClaimID)>::new();
+        let mut transfers = Vec::<(ActorID, ClaimID, Claim)>::new();
+        let mut sectors = Vec::with_capacity(params.sectors.len());
+        for sector in params.sectors {
+            let mut added = Vec::with_capacity(sector.added.len());
+            for piece in &sector.added {
+                let accepted = match transfer_request_is_valid(
+                    rt,
+                    &mut claims,
+                    &mut seen,
+                    new_provider,
+                    &sector,
+                    piece,
+                    curr_epoch,
+                )? {
+                    Some((req, claim)) => {
+                        let new_claim = Claim {
+                            provider: new_provider,
+                            sector: sector.sector,
+                            ..claim.clone()
+                        };
+                        transfers.push((req.provider, req.claim_id, new_claim));
+                        true
+                    }
+                    None => false,
+                };
+                added.push(ext::miner::PieceReturn { accepted });
+            }
+            sectors.push(ext::miner::SectorReturn { added });
+        }
+
+        // The claims transferred away from each previous provider, in its sectors.
+        let mut notifications = BTreeMap::<ActorID, Vec<ext::miner::TransferredClaim>>::new();
+        // The space already transferred away from each previous provider's sectors.
+        let mut prior_space = BTreeMap::<ActorID, BTreeMap<SectorNumber, u64>>::new();
+        if !transfers.is_empty() {
+            rt.transaction(|st: &mut State, rt| {
+                let mut claims = st.load_claims(rt.store())?;
+                let mut transferred = st.load_transferred_claims(rt.store())?;
+                for (provider, _, _) in &transfers {
+                    if prior_space.contains_key(provider) {
+                        continue;
+                    }
+                    let mut by_sector = BTreeMap::<SectorNumber, u64>::new();
+                    transferred
+                        .for_each(*provider, |_, claim| {
+                            *by_sector.entry(claim.sector).or_default() += claim.size.0;
+                            Ok(())
+                        })
+                        .context_code(
+                            ExitCode::USR_ILLEGAL_STATE,
+                            "failed to iterate over transferred claims",
+                        )?;
+                    prior_space.insert(*provider, by_sector);
+                }
+                for (provider, id, new_claim) in transfers {
+                    let old_claim = state::get_claim(&mut claims, provider, id)?
+                        .with_context_code(ExitCode::USR_ILLEGAL_STATE, || {
+                            format!("no claim {} for provider {}", id, provider)
+                        })?
+                        .clone();
+                    claims.remove(provider, id).context_code(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to remove claim {}", id),
+                    )?;
+                    let inserted = claims.put_if_absent(new_provider, id, new_claim).context_code(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to write claim {}", id),
+                    )?;
+                    if !inserted {
+                        return Err(actor_error!(
+                            illegal_state,
+                            "claim {} already exists for provider {}",
+                            id,
+                            new_provider
+                        ));
+                    }
+                    notifications.entry(provider).or_default().push(ext::miner::TransferredClaim {
+                        sector: old_claim.sector,
+                        size: old_claim.size,
+                    });
+                    transferred.put(provider, id, old_claim).context_code(
+                        ExitCode::USR_ILLEGAL_STATE,
+                        format!("failed to write transferred claim {}", id),
+                    )?;
+                }
+                st.save_claims(&mut claims)?;
+                st.save_transferred_claims(&mut transferred)?;
+                Ok(())
+            })
+            .context("state transaction failed")?;
+        }
+
+        for (provider, claims) in notifications {
+            let sectors: BTreeSet<SectorNumber> = claims.iter().map(|c| c.sector).collect();
+            let prior_transfers = prior_space
+                .remove(&provider)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|(sector, _)| sectors.contains(sector))
+                .map(|(sector, size)| ext::miner::TransferredClaim {
+                    sector,
+                    size: PaddedPieceSize(size),
+                })
+                .collect();
+            extract_send_result(rt.send_simple(
+                &Address::new_id(provider),
+                ext::miner::CLAIMS_TRANSFERRED_METHOD,
+                IpldBlock::serialize_cbor(&ext::miner::ClaimsTransferredParams {
+                    claims,
+                    prior_transfers,
+                })?,
+                TokenAmount::zero(),
+            ))
+            .with_context(|| {
+                format!("failed to notify provider {} of transferred claims", provider)
+            })?;
+        }
+
+        Ok(ext::miner::SectorContentChangedReturn { sectors })
+    }
+
     // Receives data cap tokens (only) and creates allocations according to one or more
     // allocation requests specified in the transfer's operator data.
     // The token amount received must exactly correspond to the sum of the requested allocation sizes.
@@ -629,14 +1165,19 @@ impl Actor {
         let client = tokens_received.from;
 
         // Extract and validate allocation request from the operator data.
-        let reqs: AllocationRequests =
-            deserialize(&tokens_received.operator_data, "allocation requests")?;
+        // Requests including replicated allocations are sent in the longer AllocationRequests2.
+        let reqs: AllocationRequests2 =
+            match deserialize::<AllocationRequests>(&tokens_received.operator_data, "requests") {
+                Ok(reqs) => reqs.into(),
+                Err(_) => deserialize(&tokens_received.operator_data, "allocation requests")?,
+            };
+        let tokens_as_datacap = tokens_to_datacap(&tokens_received.amount);
         let mut datacap_total = DataCap::zero();
 
         // Construct new allocation records.
         let mut new_allocs = Vec::with_capacity(reqs.allocations.len());
-        for req in &reqs.allocations {
-            validate_new_allocation(req, rt.policy(), curr_epoch)?;
+        for (index, req) in reqs.allocations.iter().enumerate() {
+            validate_new_allocation(index, req, rt.policy(), curr_epoch)?;
             // Require the provider for new allocations to be a miner actor.
             // This doesn't matter much, but is more ergonomic to fail rather than lock up datacap.
             check_miner_id(rt, req.provider)?;
@@ -648,10 +1189,66 @@ impl Actor {
                 term_min: req.term_min,
                 term_max: req.term_max,
                 expiration: req.expiration,
+                replicas: None,
             });
             datacap_total += DataCap::from(req.size.0);
         }
 
+        // Construct a set of allocations for each replicated request.
+        // Each replica may be claimed by any one of the request's providers, or by any miner.
+        for (i, req) in reqs.replicated.iter().enumerate() {
+            let index = reqs.allocations.len() + i;
+            let template = AllocationRequest {
+                provider: 0,
+                data: req.data,
+                size: req.size,
+                term_min: req.term_min,
+                term_max: req.term_max,
+                expiration: req.expiration,
+            };
+            validate_new_allocation(index, &template, rt.policy(), curr_epoch)?;
+            if req.replicas == 0 {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "replicated allocation request {} has no replicas",
+                    index
+                )
+                .with_reason(reason::ALLOCATION_NO_REPLICAS, index as u64));
+            }
+            // Check the replicas are paid for before constructing them.
+            datacap_total += DataCap::from(req.size.0) * req.replicas;
+            if datacap_total > tokens_as_datacap {
+                return Err(actor_error!(
+                    illegal_argument,
+                    "total allocation size {} exceeds data cap amount received {}",
+                    datacap_total,
+                    tokens_as_datacap
+                ));
+            }
+            for provider in &req.providers {
+                check_miner_id(rt, *provider)?;
+            }
+            // The set is numbered relative to the first new allocation until inserted.
+            let set = ReplicaSet {
+                providers: req.providers.clone(),
+                first: new_allocs.len() as u64,
+                count: req.replicas,
+                claimed_by: vec![],
+            };
+            for _ in 0..req.replicas {
+                new_allocs.push(Allocation {
+                    client,
+                    provider: 0,
+                    data: req.data,
+                    size: req.size,
+                    term_min: req.term_min,
+                    term_max: req.term_max,
+                    expiration: req.expiration,
+                    replicas: Some(set.clone()),
+                });
+            }
+        }
+
         let st: State = rt.state()?;
         let mut claims = st.load_claims(rt.store())?;
         let mut updated_claims = Vec::<(ClaimID, Claim)>::new();
@@ -662,7 +1259,8 @@ impl Actor {
             let claim = state::get_claim(&mut claims, req.provider, req.claim)?
                 .with_context_code(ExitCode::USR_NOT_FOUND, || {
                     format!("no claim {} for provider {}", req.claim, req.provider)
-                })?;
+                })
+                .map_err(|e| e.with_reason(reason::CLAIM_NOT_FOUND, req.claim))?;
             let policy = rt.policy();
 
             validate_claim_extension(req, claim, policy, curr_epoch)?;
@@ -674,7 +1272,6 @@ impl Actor {
         }
 
         // Allocation size must match the tokens received exactly (we don't return change).
-        let tokens_as_datacap = tokens_to_datacap(&tokens_received.amount);
         if datacap_total != tokens_as_datacap {
             return Err(actor_error!(
                 illegal_argument,
@@ -689,7 +1286,8 @@ impl Actor {
         burn(rt, &extension_total)?;
 
         // Partial success isn't supported yet, but these results make space for it in the future.
-        let allocation_results = BatchReturn::ok(new_allocs.len() as u32);
+        let allocation_results =
+            BatchReturn::ok((reqs.allocations.len() + reqs.replicated.len()) as u32);
         let extension_results = BatchReturn::ok(updated_claims.len() as u32);
 
         // Save new allocations and updated claims.
@@ -732,22 +1330,41 @@ fn balance(rt: &mut impl Runtime, owner: &Address) -> Result<DataCap, ActorError
     Ok(tokens_to_datacap(&x))
 }
 
-// Invokes Mint on a data cap token actor for whole units of data cap.
+// Invokes Mint on a data cap token actor for whole units of data cap,
+// or MintExpiring if the data cap expires.
 fn mint(
     rt: &mut impl Runtime,
     to: &Address,
     amount: &DataCap,
     operators: Vec<Address>,
+    expiration: Option<ChainEpoch>,
 ) -> Result<(), ActorError> {
     let token_amt = datacap_to_tokens(amount);
-    let params = MintParams { to: *to, amount: token_amt, operators };
+    let (method, params, description) = match expiration {
+        None => {
+            let params = MintParams { to: *to, amount: token_amt, operators };
+            (
+                ext::datacap::Method::Mint,
+                IpldBlock::serialize_cbor(&params)?,
+                format!("{:?}", params),
+            )
+        }
+        Some(expiration) => {
+            let params = MintExpiringParams { to: *to, amount: token_amt, operators, expiration };
+            (
+                ext::datacap::Method::MintExpiring,
+                IpldBlock::serialize_cbor(&params)?,
+                format!("{:?}", params),
+            )
+        }
+    };
     extract_send_result(rt.send_simple(
         &DATACAP_TOKEN_ACTOR_ADDR,
-        ext::datacap::Method::Mint as u64,
-        IpldBlock::serialize_cbor(&params)?,
+        method as u64,
+        params,
         TokenAmount::zero(),
     ))
-    .context(format!("failed to send mint {:?} to datacap", params))?;
+    .context(format!("failed to send mint {} to datacap", description))?;
     Ok(())
 }
 
@@ -889,6 +1506,74 @@ fn remove_data_cap_request_is_valid(
     Ok(())
 }
 
+// Checks a piece's request to transfer a claim to the calling provider's sector.
+// Returns the request and the claim to transfer, or None if the piece is rejected.
+fn transfer_request_is_valid<'a, BS: Blockstore>(
+    rt: &impl Runtime,
+    claims: &'a mut MapMap<BS, Claim, ActorID, ClaimID>,
+    seen: &mut BTreeSet<(ActorID, ClaimID)>,
+    new_provider: ActorID,
+    sector: &ext::miner::SectorChanges,
+    piece: &ext::miner::PieceChange,
+    curr_epoch: ChainEpoch,
+) -> Result<Option<(ClaimTransferRequest, &'a Claim)>, ActorError> {
+    let req: ClaimTransferRequest = match deserialize(&piece.payload, "claim transfer request") {
+        Ok(req) => req,
+        Err(e) => {
+            info!("invalid transfer request for piece {}: {}", piece.data, e);
+            return Ok(None);
+        }
+    };
+    if !seen.insert((req.provider, req.claim_id)) {
+        info!("duplicate transfer request for claim {}", req.claim_id);
+        return Ok(None);
+    }
+    let claim = match state::get_claim(claims, req.provider, req.claim_id)? {
+        None => {
+            info!("no claim {} for provider {}", req.claim_id, req.provider);
+            return Ok(None);
+        }
+        Some(claim) => claim,
+    };
+    let commitment_ok = sector.minimum_commitment_epoch >= claim.term_start + claim.term_min
+        && sector.minimum_commitment_epoch <= claim.term_start + claim.term_max;
+    if req.provider == new_provider
+        || claim.data != piece.data
+        || claim.size != piece.size
+        || curr_epoch > req.expiration
+        || !commitment_ok
+    {
+        info!("invalid sector {} for transfer of claim {}", sector.sector, req.claim_id);
+        return Ok(None);
+    }
+
+    let proposal = ClaimTransferProposal {
+        provider: req.provider,
+        claim_id: req.claim_id,
+        new_provider,
+        expiration: req.expiration,
+    };
+    let b = RawBytes::serialize(proposal).map_err(
+        |e| actor_error!(serialization; "failed to marshal claim transfer proposal: {}", e),
+    )?;
+    let payload = [SIGNATURE_DOMAIN_SEPARATION_TRANSFER_CLAIM, b.bytes()].concat();
+    if let Err(e) = extract_send_result(rt.send(
+        &Address::new_id(claim.client),
+        ext::account::AUTHENTICATE_MESSAGE_METHOD,
+        IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
+            signature: req.signature.clone(),
+            message: payload,
+        })?,
+        TokenAmount::zero(),
+        None,
+        SendFlags::READ_ONLY,
+    )) {
+        info!("claim transfer authentication failed for claim {}: {}", req.claim_id, e);
+        return Ok(None);
+    }
+    Ok(Some((req, claim)))
+}
+
 // Deserializes and validates a receiver hook payload, expecting only an FRC-46 transfer.
 fn validate_tokens_received(
     params: &UniversalReceiverParams,
@@ -917,6 +1602,7 @@ fn validate_tokens_received(
 
 // Validates an allocation request.
 fn validate_new_allocation(
+    index: usize,
     req: &AllocationRequest,
     policy: &Policy,
     curr_epoch: ChainEpoch,
@@ -928,7 +1614,8 @@ fn validate_new_allocation(
             "allocation size {} below minimum {}",
             req.size.0,
             policy.minimum_verified_allocation_size
-        ));
+        )
+        .with_reason(reason::ALLOCATION_TOO_SMALL, index as u64));
     }
     // Term must be at least the policy minimum.
     if req.term_min < policy.minimum_verified_allocation_term {
@@ -937,7 +1624,8 @@ fn validate_new_allocation(
             "allocation term min {} below limit {}",
             req.term_min,
             policy.minimum_verified_allocation_term
-        ));
+        )
+        .with_reason(reason::ALLOCATION_TERM_TOO_SHORT, index as u64));
     }
     // Term cannot exceed the policy maximum.
     if req.term_max > policy.maximum_verified_allocation_term {
@@ -946,7 +1634,8 @@ fn validate_new_allocation(
             "allocation term max {} above limit {}",
             req.term_max,
             policy.maximum_verified_allocation_term
-        ));
+        )
+        .with_reason(reason::ALLOCATION_TERM_TOO_LONG, index as u64));
     }
     // Term range must be non-empty.
     if req.term_min > req.term_max {
@@ -955,7 +1644,8 @@ fn validate_new_allocation(
             "allocation term min {} exceeds term max {}",
             req.term_min,
             req.term_max
-        ));
+        )
+        .with_reason(reason::ALLOCATION_TERM_INVALID, index as u64));
     }
 
     // Allocation must expire in the future.
@@ -965,7 +1655,8 @@ fn validate_new_allocation(
             "allocation expiration epoch {} has passed current epoch {}",
             req.expiration,
             curr_epoch
-        ));
+        )
+        .with_reason(reason::ALLOCATION_EXPIRED, index as u64));
     }
     // Allocation must expire soon enough.
     let max_expiration = curr_epoch + policy.maximum_verified_allocation_expiration;
@@ -975,7 +1666,8 @@ fn validate_new_allocation(
             "allocation expiration {} exceeds maximum {}",
             req.expiration,
             max_expiration
-        ));
+        )
+        .with_reason(reason::ALLOCATION_EXPIRATION_TOO_LATE, index as u64));
     }
     Ok(())
 }
@@ -996,7 +1688,8 @@ fn validate_claim_extension(
                 "term_max {} for claim {} exceeds maximum {} at current epoch {}",
                 req.term_max, req.claim, term_limit_relative, curr_epoch
             )
-        ));
+        )
+        .with_reason(reason::CLAIM_TERM_TOO_LONG, req.claim));
     }
     // The new term max must be larger than the old one.
     // Cannot reduce term, and cannot spend datacap on a zero increase.
@@ -1008,7 +1701,8 @@ fn validate_claim_extension(
             req.term_max,
             req.claim,
             claim.term_max
-        ));
+        )
+        .with_reason(reason::CLAIM_TERM_NOT_INCREASED, req.claim));
     }
     // The claim must not have already expired.
     // Unlike when the claim client extends term up to the originally-allowed max,
@@ -1024,7 +1718,58 @@ fn validate_claim_extension(
             req.claim,
             claim_expiration,
             curr_epoch
-        ));
+        )
+        .with_reason(reason::CLAIM_EXPIRED, req.claim));
+    }
+    Ok(())
+}
+
+// Checks that a verifier's allowance has not expired, returning its expiration, if any.
+fn check_verifier_active(
+    rt: &impl Runtime,
+    st: &State,
+    verifier: &Address,
+) -> Result<Option<ChainEpoch>, ActorError> {
+    let expiration = st.get_verifier_info(rt.store(), verifier)?.and_then(|info| info.expiration);
+    if let Some(expiration) = expiration {
+        if expiration < rt.curr_epoch() {
+            return Err(actor_error!(
+                forbidden,
+                "verifier {} allowance expired at epoch {}",
+                verifier,
+                expiration
+            )
+            .with_reason(reason::VERIFIER_EXPIRED, expiration));
+        }
+    }
+    Ok(expiration)
+}
+
+// Checks that a requested expiration has not passed, and does not exceed any limit.
+fn validate_expiration(
+    curr_epoch: ChainEpoch,
+    expiration: ChainEpoch,
+    limit: Option<ChainEpoch>,
+) -> Result<(), ActorError> {
+    if expiration < curr_epoch {
+        return Err(actor_error!(
+            illegal_argument,
+            "expiration {} has passed current epoch {}",
+            expiration,
+            curr_epoch
+        )
+        .with_reason(reason::EXPIRATION_INVALID, expiration));
+    }
+    if let Some(limit) = limit {
+        if expiration > limit {
+            return Err(actor_error!(
+                illegal_argument,
+                "expiration {} exceeds verifier's expiration {}",
+                expiration,
+                limit
+            )
+            .with_reason(reason::EXPIRATION_INVALID, expiration));
+        }
     }
     Ok(())
 }
@@ -1047,7 +1792,8 @@ fn check_miner_id(rt: &mut impl Runtime, id: ActorID) -> Result<(), ActorError>
             "allocation provider {} must be a miner actor, was {:?}",
             id,
             provider_type
-        ));
+        )
+        .with_reason(reason::PROVIDER_NOT_MINER, id));
     }
     Ok(())
 }
@@ -1059,8 +1805,12 @@ fn can_claim_alloc(
     curr_epoch: ChainEpoch,
 ) -> bool {
     let sector_lifetime = claim_alloc.sector_expiry - curr_epoch;
+    let provider_ok = match &alloc.replicas {
+        None => provider == alloc.provider,
+        Some(set) => set.allows(provider),
+    };
 
-    provider == alloc.provider
+    provider_ok
         && claim_alloc.client == alloc.client
         && claim_alloc.data == alloc.data
         && claim_alloc.size == alloc.size
@@ -1077,6 +1827,10 @@ impl ActorCode for Actor {
         RemoveVerifier => remove_verifier,
         AddVerifiedClient => add_verified_client,
         AddVerifiedClientExported => add_verified_client,
+        AddSubVerifier => add_sub_verifier,
+        AddSubVerifierExported => add_sub_verifier,
+        AddExpiringVerifiedClient => add_expiring_verified_client,
+        AddExpiringVerifiedClientExported => add_expiring_verified_client,
         RemoveVerifiedClientDataCap => remove_verified_client_data_cap,
         RemoveExpiredAllocations => remove_expired_allocations,
         RemoveExpiredAllocationsExported => remove_expired_allocations,
@@ -1087,6 +1841,16 @@ impl ActorCode for Actor {
         ExtendClaimTermsExported => extend_claim_terms,
         RemoveExpiredClaims => remove_expired_claims,
         RemoveExpiredClaimsExported => remove_expired_claims,
+        RemoveSectorClaims => remove_sector_claims,
+        GetAllocations => get_allocations,
+        GetAllocationsExported => get_allocations,
+        ListAllocations => list_allocations,
+        ListAllocationsExported => list_allocations,
+        CancelAllocations => cancel_allocations,
+        CancelAllocationsExported => cancel_allocations,
+        GetTransferredClaims => get_transferred_claims,
+        GetTransferredClaimsExported => get_transferred_claims,
+        SectorContentChanged => sector_content_changed,
         UniversalReceiverHook => universal_receiver_hook,
     }
 }
@@ -0,0 +1,54 @@
+// Copyright 2019-2022 ChainSafe Systems
+// SPDX-License-Identifier: Apache-2.0, MIT
+
+//! Codes of the reasons attached to verified registry actor errors, decoded with
+//! `ActorError::reason`. The type of each reason's detail is noted with its code.
+
+use fvm_ipld_encoding::tuple::*;
+use fvm_shared::bigint::bigint_ser;
+
+use crate::DataCap;
+
+/// The verifier's allowance cannot cover the DataCap to grant. Detail: `InsufficientDataCap`.
+pub const INSUFFICIENT_ALLOWANCE: u64 = 1;
+/// The address is not a verifier. Detail: the `Address`.
+pub const NOT_A_VERIFIER: u64 = 2;
+/// The allocation size is below the policy minimum. Detail: the request's index.
+pub const ALLOCATION_TOO_SMALL: u64 = 3;
+/// The allocation's minimum term is below the policy minimum. Detail: the request's index.
+pub const ALLOCATION_TERM_TOO_SHORT: u64 = 4;
+/// The allocation's maximum term exceeds the policy maximum. Detail: the request's index.
+pub const ALLOCATION_TERM_TOO_LONG: u64 = 5;
+/// The allocation's minimum term exceeds its maximum term. Detail: the request's index.
+pub const ALLOCATION_TERM_INVALID: u64 = 6;
+/// The allocation's expiration has passed. Detail: the request's index.
+pub const ALLOCATION_EXPIRED: u64 = 7;
+/// The allocation's expiration exceeds the policy maximum. Detail: the request's index.
+pub const ALLOCATION_EXPIRATION_TOO_LATE: u64 = 8;
+/// The allocation provider is not a miner actor. Detail: the provider's `ActorID`.
+pub const PROVIDER_NOT_MINER: u64 = 9;
+/// No claim with the ID exists for the provider. Detail: the `ClaimID`.
+pub const CLAIM_NOT_FOUND: u64 = 10;
+/// The claim's new maximum term exceeds the policy maximum. Detail: the `ClaimID`.
+pub const CLAIM_TERM_TOO_LONG: u64 = 11;
+/// The claim's new maximum term does not exceed its existing one. Detail: the `ClaimID`.
+pub const CLAIM_TERM_NOT_INCREASED: u64 = 12;
+/// The claim to extend has expired. Detail: the `ClaimID`.
+pub const CLAIM_EXPIRED: u64 = 13;
+/// Some allocations could not be claimed in an all-or-nothing call. Detail: the `BatchReturn`.
+pub const CLAIMS_FAILED: u64 = 14;
+/// The replicated allocation request has no replicas. Detail: the request's index.
+pub const ALLOCATION_NO_REPLICAS: u64 = 15;
+/// The verifier's allowance has expired. Detail: the expiration epoch.
+pub const VERIFIER_EXPIRED: u64 = 16;
+/// The requested expiration has passed or exceeds the granting verifier's. Detail: the epoch.
+pub const EXPIRATION_INVALID: u64 = 17;
+
+/// Detail of a reason for an allowance insufficient to cover an amount of DataCap.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct InsufficientDataCap {
+    #[serde(with = "bigint_ser")]
+    pub required: DataCap,
+    #[serde(with = "bigint_ser")]
+    pub available: DataCap,
+}
@@ -33,6 +33,21 @@ pub struct State {
     pub next_allocation_id: u64,
     // Maps provider IDs to allocations claimed by that provider.
     pub claims: Cid, // HAMT[ActorID]HAMT[ClaimID]Claim
+    // Maps provider IDs to claims transferred away from that provider, as they were
+    // before the transfer, until they expire.
+    pub transferred_claims: Cid, // HAMT[ActorID]HAMT[ClaimID]Claim
+    // Maps verifier addresses to the verifier which delegated their allowance and its
+    // expiration, for verifiers added by another verifier or with an expiring allowance.
+    pub verifier_info: Cid, // HAMT[Address]VerifierInfo
+}
+
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct VerifierInfo {
+    // The verifier which delegated this verifier's allowance from its own, or None if the
+    // verifier was added by the root key.
+    pub parent: Option<Address>,
+    // The last epoch at which the verifier's allowance may be used, if any.
+    pub expiration: Option<ChainEpoch>,
 }
 
 impl State {
@@ -55,6 +70,8 @@ impl State {
             allocations: empty_mapmap,
             next_allocation_id: 1,
             claims: empty_mapmap,
+            transferred_claims: empty_mapmap,
+            verifier_info: empty_map,
         })
     }
 
@@ -119,6 +136,47 @@ impl State {
             .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load verifiers")
     }
 
+    pub fn load_verifier_info<'a, BS: Blockstore>(
+        &self,
+        store: &'a BS,
+    ) -> Result<Map<'a, BS, VerifierInfo>, ActorError> {
+        make_map_with_root_and_bitwidth(&self.verifier_info, store, HAMT_BIT_WIDTH)
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load verifier info")
+    }
+
+    pub fn get_verifier_info(
+        &self,
+        store: &impl Blockstore,
+        verifier: &Address,
+    ) -> Result<Option<VerifierInfo>, ActorError> {
+        let info = self.load_verifier_info(store)?;
+        let found = info
+            .get(&verifier.to_bytes())
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to get verifier info")?;
+        Ok(found.cloned())
+    }
+
+    // Records a verifier's parent and expiration, removing any record if it has neither.
+    pub fn put_verifier_info(
+        &mut self,
+        store: &impl Blockstore,
+        verifier: &Address,
+        verifier_info: VerifierInfo,
+    ) -> Result<(), ActorError> {
+        let mut info = self.load_verifier_info(store)?;
+        if verifier_info.parent.is_none() && verifier_info.expiration.is_none() {
+            info.delete(&verifier.to_bytes())
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to remove verifier info")?;
+        } else {
+            info.set(verifier.to_bytes().into(), verifier_info)
+                .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to set verifier info")?;
+        }
+        self.verifier_info = info
+            .flush()
+            .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to flush verifier info")?;
+        Ok(())
+    }
+
     pub fn load_allocs<'a, BS: Blockstore>(
         &self,
         store: &'a BS,
@@ -162,9 +220,13 @@ impl State {
         allocs
             .put_many(
                 client,
-                new_allocs.into_iter().map(move |a| {
+                new_allocs.into_iter().map(move |mut a| {
                     let id = first_id + *count_ref;
                     *count_ref += 1;
+                    // Replica sets of new allocations are numbered from the first of them.
+                    if let Some(set) = a.replicas.as_mut() {
+                        set.first += first_id;
+                    }
                     (id, a)
                 }),
             )
@@ -198,6 +260,30 @@ impl State {
         Ok(())
     }
 
+    pub fn load_transferred_claims<'a, BS: Blockstore>(
+        &self,
+        store: &'a BS,
+    ) -> Result<MapMap<'a, BS, Claim, ActorID, ClaimID>, ActorError> {
+        MapMap::<BS, Claim, ActorID, ClaimID>::from_root(
+            store,
+            &self.transferred_claims,
+            HAMT_BIT_WIDTH,
+            HAMT_BIT_WIDTH,
+        )
+        .context_code(ExitCode::USR_ILLEGAL_STATE, "failed to load transferred claims table")
+    }
+
+    pub fn save_transferred_claims<'a, BS: Blockstore>(
+        &mut self,
+        claims: &mut MapMap<'a, BS, Claim, ActorID, ClaimID>,
+    ) -> Result<(), ActorError> {
+        self.transferred_claims = claims.flush().context_code(
+            ExitCode::USR_ILLEGAL_STATE,
+            "failed to flush transferred claims table",
+        )?;
+        Ok(())
+    }
+
     pub fn put_claims<BS: Blockstore>(
         &mut self,
         store: &BS,
@@ -254,6 +340,29 @@ pub struct Allocation {
     pub term_max: ChainEpoch,
     // The latest epoch by which a provider must commit data before the allocation expires.
     pub expiration: ChainEpoch,
+    // For one of a set of replicas, the providers which may claim it instead of `provider`,
+    // which is then zero.
+    pub replicas: Option<ReplicaSet>,
+}
+
+// A set of allocations of the same data, created together, of which each provider may claim
+// at most one.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ReplicaSet {
+    // The providers (miner actors) which may claim a replica, or empty if any miner may.
+    pub providers: Vec<ActorID>,
+    // ID of the first allocation in the set. The replicas have consecutive IDs.
+    pub first: AllocationID,
+    // The number of replicas in the set.
+    pub count: u64,
+    // The providers which have claimed a replica of the set, and so may not claim another.
+    pub claimed_by: Vec<ActorID>,
+}
+
+impl ReplicaSet {
+    pub fn allows(&self, provider: ActorID) -> bool {
+        self.providers.is_empty() || self.providers.contains(&provider)
+    }
 }
 
 pub fn get_allocation<'a, BS>(
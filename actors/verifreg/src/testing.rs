@@ -16,12 +16,15 @@ use fvm_shared::clock::ChainEpoch;
 use fvm_shared::ActorID;
 use num_traits::Signed;
 
-use crate::{Allocation, AllocationID, Claim, ClaimID, DataCap, State};
+use crate::{Allocation, AllocationID, Claim, ClaimID, DataCap, State, VerifierInfo};
 
+#[derive(Debug)]
 pub struct StateSummary {
     pub verifiers: HashMap<Address, DataCap>,
     pub allocations: HashMap<AllocationID, Allocation>,
     pub claims: HashMap<ClaimID, Claim>,
+    /// The providers from which each claim has been transferred.
+    pub transferred_claims: HashMap<ClaimID, Vec<ActorID>>,
 }
 
 /// Checks internal invariants of verified registry state.
@@ -39,12 +42,13 @@ pub fn check_state_invariants<BS: Blockstore>(
             let ret = verifiers.for_each(|key, cap| {
                 let verifier = Address::from_bytes(key)?;
                 let cap = &cap.0;
+                let acc = acc.with_context("verifier", verifier);
 
-                acc.require(
+                acc.with_code("verifreg.verifier.protocol").require(
                     verifier.protocol() == Protocol::ID,
                     format!("verifier {verifier} should have ID protocol"),
                 );
-                acc.require(
+                acc.with_code("verifreg.verifier.negative_cap").require(
                     !cap.is_negative(),
                     format!("verifier {verifier} cap {cap} is negative"),
                 );
@@ -52,9 +56,40 @@ pub fn check_state_invariants<BS: Blockstore>(
                 Ok(())
             });
 
-            acc.require_no_error(ret, "error iterating verifiers");
+            acc.with_code("verifreg.verifiers").require_no_error(ret, "error iterating verifiers");
+        }
+        Err(e) => acc.with_code("verifreg.verifiers").add(format!("error loading verifiers {e}")),
+    }
+
+    // Check the delegation records of verifiers
+    match state.load_verifier_info(store) {
+        Ok(verifier_info) => {
+            let ret = verifier_info.for_each(|key, info: &VerifierInfo| {
+                let verifier = Address::from_bytes(key)?;
+                let acc = acc.with_context("verifier", verifier);
+
+                acc.with_code("verifreg.verifier_info.verifier").require(
+                    all_verifiers.contains_key(&verifier),
+                    format!("verifier info for {verifier} which is not a verifier"),
+                );
+                acc.with_code("verifreg.verifier_info.empty").require(
+                    info.parent.is_some() || info.expiration.is_some(),
+                    format!("verifier info for {verifier} has no parent or expiration"),
+                );
+                if let Some(parent) = info.parent {
+                    acc.with_code("verifreg.verifier_info.parent").require(
+                        parent.protocol() == Protocol::ID && parent != verifier,
+                        format!("verifier {verifier} has invalid parent {parent}"),
+                    );
+                }
+                Ok(())
+            });
+            acc.with_code("verifreg.verifier_info")
+                .require_no_error(ret, "error iterating verifier info");
+        }
+        Err(e) => {
+            acc.with_code("verifreg.verifier_info").add(format!("error loading verifier info {e}"))
         }
-        Err(e) => acc.add(format!("error loading verifiers {e}")),
     }
 
     // Load and check allocations
@@ -73,25 +108,30 @@ pub fn check_state_invariants<BS: Blockstore>(
                                 client_id,
                                 state.next_allocation_id,
                                 prior_epoch,
-                                &acc,
+                                &acc.with_context("allocation", allocation_id),
                             );
 
                             all_allocations.insert(allocation_id, allocation.clone());
                             Ok(())
                         });
-                        acc.require_no_error(
+                        acc.with_code("verifreg.allocations").require_no_error(
                             ret,
                             format!("error iterating allocations inner for {client_id}"),
                         );
                     }
-                    Err(e) => acc.add(format!("error loading allocations {e}")),
+                    Err(e) => acc
+                        .with_code("verifreg.allocations")
+                        .add(format!("error loading allocations {e}")),
                 }
                 Ok(())
             });
 
-            acc.require_no_error(ret, "error iterating allocations outer");
+            acc.with_code("verifreg.allocations")
+                .require_no_error(ret, "error iterating allocations outer");
+        }
+        Err(e) => {
+            acc.with_code("verifreg.allocations").add(format!("error loading allocations from {e}"))
         }
-        Err(e) => acc.add(format!("error loading allocations from {e}")),
     }
 
     let mut all_claims = HashMap::new();
@@ -109,28 +149,67 @@ pub fn check_state_invariants<BS: Blockstore>(
                                 provider_id,
                                 state.next_allocation_id,
                                 prior_epoch,
-                                &acc,
+                                &acc.with_context("claim", claim_id),
                             );
                             all_claims.insert(claim_id, claim.clone());
                             Ok(())
                         });
-                        acc.require_no_error(
+                        acc.with_code("verifreg.claims").require_no_error(
                             ret,
                             format!("error iterating allocations inner for {provider_id}"),
                         );
                     }
-                    Err(e) => acc.add(format!("error loading allocations {e}")),
+                    Err(e) => acc
+                        .with_code("verifreg.claims")
+                        .add(format!("error loading allocations {e}")),
                 }
                 Ok(())
             });
 
-            acc.require_no_error(ret, "error iterating allocations outer");
+            acc.with_code("verifreg.claims")
+                .require_no_error(ret, "error iterating allocations outer");
+        }
+        Err(e) => acc.with_code("verifreg.claims").add(format!("error loading claims {e}")),
+    }
+
+    // Claims transferred away from a provider are kept as they were before the transfer.
+    let mut transferred_claims = HashMap::<ClaimID, Vec<ActorID>>::new();
+    match make_map_with_root_and_bitwidth(&state.transferred_claims, store, HAMT_BIT_WIDTH) {
+        Ok(transferred) => {
+            let ret = transferred.for_each(|provider_key, inner_root| {
+                let provider_id = decode_actor_id(provider_key).unwrap();
+                let claims = make_map_with_root_and_bitwidth(inner_root, store, HAMT_BIT_WIDTH)?;
+                claims.for_each(|claim_id_key, _: &Claim| {
+                    let claim_id = parse_uint_key(claim_id_key).unwrap();
+                    transferred_claims.entry(claim_id).or_default().push(provider_id);
+                    Ok(())
+                })?;
+                Ok(())
+            });
+            acc.with_code("verifreg.transferred_claims")
+                .require_no_error(ret, "error iterating transferred claims");
         }
-        Err(e) => acc.add(format!("error loading claims {e}")),
+        Err(e) => acc
+            .with_code("verifreg.transferred_claims")
+            .add(format!("error loading transferred claims {e}")),
+    }
+    for (claim_id, claim) in &all_claims {
+        let transferred = transferred_claims
+            .get(claim_id)
+            .map_or(false, |providers| providers.contains(&claim.provider));
+        acc.with_code("verifreg.transferred_claims").require(
+            !transferred,
+            format!("claim {claim_id} is both held and transferred by {}", claim.provider),
+        );
     }
 
     (
-        StateSummary { verifiers: all_verifiers, allocations: all_allocations, claims: all_claims },
+        StateSummary {
+            verifiers: all_verifiers,
+            allocations: all_allocations,
+            claims: all_claims,
+            transferred_claims,
+        },
         acc,
     )
 }
@@ -143,28 +222,40 @@ fn check_allocation_state(
     prior_epoch: ChainEpoch,
     acc: &MessageAccumulator,
 ) {
-    acc.require(id < next_alloc_id, format!("allocation id {} exceeds next {}", id, next_alloc_id));
-    acc.require(
+    acc.with_code("verifreg.allocation.id").require(
+        id < next_alloc_id,
+        format!("allocation id {} exceeds next {}", id, next_alloc_id),
+    );
+    acc.with_code("verifreg.allocation.client").require(
         alloc.client == client,
         format!("allocation {} client {} doesn't match key {}", id, alloc.client, client),
     );
-    acc.require(
+    if let Some(set) = &alloc.replicas {
+        acc.with_code("verifreg.allocation.replicas").require(
+            alloc.provider == 0 && set.first <= id && id < set.first + set.count,
+            format!(
+                "allocation {} provider {} or id outside replica set {:?}",
+                id, alloc.provider, set
+            ),
+        );
+    }
+    acc.with_code("verifreg.allocation.size").require(
         alloc.size.0 >= MINIMUM_VERIFIED_ALLOCATION_SIZE as u64,
         format!("allocation {} size {} too small", id, alloc.size.0),
     );
-    acc.require(
+    acc.with_code("verifreg.allocation.term_min").require(
         alloc.term_min >= MINIMUM_VERIFIED_ALLOCATION_TERM,
         format!("allocation {} term min {} too small", id, alloc.term_min),
     );
-    acc.require(
+    acc.with_code("verifreg.allocation.term_max").require(
         alloc.term_max <= MAXIMUM_VERIFIED_ALLOCATION_TERM,
         format!("allocation {} term max {} too large ", id, alloc.term_max),
     );
-    acc.require(
+    acc.with_code("verifreg.allocation.term_range").require(
         alloc.term_min <= alloc.term_max,
         format!("allocation {} term min {} exceeds max {}", id, alloc.term_min, alloc.term_max),
     );
-    acc.require(
+    acc.with_code("verifreg.allocation.expiration").require(
         alloc.expiration <= prior_epoch + MAXIMUM_VERIFIED_ALLOCATION_EXPIRATION,
         format!(
             "allocation {} expiration {} too far from now {}",
@@ -181,19 +272,20 @@ fn check_claim_state(
     prior_epoch: ChainEpoch,
     acc: &MessageAccumulator,
 ) {
-    acc.require(id < next_alloc_id, format!("claim id {} exceeds next {}", id, next_alloc_id));
-    acc.require(
+    acc.with_code("verifreg.claim.id")
+        .require(id < next_alloc_id, format!("claim id {} exceeds next {}", id, next_alloc_id));
+    acc.with_code("verifreg.claim.provider").require(
         claim.provider == provider,
         format!("claim {} provider {} doesn't match key {}", id, claim.provider, provider),
     );
-    acc.require(
+    acc.with_code("verifreg.claim.size").require(
         claim.size.0 >= MINIMUM_VERIFIED_ALLOCATION_SIZE as u64,
         format!(
             "claim {} size {} below minimum {}",
             id, claim.size.0, MINIMUM_VERIFIED_ALLOCATION_SIZE
         ),
     );
-    acc.require(
+    acc.with_code("verifreg.claim.term_min").require(
         claim.term_min >= MINIMUM_VERIFIED_ALLOCATION_TERM,
         format!(
             "claim {} term min {} below minimum {}",
@@ -202,11 +294,11 @@ fn check_claim_state(
     );
     // The maximum term is not limited because it can be extended
     // arbitrarily long by a client spending new datacap.
-    acc.require(
+    acc.with_code("verifreg.claim.term_range").require(
         claim.term_min <= claim.term_max,
         format!("claim {} term min {} exceeds max {}", id, claim.term_min, claim.term_max),
     );
-    acc.require(
+    acc.with_code("verifreg.claim.term_start").require(
         claim.term_start <= prior_epoch,
         format!("claim {} term start {} after now {}", id, claim.term_start, prior_epoch),
     );
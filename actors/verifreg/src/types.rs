@@ -3,6 +3,7 @@
 
 use cid::Cid;
 use fil_actors_runtime::BatchReturn;
+use fvm_ipld_encoding::strict_bytes;
 use fvm_ipld_encoding::tuple::*;
 use fvm_shared::address::Address;
 use fvm_shared::bigint::{bigint_ser, BigInt};
@@ -13,7 +14,7 @@ use fvm_shared::sector::SectorNumber;
 use fvm_shared::sector::StoragePower;
 use fvm_shared::ActorID;
 
-use crate::Claim;
+use crate::{Allocation, Claim};
 
 pub type AllocationID = u64;
 pub type ClaimID = u64;
@@ -29,6 +30,19 @@ pub type AddVerifierParams = VerifierParams;
 
 pub type AddVerifiedClientParams = VerifierParams;
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ExpiringVerifierParams {
+    pub address: Address,
+    #[serde(with = "bigint_ser")]
+    pub allowance: DataCap,
+    // The last epoch at which the allowance may be used, if any.
+    pub expiration: Option<ChainEpoch>,
+}
+
+pub type AddSubVerifierParams = ExpiringVerifierParams;
+
+pub type AddExpiringVerifiedClientParams = ExpiringVerifierParams;
+
 /// DataCap is an integer number of bytes.
 /// We can introduce policy changes and replace this in the future.
 pub type DataCap = StoragePower;
@@ -161,6 +175,21 @@ pub struct AllocationRequest {
     pub expiration: ChainEpoch,
 }
 
+// A request to create a number of allocations of the same data, each of which may be claimed
+// by any one of a set of providers, or by any miner if the set is empty.
+// Each provider may claim at most one of the replicas.
+// See Allocation state for description of the other fields' semantics.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ReplicatedAllocationRequest {
+    pub providers: Vec<ActorID>,
+    pub replicas: u64,
+    pub data: Cid,
+    pub size: PaddedPieceSize,
+    pub term_min: ChainEpoch,
+    pub term_max: ChainEpoch,
+    pub expiration: ChainEpoch,
+}
+
 // A request to extend the term of an existing claim with datacap tokens.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct ClaimExtensionRequest {
@@ -177,10 +206,29 @@ pub struct AllocationRequests {
     pub extensions: Vec<ClaimExtensionRequest>,
 }
 
+// Operator data for a datacap token transfer which also requests replicated allocations.
+// Transfers which don't may send the shorter AllocationRequests.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct AllocationRequests2 {
+    pub allocations: Vec<AllocationRequest>,
+    pub extensions: Vec<ClaimExtensionRequest>,
+    pub replicated: Vec<ReplicatedAllocationRequest>,
+}
+
+impl From<AllocationRequests> for AllocationRequests2 {
+    fn from(reqs: AllocationRequests) -> Self {
+        AllocationRequests2 {
+            allocations: reqs.allocations,
+            extensions: reqs.extensions,
+            replicated: vec![],
+        }
+    }
+}
+
 /// Recipient data payload in response to a datacap token transfer.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct AllocationsResponse {
-    // Result for each allocation request.
+    // Result for each allocation request, followed by each replicated allocation request.
     pub allocation_results: BatchReturn,
     // Result for each extension request.
     pub extension_results: BatchReturn,
@@ -200,6 +248,109 @@ pub struct GetClaimsReturn {
     pub claims: Vec<Claim>,
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetAllocationsParams {
+    pub client: ActorID,
+    pub allocation_ids: Vec<AllocationID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct GetAllocationsReturn {
+    pub batch_info: BatchReturn,
+    pub allocations: Vec<Allocation>,
+}
+
+/// Maximum number of allocations returned by a single call to ListAllocations.
+pub const LIST_ALLOCATIONS_MAX_LIMIT: u64 = 1000;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ListAllocationsParams {
+    pub client: ActorID,
+    // Lowest allocation id to list, taken from a previous call's next_cursor.
+    // Zero lists from the first allocation.
+    pub cursor: AllocationID,
+    // Maximum number of allocations to list, at most LIST_ALLOCATIONS_MAX_LIMIT.
+    pub limit: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ListAllocationsReturn {
+    // Ids of the client's allocations from the cursor, in ascending order.
+    pub allocation_ids: Vec<AllocationID>,
+    // The allocations, in the same order as their ids.
+    pub allocations: Vec<Allocation>,
+    // Cursor from which to list the remaining allocations, if any.
+    pub next_cursor: Option<AllocationID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct CancelAllocationsParams {
+    // Ids of the caller's allocations to cancel.
+    pub allocation_ids: Vec<AllocationID>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct CancelAllocationsReturn {
+    // Results for each allocation.
+    pub results: BatchReturn,
+    // The epoch by which all the cancelled allocations expire, after which their
+    // datacap may be recovered with RemoveExpiredAllocations.
+    pub expiration: ChainEpoch,
+}
+
+pub type GetTransferredClaimsParams = GetClaimsParams;
+
+pub type GetTransferredClaimsReturn = GetClaimsReturn;
+
+pub const SIGNATURE_DOMAIN_SEPARATION_TRANSFER_CLAIM: &[u8] = b"fil_transferclaim:";
+
+/// A client's approval for one of its claims to be transferred to a new provider,
+/// which the client signs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ClaimTransferProposal {
+    pub provider: ActorID,
+    pub claim_id: ClaimID,
+    pub new_provider: ActorID,
+    // The last epoch at which the new provider may take over the claim.
+    pub expiration: ChainEpoch,
+}
+
+/// Payload of a piece activation notification to the verified registry, requesting that
+/// a claim for the piece be transferred to the sector of the notifying provider.
+/// The new provider is implied by the notifying miner actor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct ClaimTransferRequest {
+    // The provider currently holding the claim.
+    pub provider: ActorID,
+    pub claim_id: ClaimID,
+    // The expiration of the client's approval.
+    pub expiration: ChainEpoch,
+    // The claim client's signature of the ClaimTransferProposal.
+    #[serde(with = "strict_bytes")]
+    pub signature: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RemoveSectorClaimsParams {
+    // The calling provider's sector from which the pieces have been dropped.
+    pub sector: SectorNumber,
+    pub pieces: Vec<DroppedPiece>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct DroppedPiece {
+    pub data: Cid,
+    pub size: PaddedPieceSize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
+pub struct RemoveSectorClaimsReturn {
+    // Ids of the claims removed, in order of the pieces for which a claim was found.
+    pub removed: Vec<ClaimID>,
+    // Results for each piece.
+    pub results: BatchReturn,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize_tuple, Deserialize_tuple)]
 pub struct RemoveExpiredClaimsParams {
     // Provider to clean up (need not be the caller)
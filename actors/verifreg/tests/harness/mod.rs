@@ -13,14 +13,23 @@ use fvm_shared::sector::SectorNumber;
 use fvm_shared::{ActorID, MethodNum, HAMT_BIT_WIDTH};
 use num_traits::{ToPrimitive, Zero};
 
+use fil_actor_verifreg::ext::miner::{
+    ClaimsTransferredParams, PieceChange, SectorChanges, SectorContentChangedParams,
+    SectorContentChangedReturn, TransferredClaim, CLAIMS_TRANSFERRED_METHOD,
+};
 use fil_actor_verifreg::testing::check_state_invariants;
 use fil_actor_verifreg::{
-    ext, Actor as VerifregActor, AddVerifiedClientParams, AddVerifierParams, Allocation,
-    AllocationID, AllocationRequest, AllocationRequests, AllocationsResponse, Claim,
-    ClaimAllocationsParams, ClaimAllocationsReturn, ClaimExtensionRequest, ClaimID, DataCap,
-    ExtendClaimTermsParams, ExtendClaimTermsReturn, GetClaimsParams, GetClaimsReturn, Method,
+    ext, Actor as VerifregActor, AddExpiringVerifiedClientParams, AddSubVerifierParams,
+    AddVerifiedClientParams, AddVerifierParams, Allocation, AllocationID, AllocationRequest,
+    AllocationRequests, AllocationRequests2, AllocationsResponse, CancelAllocationsParams,
+    CancelAllocationsReturn, Claim, ClaimAllocationsParams, ClaimAllocationsReturn,
+    ClaimExtensionRequest, ClaimID, ClaimTransferProposal, ClaimTransferRequest, DataCap,
+    ExtendClaimTermsParams, ExtendClaimTermsReturn, GetAllocationsParams, GetAllocationsReturn,
+    GetClaimsParams, GetClaimsReturn, ListAllocationsParams, ListAllocationsReturn, Method,
     RemoveExpiredAllocationsParams, RemoveExpiredAllocationsReturn, RemoveExpiredClaimsParams,
-    RemoveExpiredClaimsReturn, SectorAllocationClaim, State,
+    RemoveExpiredClaimsReturn, RemoveSectorClaimsParams, RemoveSectorClaimsReturn, ReplicaSet,
+    ReplicatedAllocationRequest, SectorAllocationClaim, State, VerifierInfo,
+    SIGNATURE_DOMAIN_SEPARATION_TRANSFER_CLAIM,
 };
 use fil_actors_runtime::cbor::serialize;
 use fil_actors_runtime::runtime::builtins::Type;
@@ -34,6 +43,7 @@ use fil_actors_runtime::{
     STORAGE_MARKET_ACTOR_ADDR, SYSTEM_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
 };
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_shared::sys::SendFlags;
 
 pub const ROOT_ADDR: Address = Address::new_id(101);
 
@@ -163,37 +173,117 @@ impl Harness {
         assert!(!verifiers.contains_key(&verifier_id_addr.to_bytes()).unwrap())
     }
 
+    // Invokes AddSubVerifier from a verifier, or the root key.
+    pub fn add_sub_verifier(
+        &self,
+        rt: &mut MockRuntime,
+        caller: &Address,
+        verifier: &Address,
+        allowance: &DataCap,
+        expiration: Option<ChainEpoch>,
+    ) -> Result<(), ActorError> {
+        rt.expect_validate_caller_any();
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *caller);
+        let verifier_resolved = rt.get_id_address(verifier).unwrap_or(*verifier);
+        // Expect checking the verifier's token balance.
+        rt.expect_send_simple(
+            DATACAP_TOKEN_ACTOR_ADDR,
+            ext::datacap::Method::Balance as MethodNum,
+            IpldBlock::serialize_cbor(&verifier_resolved).unwrap(),
+            TokenAmount::zero(),
+            IpldBlock::serialize_cbor(&BigIntSer(&DataCap::zero())).unwrap(),
+            ExitCode::OK,
+        );
+
+        let params =
+            AddSubVerifierParams { address: *verifier, allowance: allowance.clone(), expiration };
+        let ret = rt.call::<VerifregActor>(
+            Method::AddSubVerifier as MethodNum,
+            IpldBlock::serialize_cbor(&params).unwrap(),
+        )?;
+        assert!(ret.is_none());
+        rt.verify();
+
+        self.assert_verifier_allowance(rt, verifier, allowance);
+        Ok(())
+    }
+
+    pub fn get_verifier_info(&self, rt: &MockRuntime, verifier: &Address) -> Option<VerifierInfo> {
+        rt.get_state::<State>().get_verifier_info(rt.store(), verifier).unwrap()
+    }
+
     pub fn add_client(
         &self,
         rt: &mut MockRuntime,
         verifier: &Address,
         client: &Address,
         allowance: &DataCap,
+    ) -> Result<(), ActorError> {
+        self.add_client_expiring(rt, verifier, client, allowance, None)
+    }
+
+    // Invokes AddVerifiedClient, or AddExpiringVerifiedClient if an expiration is specified.
+    pub fn add_client_expiring(
+        &self,
+        rt: &mut MockRuntime,
+        verifier: &Address,
+        client: &Address,
+        allowance: &DataCap,
+        expiration: Option<ChainEpoch>,
     ) -> Result<(), ActorError> {
         rt.expect_validate_caller_any();
         rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, *verifier);
         let client_resolved = rt.get_id_address(client).unwrap_or(*client);
 
         // Expect tokens to be minted.
-        let mint_params = ext::datacap::MintParams {
-            to: client_resolved,
-            amount: TokenAmount::from_whole(allowance.to_i64().unwrap()),
-            operators: vec![STORAGE_MARKET_ACTOR_ADDR],
+        let to = client_resolved;
+        let amount = TokenAmount::from_whole(allowance.to_i64().unwrap());
+        let operators = vec![STORAGE_MARKET_ACTOR_ADDR];
+        let (method, mint_params) = match expiration {
+            None => (
+                ext::datacap::Method::Mint,
+                IpldBlock::serialize_cbor(&ext::datacap::MintParams { to, amount, operators }),
+            ),
+            Some(expiration) => (
+                ext::datacap::Method::MintExpiring,
+                IpldBlock::serialize_cbor(&ext::datacap::MintExpiringParams {
+                    to,
+                    amount,
+                    operators,
+                    expiration,
+                }),
+            ),
         };
         rt.expect_send_simple(
             DATACAP_TOKEN_ACTOR_ADDR,
-            ext::datacap::Method::Mint as MethodNum,
-            IpldBlock::serialize_cbor(&mint_params).unwrap(),
+            method as MethodNum,
+            mint_params.unwrap(),
             TokenAmount::zero(),
             None,
             ExitCode::OK,
         );
 
-        let params = AddVerifiedClientParams { address: *client, allowance: allowance.clone() };
-        let ret = rt.call::<VerifregActor>(
-            Method::AddVerifiedClient as MethodNum,
-            IpldBlock::serialize_cbor(&params).unwrap(),
-        )?;
+        let ret = match expiration {
+            None => {
+                let params =
+                    AddVerifiedClientParams { address: *client, allowance: allowance.clone() };
+                rt.call::<VerifregActor>(
+                    Method::AddVerifiedClient as MethodNum,
+                    IpldBlock::serialize_cbor(&params).unwrap(),
+                )?
+            }
+            Some(_) => {
+                let params = AddExpiringVerifiedClientParams {
+                    address: *client,
+                    allowance: allowance.clone(),
+                    expiration,
+                };
+                rt.call::<VerifregActor>(
+                    Method::AddExpiringVerifiedClient as MethodNum,
+                    IpldBlock::serialize_cbor(&params).unwrap(),
+                )?
+            }
+        };
         assert!(ret.is_none());
         rt.verify();
 
@@ -332,6 +422,26 @@ impl Harness {
         Ok(ret)
     }
 
+    pub fn remove_sector_claims(
+        &self,
+        rt: &mut MockRuntime,
+        provider: ActorID,
+        params: RemoveSectorClaimsParams,
+    ) -> Result<RemoveSectorClaimsReturn, ActorError> {
+        rt.set_caller(*MINER_ACTOR_CODE_ID, Address::new_id(provider));
+        rt.expect_validate_caller_type(vec![Type::Miner]);
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::RemoveSectorClaims as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize remove sector claims return");
+        rt.verify();
+        Ok(ret)
+    }
+
     pub fn load_claim(
         &self,
         rt: &mut MockRuntime,
@@ -423,6 +533,154 @@ impl Harness {
         Ok(ret)
     }
 
+    pub fn get_transferred_claims(
+        &self,
+        rt: &mut MockRuntime,
+        provider: ActorID,
+        claim_ids: Vec<ClaimID>,
+    ) -> Result<GetClaimsReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = GetClaimsParams { claim_ids, provider };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::GetTransferredClaims as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize get transferred claims return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    // Invokes the SectorContentChanged actor method as a provider, expecting the client of
+    // each claim to be transferred to authenticate the transfer, with the given exit codes,
+    // and then each previous provider to be notified of the claims transferred from it,
+    // along with the space transferred earlier from the same sectors.
+    pub fn sector_content_changed(
+        &self,
+        rt: &mut MockRuntime,
+        provider: ActorID,
+        sectors: Vec<SectorChanges>,
+        expected_auths: Vec<(ActorID, ClaimTransferProposal, Vec<u8>, ExitCode)>,
+        expected_notifications: Vec<(
+            ActorID,
+            Vec<TransferredClaim>,
+            Vec<TransferredClaim>,
+            ExitCode,
+        )>,
+    ) -> Result<SectorContentChangedReturn, ActorError> {
+        rt.set_caller(*MINER_ACTOR_CODE_ID, Address::new_id(provider));
+        rt.expect_validate_caller_type(vec![Type::Miner]);
+        for (client, proposal, signature, exit_code) in expected_auths {
+            let message = [
+                SIGNATURE_DOMAIN_SEPARATION_TRANSFER_CLAIM,
+                serialize(&proposal, "proposal").unwrap().bytes(),
+            ]
+            .concat();
+            rt.expect_send(
+                Address::new_id(client),
+                ext::account::AUTHENTICATE_MESSAGE_METHOD,
+                IpldBlock::serialize_cbor(&ext::account::AuthenticateMessageParams {
+                    signature,
+                    message,
+                })
+                .unwrap(),
+                TokenAmount::zero(),
+                None,
+                SendFlags::READ_ONLY,
+                None,
+                exit_code,
+                None,
+            );
+        }
+        for (previous_provider, claims, prior_transfers, exit_code) in expected_notifications {
+            rt.expect_send_simple(
+                Address::new_id(previous_provider),
+                CLAIMS_TRANSFERRED_METHOD,
+                IpldBlock::serialize_cbor(&ClaimsTransferredParams { claims, prior_transfers })
+                    .unwrap(),
+                TokenAmount::zero(),
+                None,
+                exit_code,
+            );
+        }
+        let params = SectorContentChangedParams { sectors };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::SectorContentChanged as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize sector content changed return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    pub fn get_allocations(
+        &self,
+        rt: &mut MockRuntime,
+        client: ActorID,
+        allocation_ids: Vec<AllocationID>,
+    ) -> Result<GetAllocationsReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = GetAllocationsParams { client, allocation_ids };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::GetAllocations as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize get allocations return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    pub fn list_allocations(
+        &self,
+        rt: &mut MockRuntime,
+        client: ActorID,
+        cursor: AllocationID,
+        limit: u64,
+    ) -> Result<ListAllocationsReturn, ActorError> {
+        rt.expect_validate_caller_any();
+        let params = ListAllocationsParams { client, cursor, limit };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::ListAllocations as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize list allocations return");
+        rt.verify();
+        Ok(ret)
+    }
+
+    // Invokes the CancelAllocations actor method as the client.
+    pub fn cancel_allocations(
+        &self,
+        rt: &mut MockRuntime,
+        client: ActorID,
+        allocation_ids: Vec<AllocationID>,
+    ) -> Result<CancelAllocationsReturn, ActorError> {
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(client));
+        rt.expect_validate_caller_any();
+        let params = CancelAllocationsParams { allocation_ids };
+        let ret = rt
+            .call::<VerifregActor>(
+                Method::CancelAllocations as MethodNum,
+                IpldBlock::serialize_cbor(&params).unwrap(),
+            )?
+            .unwrap()
+            .deserialize()
+            .expect("failed to deserialize cancel allocations return");
+        rt.verify();
+        Ok(ret)
+    }
+
     pub fn extend_claim_terms(
         &self,
         rt: &mut MockRuntime,
@@ -451,6 +709,7 @@ pub fn make_alloc(data_id: &str, client: ActorID, provider: ActorID, size: u64)
         term_min: MINIMUM_VERIFIED_ALLOCATION_TERM,
         term_max: MINIMUM_VERIFIED_ALLOCATION_TERM * 2,
         expiration: 100,
+        replicas: None,
     }
 }
 
@@ -466,6 +725,24 @@ pub fn make_alloc_req(rt: &MockRuntime, provider: ActorID, size: u64) -> Allocat
     }
 }
 
+// Creates a replicated allocation request for fixed data with default terms.
+pub fn make_replicated_alloc_req(
+    rt: &MockRuntime,
+    providers: Vec<ActorID>,
+    replicas: u64,
+    size: u64,
+) -> ReplicatedAllocationRequest {
+    ReplicatedAllocationRequest {
+        providers,
+        replicas,
+        data: make_piece_cid("1234".as_bytes()),
+        size: PaddedPieceSize(size),
+        term_min: MINIMUM_VERIFIED_ALLOCATION_TERM,
+        term_max: MAXIMUM_VERIFIED_ALLOCATION_TERM,
+        expiration: rt.epoch + 100,
+    }
+}
+
 pub fn make_extension_req(
     provider: ActorID,
     claim: ClaimID,
@@ -484,6 +761,25 @@ pub fn alloc_from_req(client: ActorID, req: &AllocationRequest) -> Allocation {
         term_min: req.term_min,
         term_max: req.term_max,
         expiration: req.expiration,
+        replicas: None,
+    }
+}
+
+// Creates the expected allocation for each replica of a replicated request.
+pub fn alloc_from_replicated_req(
+    client: ActorID,
+    req: &ReplicatedAllocationRequest,
+    set: ReplicaSet,
+) -> Allocation {
+    Allocation {
+        client,
+        provider: 0,
+        data: req.data,
+        size: req.size,
+        term_min: req.term_min,
+        term_max: req.term_max,
+        expiration: req.expiration,
+        replicas: Some(set),
     }
 }
 
@@ -539,6 +835,26 @@ pub fn claim_from_alloc(alloc: &Allocation, term_start: ChainEpoch, sector: Sect
     }
 }
 
+// Makes a piece activation payload requesting the transfer of a claim.
+pub fn make_transfer_piece(
+    claim: &Claim,
+    claim_id: ClaimID,
+    expiration: ChainEpoch,
+    signature: &[u8],
+) -> PieceChange {
+    let req = ClaimTransferRequest {
+        provider: claim.provider,
+        claim_id,
+        expiration,
+        signature: signature.to_vec(),
+    };
+    PieceChange {
+        data: claim.data,
+        size: claim.size,
+        payload: serialize(&req, "transfer request").unwrap(),
+    }
+}
+
 pub fn make_receiver_hook_token_payload(
     client: ActorID,
     alloc_requests: Vec<AllocationRequest>,
@@ -558,6 +874,28 @@ pub fn make_receiver_hook_token_payload(
     }
 }
 
+pub fn make_replicated_receiver_hook_token_payload(
+    client: ActorID,
+    alloc_requests: Vec<AllocationRequest>,
+    extension_requests: Vec<ClaimExtensionRequest>,
+    replicated_requests: Vec<ReplicatedAllocationRequest>,
+    datacap_received: u64,
+) -> FRC46TokenReceived {
+    let payload = AllocationRequests2 {
+        allocations: alloc_requests,
+        extensions: extension_requests,
+        replicated: replicated_requests,
+    };
+    FRC46TokenReceived {
+        from: client,
+        to: VERIFIED_REGISTRY_ACTOR_ADDR.id().unwrap(),
+        operator: client,
+        amount: TokenAmount::from_whole(datacap_received as i64),
+        operator_data: serialize(&payload, "operator data").unwrap(),
+        token_data: Default::default(),
+    }
+}
+
 pub fn assert_allocation(
     rt: &MockRuntime,
     client: ActorID,
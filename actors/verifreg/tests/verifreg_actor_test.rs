@@ -246,6 +246,7 @@ mod clients {
     use fvm_shared::{MethodNum, METHOD_SEND};
     use num_traits::Zero;
 
+    use fil_actor_verifreg::reason::{self, InsufficientDataCap};
     use fil_actor_verifreg::{
         ext, Actor as VerifregActor, AddVerifiedClientParams, DataCap, Method,
     };
@@ -450,9 +451,13 @@ mod clients {
         h.add_verifier(&mut rt, &VERIFIER, &allowance_verifier).unwrap();
 
         let allowance = allowance_verifier.clone() + 1;
-        expect_abort(
-            ExitCode::USR_ILLEGAL_ARGUMENT,
-            h.add_client(&mut rt, &VERIFIER, &h.root, &allowance),
+        let err = h.add_client(&mut rt, &VERIFIER, &h.root, &allowance).unwrap_err();
+        assert_eq!(ExitCode::USR_ILLEGAL_ARGUMENT, err.exit_code());
+        let reason = err.reason::<InsufficientDataCap>().unwrap();
+        assert_eq!(reason::INSUFFICIENT_ALLOWANCE, reason.code);
+        assert_eq!(
+            InsufficientDataCap { required: allowance, available: allowance_verifier },
+            reason.detail
         );
         rt.reset();
         h.check_state(&rt);
@@ -494,6 +499,136 @@ mod clients {
     }
 }
 
+mod sub_verifiers {
+    use fvm_shared::error::ExitCode;
+
+    use fil_actor_verifreg::{reason, VerifierInfo};
+    use fil_actors_runtime::test_utils::*;
+    use fil_actors_runtime::ErrorReason;
+    use harness::*;
+    use util::*;
+
+    use crate::*;
+
+    #[test]
+    fn delegates_allowance() {
+        let (h, mut rt) = new_harness();
+        let allowance = client_allowance(&rt);
+        h.add_verifier(&mut rt, &VERIFIER, &(allowance.clone() * 3)).unwrap();
+
+        // The sub-verifier's allowance is taken from its parent's.
+        h.add_sub_verifier(&mut rt, &VERIFIER, &VERIFIER2, &(allowance.clone() * 2), None).unwrap();
+        h.assert_verifier_allowance(&rt, &VERIFIER, &allowance);
+        assert_eq!(
+            Some(VerifierInfo { parent: Some(*VERIFIER), expiration: None }),
+            h.get_verifier_info(&rt, &VERIFIER2)
+        );
+        assert_eq!(None, h.get_verifier_info(&rt, &VERIFIER));
+
+        // The sub-verifier can add clients.
+        h.add_client(&mut rt, &VERIFIER2, &CLIENT, &allowance).unwrap();
+        h.assert_verifier_allowance(&rt, &VERIFIER2, &allowance);
+        h.check_state(&rt);
+
+        // Can't delegate more than the parent's allowance.
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "than allocated",
+            h.add_sub_verifier(&mut rt, &VERIFIER, &CLIENT2, &(allowance.clone() * 2), None),
+        );
+        rt.reset();
+
+        // Re-adding by the root key makes it a top-level verifier.
+        h.add_verifier(&mut rt, &VERIFIER2, &allowance).unwrap();
+        assert_eq!(None, h.get_verifier_info(&rt, &VERIFIER2));
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn rejects_existing_verifier() {
+        let (h, mut rt) = new_harness();
+        let allowance = client_allowance(&rt);
+        h.add_verifier(&mut rt, &VERIFIER, &(allowance.clone() * 2)).unwrap();
+        h.add_verifier(&mut rt, &VERIFIER2, &allowance).unwrap();
+
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "already a verifier",
+            h.add_sub_verifier(&mut rt, &VERIFIER, &VERIFIER2, &allowance, None),
+        );
+        rt.reset();
+        expect_abort_contains_message(
+            ExitCode::USR_NOT_FOUND,
+            "is not a verifier",
+            h.add_sub_verifier(&mut rt, &CLIENT, &CLIENT2, &allowance, None),
+        );
+        rt.reset();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn allowance_expires() {
+        let (h, mut rt) = new_harness();
+        let allowance = client_allowance(&rt);
+        let root = h.root;
+
+        // The root key can add a verifier with an expiring allowance.
+        h.add_sub_verifier(&mut rt, &root, &VERIFIER, &(allowance.clone() * 2), Some(100)).unwrap();
+        assert_eq!(
+            Some(VerifierInfo { parent: None, expiration: Some(100) }),
+            h.get_verifier_info(&rt, &VERIFIER)
+        );
+
+        // A sub-verifier inherits its parent's expiration, and can't exceed it.
+        let ret = h.add_sub_verifier(&mut rt, &VERIFIER, &VERIFIER2, &allowance, Some(101));
+        assert_eq!(
+            Some(ErrorReason { code: reason::EXPIRATION_INVALID, detail: 101i64 }),
+            ret.as_ref().unwrap_err().reason()
+        );
+        expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "exceeds verifier's", ret);
+        rt.reset();
+        h.add_sub_verifier(&mut rt, &VERIFIER, &VERIFIER2, &allowance, None).unwrap();
+        assert_eq!(
+            Some(VerifierInfo { parent: Some(*VERIFIER), expiration: Some(100) }),
+            h.get_verifier_info(&rt, &VERIFIER2)
+        );
+        h.check_state(&rt);
+
+        // The allowance can't be used after it expires.
+        rt.set_epoch(101);
+        let ret = h.add_client(&mut rt, &VERIFIER2, &CLIENT, &allowance);
+        assert_eq!(
+            Some(ErrorReason { code: reason::VERIFIER_EXPIRED, detail: 100i64 }),
+            ret.as_ref().unwrap_err().reason()
+        );
+        expect_abort_contains_message(ExitCode::USR_FORBIDDEN, "expired", ret);
+        rt.reset();
+        h.assert_verifier_allowance(&rt, &VERIFIER2, &allowance);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn expiring_client_datacap() {
+        let (h, mut rt) = new_harness();
+        let allowance = client_allowance(&rt);
+        h.add_verifier(&mut rt, &VERIFIER, &(allowance.clone() * 2)).unwrap();
+
+        rt.set_epoch(10);
+        h.add_client_expiring(&mut rt, &VERIFIER, &CLIENT, &allowance, Some(50)).unwrap();
+        h.assert_verifier_allowance(&rt, &VERIFIER, &allowance);
+
+        let ret = h.add_client_expiring(&mut rt, &VERIFIER, &CLIENT2, &allowance, Some(9));
+        assert_eq!(
+            Some(ErrorReason { code: reason::EXPIRATION_INVALID, detail: 9i64 }),
+            ret.as_ref().unwrap_err().reason()
+        );
+        expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "has passed", ret);
+        rt.reset();
+        h.assert_verifier_allowance(&rt, &VERIFIER, &allowance);
+        h.check_state(&rt);
+    }
+}
+
 mod allocs_claims {
     use cid::Cid;
     use fvm_ipld_encoding::ipld_block::IpldBlock;
@@ -504,17 +639,20 @@ mod allocs_claims {
     use num_traits::Zero;
     use std::str::FromStr;
 
+    use fil_actor_verifreg::ext::miner::{SectorChanges, TransferredClaim};
     use fil_actor_verifreg::{
-        Actor, AllocationID, ClaimTerm, DataCap, ExtendClaimTermsParams, GetClaimsParams,
-        GetClaimsReturn, Method, State,
+        Actor, Allocation, AllocationID, ClaimTerm, ClaimTransferProposal, DataCap, DroppedPiece,
+        ExtendClaimTermsParams, GetClaimsParams, GetClaimsReturn, Method, RemoveSectorClaimsParams,
+        ReplicaSet, State, LIST_ALLOCATIONS_MAX_LIMIT,
     };
     use fil_actor_verifreg::{Claim, ExtendClaimTermsReturn};
+    use fil_actors_runtime::runtime::builtins::Type;
     use fil_actors_runtime::runtime::policy_constants::{
         MAXIMUM_VERIFIED_ALLOCATION_TERM, MINIMUM_VERIFIED_ALLOCATION_SIZE,
         MINIMUM_VERIFIED_ALLOCATION_TERM,
     };
     use fil_actors_runtime::test_utils::{
-        expect_abort_contains_message, make_identity_cid, ACCOUNT_ACTOR_CODE_ID,
+        expect_abort_contains_message, make_identity_cid, make_piece_cid, ACCOUNT_ACTOR_CODE_ID,
     };
     use fil_actors_runtime::FailCode;
     use harness::*;
@@ -727,6 +865,74 @@ mod allocs_claims {
         }
     }
 
+    #[test]
+    fn claim_replicated_allocs() {
+        let (h, mut rt) = new_harness();
+        let provider3: ActorID = 303;
+
+        let size = MINIMUM_VERIFIED_ALLOCATION_SIZE as u64;
+        let set = ReplicaSet {
+            providers: vec![PROVIDER1, PROVIDER2],
+            first: 1,
+            count: 2,
+            claimed_by: vec![],
+        };
+        let alloc = Allocation { replicas: Some(set.clone()), ..make_alloc("1", CLIENT1, 0, size) };
+        let any = ReplicaSet { providers: vec![], first: 3, count: 1, claimed_by: vec![] };
+        let alloc_any = Allocation { replicas: Some(any), ..make_alloc("2", CLIENT1, 0, size) };
+        h.create_alloc(&mut rt, &alloc).unwrap();
+        h.create_alloc(&mut rt, &alloc).unwrap();
+        h.create_alloc(&mut rt, &alloc_any).unwrap();
+        h.check_state(&rt);
+
+        let sector = 1000;
+        let expiry = MINIMUM_VERIFIED_ALLOCATION_TERM;
+
+        // A provider may claim only one replica of a set.
+        let reqs = vec![
+            make_claim_req(1, &alloc, sector, expiry),
+            make_claim_req(2, &alloc, sector, expiry),
+        ];
+        let ret = h.claim_allocations(&mut rt, PROVIDER1, reqs, size, false).unwrap();
+        assert_eq!(ret.batch_info.codes(), vec![ExitCode::OK, ExitCode::USR_FORBIDDEN]);
+        let expected = Claim { provider: PROVIDER1, ..claim_from_alloc(&alloc, 0, sector) };
+        assert_claim(&rt, PROVIDER1, 1, &expected);
+        // The remaining replica records the claiming provider.
+        let claimed_set = ReplicaSet { claimed_by: vec![PROVIDER1], ..set };
+        let alloc = Allocation { replicas: Some(claimed_set), ..alloc };
+        assert_allocation(&rt, CLIENT1, 2, &alloc);
+        h.check_state(&rt);
+
+        // The provider still can't claim another replica once its claim is gone.
+        let mut st: State = rt.get_state();
+        let mut claims = st.load_claims(&*rt.store).unwrap();
+        claims.remove(PROVIDER1, 1).unwrap();
+        st.save_claims(&mut claims).unwrap();
+        rt.replace_state(&st);
+        let reqs = vec![make_claim_req(2, &alloc, sector, expiry)];
+        let ret = h.claim_allocations(&mut rt, PROVIDER1, reqs, 0, false).unwrap();
+        assert_eq!(ret.batch_info.codes(), vec![ExitCode::USR_FORBIDDEN]);
+
+        // A provider outside the set can't claim a replica.
+        let reqs = vec![make_claim_req(2, &alloc, sector, expiry)];
+        let ret = h.claim_allocations(&mut rt, provider3, reqs, 0, false).unwrap();
+        assert_eq!(ret.batch_info.codes(), vec![ExitCode::USR_FORBIDDEN]);
+
+        // Another provider in the set claims the remaining replica,
+        // and any provider can claim from an empty set.
+        let reqs = vec![make_claim_req(2, &alloc, sector, expiry)];
+        let ret = h.claim_allocations(&mut rt, PROVIDER2, reqs, size, false).unwrap();
+        assert_eq!(ret.batch_info.codes(), vec![ExitCode::OK]);
+        let reqs = vec![make_claim_req(3, &alloc_any, sector, expiry)];
+        let ret = h.claim_allocations(&mut rt, provider3, reqs, size, false).unwrap();
+        assert_eq!(ret.batch_info.codes(), vec![ExitCode::OK]);
+        let expected = Claim { provider: PROVIDER2, ..claim_from_alloc(&alloc, 0, sector) };
+        assert_claim(&rt, PROVIDER2, 2, &expected);
+        let expected = Claim { provider: provider3, ..claim_from_alloc(&alloc_any, 0, sector) };
+        assert_claim(&rt, provider3, 3, &expected);
+        h.check_state(&rt);
+    }
+
     #[test]
     fn get_claims() {
         let (h, mut rt) = new_harness();
@@ -769,6 +975,307 @@ mod allocs_claims {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn get_and_list_allocations() {
+        let (h, mut rt) = new_harness();
+        let alloc1 = make_alloc("1", CLIENT1, PROVIDER1, ALLOC_SIZE);
+        let alloc2 = make_alloc("2", CLIENT1, PROVIDER2, ALLOC_SIZE);
+        let alloc3 = make_alloc("3", CLIENT2, PROVIDER1, ALLOC_SIZE);
+        let id1 = h.create_alloc(&mut rt, &alloc1).unwrap();
+        let id2 = h.create_alloc(&mut rt, &alloc2).unwrap();
+        let id3 = h.create_alloc(&mut rt, &alloc3).unwrap();
+
+        {
+            // Mixed bag, including another client's allocation
+            let ret = h.get_allocations(&mut rt, CLIENT1, vec![id2, id3, id1]).unwrap();
+            assert_eq!(2, ret.batch_info.success_count);
+            assert_eq!(vec![alloc2.clone(), alloc1.clone()], ret.allocations);
+            assert_eq!(
+                vec![FailCode { idx: 1, code: ExitCode::USR_NOT_FOUND }],
+                ret.batch_info.fail_codes
+            );
+        }
+        {
+            let ret = h.list_allocations(&mut rt, CLIENT1, 0, 10).unwrap();
+            assert_eq!(vec![id1, id2], ret.allocation_ids);
+            assert_eq!(vec![alloc1.clone(), alloc2.clone()], ret.allocations);
+            assert_eq!(None, ret.next_cursor);
+
+            let ret = h.list_allocations(&mut rt, CLIENT2, 0, 10).unwrap();
+            assert_eq!(vec![id3], ret.allocation_ids);
+            assert_eq!(vec![alloc3], ret.allocations);
+            assert_eq!(None, ret.next_cursor);
+
+            let ret = h.list_allocations(&mut rt, PROVIDER1, 0, 10).unwrap();
+            assert!(ret.allocation_ids.is_empty());
+            assert_eq!(None, ret.next_cursor);
+        }
+        {
+            // A page at a time, resuming from the returned cursor.
+            let ret = h.list_allocations(&mut rt, CLIENT1, 0, 1).unwrap();
+            assert_eq!(vec![id1], ret.allocation_ids);
+            assert_eq!(vec![alloc1], ret.allocations);
+            assert_eq!(Some(id2), ret.next_cursor);
+
+            let ret = h.list_allocations(&mut rt, CLIENT1, id2, 1).unwrap();
+            assert_eq!(vec![id2], ret.allocation_ids);
+            assert_eq!(vec![alloc2], ret.allocations);
+            assert_eq!(None, ret.next_cursor);
+
+            let ret = h.list_allocations(&mut rt, CLIENT1, id2 + 1, 1).unwrap();
+            assert!(ret.allocation_ids.is_empty());
+            assert_eq!(None, ret.next_cursor);
+        }
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn list_allocations_limit_bounded() {
+        let (h, mut rt) = new_harness();
+        h.create_alloc(&mut rt, &make_alloc("1", CLIENT1, PROVIDER1, ALLOC_SIZE)).unwrap();
+        for limit in [0, LIST_ALLOCATIONS_MAX_LIMIT + 1] {
+            expect_abort_contains_message(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                "limit",
+                h.list_allocations(&mut rt, CLIENT1, 0, limit),
+            );
+            rt.reset();
+        }
+        let ret = h.list_allocations(&mut rt, CLIENT1, 0, LIST_ALLOCATIONS_MAX_LIMIT).unwrap();
+        assert_eq!(1, ret.allocation_ids.len());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn cancel_allocations() {
+        let (h, mut rt) = new_harness();
+        let grace = rt.policy.verified_allocation_cancellation_grace;
+        let mut alloc1 = make_alloc("1", CLIENT1, PROVIDER1, ALLOC_SIZE);
+        alloc1.expiration = 10 + grace + 500;
+        // Already expires within the grace period.
+        let mut alloc2 = make_alloc("2", CLIENT1, PROVIDER1, ALLOC_SIZE);
+        alloc2.expiration = 100;
+        let alloc3 = make_alloc("3", CLIENT2, PROVIDER1, ALLOC_SIZE);
+        let id1 = h.create_alloc(&mut rt, &alloc1).unwrap();
+        let id2 = h.create_alloc(&mut rt, &alloc2).unwrap();
+        let id3 = h.create_alloc(&mut rt, &alloc3).unwrap();
+
+        rt.set_epoch(10);
+        expect_abort_contains_message(
+            ExitCode::USR_ILLEGAL_ARGUMENT,
+            "no ids",
+            h.cancel_allocations(&mut rt, CLIENT1, vec![]),
+        );
+        rt.reset();
+
+        // Only the caller's own allocations may be cancelled.
+        let ret = h.cancel_allocations(&mut rt, CLIENT1, vec![id1, id3, id2]).unwrap();
+        assert_eq!(vec![ExitCode::OK, ExitCode::USR_NOT_FOUND, ExitCode::OK], ret.results.codes());
+        assert_eq!(10 + grace, ret.expiration);
+        assert_allocation(&rt, CLIENT1, id1, &Allocation { expiration: 10 + grace, ..alloc1 });
+        assert_allocation(&rt, CLIENT1, id2, &alloc2);
+        assert_allocation(&rt, CLIENT2, id3, &alloc3);
+        h.check_state(&rt);
+
+        // The datacap can't be recovered until the grace period has passed.
+        rt.set_epoch(10 + grace - 1);
+        let ret = h.remove_expired_allocations(&mut rt, CLIENT1, vec![id1], 0).unwrap();
+        assert_eq!(vec![ExitCode::USR_FORBIDDEN], ret.results.codes());
+
+        rt.set_epoch(10 + grace);
+        let ret = h.remove_expired_allocations(&mut rt, CLIENT1, vec![], ALLOC_SIZE * 2).unwrap();
+        assert_eq!(vec![ExitCode::OK, ExitCode::OK], ret.results.codes());
+        assert!(h.load_alloc(&mut rt, CLIENT1, id1).is_none());
+        assert!(h.load_alloc(&mut rt, CLIENT1, id2).is_none());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn transfer_claims() {
+        let (h, mut rt) = new_harness();
+        let term_min = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let term_max = term_min + 1000;
+        let claim1 = make_claim("1", CLIENT1, PROVIDER1, ALLOC_SIZE, term_min, term_max, 0, 5);
+        let claim2 = make_claim("2", CLIENT1, PROVIDER1, ALLOC_SIZE, term_min, term_max, 0, 5);
+        let id1 = h.create_claim(&mut rt, &claim1).unwrap();
+        let id2 = h.create_claim(&mut rt, &claim2).unwrap();
+
+        rt.set_epoch(100);
+        let approval_expiration = 200;
+        let mut wrong_size = make_transfer_piece(&claim2, id2, approval_expiration, b"sig2");
+        wrong_size.size = PaddedPieceSize(ALLOC_SIZE * 2);
+        let sectors = vec![
+            SectorChanges {
+                sector: 7,
+                minimum_commitment_epoch: term_min + 500,
+                added: vec![
+                    make_transfer_piece(&claim1, id1, approval_expiration, b"sig1"),
+                    // Not approved by the client.
+                    make_transfer_piece(&claim2, id2, approval_expiration, b"bad"),
+                    // Already transferred in this batch.
+                    make_transfer_piece(&claim1, id1, approval_expiration, b"sig1"),
+                    wrong_size,
+                ],
+            },
+            // The sector would outlive the claim's maximum term.
+            SectorChanges {
+                sector: 8,
+                minimum_commitment_epoch: term_max + 1,
+                added: vec![make_transfer_piece(&claim2, id2, approval_expiration, b"sig2")],
+            },
+        ];
+        let proposal = |claim_id| ClaimTransferProposal {
+            provider: PROVIDER1,
+            claim_id,
+            new_provider: PROVIDER2,
+            expiration: approval_expiration,
+        };
+        let ret = h
+            .sector_content_changed(
+                &mut rt,
+                PROVIDER2,
+                sectors,
+                vec![
+                    (CLIENT1, proposal(id1), b"sig1".to_vec(), ExitCode::OK),
+                    (CLIENT1, proposal(id2), b"bad".to_vec(), ExitCode::USR_ILLEGAL_ARGUMENT),
+                ],
+                // The previous provider drops the claim's power from its sector.
+                vec![(
+                    PROVIDER1,
+                    vec![TransferredClaim { sector: 5, size: PaddedPieceSize(ALLOC_SIZE) }],
+                    vec![],
+                    ExitCode::OK,
+                )],
+            )
+            .unwrap();
+        let accepted: Vec<Vec<bool>> =
+            ret.sectors.iter().map(|s| s.added.iter().map(|p| p.accepted).collect()).collect();
+        assert_eq!(vec![vec![true, false, false, false], vec![false]], accepted);
+
+        // The claim is re-homed with its remaining term, and kept as transferred.
+        assert!(h.load_claim(&mut rt, PROVIDER1, id1).is_none());
+        assert_claim(&rt, PROVIDER2, id1, &Claim { provider: PROVIDER2, sector: 7, ..claim1 });
+        assert_claim(&rt, PROVIDER1, id2, &claim2);
+        let ret = h.get_transferred_claims(&mut rt, PROVIDER1, vec![id1, id2]).unwrap();
+        assert_eq!(vec![ExitCode::OK, ExitCode::USR_NOT_FOUND], ret.batch_info.codes());
+        assert_eq!(vec![claim1.clone()], ret.claims);
+        h.check_state(&rt);
+
+        // The approval can't be used once expired.
+        rt.set_epoch(approval_expiration + 1);
+        let sectors = vec![SectorChanges {
+            sector: 9,
+            minimum_commitment_epoch: term_min + 500,
+            added: vec![make_transfer_piece(&claim2, id2, approval_expiration, b"sig2")],
+        }];
+        let ret = h.sector_content_changed(&mut rt, PROVIDER2, sectors, vec![], vec![]).unwrap();
+        assert!(!ret.sectors[0].added[0].accepted);
+
+        // Expired transferred claims are removed with the provider's expired claims.
+        rt.set_epoch(term_max);
+        let ret = h.remove_expired_claims(&mut rt, PROVIDER1, vec![]).unwrap();
+        assert_eq!(vec![id2], ret.considered);
+        let ret = h.get_transferred_claims(&mut rt, PROVIDER1, vec![id1]).unwrap();
+        assert_eq!(vec![ExitCode::USR_NOT_FOUND], ret.batch_info.codes());
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn transfer_claims_reports_prior_transfers() {
+        let (h, mut rt) = new_harness();
+        let term_min = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let claim1 = make_claim("1", CLIENT1, PROVIDER1, ALLOC_SIZE, term_min, term_min, 0, 5);
+        let claim2 = make_claim("2", CLIENT1, PROVIDER1, ALLOC_SIZE, term_min, term_min, 0, 5);
+        let claim3 = make_claim("3", CLIENT1, PROVIDER1, ALLOC_SIZE, term_min, term_min, 0, 6);
+        let id1 = h.create_claim(&mut rt, &claim1).unwrap();
+        let id2 = h.create_claim(&mut rt, &claim2).unwrap();
+        let id3 = h.create_claim(&mut rt, &claim3).unwrap();
+
+        let proposal = |claim_id| ClaimTransferProposal {
+            provider: PROVIDER1,
+            claim_id,
+            new_provider: PROVIDER2,
+            expiration: 200,
+        };
+        let transferred = |sector| TransferredClaim { sector, size: PaddedPieceSize(ALLOC_SIZE) };
+
+        // The first transfer from sector 5 follows none.
+        let sectors = vec![SectorChanges {
+            sector: 7,
+            minimum_commitment_epoch: term_min,
+            added: vec![make_transfer_piece(&claim1, id1, 200, b"sig1")],
+        }];
+        h.sector_content_changed(
+            &mut rt,
+            PROVIDER2,
+            sectors,
+            vec![(CLIENT1, proposal(id1), b"sig1".to_vec(), ExitCode::OK)],
+            vec![(PROVIDER1, vec![transferred(5)], vec![], ExitCode::OK)],
+        )
+        .unwrap();
+
+        // A later transfer from sector 5 reports the earlier one, but not for sector 6.
+        let sectors = vec![SectorChanges {
+            sector: 8,
+            minimum_commitment_epoch: term_min,
+            added: vec![
+                make_transfer_piece(&claim2, id2, 200, b"sig2"),
+                make_transfer_piece(&claim3, id3, 200, b"sig3"),
+            ],
+        }];
+        h.sector_content_changed(
+            &mut rt,
+            PROVIDER2,
+            sectors,
+            vec![
+                (CLIENT1, proposal(id2), b"sig2".to_vec(), ExitCode::OK),
+                (CLIENT1, proposal(id3), b"sig3".to_vec(), ExitCode::OK),
+            ],
+            vec![(
+                PROVIDER1,
+                vec![transferred(5), transferred(6)],
+                vec![transferred(5)],
+                ExitCode::OK,
+            )],
+        )
+        .unwrap();
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn transfer_claims_fails_if_previous_provider_fails() {
+        let (h, mut rt) = new_harness();
+        let term_min = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let claim = make_claim("1", CLIENT1, PROVIDER1, ALLOC_SIZE, term_min, term_min, 0, 5);
+        let id = h.create_claim(&mut rt, &claim).unwrap();
+
+        let sectors = vec![SectorChanges {
+            sector: 7,
+            minimum_commitment_epoch: term_min,
+            added: vec![make_transfer_piece(&claim, id, 200, b"sig")],
+        }];
+        let proposal = ClaimTransferProposal {
+            provider: PROVIDER1,
+            claim_id: id,
+            new_provider: PROVIDER2,
+            expiration: 200,
+        };
+        // The previous provider can't drop the claim's power, e.g. from a faulty sector.
+        let result = h.sector_content_changed(
+            &mut rt,
+            PROVIDER2,
+            sectors,
+            vec![(CLIENT1, proposal, b"sig".to_vec(), ExitCode::OK)],
+            vec![(
+                PROVIDER1,
+                vec![TransferredClaim { sector: 5, size: PaddedPieceSize(ALLOC_SIZE) }],
+                vec![],
+                ExitCode::USR_FORBIDDEN,
+            )],
+        );
+        expect_abort_contains_message(ExitCode::USR_FORBIDDEN, "failed to notify provider", result);
+    }
+
     #[test]
     fn extend_claims_basic() {
         let (h, mut rt) = new_harness();
@@ -968,6 +1475,80 @@ mod allocs_claims {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn remove_sector_claims() {
+        let (h, mut rt) = new_harness();
+        let term_min = MINIMUM_VERIFIED_ALLOCATION_TERM;
+        let claim = |data: &str, provider: ActorID, sector: u64| {
+            make_claim(data, CLIENT1, provider, ALLOC_SIZE, term_min, term_min, 0, sector)
+        };
+        // Two claims for the same data in the sector, and others for it elsewhere.
+        let id1 = h.create_claim(&mut rt, &claim("1", PROVIDER1, 1)).unwrap();
+        let id2 = h.create_claim(&mut rt, &claim("1", PROVIDER1, 1)).unwrap();
+        let id3 = h.create_claim(&mut rt, &claim("1", PROVIDER1, 2)).unwrap();
+        let id4 = h.create_claim(&mut rt, &claim("1", PROVIDER2, 1)).unwrap();
+        let id5 = h.create_claim(&mut rt, &claim("2", PROVIDER1, 1)).unwrap();
+
+        let piece = |data: &str, size: u64| DroppedPiece {
+            data: make_piece_cid(data.as_bytes()),
+            size: PaddedPieceSize(size),
+        };
+        let ret = h
+            .remove_sector_claims(
+                &mut rt,
+                PROVIDER1,
+                RemoveSectorClaimsParams {
+                    sector: 1,
+                    pieces: vec![
+                        piece("1", ALLOC_SIZE),
+                        piece("2", ALLOC_SIZE * 2),
+                        piece("3", ALLOC_SIZE),
+                    ],
+                },
+            )
+            .unwrap();
+        assert_eq!(1, ret.removed.len());
+        assert!(ret.removed[0] == id1 || ret.removed[0] == id2);
+        assert_eq!(
+            vec![ExitCode::OK, ExitCode::USR_NOT_FOUND, ExitCode::USR_NOT_FOUND],
+            ret.results.codes()
+        );
+        assert!(h.load_claim(&mut rt, PROVIDER1, ret.removed[0]).is_none());
+
+        // A piece removes only one of the claims for its data.
+        let ret = h
+            .remove_sector_claims(
+                &mut rt,
+                PROVIDER1,
+                RemoveSectorClaimsParams { sector: 1, pieces: vec![piece("1", ALLOC_SIZE)] },
+            )
+            .unwrap();
+        assert_eq!(vec![ExitCode::OK], ret.results.codes());
+        assert!(h.load_claim(&mut rt, PROVIDER1, id1).is_none());
+        assert!(h.load_claim(&mut rt, PROVIDER1, id2).is_none());
+        assert!(h.load_claim(&mut rt, PROVIDER1, id3).is_some());
+        assert!(h.load_claim(&mut rt, PROVIDER2, id4).is_some());
+        assert!(h.load_claim(&mut rt, PROVIDER1, id5).is_some());
+
+        // Only miners may remove their claims.
+        rt.set_caller(*ACCOUNT_ACTOR_CODE_ID, Address::new_id(PROVIDER1));
+        rt.expect_validate_caller_type(vec![Type::Miner]);
+        expect_abort_contains_message(
+            ExitCode::USR_FORBIDDEN,
+            "caller",
+            rt.call::<Actor>(
+                Method::RemoveSectorClaims as MethodNum,
+                IpldBlock::serialize_cbor(&RemoveSectorClaimsParams {
+                    sector: 2,
+                    pieces: vec![piece("1", ALLOC_SIZE)],
+                })
+                .unwrap(),
+            ),
+        );
+        rt.reset();
+        h.check_state(&rt);
+    }
+
     #[test]
     fn claims_restricted_correctly() {
         let (h, mut rt) = new_harness();
@@ -1059,7 +1640,7 @@ mod datacap {
     use fvm_shared::error::ExitCode;
     use fvm_shared::{ActorID, MethodNum};
 
-    use fil_actor_verifreg::{Actor as VerifregActor, Claim, Method, State};
+    use fil_actor_verifreg::{reason, Actor as VerifregActor, Claim, Method, ReplicaSet, State};
     use fil_actors_runtime::cbor::serialize;
     use fil_actors_runtime::runtime::policy_constants::{
         MAXIMUM_VERIFIED_ALLOCATION_EXPIRATION, MAXIMUM_VERIFIED_ALLOCATION_TERM,
@@ -1067,7 +1648,8 @@ mod datacap {
     };
     use fil_actors_runtime::test_utils::*;
     use fil_actors_runtime::{
-        BatchReturn, DATACAP_TOKEN_ACTOR_ADDR, EPOCHS_IN_YEAR, STORAGE_MARKET_ACTOR_ADDR,
+        BatchReturn, ErrorReason, DATACAP_TOKEN_ACTOR_ADDR, EPOCHS_IN_YEAR,
+        STORAGE_MARKET_ACTOR_ADDR,
     };
     use fvm_ipld_encoding::ipld_block::IpldBlock;
     use harness::*;
@@ -1117,6 +1699,126 @@ mod datacap {
         h.check_state(&rt);
     }
 
+    #[test]
+    fn receive_tokens_make_replicated_allocs() {
+        let (h, mut rt) = new_harness();
+        add_miner(&mut rt, PROVIDER1);
+        add_miner(&mut rt, PROVIDER2);
+
+        let reqs = vec![make_alloc_req(&rt, PROVIDER1, SIZE)];
+        let replicated = vec![
+            make_replicated_alloc_req(&rt, vec![PROVIDER1, PROVIDER2], 2, SIZE),
+            make_replicated_alloc_req(&rt, vec![], 1, SIZE * 2),
+        ];
+        let payload = make_replicated_receiver_hook_token_payload(
+            CLIENT1,
+            reqs.clone(),
+            vec![],
+            replicated.clone(),
+            SIZE * 5,
+        );
+        h.receive_tokens(&mut rt, payload, BatchReturn::ok(3), BATCH_EMPTY, vec![1, 2, 3, 4], 0)
+            .unwrap();
+
+        // Verify allocations in state.
+        assert_allocation(&rt, CLIENT1, 1, &alloc_from_req(CLIENT1, &reqs[0]));
+        let set = ReplicaSet {
+            providers: vec![PROVIDER1, PROVIDER2],
+            first: 2,
+            count: 2,
+            claimed_by: vec![],
+        };
+        let replica = alloc_from_replicated_req(CLIENT1, &replicated[0], set);
+        assert_allocation(&rt, CLIENT1, 2, &replica);
+        assert_allocation(&rt, CLIENT1, 3, &replica);
+        let set = ReplicaSet { providers: vec![], first: 4, count: 1, claimed_by: vec![] };
+        assert_allocation(
+            &rt,
+            CLIENT1,
+            4,
+            &alloc_from_replicated_req(CLIENT1, &replicated[1], set),
+        );
+        let st: State = rt.get_state();
+        assert_eq!(5, st.next_allocation_id);
+        h.check_state(&rt);
+    }
+
+    #[test]
+    fn receive_invalid_replicated_alloc_reqs() {
+        let (h, mut rt) = new_harness();
+        add_miner(&mut rt, PROVIDER1);
+        rt.set_address_actor_type(Address::new_id(PROVIDER2), *ACCOUNT_ACTOR_CODE_ID);
+
+        // No replicas
+        {
+            let reqs = vec![make_alloc_req(&rt, PROVIDER1, SIZE)];
+            let replicated = vec![make_replicated_alloc_req(&rt, vec![PROVIDER1], 0, SIZE)];
+            let payload = make_replicated_receiver_hook_token_payload(
+                CLIENT1,
+                reqs,
+                vec![],
+                replicated,
+                SIZE,
+            );
+            let ret = h.receive_tokens(&mut rt, payload, BATCH_EMPTY, BATCH_EMPTY, vec![], 0);
+            assert_eq!(
+                Some(ErrorReason { code: reason::ALLOCATION_NO_REPLICAS, detail: 1u64 }),
+                ret.as_ref().unwrap_err().reason()
+            );
+            expect_abort_contains_message(ExitCode::USR_ILLEGAL_ARGUMENT, "no replicas", ret);
+        }
+        // Provider set includes a non-miner
+        {
+            let replicated =
+                vec![make_replicated_alloc_req(&rt, vec![PROVIDER1, PROVIDER2], 1, SIZE)];
+            let payload = make_replicated_receiver_hook_token_payload(
+                CLIENT1,
+                vec![],
+                vec![],
+                replicated,
+                SIZE,
+            );
+            expect_abort_contains_message(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                "must be a miner actor",
+                h.receive_tokens(&mut rt, payload, BATCH_EMPTY, BATCH_EMPTY, vec![], 0),
+            );
+        }
+        // Total size doesn't account for replicas
+        {
+            let replicated = vec![make_replicated_alloc_req(&rt, vec![PROVIDER1], 2, SIZE)];
+            let payload = make_replicated_receiver_hook_token_payload(
+                CLIENT1,
+                vec![],
+                vec![],
+                replicated,
+                SIZE,
+            );
+            expect_abort_contains_message(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                "total allocation size",
+                h.receive_tokens(&mut rt, payload, BATCH_EMPTY, BATCH_EMPTY, vec![], 0),
+            );
+        }
+        // Replicas far beyond the tokens received are rejected before they're constructed
+        {
+            let replicated = vec![make_replicated_alloc_req(&rt, vec![PROVIDER1], u64::MAX, SIZE)];
+            let payload = make_replicated_receiver_hook_token_payload(
+                CLIENT1,
+                vec![],
+                vec![],
+                replicated,
+                SIZE,
+            );
+            expect_abort_contains_message(
+                ExitCode::USR_ILLEGAL_ARGUMENT,
+                "exceeds data cap amount received",
+                h.receive_tokens(&mut rt, payload, BATCH_EMPTY, BATCH_EMPTY, vec![], 0),
+            );
+        }
+        h.check_state(&rt);
+    }
+
     #[test]
     fn receive_tokens_extend_claims() {
         let (h, mut rt) = new_harness();
@@ -1291,10 +1993,15 @@ mod datacap {
         {
             let reqs = vec![make_alloc_req(&rt, PROVIDER1, SIZE - 1)];
             let payload = make_receiver_hook_token_payload(CLIENT1, reqs, vec![], SIZE - 1);
+            let ret = h.receive_tokens(&mut rt, payload, BATCH_EMPTY, BATCH_EMPTY, vec![], 0);
+            assert_eq!(
+                Some(ErrorReason { code: reason::ALLOCATION_TOO_SMALL, detail: 0u64 }),
+                ret.as_ref().unwrap_err().reason()
+            );
             expect_abort_contains_message(
                 ExitCode::USR_ILLEGAL_ARGUMENT,
                 "allocation size 1048575 below minimum 1048576",
-                h.receive_tokens(&mut rt, payload, BATCH_EMPTY, BATCH_EMPTY, vec![], 0),
+                ret,
             );
         }
         // Min term too short
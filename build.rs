@@ -1,9 +1,12 @@
 use fil_actor_bundler::Bundler;
 use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::{Policy, POLICY_FILE_ENV};
 use num_traits::cast::FromPrimitive;
 use std::error::Error;
+use std::ffi::OsStr;
+use std::fmt::Write;
 use std::io::{BufRead, BufReader};
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 use std::thread;
 
@@ -26,10 +29,14 @@ const ACTORS: &[(&Package, &ID)] = &[
     ("reward", "reward"),
     ("verifreg", "verifiedregistry"),
     ("datacap", "datacap"),
+    ("passkey", "passkeyaccount"),
 ];
 
 const NETWORK_ENV: &str = "BUILD_FIL_NETWORK";
 
+/// Comma-separated networks to bundle in addition to the one configured by [`NETWORK_ENV`].
+const EXTRA_NETWORKS_ENV: &str = "BUILD_FIL_NETWORKS";
+
 /// Returns the configured network name, checking both the environment and feature flags.
 fn network_name() -> String {
     let env_network = std::env::var_os(NETWORK_ENV);
@@ -65,46 +72,33 @@ fn network_name() -> String {
     }.to_owned()
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // Cargo executable location.
-    let cargo = std::env::var_os("CARGO").expect("no CARGO env var");
-    println!("cargo:warning=cargo: {:?}", &cargo);
+/// Returns the networks to bundle in addition to the configured one, if any.
+fn extra_network_names() -> Vec<String> {
+    std::env::var(EXTRA_NETWORKS_ENV)
+        .map(|nets| {
+            nets.split(',').map(str::trim).filter(|n| !n.is_empty()).map(String::from).collect()
+        })
+        .unwrap_or_default()
+}
 
-    let out_dir = std::env::var_os("OUT_DIR")
-        .as_ref()
-        .map(Path::new)
-        .map(|p| p.join("bundle"))
-        .expect("no OUT_DIR env var");
-    println!("cargo:warning=out_dir: {:?}", &out_dir);
+/// Builds the actors for a network, with the given policy file if any, and bundles them into
+/// `<out_dir>/<network>/bundle.car`, returning the bundle's path.
+fn build_bundle(
+    cargo: &OsStr,
+    manifest_path: &Path,
+    network_name: &str,
+    policy_file: Option<&Path>,
+    out_dir: &Path,
+) -> Result<PathBuf, Box<dyn Error>> {
+    // Each network gets its own target directory, as the actors are compiled differently.
+    let target_dir = out_dir.join(network_name);
 
     // Compute the package names.
     let packages =
         ACTORS.iter().map(|(pkg, _)| String::from("fil_actor_") + pkg).collect::<Vec<String>>();
 
-    let manifest_path =
-        Path::new(&std::env::var_os("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR unset"))
-            .join("Cargo.toml");
-    println!("cargo:warning=manifest_path={:?}", &manifest_path);
-
-    // Determine the network name.
-    let network_name = network_name();
-    println!("cargo:warning=network name: {}", network_name);
-
-    // Make sure we re-build if the network name changes.
-    println!("cargo:rerun-if-env-changed={}", NETWORK_ENV);
-
-    // Rerun if the source, dependencies, build options, build script _or_ actors have changed. We
-    // need to check if the actors have changed because otherwise, when building in a workspace, we
-    // won't re-run the build script and therefore won't re-compile them.
-    //
-    // This _isn't_ an issue when building as a dependency fetched from crates.io (because the crate
-    // is immutable).
-    for file in ["actors", "Cargo.toml", "Cargo.lock", "src", "build.rs"] {
-        println!("cargo:rerun-if-changed={}", file);
-    }
-
     // Cargo build command for all actors at once.
-    let mut cmd = Command::new(&cargo);
+    let mut cmd = Command::new(cargo);
     cmd.arg("build")
         .args(packages.iter().map(|pkg| "-p=".to_owned() + pkg))
         .arg("--target=wasm32-unknown-unknown")
@@ -117,11 +111,14 @@ fn main() -> Result<(), Box<dyn Error>> {
         .stderr(Stdio::piped())
         // We are supposed to only generate artifacts under OUT_DIR,
         // so set OUT_DIR as the target directory for this build.
-        .env("CARGO_TARGET_DIR", &out_dir)
+        .env("CARGO_TARGET_DIR", &target_dir)
         // As we are being called inside a build-script, this env variable is set. However, we set
         // our own `RUSTFLAGS` and thus, we need to remove this. Otherwise cargo favors this
         // env variable.
         .env_remove("CARGO_ENCODED_RUSTFLAGS");
+    if let Some(path) = policy_file {
+        cmd.env(POLICY_FILE_ENV, path);
+    }
 
     // Print out the command line we're about to run.
     println!("cargo:warning=cmd={:?}", &cmd);
@@ -149,10 +146,10 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let result = child.wait().expect("failed to wait for build to finish");
     if !result.success() {
-        return Err("actor build failed".into());
+        return Err(format!("actor build failed for network {}", network_name).into());
     }
 
-    let dst = Path::new(&out_dir).join("bundle.car");
+    let dst = target_dir.join("bundle.car");
     let mut bundler = Bundler::new(&dst);
     for (&(pkg, name), id) in ACTORS.iter().zip(1u32..) {
         assert_eq!(
@@ -160,9 +157,8 @@ fn main() -> Result<(), Box<dyn Error>> {
             Type::from_u32(id).expect("type not defined").name(),
             "actor types don't match actors included in the bundle"
         );
-        let bytecode_path = Path::new(&out_dir)
-            .join("wasm32-unknown-unknown/wasm")
-            .join(format!("fil_actor_{}.wasm", pkg));
+        let bytecode_path =
+            target_dir.join("wasm32-unknown-unknown/wasm").join(format!("fil_actor_{}.wasm", pkg));
 
         // This actor version doesn't force synthetic CIDs; it uses genuine
         // content-addressed CIDs.
@@ -173,11 +169,92 @@ fn main() -> Result<(), Box<dyn Error>> {
             .unwrap_or_else(|err| {
                 panic!("failed to add file {:?} to bundle for actor {}: {}", bytecode_path, id, err)
             });
-        println!("cargo:warning=added {} ({}) to bundle with CID {}", name, id, cid);
+        println!(
+            "cargo:warning=added {} ({}) to {} bundle with CID {}",
+            name, id, network_name, cid
+        );
     }
     bundler.finish().expect("failed to finish bundle");
 
     println!("cargo:warning=bundle={}", dst.display());
+    Ok(dst)
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    // Cargo executable location.
+    let cargo = std::env::var_os("CARGO").expect("no CARGO env var");
+    println!("cargo:warning=cargo: {:?}", &cargo);
+
+    let out_dir = std::env::var_os("OUT_DIR")
+        .as_ref()
+        .map(Path::new)
+        .map(|p| p.join("bundle"))
+        .expect("no OUT_DIR env var");
+    println!("cargo:warning=out_dir: {:?}", &out_dir);
+
+    let manifest_path =
+        Path::new(&std::env::var_os("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR unset"))
+            .join("Cargo.toml");
+    println!("cargo:warning=manifest_path={:?}", &manifest_path);
+
+    // Determine the network names. The primary network comes first.
+    let network_name = network_name();
+    println!("cargo:warning=network name: {}", network_name);
+    let mut networks = vec![network_name.clone()];
+    for net in extra_network_names() {
+        if !networks.contains(&net) {
+            networks.push(net);
+        }
+    }
+
+    // Make sure we re-build if the network names or policy change.
+    println!("cargo:rerun-if-env-changed={}", NETWORK_ENV);
+    println!("cargo:rerun-if-env-changed={}", EXTRA_NETWORKS_ENV);
+    println!("cargo:rerun-if-env-changed={}", POLICY_FILE_ENV);
+
+    // Check the policy file, if any, before building the actors with it.
+    let policy_file = std::env::var_os(POLICY_FILE_ENV).map(PathBuf::from);
+    if let Some(path) = &policy_file {
+        Policy::from_file(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        println!("cargo:rerun-if-changed={}", path.display());
+        println!("cargo:warning=policy file: {}", path.display());
+    }
+
+    // Rerun if the source, dependencies, build options, build script _or_ actors have changed. We
+    // need to check if the actors have changed because otherwise, when building in a workspace, we
+    // won't re-run the build script and therefore won't re-compile them.
+    //
+    // This _isn't_ an issue when building as a dependency fetched from crates.io (because the crate
+    // is immutable).
+    for file in ["actors", "Cargo.toml", "Cargo.lock", "src", "build.rs"] {
+        println!("cargo:rerun-if-changed={}", file);
+    }
+
+    let mut bundles = Vec::new();
+    for net in &networks {
+        let bundle = build_bundle(&cargo, &manifest_path, net, policy_file.as_deref(), &out_dir)?;
+        bundles.push((net, bundle));
+    }
+
+    // The primary network's bundle is embedded as BUNDLE_CAR, and every bundle
+    // (including the primary) is listed in BUNDLES.
+    std::fs::copy(&bundles[0].1, out_dir.join("bundle.car"))?;
+    let mut index = String::new();
+    writeln!(index, "/// The network the embedded [`BUNDLE_CAR`] was built for.")?;
+    writeln!(index, "pub const BUNDLE_NETWORK: &str = {:?};", network_name)?;
+    writeln!(index, "/// The bundles embedded in this build, keyed by network name.")?;
+    writeln!(
+        index,
+        "/// Additional networks are selected with the {} variable.",
+        EXTRA_NETWORKS_ENV
+    )?;
+    writeln!(index, "pub const BUNDLES: &[(&str, &[u8])] = &[")?;
+    for (net, bundle) in &bundles {
+        let path = bundle.to_str().expect("bundle path not utf8");
+        writeln!(index, "    ({:?}, include_bytes!({:?})),", net, path)?;
+    }
+    writeln!(index, "];")?;
+    std::fs::write(out_dir.join("bundles.rs"), index)?;
 
     Ok(())
 }
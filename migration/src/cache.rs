@@ -0,0 +1,63 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use cid::Cid;
+use fvm_shared::address::Address;
+
+/// A cache of migrated structures, shared between a pre-migration run and the final migration.
+///
+/// A pre-migration runs ahead of the upgrade epoch against an earlier state, populating
+/// the cache so that the final migration only needs to migrate what has changed since.
+/// Entries map a key describing an input structure to the root of its migrated form: either
+/// an actor's whole head, or a sub-structure of its state which may be shared with other actors.
+#[derive(Clone, Default, Debug)]
+pub struct MigrationCache {
+    entries: Arc<RwLock<HashMap<String, Cid>>>,
+}
+
+impl MigrationCache {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn get(&self, key: &str) -> Option<Cid> {
+        self.entries.read().unwrap().get(key).copied()
+    }
+
+    pub fn insert(&self, key: String, value: Cid) {
+        self.entries.write().unwrap().insert(key, value);
+    }
+
+    /// Returns the cached value for a key, or computes and caches it.
+    pub fn load<F>(&self, key: String, f: F) -> anyhow::Result<Cid>
+    where
+        F: FnOnce() -> anyhow::Result<Cid>,
+    {
+        if let Some(value) = self.get(&key) {
+            return Ok(value);
+        }
+        let value = f()?;
+        self.insert(key, value);
+        Ok(value)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Key for the migrated head of an actor, given its pre-migration head.
+pub fn actor_head_key(address: &Address, head: &Cid) -> String {
+    format!("{address}-head-{head}")
+}
+
+/// Key for the migrated form of a sub-structure of actor state, such as a HAMT or AMT, given
+/// its pre-migration root. The key doesn't name the actor, so identical sub-structures shared
+/// by many actors (empty collections, in particular) are migrated only once.
+pub fn sub_structure_key(kind: &str, root: &Cid) -> String {
+    format!("{kind}-{root}")
+}
@@ -0,0 +1,47 @@
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_datacap::State;
+use fil_actors_runtime::make_empty_map;
+use frc46_token::token::state::TokenState;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::HAMT_BIT_WIDTH;
+
+use crate::{ActorMigration, ActorMigrationInput, ActorMigrationOutput};
+
+/// Datacap state as laid out before expiring grants.
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct DatacapStateV0 {
+    pub governor: Address,
+    pub token: TokenState,
+}
+
+/// Migrates the datacap actor, adding an empty collection of grants.
+/// Tokens minted before the migration never lapse.
+pub struct DatacapMigrator {
+    pub new_code: Cid,
+}
+
+impl<BS: Blockstore> ActorMigration<BS> for DatacapMigrator {
+    fn migrate_state(
+        &self,
+        store: &BS,
+        input: ActorMigrationInput,
+    ) -> anyhow::Result<ActorMigrationOutput> {
+        let state: DatacapStateV0 = store
+            .get_cbor(&input.head)?
+            .ok_or_else(|| anyhow::anyhow!("datacap state {} not found", input.head))?;
+
+        let grants = make_empty_map::<_, ()>(store, HAMT_BIT_WIDTH).flush()?;
+        let new_state = State { governor: state.governor, token: state.token, grants };
+
+        let new_head = store.put_cbor(&new_state, Code::Blake2b256)?;
+        Ok(ActorMigrationOutput { new_code: self.new_code, new_head })
+    }
+
+    fn migrated_code_cid(&self) -> Cid {
+        self.new_code
+    }
+}
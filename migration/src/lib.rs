@@ -0,0 +1,83 @@
+//! State migrations between versions of the builtin actors.
+//!
+//! A migration rewrites every actor in a state tree from the code and state layout of one
+//! actors bundle to those of the next. Each actor type with changed state supplies an
+//! [`ActorMigration`], keyed by its old code CID. Types whose layout is unchanged
+//! only have their code CID updated, except for the system actor, whose registry of
+//! builtin actors is replaced with the new manifest. Miners have their info and pre-committed
+//! sectors rewritten into the layouts with beneficiary splits and pre-commit extensions.
+//! The verified registry has its allocations rewritten as unreplicated, and gains empty
+//! collections of transferred claims and verifier info. The datacap actor gains an empty
+//! collection of expiring grants.
+
+use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+
+mod cache;
+mod datacap;
+mod miner;
+mod state_migration;
+mod system;
+mod verifreg;
+
+pub use cache::*;
+pub use datacap::*;
+pub use miner::*;
+pub use state_migration::*;
+pub use system::*;
+pub use verifreg::*;
+
+/// The inputs to migrating a single actor.
+pub struct ActorMigrationInput {
+    /// ID address of the actor.
+    pub address: Address,
+    pub balance: TokenAmount,
+    /// Head of the actor's pre-migration state.
+    pub head: Cid,
+    /// The epoch of the last state transition before the migration.
+    pub prior_epoch: ChainEpoch,
+    /// Cache shared with any pre-migration runs.
+    pub cache: MigrationCache,
+}
+
+/// The result of migrating a single actor.
+pub struct ActorMigrationOutput {
+    pub new_code: Cid,
+    pub new_head: Cid,
+}
+
+/// Migrates the state of actors of one type.
+/// Implementations must be deterministic, and may be run concurrently for different actors.
+pub trait ActorMigration<BS: Blockstore>: Send + Sync {
+    /// Rewrites an actor's state into the new layout, writing any new blocks to the store.
+    fn migrate_state(
+        &self,
+        store: &BS,
+        input: ActorMigrationInput,
+    ) -> anyhow::Result<ActorMigrationOutput>;
+
+    /// The code CID of actors produced by this migration.
+    fn migrated_code_cid(&self) -> Cid;
+}
+
+/// Migrates an actor whose state layout is unchanged, updating only its code CID.
+pub struct CodeMigrator {
+    pub new_code: Cid,
+}
+
+impl<BS: Blockstore> ActorMigration<BS> for CodeMigrator {
+    fn migrate_state(
+        &self,
+        _store: &BS,
+        input: ActorMigrationInput,
+    ) -> anyhow::Result<ActorMigrationOutput> {
+        Ok(ActorMigrationOutput { new_code: self.new_code, new_head: input.head })
+    }
+
+    fn migrated_code_cid(&self) -> Cid {
+        self.new_code
+    }
+}
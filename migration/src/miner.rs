@@ -0,0 +1,129 @@
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_miner::{
+    BeneficiaryTerm, MinerInfo, PendingBeneficiaryChange, SectorPreCommitInfo,
+    SectorPreCommitOnChainInfo, State, WorkerKeyChange,
+};
+use fil_actors_runtime::{make_empty_map, make_map_with_root_and_bitwidth};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::{strict_bytes, BytesDe, CborStore};
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::{RegisteredPoStProof, SectorSize};
+use fvm_shared::HAMT_BIT_WIDTH;
+
+use crate::{sub_structure_key, ActorMigration, ActorMigrationInput, ActorMigrationOutput};
+
+/// Miner info as laid out before beneficiary splits.
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct MinerInfoV0 {
+    pub owner: Address,
+    pub worker: Address,
+    pub control_addresses: Vec<Address>,
+    pub pending_worker_key: Option<WorkerKeyChange>,
+    #[serde(with = "strict_bytes")]
+    pub peer_id: Vec<u8>,
+    pub multi_address: Vec<BytesDe>,
+    pub window_post_proof_type: RegisteredPoStProof,
+    pub sector_size: SectorSize,
+    pub window_post_partition_sectors: u64,
+    pub consensus_fault_elapsed: ChainEpoch,
+    pub pending_owner_address: Option<Address>,
+    pub beneficiary: Address,
+    pub beneficiary_term: BeneficiaryTerm,
+    pub pending_beneficiary_term: Option<PendingBeneficiaryChange>,
+}
+
+impl From<MinerInfoV0> for MinerInfo {
+    fn from(info: MinerInfoV0) -> Self {
+        MinerInfo {
+            owner: info.owner,
+            worker: info.worker,
+            control_addresses: info.control_addresses,
+            pending_worker_key: info.pending_worker_key,
+            peer_id: info.peer_id,
+            multi_address: info.multi_address,
+            window_post_proof_type: info.window_post_proof_type,
+            sector_size: info.sector_size,
+            window_post_partition_sectors: info.window_post_partition_sectors,
+            consensus_fault_elapsed: info.consensus_fault_elapsed,
+            pending_owner_address: info.pending_owner_address,
+            beneficiary: info.beneficiary,
+            beneficiary_term: info.beneficiary_term,
+            pending_beneficiary_term: info.pending_beneficiary_term,
+            beneficiary_split: None,
+            pending_beneficiary_split: None,
+        }
+    }
+}
+
+/// A pre-committed sector as laid out before pre-commit extensions.
+#[derive(Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct SectorPreCommitOnChainInfoV0 {
+    pub info: SectorPreCommitInfo,
+    pub pre_commit_deposit: TokenAmount,
+    pub pre_commit_epoch: ChainEpoch,
+}
+
+impl From<SectorPreCommitOnChainInfoV0> for SectorPreCommitOnChainInfo {
+    fn from(precommit: SectorPreCommitOnChainInfoV0) -> Self {
+        SectorPreCommitOnChainInfo {
+            info: precommit.info,
+            pre_commit_deposit: precommit.pre_commit_deposit,
+            pre_commit_epoch: precommit.pre_commit_epoch,
+            extension: 0,
+        }
+    }
+}
+
+/// Migrates miner actors, rewriting their info with no beneficiary split
+/// and their pre-committed sectors with no extension.
+pub struct MinerMigrator {
+    pub new_code: Cid,
+}
+
+impl<BS: Blockstore> ActorMigration<BS> for MinerMigrator {
+    fn migrate_state(
+        &self,
+        store: &BS,
+        input: ActorMigrationInput,
+    ) -> anyhow::Result<ActorMigrationOutput> {
+        let mut state: State = store
+            .get_cbor(&input.head)?
+            .ok_or_else(|| anyhow::anyhow!("miner state {} not found", input.head))?;
+        let info: MinerInfoV0 = store
+            .get_cbor(&state.info)?
+            .ok_or_else(|| anyhow::anyhow!("miner info {} not found", state.info))?;
+        state.info = store.put_cbor(&MinerInfo::from(info), Code::Blake2b256)?;
+
+        // Miners without pre-commitments share the root of the empty map.
+        state.pre_committed_sectors = input
+            .cache
+            .load(sub_structure_key("miner-precommits", &state.pre_committed_sectors), || {
+                migrate_precommits(store, &state.pre_committed_sectors)
+            })?;
+
+        let new_head = store.put_cbor(&state, Code::Blake2b256)?;
+        Ok(ActorMigrationOutput { new_code: self.new_code, new_head })
+    }
+
+    fn migrated_code_cid(&self) -> Cid {
+        self.new_code
+    }
+}
+
+fn migrate_precommits<BS: Blockstore>(store: &BS, root: &Cid) -> anyhow::Result<Cid> {
+    let old = make_map_with_root_and_bitwidth::<_, SectorPreCommitOnChainInfoV0>(
+        root,
+        store,
+        HAMT_BIT_WIDTH,
+    )?;
+    let mut new = make_empty_map::<_, SectorPreCommitOnChainInfo>(store, HAMT_BIT_WIDTH);
+    old.for_each(|key, precommit| {
+        new.set(key.clone(), precommit.clone().into())?;
+        Ok(())
+    })?;
+    Ok(new.flush()?)
+}
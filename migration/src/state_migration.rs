@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+
+use anyhow::{anyhow, bail};
+use bimap::BiBTreeMap;
+use cid::Cid;
+use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::{make_empty_map, make_map_with_root_and_bitwidth, MessageAccumulator};
+use fil_builtin_actors_state::check::{check_state_invariants, Actor, Tree};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::HAMT_BIT_WIDTH;
+use num_traits::Zero;
+
+use crate::{
+    actor_head_key, ActorMigration, ActorMigrationInput, CodeMigrator, DatacapMigrator,
+    MigrationCache, MinerMigrator, SystemMigrator, VerifregMigrator,
+};
+
+/// The result of migrating a state tree.
+pub struct MigrationResult {
+    /// Root of the migrated tree of actors.
+    pub actors_root: Cid,
+    /// Invariant violations found in the migrated state.
+    pub invariants: MessageAccumulator,
+}
+
+/// Migrates a state tree from one actors bundle to the next.
+pub struct StateMigration<BS: Blockstore> {
+    migrations: HashMap<Cid, Box<dyn ActorMigration<BS>>>,
+    new_manifest: BiBTreeMap<Cid, Type>,
+    policy: Policy,
+    hamt_bit_width: u32,
+}
+
+impl<BS: Blockstore + Send + Sync + Debug> StateMigration<BS> {
+    /// Creates a migration which updates the code CID of every actor type present in
+    /// both manifests, and points the system actor's registry at the new manifest.
+    /// Types with changed state layouts must be given a migrator with
+    /// [`StateMigration::add_migrator`].
+    pub fn new(old_manifest: &BiBTreeMap<Cid, Type>, new_manifest: BiBTreeMap<Cid, Type>) -> Self {
+        let mut migrations = HashMap::<Cid, Box<dyn ActorMigration<BS>>>::new();
+        for (old_code, typ) in old_manifest {
+            if let Some(new_code) = new_manifest.get_by_right(typ) {
+                let migrator: Box<dyn ActorMigration<BS>> = match typ {
+                    Type::System => Box::new(SystemMigrator::new(*new_code, &new_manifest)),
+                    Type::Miner => Box::new(MinerMigrator { new_code: *new_code }),
+                    Type::VerifiedRegistry => Box::new(VerifregMigrator { new_code: *new_code }),
+                    Type::DataCap => Box::new(DatacapMigrator { new_code: *new_code }),
+                    _ => Box::new(CodeMigrator { new_code: *new_code }),
+                };
+                migrations.insert(*old_code, migrator);
+            }
+        }
+        StateMigration {
+            migrations,
+            new_manifest,
+            policy: Policy::default(),
+            hamt_bit_width: HAMT_BIT_WIDTH,
+        }
+    }
+
+    /// Sets the migrator for actors with the given pre-migration code CID,
+    /// replacing any existing one.
+    pub fn add_migrator(&mut self, old_code: Cid, migrator: Box<dyn ActorMigration<BS>>) {
+        self.migrations.insert(old_code, migrator);
+    }
+
+    /// Sets the policy used to check the migrated state.
+    pub fn with_policy(self, policy: Policy) -> Self {
+        Self { policy, ..self }
+    }
+
+    /// Sets the bit width of the state tree's HAMT, which defaults to that of the chain.
+    pub fn with_hamt_bit_width(self, hamt_bit_width: u32) -> Self {
+        Self { hamt_bit_width, ..self }
+    }
+
+    /// Migrates every actor in the tree, spreading the work over `workers` threads,
+    /// then checks the invariants of the migrated state.
+    ///
+    /// Actors whose head is found in the cache (from a pre-migration run) are not migrated again.
+    pub fn migrate_state_tree(
+        &self,
+        store: &BS,
+        actors_root: &Cid,
+        prior_epoch: ChainEpoch,
+        cache: &MigrationCache,
+        workers: usize,
+    ) -> anyhow::Result<MigrationResult> {
+        let actors =
+            make_map_with_root_and_bitwidth::<_, Actor>(actors_root, store, self.hamt_bit_width)?;
+        let mut entries = Vec::new();
+        let mut total_balance = TokenAmount::zero();
+        actors.for_each(|key, actor| {
+            total_balance += &actor.balance;
+            entries.push((Address::from_bytes(key)?, actor.clone()));
+            Ok(())
+        })?;
+
+        let chunk_size = ((entries.len() + workers.max(1) - 1) / workers.max(1)).max(1);
+        let migrated = std::thread::scope(|scope| {
+            let handles: Vec<_> = entries
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|(address, actor)| {
+                                self.migrate_actor(store, address, actor, prior_epoch, cache)
+                            })
+                            .collect::<anyhow::Result<Vec<_>>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().map_err(|_| anyhow!("migration worker panicked"))?)
+                .collect::<anyhow::Result<Vec<_>>>()
+        })?;
+
+        let mut new_actors = make_empty_map::<_, Actor>(store, self.hamt_bit_width);
+        for (address, actor) in migrated.into_iter().flatten() {
+            new_actors.set(address.to_bytes().into(), actor)?;
+        }
+        let new_root = new_actors.flush()?;
+
+        let tree = Tree {
+            map: make_map_with_root_and_bitwidth(&new_root, store, self.hamt_bit_width)?,
+            store,
+        };
+        let invariants = check_state_invariants(
+            &self.new_manifest,
+            &self.policy,
+            tree,
+            &total_balance,
+            prior_epoch,
+        )?;
+
+        Ok(MigrationResult { actors_root: new_root, invariants })
+    }
+
+    fn migrate_actor(
+        &self,
+        store: &BS,
+        address: &Address,
+        actor: &Actor,
+        prior_epoch: ChainEpoch,
+        cache: &MigrationCache,
+    ) -> anyhow::Result<(Address, Actor)> {
+        let migrator = self
+            .migrations
+            .get(&actor.code)
+            .ok_or_else(|| anyhow!("no migration for actor {address} with code {}", actor.code))?;
+
+        let key = actor_head_key(address, &actor.head);
+        let (new_code, new_head) = match cache.get(&key) {
+            Some(new_head) => (migrator.migrated_code_cid(), new_head),
+            None => {
+                let output = migrator.migrate_state(
+                    store,
+                    ActorMigrationInput {
+                        address: *address,
+                        balance: actor.balance.clone(),
+                        head: actor.head,
+                        prior_epoch,
+                        cache: cache.clone(),
+                    },
+                )?;
+                // Only heads that changed are worth remembering.
+                if output.new_head != actor.head {
+                    cache.insert(key, output.new_head);
+                }
+                (output.new_code, output.new_head)
+            }
+        };
+
+        if !self.new_manifest.contains_left(&new_code) {
+            bail!("actor {address} migrated to code {new_code} which is not in the new manifest");
+        }
+        Ok((*address, Actor { code: new_code, head: new_head, ..actor.clone() }))
+    }
+}
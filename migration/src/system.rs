@@ -0,0 +1,49 @@
+use bimap::BiBTreeMap;
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_system::State;
+use fil_actors_runtime::runtime::builtins::Type;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore;
+
+use crate::{sub_structure_key, ActorMigration, ActorMigrationInput, ActorMigrationOutput};
+
+/// Migrates the system actor, replacing its registry of builtin actors with the new manifest.
+pub struct SystemMigrator {
+    pub new_code: Cid,
+    /// The new manifest's actor names and code CIDs, in order of actor type.
+    pub builtin_actors: Vec<(String, Cid)>,
+}
+
+impl SystemMigrator {
+    pub fn new(new_code: Cid, new_manifest: &BiBTreeMap<Cid, Type>) -> Self {
+        let mut entries: Vec<_> = new_manifest.iter().map(|(code, typ)| (*typ, *code)).collect();
+        entries.sort();
+        let builtin_actors =
+            entries.into_iter().map(|(typ, code)| (typ.name().to_string(), code)).collect();
+        SystemMigrator { new_code, builtin_actors }
+    }
+}
+
+impl<BS: Blockstore> ActorMigration<BS> for SystemMigrator {
+    fn migrate_state(
+        &self,
+        store: &BS,
+        input: ActorMigrationInput,
+    ) -> anyhow::Result<ActorMigrationOutput> {
+        let state: State = store
+            .get_cbor(&input.head)?
+            .ok_or_else(|| anyhow::anyhow!("system actor state {} not found", input.head))?;
+        let builtin_actors = input
+            .cache
+            .load(sub_structure_key("system-builtin-actors", &state.builtin_actors), || {
+                store.put_cbor(&self.builtin_actors, Code::Blake2b256)
+            })?;
+        let new_head = store.put_cbor(&State { builtin_actors }, Code::Blake2b256)?;
+        Ok(ActorMigrationOutput { new_code: self.new_code, new_head })
+    }
+
+    fn migrated_code_cid(&self) -> Cid {
+        self.new_code
+    }
+}
@@ -0,0 +1,114 @@
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_verifreg::{Allocation, State};
+use fil_actors_runtime::{make_empty_map, make_map_with_root_and_bitwidth, MapMap};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::{ActorID, HAMT_BIT_WIDTH};
+
+use crate::{sub_structure_key, ActorMigration, ActorMigrationInput, ActorMigrationOutput};
+
+/// Verified registry state as laid out before claim transfers and verifier delegation.
+#[derive(Serialize_tuple, Deserialize_tuple)]
+pub struct VerifregStateV0 {
+    pub root_key: Address,
+    pub verifiers: Cid,
+    pub remove_data_cap_proposal_ids: Cid,
+    pub allocations: Cid,
+    pub next_allocation_id: u64,
+    pub claims: Cid,
+}
+
+/// An allocation as laid out before replicated allocations.
+#[derive(Clone, Serialize_tuple, Deserialize_tuple)]
+pub struct AllocationV0 {
+    pub client: ActorID,
+    pub provider: ActorID,
+    pub data: Cid,
+    pub size: PaddedPieceSize,
+    pub term_min: ChainEpoch,
+    pub term_max: ChainEpoch,
+    pub expiration: ChainEpoch,
+}
+
+impl From<AllocationV0> for Allocation {
+    fn from(alloc: AllocationV0) -> Self {
+        Allocation {
+            client: alloc.client,
+            provider: alloc.provider,
+            data: alloc.data,
+            size: alloc.size,
+            term_min: alloc.term_min,
+            term_max: alloc.term_max,
+            expiration: alloc.expiration,
+            replicas: None,
+        }
+    }
+}
+
+/// Migrates the verified registry, rewriting its allocations as unreplicated and
+/// adding empty collections of transferred claims and verifier info.
+pub struct VerifregMigrator {
+    pub new_code: Cid,
+}
+
+impl<BS: Blockstore> ActorMigration<BS> for VerifregMigrator {
+    fn migrate_state(
+        &self,
+        store: &BS,
+        input: ActorMigrationInput,
+    ) -> anyhow::Result<ActorMigrationOutput> {
+        let state: VerifregStateV0 = store
+            .get_cbor(&input.head)?
+            .ok_or_else(|| anyhow::anyhow!("verifreg state {} not found", input.head))?;
+
+        let allocations = input
+            .cache
+            .load(sub_structure_key("verifreg-allocations", &state.allocations), || {
+                migrate_allocations(store, &state.allocations)
+            })?;
+
+        let empty_map = make_empty_map::<_, ()>(store, HAMT_BIT_WIDTH).flush()?;
+        let empty_mapmap =
+            MapMap::<_, (), ActorID, u64>::new(store, HAMT_BIT_WIDTH, HAMT_BIT_WIDTH).flush()?;
+        let new_state = State {
+            root_key: state.root_key,
+            verifiers: state.verifiers,
+            remove_data_cap_proposal_ids: state.remove_data_cap_proposal_ids,
+            allocations,
+            next_allocation_id: state.next_allocation_id,
+            claims: state.claims,
+            transferred_claims: empty_mapmap,
+            verifier_info: empty_map,
+        };
+
+        let new_head = store.put_cbor(&new_state, Code::Blake2b256)?;
+        Ok(ActorMigrationOutput { new_code: self.new_code, new_head })
+    }
+
+    fn migrated_code_cid(&self) -> Cid {
+        self.new_code
+    }
+}
+
+// Rewrites each client's map of allocations, keeping the outer map's keys.
+fn migrate_allocations<BS: Blockstore>(store: &BS, root: &Cid) -> anyhow::Result<Cid> {
+    let old = make_map_with_root_and_bitwidth::<_, Cid>(root, store, HAMT_BIT_WIDTH)?;
+    let mut new = make_empty_map::<_, Cid>(store, HAMT_BIT_WIDTH);
+    old.for_each(|client, inner_root| {
+        let old_inner =
+            make_map_with_root_and_bitwidth::<_, AllocationV0>(inner_root, store, HAMT_BIT_WIDTH)?;
+        let mut new_inner = make_empty_map::<_, Allocation>(store, HAMT_BIT_WIDTH);
+        old_inner.for_each(|id, alloc| {
+            new_inner.set(id.clone(), alloc.clone().into())?;
+            Ok(())
+        })?;
+        new.set(client.clone(), new_inner.flush()?)?;
+        Ok(())
+    })?;
+    Ok(new.flush()?)
+}
@@ -0,0 +1,54 @@
+use cid::multihash::Code;
+use fil_actor_datacap::State;
+use fil_actors_runtime::test_utils::*;
+use fil_builtin_actors_migration::{
+    ActorMigration, ActorMigrationInput, DatacapMigrator, DatacapStateV0, MigrationCache,
+};
+use frc46_token::token::state::TokenState;
+use fvm_ipld_blockstore::MemoryBlockstore;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use num_traits::Zero;
+
+#[test]
+fn migrates_datacap_with_no_grants() {
+    let store = MemoryBlockstore::new();
+    let governor = Address::new_id(6);
+    let empty = State::new(&store, governor).unwrap();
+
+    let holder = 101;
+    let amount = TokenAmount::from_whole(10);
+    let mut token = TokenState::new(&store).unwrap();
+    token.change_balance_by(&store, holder, &amount).unwrap();
+    token.supply = amount.clone();
+    let old = DatacapStateV0 { governor, token };
+    let head = store.put_cbor(&old, Code::Blake2b256).unwrap();
+
+    // The current layout can't decode the old state.
+    assert!(store.get_cbor::<State>(&head).is_err());
+
+    let migrator = DatacapMigrator { new_code: make_identity_cid(b"fil/next/datacap") };
+    let output = migrator
+        .migrate_state(
+            &store,
+            ActorMigrationInput {
+                address: Address::new_id(7),
+                balance: TokenAmount::zero(),
+                head,
+                prior_epoch: 0,
+                cache: MigrationCache::new(),
+            },
+        )
+        .unwrap();
+    assert_eq!(migrator.new_code, output.new_code);
+
+    let new_state: State = store.get_cbor(&output.new_head).unwrap().unwrap();
+    assert_eq!(governor, new_state.governor);
+    assert_eq!(amount, new_state.token.supply);
+    assert_eq!(amount, new_state.balance(&store, holder).unwrap());
+
+    // Tokens minted before the migration have no expiration.
+    assert_eq!(empty.grants, new_state.grants);
+    assert!(new_state.get_grants(&store, holder).unwrap().is_empty());
+}
@@ -0,0 +1,253 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+use bimap::BiBTreeMap;
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_account::State as AccountState;
+use fil_actor_init::State as InitState;
+use fil_actor_system::State as SystemState;
+use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{
+    make_empty_map, make_map_with_root_and_bitwidth, INIT_ACTOR_ADDR, SYSTEM_ACTOR_ADDR,
+};
+use fil_builtin_actors_migration::{
+    sub_structure_key, ActorMigration, ActorMigrationInput, ActorMigrationOutput, MigrationCache,
+    StateMigration,
+};
+use fil_builtin_actors_state::check::Actor;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::HAMT_BIT_WIDTH;
+
+/// A thread-safe in-memory blockstore, so that migrations can run in parallel.
+#[derive(Debug, Default)]
+struct SyncBlockstore(Mutex<HashMap<Cid, Vec<u8>>>);
+
+impl Blockstore for SyncBlockstore {
+    fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        Ok(self.0.lock().unwrap().get(k).cloned())
+    }
+
+    fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
+        self.0.lock().unwrap().insert(*k, block.to_vec());
+        Ok(())
+    }
+}
+
+fn old_manifest() -> BiBTreeMap<Cid, Type> {
+    ACTOR_TYPES.iter().map(|(code, typ)| (*code, *typ)).collect()
+}
+
+fn new_manifest() -> BiBTreeMap<Cid, Type> {
+    ACTOR_TYPES
+        .values()
+        .map(|typ| (make_identity_cid(format!("fil/next/{}", typ.name()).as_bytes()), *typ))
+        .collect()
+}
+
+fn new_code(typ: Type) -> Cid {
+    *new_manifest().get_by_right(&typ).unwrap()
+}
+
+// Builds a state tree of the system and init actors and two accounts, returning its root
+// and the account addresses.
+fn setup(store: &SyncBlockstore) -> (Cid, Vec<Address>) {
+    let mut actors = make_empty_map::<_, Actor>(store, HAMT_BIT_WIDTH);
+    let mut put = |address: Address, code: Cid, head: Cid, balance: u64| {
+        let actor = Actor {
+            code,
+            head,
+            call_seq_num: 0,
+            balance: TokenAmount::from_whole(balance),
+            address: None,
+        };
+        actors.set(address.to_bytes().into(), actor).unwrap();
+    };
+
+    let system = store.put_cbor(&SystemState::new(store).unwrap(), Code::Blake2b256).unwrap();
+    put(SYSTEM_ACTOR_ADDR, *SYSTEM_ACTOR_CODE_ID, system, 0);
+
+    let mut init = InitState::new(store, "migration-test".to_string()).unwrap();
+    let mut accounts = vec![];
+    for i in 1..=2 {
+        let key = new_bls_addr(i);
+        let (id, _) = init.map_addresses_to_id(store, &key, None).unwrap();
+        let head = store.put_cbor(&AccountState { address: key }, Code::Blake2b256).unwrap();
+        put(Address::new_id(id), *ACCOUNT_ACTOR_CODE_ID, head, 100);
+        accounts.push(Address::new_id(id));
+    }
+    let init = store.put_cbor(&init, Code::Blake2b256).unwrap();
+    put(INIT_ACTOR_ADDR, *INIT_ACTOR_CODE_ID, init, 0);
+
+    (actors.flush().unwrap(), accounts)
+}
+
+fn get_actor(store: &SyncBlockstore, root: &Cid, address: &Address) -> Actor {
+    let actors = make_map_with_root_and_bitwidth::<_, Actor>(root, store, HAMT_BIT_WIDTH).unwrap();
+    actors.get(&address.to_bytes()).unwrap().unwrap().clone()
+}
+
+// Rewrites each account's key, counting how many actors it migrated.
+struct AccountMigrator {
+    count: Arc<AtomicUsize>,
+}
+
+impl<BS: Blockstore> ActorMigration<BS> for AccountMigrator {
+    fn migrate_state(
+        &self,
+        store: &BS,
+        input: ActorMigrationInput,
+    ) -> anyhow::Result<ActorMigrationOutput> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        let new_state = AccountState { address: new_bls_addr(input.address.id()? as u8) };
+        Ok(ActorMigrationOutput {
+            new_code: new_code(Type::Account),
+            new_head: store.put_cbor(&new_state, Code::Blake2b256)?,
+        })
+    }
+
+    fn migrated_code_cid(&self) -> Cid {
+        new_code(Type::Account)
+    }
+}
+
+#[test]
+fn migrates_code_of_unchanged_actors() {
+    let store = SyncBlockstore::default();
+    let (root, accounts) = setup(&store);
+
+    let migration = StateMigration::new(&old_manifest(), new_manifest());
+    let result = migration.migrate_state_tree(&store, &root, 0, &MigrationCache::new(), 4).unwrap();
+    result.invariants.assert_empty();
+
+    for (address, typ) in [(INIT_ACTOR_ADDR, Type::Init), (accounts[0], Type::Account)] {
+        let before = get_actor(&store, &root, &address);
+        let after = get_actor(&store, &result.actors_root, &address);
+        assert_eq!(new_code(typ), after.code);
+        assert_eq!(before.head, after.head);
+        assert_eq!(before.balance, after.balance);
+    }
+}
+
+#[test]
+fn migrates_system_registry_to_new_manifest() {
+    let store = SyncBlockstore::default();
+    let (root, _) = setup(&store);
+
+    let migration = StateMigration::new(&old_manifest(), new_manifest());
+    let result = migration.migrate_state_tree(&store, &root, 0, &MigrationCache::new(), 1).unwrap();
+    result.invariants.assert_empty();
+
+    let system = get_actor(&store, &result.actors_root, &SYSTEM_ACTOR_ADDR);
+    assert_eq!(new_code(Type::System), system.code);
+    let state: SystemState = store.get_cbor(&system.head).unwrap().unwrap();
+    let mut expected: Vec<_> = ACTOR_TYPES.values().copied().collect();
+    expected.sort();
+    let expected: Vec<_> =
+        expected.into_iter().map(|typ| (typ.name().to_string(), new_code(typ))).collect();
+    assert_eq!(expected, state.get_builtin_actors(&store).unwrap());
+}
+
+#[test]
+fn migration_reuses_pre_migration_results() {
+    let store = SyncBlockstore::default();
+    let (root, accounts) = setup(&store);
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut migration = StateMigration::new(&old_manifest(), new_manifest());
+    migration
+        .add_migrator(*ACCOUNT_ACTOR_CODE_ID, Box::new(AccountMigrator { count: count.clone() }));
+
+    // A pre-migration populates the cache with both accounts, and the system actor's head
+    // and registry.
+    let cache = MigrationCache::new();
+    let pre = migration.migrate_state_tree(&store, &root, 0, &cache, 2).unwrap();
+    pre.invariants.assert_empty();
+    assert_eq!(2, count.load(Ordering::SeqCst));
+    assert_eq!(4, cache.len());
+
+    // The final migration only migrates the account whose state changed since.
+    let mut actors =
+        make_map_with_root_and_bitwidth::<_, Actor>(&root, &store, HAMT_BIT_WIDTH).unwrap();
+    let mut changed = get_actor(&store, &root, &accounts[1]);
+    changed.head =
+        store.put_cbor(&AccountState { address: new_bls_addr(42) }, Code::Blake2b256).unwrap();
+    actors.set(accounts[1].to_bytes().into(), changed).unwrap();
+    let root = actors.flush().unwrap();
+
+    let result = migration.migrate_state_tree(&store, &root, 10, &cache, 2).unwrap();
+    result.invariants.assert_empty();
+    assert_eq!(3, count.load(Ordering::SeqCst));
+    assert_eq!(pre.actors_root, result.actors_root);
+}
+
+#[test]
+fn fails_to_migrate_actor_without_migration() {
+    let store = SyncBlockstore::default();
+    let (root, _) = setup(&store);
+
+    let mut manifest = new_manifest();
+    manifest.remove_by_right(&Type::Account);
+    let migration = StateMigration::new(&old_manifest(), manifest);
+    let err =
+        migration.migrate_state_tree(&store, &root, 0, &MigrationCache::new(), 1).err().unwrap();
+    assert!(err.to_string().contains("no migration for actor"), "{err}");
+}
+
+// Caches each account's new state by its old state, rather than by the account's head.
+struct SharedStateMigrator {
+    count: Arc<AtomicUsize>,
+}
+
+impl<BS: Blockstore> ActorMigration<BS> for SharedStateMigrator {
+    fn migrate_state(
+        &self,
+        store: &BS,
+        input: ActorMigrationInput,
+    ) -> anyhow::Result<ActorMigrationOutput> {
+        let new_head = input.cache.load(sub_structure_key("account", &input.head), || {
+            self.count.fetch_add(1, Ordering::SeqCst);
+            let state: AccountState = store.get_cbor(&input.head)?.unwrap();
+            store.put_cbor(&AccountState { address: state.address }, Code::Sha2_256)
+        })?;
+        Ok(ActorMigrationOutput { new_code: new_code(Type::Account), new_head })
+    }
+
+    fn migrated_code_cid(&self) -> Cid {
+        new_code(Type::Account)
+    }
+}
+
+#[test]
+fn shared_sub_structures_are_migrated_once() {
+    let store = SyncBlockstore::default();
+    let (root, accounts) = setup(&store);
+
+    // Both accounts share the same state.
+    let mut actors =
+        make_map_with_root_and_bitwidth::<_, Actor>(&root, &store, HAMT_BIT_WIDTH).unwrap();
+    let mut second = get_actor(&store, &root, &accounts[1]);
+    second.head = get_actor(&store, &root, &accounts[0]).head;
+    actors.set(accounts[1].to_bytes().into(), second).unwrap();
+    let root = actors.flush().unwrap();
+
+    let count = Arc::new(AtomicUsize::new(0));
+    let mut migration = StateMigration::new(&old_manifest(), new_manifest());
+    migration.add_migrator(
+        *ACCOUNT_ACTOR_CODE_ID,
+        Box::new(SharedStateMigrator { count: count.clone() }),
+    );
+    let result = migration.migrate_state_tree(&store, &root, 0, &MigrationCache::new(), 1).unwrap();
+    result.invariants.assert_empty();
+    assert_eq!(1, count.load(Ordering::SeqCst));
+
+    let first = get_actor(&store, &result.actors_root, &accounts[0]);
+    let second = get_actor(&store, &result.actors_root, &accounts[1]);
+    assert_eq!(first.head, second.head);
+    assert_ne!(get_actor(&store, &root, &accounts[0]).head, first.head);
+}
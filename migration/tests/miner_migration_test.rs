@@ -0,0 +1,136 @@
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_miner::{BeneficiaryTerm, CompactCommD, MinerInfo, SectorPreCommitInfo, State};
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::{make_empty_map, u64_key};
+use fil_builtin_actors_migration::{
+    ActorMigration, ActorMigrationInput, MigrationCache, MinerInfoV0, MinerMigrator,
+    SectorPreCommitOnChainInfoV0,
+};
+use fvm_ipld_blockstore::MemoryBlockstore;
+use fvm_ipld_encoding::{BytesDe, CborStore};
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof};
+use fvm_shared::HAMT_BIT_WIDTH;
+use num_traits::Zero;
+
+fn old_info() -> MinerInfoV0 {
+    let proof = RegisteredPoStProof::StackedDRGWindow32GiBV1;
+    MinerInfoV0 {
+        owner: Address::new_id(100),
+        worker: Address::new_id(101),
+        control_addresses: vec![Address::new_id(102)],
+        pending_worker_key: None,
+        peer_id: b"peer".to_vec(),
+        multi_address: vec![BytesDe(b"addr".to_vec())],
+        window_post_proof_type: proof,
+        sector_size: proof.sector_size().unwrap(),
+        window_post_partition_sectors: proof.window_post_partitions_sector().unwrap(),
+        consensus_fault_elapsed: -1,
+        pending_owner_address: Some(Address::new_id(103)),
+        beneficiary: Address::new_id(104),
+        beneficiary_term: BeneficiaryTerm::new(
+            TokenAmount::from_whole(10),
+            TokenAmount::zero(),
+            1000,
+        ),
+        pending_beneficiary_term: None,
+    }
+}
+
+fn migrate(store: &MemoryBlockstore, head: Cid, cache: &MigrationCache) -> State {
+    let migrator = MinerMigrator { new_code: make_identity_cid(b"fil/next/storageminer") };
+    let output = migrator
+        .migrate_state(
+            store,
+            ActorMigrationInput {
+                address: Address::new_id(1000),
+                balance: TokenAmount::zero(),
+                head,
+                prior_epoch: 0,
+                cache: cache.clone(),
+            },
+        )
+        .unwrap();
+    assert_eq!(migrator.new_code, output.new_code);
+    store.get_cbor(&output.new_head).unwrap().unwrap()
+}
+
+#[test]
+fn migrates_miner_info_without_beneficiary_split() {
+    let store = MemoryBlockstore::new();
+    let info = store.put_cbor(&old_info(), Code::Blake2b256).unwrap();
+    let state = State::new(&Policy::default(), &store, info, 0, 0).unwrap();
+    let head = store.put_cbor(&state, Code::Blake2b256).unwrap();
+
+    // The current layout can't decode the old miner info.
+    assert!(store.get_cbor::<MinerInfo>(&info).is_err());
+
+    let new_state = migrate(&store, head, &MigrationCache::new());
+    let new_info = new_state.get_info(&store).unwrap();
+    let old = old_info();
+    assert_eq!(old.owner, new_info.owner);
+    assert_eq!(old.worker, new_info.worker);
+    assert_eq!(old.control_addresses, new_info.control_addresses);
+    assert_eq!(old.peer_id, new_info.peer_id);
+    assert_eq!(old.pending_owner_address, new_info.pending_owner_address);
+    assert_eq!(old.beneficiary, new_info.beneficiary);
+    assert_eq!(old.beneficiary_term, new_info.beneficiary_term);
+    assert!(new_info.beneficiary_split.is_none());
+    assert!(new_info.pending_beneficiary_split.is_none());
+
+    // Everything else in the miner's state is untouched.
+    assert_eq!(state.pre_committed_sectors, new_state.pre_committed_sectors);
+    assert_eq!(state.sectors, new_state.sectors);
+    assert_eq!(state.deadlines, new_state.deadlines);
+}
+
+#[test]
+fn migrates_precommits_without_extension() {
+    let store = MemoryBlockstore::new();
+    let info = store.put_cbor(&old_info(), Code::Blake2b256).unwrap();
+    let mut state = State::new(&Policy::default(), &store, info, 0, 0).unwrap();
+
+    let mut precommits = make_empty_map(&store, HAMT_BIT_WIDTH);
+    for sector_number in [1, 2] {
+        let precommit = SectorPreCommitOnChainInfoV0 {
+            info: SectorPreCommitInfo {
+                seal_proof: RegisteredSealProof::StackedDRG32GiBV1P1,
+                sector_number,
+                sealed_cid: make_sealed_cid(&sector_number.to_be_bytes()),
+                seal_rand_epoch: 10,
+                deal_ids: vec![],
+                expiration: 100_000,
+                unsealed_cid: CompactCommD::new(None),
+            },
+            pre_commit_deposit: TokenAmount::from_atto(sector_number),
+            pre_commit_epoch: 20,
+        };
+        precommits.set(u64_key(sector_number), precommit).unwrap();
+    }
+    state.pre_committed_sectors = precommits.flush().unwrap();
+    let head = store.put_cbor(&state, Code::Blake2b256).unwrap();
+
+    // The current layout can't decode the old pre-commitments.
+    assert!(state.get_precommitted_sector(&store, 1).is_err());
+
+    // A second miner with the same pre-commitments reuses the migrated map.
+    let cache = MigrationCache::new();
+    let new_state = migrate(&store, head, &cache);
+    assert_eq!(1, cache.len());
+    assert_eq!(
+        new_state.pre_committed_sectors,
+        migrate(&store, head, &cache).pre_committed_sectors
+    );
+    assert_eq!(1, cache.len());
+
+    for sector_number in [1, 2] {
+        let precommit = new_state.get_precommitted_sector(&store, sector_number).unwrap().unwrap();
+        assert_eq!(sector_number, precommit.info.sector_number);
+        assert_eq!(TokenAmount::from_atto(sector_number), precommit.pre_commit_deposit);
+        assert_eq!(20, precommit.pre_commit_epoch);
+        assert_eq!(0, precommit.extension);
+    }
+}
@@ -0,0 +1,107 @@
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actor_verifreg::{Allocation, State};
+use fil_actors_runtime::test_utils::*;
+use fil_actors_runtime::MapMap;
+use fil_builtin_actors_migration::{
+    ActorMigration, ActorMigrationInput, AllocationV0, MigrationCache, VerifregMigrator,
+    VerifregStateV0,
+};
+use fvm_ipld_blockstore::MemoryBlockstore;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::{ActorID, HAMT_BIT_WIDTH};
+use num_traits::Zero;
+
+fn migrate(store: &MemoryBlockstore, head: Cid) -> State {
+    let migrator = VerifregMigrator { new_code: make_identity_cid(b"fil/next/verifiedregistry") };
+    let output = migrator
+        .migrate_state(
+            store,
+            ActorMigrationInput {
+                address: Address::new_id(6),
+                balance: TokenAmount::zero(),
+                head,
+                prior_epoch: 0,
+                cache: MigrationCache::new(),
+            },
+        )
+        .unwrap();
+    assert_eq!(migrator.new_code, output.new_code);
+    store.get_cbor(&output.new_head).unwrap().unwrap()
+}
+
+#[test]
+fn migrates_verifreg_with_no_transferred_claims() {
+    let store = MemoryBlockstore::new();
+    let root_key = Address::new_id(80);
+    let empty = State::new(&store, root_key).unwrap();
+    let old = VerifregStateV0 {
+        root_key,
+        verifiers: make_identity_cid(b"verifiers"),
+        remove_data_cap_proposal_ids: make_identity_cid(b"proposal ids"),
+        allocations: empty.allocations,
+        next_allocation_id: 7,
+        claims: make_identity_cid(b"claims"),
+    };
+    let head = store.put_cbor(&old, Code::Blake2b256).unwrap();
+
+    // The current layout can't decode the old state.
+    assert!(store.get_cbor::<State>(&head).is_err());
+
+    let new_state = migrate(&store, head);
+    assert_eq!(old.root_key, new_state.root_key);
+    assert_eq!(old.verifiers, new_state.verifiers);
+    assert_eq!(old.remove_data_cap_proposal_ids, new_state.remove_data_cap_proposal_ids);
+    assert_eq!(old.allocations, new_state.allocations);
+    assert_eq!(old.next_allocation_id, new_state.next_allocation_id);
+    assert_eq!(old.claims, new_state.claims);
+
+    assert_eq!(empty.transferred_claims, new_state.transferred_claims);
+    let mut transferred = new_state.load_transferred_claims(&store).unwrap();
+    assert!(transferred.get(100, 1).unwrap().is_none());
+    assert_eq!(empty.verifier_info, new_state.verifier_info);
+}
+
+#[test]
+fn migrates_allocations_as_unreplicated() {
+    let store = MemoryBlockstore::new();
+    let root_key = Address::new_id(80);
+    let empty = State::new(&store, root_key).unwrap();
+
+    let client: ActorID = 101;
+    let old_alloc = AllocationV0 {
+        client,
+        provider: 301,
+        data: make_piece_cid(b"data"),
+        size: PaddedPieceSize(1 << 20),
+        term_min: 100,
+        term_max: 200,
+        expiration: 50,
+    };
+    let mut old_allocs =
+        MapMap::<_, AllocationV0, ActorID, u64>::new(&store, HAMT_BIT_WIDTH, HAMT_BIT_WIDTH);
+    old_allocs.put(client, 1, old_alloc.clone()).unwrap();
+    old_allocs.put(client, 2, AllocationV0 { provider: 302, ..old_alloc.clone() }).unwrap();
+    let old = VerifregStateV0 {
+        root_key,
+        verifiers: empty.verifiers,
+        remove_data_cap_proposal_ids: empty.remove_data_cap_proposal_ids,
+        allocations: old_allocs.flush().unwrap(),
+        next_allocation_id: 3,
+        claims: empty.claims,
+    };
+    let head = store.put_cbor(&old, Code::Blake2b256).unwrap();
+
+    let new_state = migrate(&store, head);
+    assert_ne!(old.allocations, new_state.allocations);
+    let mut allocs = new_state.load_allocs(&store).unwrap();
+    let expected = Allocation::from(old_alloc);
+    assert_eq!(None, expected.replicas);
+    assert_eq!(Some(&expected), allocs.get(client, 1).unwrap());
+    let expected = Allocation { provider: 302, ..expected };
+    assert_eq!(Some(&expected), allocs.get(client, 2).unwrap());
+    assert!(allocs.get(client, 3).unwrap().is_none());
+}
@@ -46,6 +46,61 @@ static NETWORKS: &[(&str, &[&str])] = &[
 ];
 const NETWORK_ENV: &str = "BUILD_FIL_NETWORK";
 
+#[path = "src/runtime/policy_file.rs"]
+mod policy_file;
+use policy_file::POLICY_FILE_ENV;
+
+use serde_json::{Map, Value};
+use std::fmt::Write;
+
+/// Generates the Rust expression for the policy described by a JSON policy file, keyed by
+/// `Policy` field name. Fields left out keep the values selected by the network's features, and
+/// unknown fields or values of the wrong type fail to compile.
+fn policy_expr(json: &str) -> String {
+    let params: Map<String, Value> = serde_json::from_str(json).expect("failed to parse policy");
+    let mut expr = String::from("Policy {\n");
+    for (name, value) in &params {
+        assert!(
+            name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+            "invalid policy parameter {:?}",
+            name
+        );
+        let value = match name.as_str() {
+            // Storage power is represented in decimal, and may be given as a string or an integer.
+            "minimum_verified_allocation_size" | "minimum_consensus_power" => {
+                let power = match value {
+                    Value::String(s) => s.parse::<u128>().ok(),
+                    _ => value.as_u64().map(u128::from),
+                };
+                let power =
+                    power.unwrap_or_else(|| panic!("{} must be a non-negative integer", name));
+                format!("StoragePower::from({}u128)", power)
+            }
+            "valid_post_proof_type" => proof_set("RegisteredPoStProof", name, value),
+            "valid_pre_commit_proof_type" => proof_set("RegisteredSealProof", name, value),
+            _ => integer(name, value),
+        };
+        writeln!(expr, "    {}: {},", name, value).unwrap();
+    }
+    expr.push_str("    ..Policy::from_features()\n}\n");
+    expr
+}
+
+fn integer(name: &str, value: &Value) -> String {
+    match (value.as_i64(), value.as_u64()) {
+        (Some(n), _) => n.to_string(),
+        (None, Some(n)) => n.to_string(),
+        _ => panic!("{} must be an integer", name),
+    }
+}
+
+fn proof_set(typ: &str, name: &str, value: &Value) -> String {
+    let proofs = value.as_array().unwrap_or_else(|| panic!("{} must be an array", name));
+    let proofs: Vec<String> =
+        proofs.iter().map(|p| format!("{}::from({}i64)", typ, integer(name, p))).collect();
+    format!("HashSet::from([{}])", proofs.join(", "))
+}
+
 fn main() {
     let network = std::env::var(NETWORK_ENV).ok();
     println!("cargo:rerun-if-env-changed={}", NETWORK_ENV);
@@ -55,4 +110,21 @@ fn main() {
     for feature in features {
         println!("cargo:rustc-cfg=feature=\"{}\"", feature);
     }
+
+    // The policy file is turned into Rust, and included by Policy::default().
+    println!("cargo:rerun-if-env-changed={}", POLICY_FILE_ENV);
+    if let Some(path) = std::env::var_os(POLICY_FILE_ENV) {
+        // Build scripts run in their package's directory, so relative paths would be ambiguous.
+        assert!(
+            std::path::Path::new(&path).is_absolute(),
+            "{} must be an absolute path",
+            POLICY_FILE_ENV
+        );
+        println!("cargo:rerun-if-changed={}", path.to_str().expect("policy path not utf8"));
+        let json = std::fs::read_to_string(&path).expect("failed to read policy file");
+        let out_dir = std::env::var_os("OUT_DIR").expect("no OUT_DIR env var");
+        std::fs::write(std::path::Path::new(&out_dir).join("policy.rs"), policy_expr(&json))
+            .expect("failed to write policy");
+        println!("cargo:rustc-cfg=policy_file");
+    }
 }
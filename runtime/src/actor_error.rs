@@ -1,7 +1,11 @@
 use fvm_ipld_encoding::de::DeserializeOwned;
 use fvm_ipld_encoding::ipld_block::IpldBlock;
+use fvm_ipld_encoding::tuple::*;
+use serde::de::IgnoredAny;
+use serde::Serialize;
 use std::fmt::Display;
 
+use fvm_shared::econ::TokenAmount;
 use fvm_shared::error::ExitCode;
 use thiserror::Error;
 
@@ -88,6 +92,30 @@ impl ActorError {
         &self.msg
     }
 
+    /// Returns the optional associated data.
+    pub fn data(&self) -> Option<&IpldBlock> {
+        self.data.as_ref()
+    }
+
+    /// Attaches a machine-readable reason for the error as its data, replacing any existing data.
+    /// The code is specific to the actor, and determines the type of the detail.
+    pub fn with_reason<D: Serialize>(mut self, code: u64, detail: D) -> Self {
+        if let Ok(data) = IpldBlock::serialize_cbor(&ErrorReason { code, detail }) {
+            self.data = data;
+        }
+        self
+    }
+
+    /// Decodes the reason attached to the error, if its data is a reason with the given detail type.
+    pub fn reason<D: DeserializeOwned>(&self) -> Option<ErrorReason<D>> {
+        self.data.as_ref()?.deserialize().ok()
+    }
+
+    /// Decodes the code of the reason attached to the error, if any.
+    pub fn reason_code(&self) -> Option<u64> {
+        self.reason::<IgnoredAny>().map(|r| r.code)
+    }
+
     /// Extracts the optional associated data without copying.
     pub fn take_data(&mut self) -> Option<IpldBlock> {
         std::mem::take(&mut self.data)
@@ -100,6 +128,24 @@ impl ActorError {
     }
 }
 
+/// A machine-readable cause of an actor error, attached as the error's CBOR-encoded data.
+///
+/// Encoded as a tuple of a code, specific to the actor returning the error, and a detail whose
+/// type is determined by the code, e.g. the sector numbers or amounts involved. Callers that don't
+/// know the detail's type can decode the code alone, as an `ErrorReason<IgnoredAny>`.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorReason<D> {
+    pub code: u64,
+    pub detail: D,
+}
+
+/// Detail of an error reason for a balance insufficient to cover a requirement.
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug, PartialEq, Eq)]
+pub struct InsufficientBalance {
+    pub required: TokenAmount,
+    pub available: TokenAmount,
+}
+
 /// Converts a raw encoding error into an ErrSerialization.
 impl From<fvm_ipld_encoding::Error> for ActorError {
     fn from(e: fvm_ipld_encoding::Error) -> Self {
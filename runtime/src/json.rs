@@ -0,0 +1,549 @@
+//! Human-readable JSON representations of actor method parameters and return values.
+//!
+//! Actor types are CBOR-encoded as tuples, which carry no field names. With the `json` feature,
+//! each actor crate implements [`JsonCodec`] for its parameter and return types with
+//! [`json_struct!`](crate::json_struct), and lists its methods with
+//! [`method_codecs!`](crate::method_codecs) so that tools can decode any message given the
+//! actor type and method number.
+//!
+//! The representation is stable across releases:
+//! - structs are objects keyed by field name, except those that are transparent in CBOR,
+//!   which are represented by their single field;
+//! - addresses are strings, e.g. `"f01234"`;
+//! - token amounts (in attoFIL) and big integers are decimal strings;
+//! - CIDs are `{"/": "<cid>"}`;
+//! - bitfields are lists of run lengths, alternating between unset and set bits and
+//!   starting with unset bits;
+//! - byte strings are hex.
+
+use std::ops::Range;
+use std::str::FromStr;
+
+use cid::Cid;
+use fvm_ipld_bitfield::iter::Ranges;
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_encoding::{BytesDe, RawBytes};
+use fvm_shared::address::Address;
+use fvm_shared::bigint::bigint_ser::{BigIntDe, BigIntSer};
+use fvm_shared::bigint::BigInt;
+use fvm_shared::crypto::signature::{Signature, SignatureType};
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+use fvm_shared::piece::PaddedPieceSize;
+use fvm_shared::randomness::Randomness;
+use fvm_shared::reward::ThisEpochRewardReturn;
+use fvm_shared::sector::{
+    PoStProof, RegisteredPoStProof, RegisteredSealProof, RegisteredUpdateProof, SealVerifyInfo,
+    SectorID, SectorSize,
+};
+use fvm_shared::smooth::FilterEstimate;
+use fvm_shared::MethodNum;
+use num_traits::FromPrimitive;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::{BatchReturn, FailCode};
+
+pub use serde;
+pub use serde_json::{Map, Value};
+
+/// An error converting a value from JSON or CBOR.
+#[derive(thiserror::Error, Debug)]
+#[error("{0}")]
+pub struct JsonError(pub String);
+
+impl JsonError {
+    pub fn new(msg: impl ToString) -> Self {
+        Self(msg.to_string())
+    }
+}
+
+/// A type with a JSON representation.
+pub trait JsonCodec: Sized {
+    fn to_json(&self) -> Value;
+    fn from_json(value: &Value) -> Result<Self, JsonError>;
+}
+
+/// Reads a field of a JSON object. Missing fields are read as null.
+pub fn field<T: JsonCodec>(value: &Value, name: &str) -> Result<T, JsonError> {
+    let obj = value.as_object().ok_or_else(|| JsonError::new("expected an object"))?;
+    T::from_json(obj.get(name).unwrap_or(&Value::Null))
+        .map_err(|e| JsonError(format!("{}: {}", name, e)))
+}
+
+fn as_str(value: &Value) -> Result<&str, JsonError> {
+    value.as_str().ok_or_else(|| JsonError(format!("expected a string, got {}", value)))
+}
+
+fn from_hex(value: &Value) -> Result<Vec<u8>, JsonError> {
+    let s = as_str(value)?;
+    hex::decode(s.strip_prefix("0x").unwrap_or(s)).map_err(JsonError::new)
+}
+
+/// Implements [`JsonCodec`] for types whose serde representation is already human-readable.
+macro_rules! json_via_serde {
+    ($($ty:ty),*) => {
+        $(
+            impl JsonCodec for $ty {
+                fn to_json(&self) -> Value {
+                    serde_json::to_value(self).expect("serializable")
+                }
+
+                fn from_json(value: &Value) -> Result<Self, JsonError> {
+                    serde_json::from_value(value.clone()).map_err(JsonError::new)
+                }
+            }
+        )*
+    };
+}
+
+json_via_serde!(
+    bool,
+    u32,
+    u64,
+    i64,
+    String,
+    RegisteredSealProof,
+    RegisteredPoStProof,
+    RegisteredUpdateProof,
+    SectorSize
+);
+
+impl JsonCodec for () {
+    fn to_json(&self) -> Value {
+        Value::Null
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match value {
+            Value::Null => Ok(()),
+            _ => Err(JsonError(format!("expected null, got {}", value))),
+        }
+    }
+}
+
+impl<T: JsonCodec> JsonCodec for Option<T> {
+    fn to_json(&self) -> Value {
+        self.as_ref().map_or(Value::Null, T::to_json)
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match value {
+            Value::Null => Ok(None),
+            v => T::from_json(v).map(Some),
+        }
+    }
+}
+
+impl<T: JsonCodec> JsonCodec for Vec<T> {
+    fn to_json(&self) -> Value {
+        Value::Array(self.iter().map(T::to_json).collect())
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let items = value.as_array().ok_or_else(|| JsonError::new("expected an array"))?;
+        items
+            .iter()
+            .enumerate()
+            .map(|(i, v)| T::from_json(v).map_err(|e| JsonError(format!("[{}]: {}", i, e))))
+            .collect()
+    }
+}
+
+impl<A: JsonCodec, B: JsonCodec> JsonCodec for (A, B) {
+    fn to_json(&self) -> Value {
+        Value::Array(vec![self.0.to_json(), self.1.to_json()])
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        match value.as_array().map(Vec::as_slice) {
+            Some([a, b]) => Ok((A::from_json(a)?, B::from_json(b)?)),
+            _ => Err(JsonError::new("expected a pair")),
+        }
+    }
+}
+
+impl JsonCodec for Vec<u8> {
+    fn to_json(&self) -> Value {
+        Value::String(hex::encode(self))
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        from_hex(value)
+    }
+}
+
+impl<const N: usize> JsonCodec for [u8; N] {
+    fn to_json(&self) -> Value {
+        Value::String(hex::encode(self))
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        from_hex(value)?
+            .try_into()
+            .map_err(|b: Vec<u8>| JsonError(format!("expected {} bytes, got {}", N, b.len())))
+    }
+}
+
+impl JsonCodec for RawBytes {
+    fn to_json(&self) -> Value {
+        Value::String(hex::encode(self.bytes()))
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        from_hex(value).map(RawBytes::new)
+    }
+}
+
+impl JsonCodec for BytesDe {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        from_hex(value).map(BytesDe)
+    }
+}
+
+impl JsonCodec for Randomness {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        from_hex(value).map(Randomness)
+    }
+}
+
+impl JsonCodec for Address {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Address::from_str(as_str(value)?).map_err(JsonError::new)
+    }
+}
+
+impl JsonCodec for BigInt {
+    fn to_json(&self) -> Value {
+        Value::String(self.to_string())
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        BigInt::from_str(as_str(value)?).map_err(JsonError::new)
+    }
+}
+
+impl JsonCodec for BigIntDe {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        BigInt::from_json(value).map(BigIntDe)
+    }
+}
+
+/// A big integer method parameter or return value.
+/// Unlike [`BigIntDe`], which only deserializes, this can also be serialized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BigIntParam(pub BigInt);
+
+impl Serialize for BigIntParam {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BigIntSer(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigIntParam {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        BigIntDe::deserialize(deserializer).map(|b| BigIntParam(b.0))
+    }
+}
+
+impl JsonCodec for BigIntParam {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        BigInt::from_json(value).map(BigIntParam)
+    }
+}
+
+impl JsonCodec for TokenAmount {
+    fn to_json(&self) -> Value {
+        self.atto().to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        BigInt::from_json(value).map(TokenAmount::from_atto)
+    }
+}
+
+impl JsonCodec for Cid {
+    fn to_json(&self) -> Value {
+        serde_json::json!({ "/": self.to_string() })
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Cid::from_str(as_str(&field::<Value>(value, "/")?)?).map_err(JsonError::new)
+    }
+}
+
+impl JsonCodec for Value {
+    fn to_json(&self) -> Value {
+        self.clone()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        Ok(value.clone())
+    }
+}
+
+impl JsonCodec for BitField {
+    fn to_json(&self) -> Value {
+        let mut runs = Vec::new();
+        let mut end = 0;
+        for range in self.ranges() {
+            runs.push(range.start - end);
+            runs.push(range.end - range.start);
+            end = range.end;
+        }
+        runs.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let runs = Vec::<u64>::from_json(value)?;
+        let mut ranges = Vec::<Range<u64>>::new();
+        let mut start = 0u64;
+        for (i, run) in runs.iter().enumerate() {
+            // Only the first run may be empty, as it's always of unset bits.
+            if i > 0 && *run == 0 {
+                return Err(JsonError::new("bitfield runs must be non-empty"));
+            }
+            let end =
+                start.checked_add(*run).ok_or_else(|| JsonError::new("bitfield out of range"))?;
+            if i % 2 == 1 {
+                ranges.push(start..end);
+            }
+            start = end;
+        }
+        Ok(BitField::from_ranges(Ranges::new(ranges)))
+    }
+}
+
+impl JsonCodec for PaddedPieceSize {
+    fn to_json(&self) -> Value {
+        self.0.to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        u64::from_json(value).map(PaddedPieceSize)
+    }
+}
+
+impl JsonCodec for ExitCode {
+    fn to_json(&self) -> Value {
+        self.value().to_json()
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        u32::from_json(value).map(ExitCode::new)
+    }
+}
+
+impl JsonCodec for Signature {
+    fn to_json(&self) -> Value {
+        serde_json::json!({ "type": self.sig_type as u8, "data": self.bytes.to_json() })
+    }
+
+    fn from_json(value: &Value) -> Result<Self, JsonError> {
+        let typ: u64 = field(value, "type")?;
+        let sig_type = u8::try_from(typ)
+            .ok()
+            .and_then(SignatureType::from_u8)
+            .ok_or_else(|| JsonError(format!("invalid signature type {}", typ)))?;
+        Ok(Signature { sig_type, bytes: field(value, "data")? })
+    }
+}
+
+crate::json_struct! {
+    PoStProof { post_proof, proof_bytes }
+    SectorID { miner, number }
+    SealVerifyInfo {
+        registered_proof,
+        sector_id,
+        deal_ids,
+        randomness,
+        interactive_randomness,
+        proof,
+        sealed_cid,
+        unsealed_cid,
+    }
+    FilterEstimate { position, velocity }
+    ThisEpochRewardReturn { this_epoch_reward_smoothed, this_epoch_baseline_power }
+    BatchReturn { success_count, fail_codes }
+    FailCode { idx, code }
+}
+
+/// Converts a type's CBOR encoding to and from JSON.
+#[derive(Clone, Copy)]
+pub struct TypeCodec {
+    pub decode: fn(&[u8]) -> Result<Value, JsonError>,
+    pub encode: fn(&Value) -> Result<Vec<u8>, JsonError>,
+}
+
+impl TypeCodec {
+    pub fn of<T: JsonCodec + Serialize + DeserializeOwned>() -> Self {
+        Self { decode: decode::<T>, encode: encode::<T> }
+    }
+}
+
+// Absent parameters or return values are represented by null.
+fn decode<T: JsonCodec + DeserializeOwned>(bytes: &[u8]) -> Result<Value, JsonError> {
+    if bytes.is_empty() {
+        return T::from_json(&Value::Null).map(|v| v.to_json());
+    }
+    let value: T = fvm_ipld_encoding::from_slice(bytes).map_err(JsonError::new)?;
+    Ok(value.to_json())
+}
+
+fn encode<T: JsonCodec + Serialize>(value: &Value) -> Result<Vec<u8>, JsonError> {
+    let decoded = T::from_json(value)?;
+    if value.is_null() {
+        return Ok(Vec::new());
+    }
+    fvm_ipld_encoding::to_vec(&decoded).map_err(JsonError::new)
+}
+
+/// The JSON codecs for a method's parameters and return value.
+#[derive(Clone, Copy)]
+pub struct MethodCodec {
+    pub method: MethodNum,
+    pub name: &'static str,
+    pub params: TypeCodec,
+    pub ret: TypeCodec,
+}
+
+/// Implements [`JsonCodec`] for structs, given their fields.
+///
+/// Every field must be listed, so adding a field to a struct without updating its
+/// JSON representation fails to compile.
+///
+/// ```ignore
+/// json_struct! {
+///     WithdrawBalanceParams { provider_or_client, amount }
+///     // Single-field structs that are transparent in CBOR are also transparent in JSON.
+///     transparent WithdrawBalanceReturn { amount_withdrawn }
+///     // Types from other crates are wrapped in a local newtype.
+///     remote TransferParamsJson(TransferParams) { to, amount, operator_data }
+/// }
+/// ```
+#[macro_export]
+macro_rules! json_struct {
+    () => {};
+    (transparent $ty:ident { $field:ident $(,)? } $($rest:tt)*) => {
+        impl $crate::json::JsonCodec for $ty {
+            fn to_json(&self) -> $crate::json::Value {
+                $crate::json::JsonCodec::to_json(&self.$field)
+            }
+
+            fn from_json(
+                value: &$crate::json::Value,
+            ) -> ::std::result::Result<Self, $crate::json::JsonError> {
+                Ok($ty { $field: $crate::json::JsonCodec::from_json(value)? })
+            }
+        }
+        $crate::json_struct!($($rest)*);
+    };
+    (remote $wrapper:ident($ty:ident) { $($field:ident),* $(,)? } $($rest:tt)*) => {
+        pub struct $wrapper(pub $ty);
+
+        impl $crate::json::serde::Serialize for $wrapper {
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::json::serde::Serializer,
+            {
+                $crate::json::serde::Serialize::serialize(&self.0, serializer)
+            }
+        }
+
+        impl<'de> $crate::json::serde::Deserialize<'de> for $wrapper {
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: $crate::json::serde::Deserializer<'de>,
+            {
+                <$ty as $crate::json::serde::Deserialize>::deserialize(deserializer).map($wrapper)
+            }
+        }
+
+        impl $crate::json::JsonCodec for $wrapper {
+            fn to_json(&self) -> $crate::json::Value {
+                let $ty { $($field),* } = &self.0;
+                #[allow(unused_mut)]
+                let mut map = $crate::json::Map::new();
+                $(map.insert(
+                    stringify!($field).to_owned(),
+                    $crate::json::JsonCodec::to_json($field),
+                );)*
+                $crate::json::Value::Object(map)
+            }
+
+            fn from_json(
+                value: &$crate::json::Value,
+            ) -> ::std::result::Result<Self, $crate::json::JsonError> {
+                Ok($wrapper($ty { $($field: $crate::json::field(value, stringify!($field))?),* }))
+            }
+        }
+        $crate::json_struct!($($rest)*);
+    };
+    ($ty:ident { $($field:ident),* $(,)? } $($rest:tt)*) => {
+        impl $crate::json::JsonCodec for $ty {
+            fn to_json(&self) -> $crate::json::Value {
+                let $ty { $($field),* } = self;
+                #[allow(unused_mut)]
+                let mut map = $crate::json::Map::new();
+                $(map.insert(
+                    stringify!($field).to_owned(),
+                    $crate::json::JsonCodec::to_json($field),
+                );)*
+                $crate::json::Value::Object(map)
+            }
+
+            fn from_json(
+                value: &$crate::json::Value,
+            ) -> ::std::result::Result<Self, $crate::json::JsonError> {
+                Ok($ty { $($field: $crate::json::field(value, stringify!($field))?),* })
+            }
+        }
+        $crate::json_struct!($($rest)*);
+    };
+}
+
+/// Lists the JSON codecs of an actor's methods, given the parameter and return types
+/// of each variant of the actor's `Method` enum. Methods without parameters or a
+/// return value use `()`.
+///
+/// ```ignore
+/// pub fn methods() -> Vec<MethodCodec> {
+///     method_codecs! {
+///         Constructor => (ConstructorParams, ()),
+///         PubkeyAddress => ((), Address),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! method_codecs {
+    ($($method:ident => ($params:ty, $ret:ty $(,)?)),* $(,)?) => {
+        vec![$(
+            $crate::json::MethodCodec {
+                method: Method::$method as u64,
+                name: stringify!($method),
+                params: $crate::json::TypeCodec::of::<$params>(),
+                ret: $crate::json::TypeCodec::of::<$ret>(),
+            }
+        ),*]
+    };
+}
@@ -32,6 +32,8 @@ pub mod util;
 
 mod dispatch;
 pub use dispatch::dispatch;
+#[cfg(feature = "json")]
+pub mod json;
 #[cfg(feature = "test_utils")]
 pub mod test_utils;
 
@@ -24,6 +24,7 @@ pub enum Type {
     EVM = 14,
     EAM = 15,
     EthAccount = 16,
+    PasskeyAccount = 17,
 }
 
 impl Type {
@@ -45,6 +46,7 @@ impl Type {
             Type::EVM => "evm",
             Type::EAM => "eam",
             Type::EthAccount => "ethaccount",
+            Type::PasskeyAccount => "passkeyaccount",
         }
     }
 }
@@ -25,6 +25,7 @@ use serde::Serialize;
 
 pub use self::actor_code::*;
 pub use self::policy::*;
+pub use self::policy_file::POLICY_FILE_ENV;
 pub use self::randomness::DomainSeparationTag;
 use crate::runtime::builtins::Type;
 use crate::{actor_error, ActorError, SendError};
@@ -32,6 +33,7 @@ use crate::{actor_error, ActorError, SendError};
 mod actor_code;
 pub mod builtins;
 pub mod policy;
+mod policy_file;
 mod randomness;
 
 #[cfg(feature = "fil-actor")]
@@ -2,7 +2,7 @@ use std::collections::HashSet;
 
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof, StoragePower};
-use num_traits::FromPrimitive;
+use num_traits::{FromPrimitive, Signed};
 use serde::{Deserialize, Serialize};
 
 // A trait for runtime policy configuration
@@ -11,7 +11,11 @@ pub trait RuntimePolicy {
 }
 
 // The policy itself
+//
+// A policy may be described in JSON, keyed by field name. Fields missing from a description
+// take the values selected by the network's cargo features.
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(default = "Policy::from_features", deny_unknown_fields)]
 pub struct Policy {
     /// Maximum amount of sectors that can be aggregated.
     pub max_aggregated_sectors: u64,
@@ -128,6 +132,10 @@ pub struct Policy {
     /// This is a conservative value that is chosen via simulations of all known attacks.
     pub chain_finality: ChainEpoch,
 
+    /// Maximum number of epochs before the current epoch from which EVM contracts may draw
+    /// ticket or beacon randomness.
+    pub max_randomness_lookback: ChainEpoch,
+
     /// Allowed post proof types for new miners
     pub valid_post_proof_type: HashSet<RegisteredPoStProof>,
 
@@ -136,6 +144,7 @@ pub struct Policy {
 
     // --- verifreg policy
     /// Minimum verified deal size
+    #[serde(with = "decimal")]
     pub minimum_verified_allocation_size: StoragePower,
     /// Minimum term for a verified data allocation (epochs)
     pub minimum_verified_allocation_term: i64,
@@ -144,6 +153,10 @@ pub struct Policy {
     /// Maximum time a verified allocation can be active without being claimed (epochs).
     /// Supports recovery of erroneous allocations and prevents indefinite squatting on datacap.
     pub maximum_verified_allocation_expiration: i64,
+    /// Period after a client cancels a verified allocation during which its provider may still
+    /// claim it (epochs). Must outlast any sector pre-committed before the cancellation,
+    /// so providers that have begun sealing the allocated data can still prove it.
+    pub verified_allocation_cancellation_grace: ChainEpoch,
     // Period of time at the end of a sector's life during which claims can be dropped
     pub end_of_life_claim_drop_period: ChainEpoch,
 
@@ -165,11 +178,121 @@ pub struct Policy {
 
     // --- power ---
     /// Minimum miner consensus power
+    #[serde(with = "decimal")]
     pub minimum_consensus_power: StoragePower,
 }
 
+/// An error loading a policy description.
+#[derive(thiserror::Error, Debug)]
+pub enum PolicyError {
+    #[cfg(feature = "json")]
+    #[error("failed to read policy: {0}")]
+    Io(#[from] std::io::Error),
+    #[cfg(feature = "json")]
+    #[error("failed to parse policy: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("inconsistent policy: {0}")]
+    Invalid(String),
+}
+
+// The policy the actors were built with: the policy file named by POLICY_FILE_ENV at build
+// time, if any, or else the policy selected by the network's cargo features. The build script
+// turns the policy file into Rust, so the actors don't parse it when they run.
+#[cfg(not(policy_file))]
+impl Default for Policy {
+    fn default() -> Policy {
+        Policy::from_features()
+    }
+}
+
+#[cfg(policy_file)]
 impl Default for Policy {
     fn default() -> Policy {
+        include!(concat!(env!("OUT_DIR"), "/policy.rs"))
+    }
+}
+
+impl Policy {
+    /// Parses and validates a JSON policy description.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Policy, PolicyError> {
+        let policy: Policy = serde_json::from_str(json)?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    /// Reads, parses and validates a JSON policy description file.
+    #[cfg(feature = "json")]
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Policy, PolicyError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Checks that the policy parameters are consistent with each other.
+    pub fn validate(&self) -> Result<(), PolicyError> {
+        fn check(cond: bool, msg: &str) -> Result<(), PolicyError> {
+            if cond {
+                Ok(())
+            } else {
+                Err(PolicyError::Invalid(msg.to_string()))
+            }
+        }
+
+        check(self.wpost_period_deadlines > 0, "wpost_period_deadlines must be positive")?;
+        check(self.wpost_challenge_window > 0, "wpost_challenge_window must be positive")?;
+        check(
+            self.wpost_challenge_window.checked_mul(self.wpost_period_deadlines as i64)
+                == Some(self.wpost_proving_period),
+            "wpost_challenge_window * wpost_period_deadlines must equal wpost_proving_period",
+        )?;
+        check(self.wpost_challenge_lookback >= 0, "wpost_challenge_lookback must not be negative")?;
+        check(
+            self.fault_declaration_cutoff >= self.wpost_challenge_lookback,
+            "fault_declaration_cutoff must not be less than wpost_challenge_lookback",
+        )?;
+        check(self.chain_finality > 0, "chain_finality must be positive")?;
+        check(self.max_randomness_lookback >= 0, "max_randomness_lookback must not be negative")?;
+        check(
+            self.max_partitions_per_deadline > 0,
+            "max_partitions_per_deadline must be positive",
+        )?;
+        check(
+            self.min_aggregated_sectors <= self.max_aggregated_sectors,
+            "min_aggregated_sectors must not exceed max_aggregated_sectors",
+        )?;
+        check(
+            self.min_sector_expiration <= self.max_sector_expiration_extension,
+            "min_sector_expiration must not exceed max_sector_expiration_extension",
+        )?;
+        check(self.deal_limit_denominator > 0, "deal_limit_denominator must be positive")?;
+        check(!self.valid_post_proof_type.is_empty(), "valid_post_proof_type must not be empty")?;
+        check(
+            !self.valid_pre_commit_proof_type.is_empty(),
+            "valid_pre_commit_proof_type must not be empty",
+        )?;
+        check(
+            self.minimum_verified_allocation_size.is_positive(),
+            "minimum_verified_allocation_size must be positive",
+        )?;
+        check(
+            self.minimum_verified_allocation_term <= self.maximum_verified_allocation_term,
+            "minimum_verified_allocation_term must not exceed maximum_verified_allocation_term",
+        )?;
+        check(
+            self.verified_allocation_cancellation_grace
+                >= self.max_pre_commit_randomness_lookback + self.pre_commit_challenge_delay,
+            "verified_allocation_cancellation_grace must cover the pre-commit randomness lookback \
+             and challenge delay",
+        )?;
+        check(self.deal_updates_interval > 0, "deal_updates_interval must be positive")?;
+        check(
+            self.prov_collateral_percent_supply_denom > 0,
+            "prov_collateral_percent_supply_denom must be positive",
+        )?;
+        Ok(())
+    }
+
+    /// The policy selected by the network's cargo features, ignoring any policy file.
+    pub fn from_features() -> Policy {
         Policy {
             max_aggregated_sectors: policy_constants::MAX_AGGREGATED_SECTORS,
             min_aggregated_sectors: policy_constants::MIN_AGGREGATED_SECTORS,
@@ -205,6 +328,7 @@ impl Default for Policy {
                 policy_constants::CONSENSUS_FAULT_INELIGIBILITY_DURATION,
             new_sectors_per_period_max: policy_constants::NEW_SECTORS_PER_PERIOD_MAX,
             chain_finality: policy_constants::CHAIN_FINALITY,
+            max_randomness_lookback: policy_constants::MAX_RANDOMNESS_LOOKBACK,
 
             valid_post_proof_type: HashSet::<RegisteredPoStProof>::from([
                 #[cfg(feature = "sector-2k")]
@@ -239,6 +363,8 @@ impl Default for Policy {
             maximum_verified_allocation_term: policy_constants::MAXIMUM_VERIFIED_ALLOCATION_TERM,
             maximum_verified_allocation_expiration:
                 policy_constants::MAXIMUM_VERIFIED_ALLOCATION_EXPIRATION,
+            verified_allocation_cancellation_grace:
+                policy_constants::VERIFIED_ALLOCATION_CANCELLATION_GRACE,
             end_of_life_claim_drop_period: policy_constants::END_OF_LIFE_CLAIM_DROP_PERIOD,
             deal_updates_interval: policy_constants::DEAL_UPDATES_INTERVAL,
             prov_collateral_percent_supply_num:
@@ -253,6 +379,30 @@ impl Default for Policy {
     }
 }
 
+// Storage power is represented in decimal, and may be given as a string or an integer.
+mod decimal {
+    use fvm_shared::sector::StoragePower;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &StoragePower, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<StoragePower, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Int(u64),
+            Str(String),
+        }
+        match Repr::deserialize(d)? {
+            Repr::Int(n) => Ok(StoragePower::from(n)),
+            Repr::Str(s) => s.parse().map_err(D::Error::custom),
+        }
+    }
+}
+
 pub mod policy_constants {
     use fvm_shared::clock::ChainEpoch;
     use fvm_shared::clock::EPOCH_DURATION_SECONDS;
@@ -379,6 +529,9 @@ pub mod policy_constants {
     /// This is a conservative value that is chosen via simulations of all known attacks.
     pub const CHAIN_FINALITY: ChainEpoch = 900;
 
+    /// Contracts may draw randomness as far back as a miner may for its pre-commit seal randomness.
+    pub const MAX_RANDOMNESS_LOOKBACK: ChainEpoch = MAX_PRE_COMMIT_RANDOMNESS_LOOKBACK;
+
     #[cfg(not(feature = "small-deals"))]
     pub const MINIMUM_VERIFIED_ALLOCATION_SIZE: i32 = 1 << 20;
     #[cfg(feature = "small-deals")]
@@ -386,6 +539,11 @@ pub mod policy_constants {
     pub const MINIMUM_VERIFIED_ALLOCATION_TERM: i64 = 180 * EPOCHS_IN_DAY;
     pub const MAXIMUM_VERIFIED_ALLOCATION_TERM: i64 = 5 * EPOCHS_IN_YEAR;
     pub const MAXIMUM_VERIFIED_ALLOCATION_EXPIRATION: i64 = 60 * EPOCHS_IN_DAY;
+    /// Covers the longest a sector pre-committed before a cancellation may remain provable.
+    /// Its seal randomness is no older than the lookback, and an extended pre-commit must be
+    /// proven within the maximum prove-commit duration of that randomness.
+    pub const VERIFIED_ALLOCATION_CANCELLATION_GRACE: ChainEpoch =
+        MAX_PRE_COMMIT_RANDOMNESS_LOOKBACK + 30 * EPOCHS_IN_DAY + PRE_COMMIT_CHALLENGE_DELAY;
     pub const END_OF_LIFE_CLAIM_DROP_PERIOD: ChainEpoch = 30 * EPOCHS_IN_DAY;
 
     /// DealUpdatesInterval is the number of blocks between payouts for deals
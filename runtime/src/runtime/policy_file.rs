@@ -0,0 +1,5 @@
+// Shared with the runtime's build script, which generates the policy named by this variable.
+
+/// The environment variable naming a policy file to build the actors with, in place of the
+/// policy selected by the network's cargo features.
+pub const POLICY_FILE_ENV: &str = "BUILD_FIL_POLICY";
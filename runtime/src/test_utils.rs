@@ -68,6 +68,8 @@ lazy_static::lazy_static! {
     pub static ref EVM_ACTOR_CODE_ID: Cid = make_identity_cid(b"fil/test/evm");
     pub static ref EAM_ACTOR_CODE_ID: Cid = make_identity_cid(b"fil/test/eam");
     pub static ref ETHACCOUNT_ACTOR_CODE_ID: Cid = make_identity_cid(b"fil/test/ethaccount");
+    pub static ref PASSKEYACCOUNT_ACTOR_CODE_ID: Cid =
+        make_identity_cid(b"fil/test/passkeyaccount");
 
     pub static ref ACTOR_TYPES: BTreeMap<Cid, Type> = {
         let mut map = BTreeMap::new();
@@ -87,6 +89,7 @@ lazy_static::lazy_static! {
         map.insert(*EVM_ACTOR_CODE_ID, Type::EVM);
         map.insert(*EAM_ACTOR_CODE_ID, Type::EAM);
         map.insert(*ETHACCOUNT_ACTOR_CODE_ID, Type::EthAccount);
+        map.insert(*PASSKEYACCOUNT_ACTOR_CODE_ID, Type::PasskeyAccount);
         map
     };
     pub static ref ACTOR_CODES: BTreeMap<Type, Cid> = [
@@ -106,6 +109,7 @@ lazy_static::lazy_static! {
         (Type::EVM, *EVM_ACTOR_CODE_ID),
         (Type::EAM, *EAM_ACTOR_CODE_ID),
         (Type::EthAccount, *ETHACCOUNT_ACTOR_CODE_ID),
+        (Type::PasskeyAccount, *PASSKEYACCOUNT_ACTOR_CODE_ID),
     ]
     .into_iter()
     .collect();
@@ -118,6 +122,7 @@ lazy_static::lazy_static! {
         map.insert(*PLACEHOLDER_ACTOR_CODE_ID, ());
         map.insert(*EVM_ACTOR_CODE_ID, ());
         map.insert(*ETHACCOUNT_ACTOR_CODE_ID, ());
+        map.insert(*PASSKEYACCOUNT_ACTOR_CODE_ID, ());
         map
     };
 }
@@ -134,6 +139,21 @@ pub fn init_logging() -> Result<(), log::SetLoggerError> {
     pretty_env_logger::try_init()
 }
 
+/// Environment variable naming a policy file for the mock runtime and test VM to load,
+/// in place of the default policy.
+pub const TEST_POLICY_ENV: &str = "FIL_TEST_POLICY";
+
+/// Returns the policy in the file named by [`TEST_POLICY_ENV`], or the default policy if unset.
+/// Panics if the file is invalid.
+pub fn test_policy() -> Policy {
+    match std::env::var_os(TEST_POLICY_ENV) {
+        Some(path) => {
+            Policy::from_file(&path).unwrap_or_else(|e| panic!("{}: {}", path.to_string_lossy(), e))
+        }
+        None => Policy::default(),
+    }
+}
+
 pub struct MockRuntime<BS = MemoryBlockstore> {
     pub epoch: ChainEpoch,
     pub miner: Address,
@@ -350,7 +370,7 @@ impl<BS> MockRuntime<BS> {
             store: Rc::new(store),
             in_transaction: Default::default(),
             expectations: Default::default(),
-            policy: Default::default(),
+            policy: test_policy(),
             circulating_supply: Default::default(),
             gas_limit: 10_000_000_000u64,
             gas_premium: Default::default(),
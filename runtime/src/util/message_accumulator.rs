@@ -1,26 +1,121 @@
 use itertools::Itertools;
+use std::collections::BTreeMap;
 use std::{cell::RefCell, fmt::Display, rc::Rc};
 
+use fvm_shared::address::Address;
 use regex::Regex;
+use serde::{Serialize, Serializer};
+
+use crate::runtime::builtins::Type;
+
+/// Code of violations added without one.
+const UNCLASSIFIED: &str = "unclassified";
+
+/// How serious an invariant violation is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Informational, e.g. an unusual but legal state.
+    Warning,
+    /// The state is inconsistent.
+    Error,
+}
+
+/// A single accumulated message, with the machine-readable context in which it was raised.
+///
+/// The code identifies the check that failed, e.g. `miner.deadline.partition_count`, and is
+/// stable across releases. Messages added without a code are `unclassified`. The message is the human-readable rendering, including any prefixes.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    pub code: String,
+    pub severity: Severity,
+    #[serde(serialize_with = "serialize_actor")]
+    pub actor: Option<Address>,
+    #[serde(serialize_with = "serialize_actor_type")]
+    pub actor_type: Option<Type>,
+    pub context: BTreeMap<String, String>,
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+fn serialize_actor<S: Serializer>(actor: &Option<Address>, s: S) -> Result<S::Ok, S::Error> {
+    actor.map(|a| a.to_string()).serialize(s)
+}
+
+fn serialize_actor_type<S: Serializer>(typ: &Option<Type>, s: S) -> Result<S::Ok, S::Error> {
+    typ.map(|t| t.name()).serialize(s)
+}
 
 /// Accumulates a sequence of messages (e.g. validation failures).
+///
+/// Derived accumulators (`with_prefix()`, `with_code()`, `with_context()`, etc.) add to the
+/// same underlying collection, tagging each new message with their context.
 #[derive(Debug, Default)]
 pub struct MessageAccumulator {
     /// Accumulated messages.
     /// This is a `Rc<RefCell>` to support accumulators derived from `with_prefix()` accumulating to
     /// the same underlying collection.
-    msgs: Rc<RefCell<Vec<String>>>,
+    msgs: Rc<RefCell<Vec<Violation>>>,
     /// Optional prefix to all new messages, e.g. describing higher level context.
     prefix: String,
+    /// Code of new messages, if not the default.
+    code: Option<String>,
+    /// Severity of new messages, if not the default of `Error`.
+    severity: Option<Severity>,
+    /// Actor to which new messages pertain.
+    actor: Option<Address>,
+    actor_type: Option<Type>,
+    /// Context attached to new messages.
+    context: BTreeMap<String, String>,
 }
 
 impl MessageAccumulator {
     /// Returns a new accumulator backed by the same collection, that will prefix each new message with
     /// a formatted string.
     pub fn with_prefix<S: AsRef<str>>(&self, prefix: S) -> Self {
+        MessageAccumulator { prefix: self.prefix.to_owned() + prefix.as_ref(), ..self.derive() }
+    }
+
+    /// Returns a new accumulator backed by the same collection, that will tag each new message
+    /// with a code identifying the check.
+    pub fn with_code<S: AsRef<str>>(&self, code: S) -> Self {
+        MessageAccumulator { code: Some(code.as_ref().to_owned()), ..self.derive() }
+    }
+
+    /// Returns a new accumulator backed by the same collection, that will add new messages
+    /// with the given severity.
+    pub fn with_severity(&self, severity: Severity) -> Self {
+        MessageAccumulator { severity: Some(severity), ..self.derive() }
+    }
+
+    /// Returns a new accumulator backed by the same collection, that will attribute new messages,
+    /// and messages added from other accumulators without an actor, to an actor.
+    pub fn with_actor(&self, actor: Address, actor_type: Option<Type>) -> Self {
+        MessageAccumulator { actor: Some(actor), actor_type, ..self.derive() }
+    }
+
+    /// Returns a new accumulator backed by the same collection, that will attach a key/value
+    /// pair to each new message.
+    pub fn with_context<K: AsRef<str>, V: Display>(&self, key: K, value: V) -> Self {
+        let mut context = self.context.clone();
+        context.insert(key.as_ref().to_owned(), value.to_string());
+        MessageAccumulator { context, ..self.derive() }
+    }
+
+    fn derive(&self) -> Self {
         MessageAccumulator {
             msgs: self.msgs.clone(),
-            prefix: self.prefix.to_owned() + prefix.as_ref(),
+            prefix: self.prefix.clone(),
+            code: self.code.clone(),
+            severity: self.severity,
+            actor: self.actor,
+            actor_type: self.actor_type,
+            context: self.context.clone(),
         }
     }
 
@@ -28,10 +123,21 @@ impl MessageAccumulator {
         self.msgs.borrow().is_empty()
     }
 
+    /// Returns the accumulated messages, rendered as strings.
     pub fn messages(&self) -> Vec<String> {
+        self.msgs.borrow().iter().map(|v| v.message.clone()).collect()
+    }
+
+    /// Returns the accumulated messages with their codes and context.
+    pub fn violations(&self) -> Vec<Violation> {
         self.msgs.borrow().to_owned()
     }
 
+    /// Returns the codes of the accumulated messages, in order.
+    pub fn codes(&self) -> Vec<String> {
+        self.msgs.borrow().iter().map(|v| v.code.clone()).collect()
+    }
+
     /// Returns the number of accumulated messages
     pub fn len(&self) -> usize {
         self.msgs.borrow().len()
@@ -39,12 +145,31 @@ impl MessageAccumulator {
 
     /// Adds a message to the accumulator
     pub fn add<S: AsRef<str>>(&self, msg: S) {
-        self.msgs.borrow_mut().push(format!("{}{}", self.prefix, msg.as_ref()));
+        self.msgs.borrow_mut().push(Violation {
+            code: self.code.clone().unwrap_or_else(|| UNCLASSIFIED.to_owned()),
+            severity: self.severity.unwrap_or(Severity::Error),
+            actor: self.actor,
+            actor_type: self.actor_type,
+            context: self.context.clone(),
+            message: format!("{}{}", self.prefix, msg.as_ref()),
+        });
     }
 
-    /// Adds messages from another accumulator to this one
+    /// Adds messages from another accumulator to this one.
+    /// Messages keep their rendering, but are attributed to this accumulator's actor if they
+    /// have none, and take any of its context they don't already have.
     pub fn add_all(&self, other: &Self) {
-        self.msgs.borrow_mut().extend_from_slice(&other.msgs.borrow());
+        let others = other.violations();
+        self.msgs.borrow_mut().extend(others.into_iter().map(|mut v| {
+            if v.actor.is_none() {
+                v.actor = self.actor;
+                v.actor_type = self.actor_type;
+            }
+            for (k, val) in &self.context {
+                v.context.entry(k.clone()).or_insert_with(|| val.clone());
+            }
+            v
+        }));
     }
 
     /// Adds a message if predicate is false
@@ -162,4 +287,69 @@ mod test {
         assert_eq!(2, acc3.len());
         assert_eq!(acc3.messages(), vec!["Cthulhu", "Azathoth"]);
     }
+
+    #[test]
+    fn tags_violations() {
+        let acc = MessageAccumulator::default();
+        acc.add("Nyarlathotep");
+
+        let ctx_acc = acc
+            .with_actor(Address::new_id(1000), Some(Type::Miner))
+            .with_context("sector", 7)
+            .with_prefix("sector 7: ");
+        ctx_acc.with_code("cult.size").require(false, "Cthulhu");
+        ctx_acc.with_code("cult.name").with_severity(Severity::Warning).add("Azathoth");
+
+        assert_eq!(acc.messages(), vec!["Nyarlathotep", "sector 7: Cthulhu", "sector 7: Azathoth"]);
+        assert_eq!(acc.codes(), vec![UNCLASSIFIED, "cult.size", "cult.name"]);
+
+        let violations = acc.violations();
+        assert_eq!(violations[0].actor, None);
+        assert_eq!(violations[0].severity, Severity::Error);
+        assert_eq!(violations[1].actor, Some(Address::new_id(1000)));
+        assert_eq!(violations[1].actor_type, Some(Type::Miner));
+        assert_eq!(violations[1].context.get("sector").map(String::as_str), Some("7"));
+        assert_eq!(violations[2].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn add_all_attributes_to_actor() {
+        let inner = MessageAccumulator::default();
+        inner.with_code("cult.size").with_context("sector", 1).add("Cthulhu");
+
+        let acc = MessageAccumulator::default();
+        acc.with_actor(Address::new_id(1000), Some(Type::Miner))
+            .with_context("sector", 2)
+            .with_context("deadline", 3)
+            .add_all(&inner);
+
+        let violations = acc.violations();
+        assert_eq!(violations[0].code, "cult.size");
+        assert_eq!(violations[0].message, "Cthulhu");
+        assert_eq!(violations[0].actor, Some(Address::new_id(1000)));
+        assert_eq!(violations[0].context.get("sector").map(String::as_str), Some("1"));
+        assert_eq!(violations[0].context.get("deadline").map(String::as_str), Some("3"));
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let acc = MessageAccumulator::default();
+        acc.with_actor(Address::new_id(1000), Some(Type::Miner))
+            .with_code("cult.size")
+            .with_context("sector", 7)
+            .add("Cthulhu");
+
+        let json = serde_json::to_value(acc.violations()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "code": "cult.size",
+                "severity": "error",
+                "actor": "f01000",
+                "actor_type": "storageminer",
+                "context": {"sector": "7"},
+                "message": "Cthulhu",
+            }])
+        );
+    }
 }
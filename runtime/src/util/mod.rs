@@ -6,7 +6,7 @@ pub use self::batch_return::BatchReturnGen;
 pub use self::batch_return::FailCode;
 pub use self::downcast::*;
 pub use self::mapmap::MapMap;
-pub use self::message_accumulator::MessageAccumulator;
+pub use self::message_accumulator::{MessageAccumulator, Severity, Violation};
 pub use self::multimap::*;
 pub use self::set::Set;
 pub use self::set_multimap::SetMultimap;
@@ -0,0 +1,29 @@
+use fil_actors_runtime::{actor_error, ErrorReason, InsufficientBalance};
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::error::ExitCode;
+
+#[test]
+fn reason_round_trip() {
+    let detail = InsufficientBalance {
+        required: TokenAmount::from_atto(10),
+        available: TokenAmount::from_atto(4),
+    };
+    let err = actor_error!(insufficient_funds, "not enough").with_reason(7, detail.clone());
+    assert_eq!(ExitCode::USR_INSUFFICIENT_FUNDS, err.exit_code());
+    assert_eq!(Some(7), err.reason_code());
+    assert_eq!(Some(ErrorReason { code: 7, detail }), err.reason());
+
+    // A reason survives wrapping the message.
+    let err = err.wrap("outer");
+    assert_eq!(Some(7), err.reason_code());
+    // The detail decodes only as the type it was attached with.
+    assert_eq!(None, err.reason::<u64>());
+}
+
+#[test]
+fn no_reason() {
+    let err = actor_error!(illegal_argument, "bad");
+    assert_eq!(None, err.data());
+    assert_eq!(None, err.reason_code());
+    assert_eq!(None, err.reason::<()>());
+}
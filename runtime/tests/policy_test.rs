@@ -0,0 +1,56 @@
+use fil_actors_runtime::runtime::Policy;
+#[cfg(feature = "json")]
+use fil_actors_runtime::runtime::PolicyError;
+#[cfg(feature = "json")]
+use fvm_shared::sector::StoragePower;
+
+#[test]
+fn default_policy_is_valid() {
+    Policy::default().validate().unwrap();
+    Policy::from_features().validate().unwrap();
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn round_trips_through_json() {
+    let policy = Policy::default();
+    let json = serde_json::to_string(&policy).unwrap();
+    assert_eq!(policy, Policy::from_json(&json).unwrap());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn missing_fields_take_defaults() {
+    let policy = Policy::from_json(
+        r#"{
+            "wpost_proving_period": 600,
+            "wpost_challenge_window": 50,
+            "wpost_period_deadlines": 12,
+            "minimum_verified_allocation_size": 2048
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(600, policy.wpost_proving_period);
+    assert_eq!(StoragePower::from(2048), policy.minimum_verified_allocation_size);
+    assert_eq!(Policy::from_features().chain_finality, policy.chain_finality);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn rejects_inconsistent_policy() {
+    let err = Policy::from_json(r#"{ "wpost_proving_period": 600 }"#).unwrap_err();
+    assert!(matches!(err, PolicyError::Invalid(_)), "{}", err);
+
+    let err = Policy::from_json(r#"{ "fault_declaration_cutoff": 0 }"#).unwrap_err();
+    assert!(matches!(err, PolicyError::Invalid(_)), "{}", err);
+
+    let err = Policy::from_json(r#"{ "verified_allocation_cancellation_grace": 0 }"#).unwrap_err();
+    assert!(matches!(err, PolicyError::Invalid(_)), "{}", err);
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn rejects_unknown_fields() {
+    let err = Policy::from_json(r#"{ "wpost_proving_periods": 600 }"#).unwrap_err();
+    assert!(matches!(err, PolicyError::Parse(_)), "{}", err);
+}
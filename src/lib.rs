@@ -21,3 +21,10 @@
 /// The Filecoin client must import the contents of CAR into the blockstore, but
 /// may opt to exclude the index data structure.
 pub const BUNDLE_CAR: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/bundle/bundle.car"));
+
+include!(concat!(env!("OUT_DIR"), "/bundle/bundles.rs"));
+
+/// Returns the embedded bundle for a network, if it was included in this build.
+pub fn bundle_for_network(network: &str) -> Option<&'static [u8]> {
+    BUNDLES.iter().find(|(net, _)| *net == network).map(|(_, car)| *car)
+}
@@ -1,7 +1,14 @@
-use clap::Parser;
 use std::io::Write;
+use std::path::Path;
+use std::process::ExitCode;
 
-use fil_builtin_actors_bundle::BUNDLE_CAR;
+use anyhow::{anyhow, Context};
+use cid::Cid;
+use clap::{Parser, Subcommand};
+use fvm_ipld_blockstore::{Blockstore, MemoryBlockstore};
+use fvm_ipld_encoding::CborStore;
+
+use fil_builtin_actors_bundle::{bundle_for_network, BUNDLES, BUNDLE_NETWORK};
 
 #[derive(Parser)]
 #[clap(name = env!("CARGO_PKG_NAME"))]
@@ -11,12 +18,125 @@ struct Cli {
     /// The output car path. Defaults to STDOUT.
     #[clap(short, long, required = false)]
     output: Option<String>,
+    /// The network whose bundle to use. Defaults to the network this binary was built for.
+    #[clap(short, long, global = true)]
+    network: Option<String>,
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Lists the networks with a bundle embedded in this binary.
+    Networks,
+    /// Prints the bundle's manifest, one actor name and code CID per line.
+    Manifest,
+    /// Writes the Wasm bytecode of a single actor.
+    Extract {
+        /// The actor name, as listed in the manifest.
+        actor: String,
+        /// The output Wasm path. Defaults to STDOUT.
+        #[clap(short, long)]
+        output: Option<String>,
+    },
+    /// Checks that a CAR file is identical to the embedded bundle, listing any actors that differ.
+    Verify {
+        /// Path to the CAR file to check.
+        car: String,
+    },
+    /// Writes every embedded bundle to a directory, as builtin-actors-<network>.car.
+    WriteAll {
+        /// The output directory.
+        dir: String,
+    },
 }
 
-fn main() -> Result<(), std::io::Error> {
+fn main() -> anyhow::Result<ExitCode> {
     let cli = Cli::parse();
-    match cli.output {
-        Some(path) => std::fs::write(path, BUNDLE_CAR),
-        None => std::io::stdout().write_all(BUNDLE_CAR),
+    let network = cli.network.as_deref().unwrap_or(BUNDLE_NETWORK);
+    let car = bundle_for_network(network)
+        .ok_or_else(|| anyhow!("no bundle for network {network} in this build"))?;
+
+    match cli.command {
+        None => write(cli.output.as_deref(), car)?,
+        Some(Command::Networks) => {
+            for (net, _) in BUNDLES {
+                println!("{net}");
+            }
+        }
+        Some(Command::Manifest) => {
+            let store = MemoryBlockstore::new();
+            for (name, code) in load_bundle(&store, car)? {
+                println!("{name} {code}");
+            }
+        }
+        Some(Command::Extract { actor, output }) => {
+            let store = MemoryBlockstore::new();
+            let (_, code) = load_bundle(&store, car)?
+                .into_iter()
+                .find(|(name, _)| *name == actor)
+                .ok_or_else(|| anyhow!("no actor {actor} in the {network} bundle"))?;
+            let wasm = store.get(&code)?.ok_or_else(|| anyhow!("bytecode {code} not found"))?;
+            write(output.as_deref(), &wasm)?;
+        }
+        Some(Command::Verify { car: path }) => {
+            let given = std::fs::read(&path).with_context(|| format!("failed to read {path}"))?;
+            if given == car {
+                println!("{path} matches the {network} bundle");
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            let (expected_store, given_store) = (MemoryBlockstore::new(), MemoryBlockstore::new());
+            let expected = load_bundle(&expected_store, car)?;
+            let given = load_bundle(&given_store, &given)?;
+            for (name, code) in &expected {
+                match given.iter().find(|(n, _)| n == name) {
+                    Some((_, c)) if c == code => {}
+                    Some((_, c)) => println!("{name}: expected {code}, found {c}"),
+                    None => println!("{name}: missing"),
+                }
+            }
+            for (name, code) in &given {
+                if !expected.iter().any(|(n, _)| n == name) {
+                    println!("{name}: unexpected actor with code {code}");
+                }
+            }
+            eprintln!("{path} does not match the {network} bundle");
+            return Ok(ExitCode::FAILURE);
+        }
+        Some(Command::WriteAll { dir }) => {
+            std::fs::create_dir_all(&dir)?;
+            for (net, car) in BUNDLES {
+                let path = Path::new(&dir).join(format!("builtin-actors-{net}.car"));
+                std::fs::write(&path, car)
+                    .with_context(|| format!("failed to write {}", path.display()))?;
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Writes bytes to a file, or to STDOUT when no path is given.
+fn write(path: Option<&str>, bytes: &[u8]) -> anyhow::Result<()> {
+    match path {
+        Some(path) => std::fs::write(path, bytes),
+        None => std::io::stdout().write_all(bytes),
+    }
+    .map_err(Into::into)
+}
+
+/// Loads a bundle into the store, returning its manifest of actor names and code CIDs.
+///
+/// The CAR's root is a versioned manifest envelope, whose data is a list of
+/// (name, code CID) pairs. Each code CID is the key of the actor's Wasm bytecode.
+fn load_bundle<BS: Blockstore>(store: &BS, car: &[u8]) -> anyhow::Result<Vec<(String, Cid)>> {
+    let roots = futures::executor::block_on(fvm_ipld_car::load_car_unchecked(store, car))
+        .map_err(|e| anyhow!("failed to load CAR: {e}"))?;
+    let root = roots.first().ok_or_else(|| anyhow!("CAR has no roots"))?;
+    let (version, data): (u32, Cid) =
+        store.get_cbor(root)?.ok_or_else(|| anyhow!("manifest {root} not found"))?;
+    if version != 1 {
+        return Err(anyhow!("unsupported manifest version {version}"));
     }
+    store.get_cbor(&data)?.ok_or_else(|| anyhow!("manifest data {data} not found"))
 }
@@ -0,0 +1,58 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::str::FromStr;
+
+use anyhow::Context;
+use cid::Cid;
+use clap::Parser;
+use fil_builtin_actors_state::blockstore::DiskBlockstore;
+use fil_builtin_actors_state::diff::diff_state_trees;
+use fil_builtin_actors_state::snapshot::{
+    load_car, load_manifest, load_policy, load_state_tree, resolve_state_root,
+};
+
+#[derive(Parser)]
+#[clap(name = "diff-state")]
+#[clap(version = env!("CARGO_PKG_VERSION"))]
+#[clap(about = "Prints the actor-level differences between two state trees as JSON.", long_about = None)]
+struct Cli {
+    /// Path to a CAR file holding blocks of either state. May be repeated.
+    #[clap(long, required = true)]
+    car: Vec<String>,
+    /// The earlier state root, or a block header whose parent state to use.
+    before: String,
+    /// The later state root, or a block header whose parent state to use.
+    after: String,
+    /// Path to a JSON policy file for the states' network. Defaults to the built-in policy.
+    #[clap(long)]
+    policy: Option<String>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    // Snapshots hold far more state than fits in memory, so blocks are kept on disk.
+    let store = DiskBlockstore::temporary()?;
+    for path in &cli.car {
+        let file = File::open(path).with_context(|| format!("failed to open {path}"))?;
+        load_car(&store, BufReader::new(file))?;
+    }
+
+    let (before_root, _) = resolve_state_root(&store, &Cid::from_str(&cli.before)?)?;
+    let (after_root, _) = resolve_state_root(&store, &Cid::from_str(&cli.after)?)?;
+    let before = load_state_tree(&store, &before_root)
+        .with_context(|| format!("failed to load state {before_root}"))?;
+    let after = load_state_tree(&store, &after_root)
+        .with_context(|| format!("failed to load state {after_root}"))?;
+
+    let policy = load_policy(cli.policy.as_deref())?;
+    let diff = diff_state_trees(
+        &load_manifest(&before)?,
+        &load_manifest(&after)?,
+        &policy,
+        &before,
+        &after,
+    )?;
+    println!("{}", serde_json::to_string_pretty(&diff)?);
+    Ok(())
+}
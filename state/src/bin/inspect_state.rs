@@ -0,0 +1,139 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::process::ExitCode;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use cid::Cid;
+use clap::{Parser, Subcommand};
+use fil_actor_init::State as InitState;
+use fil_actors_runtime::INIT_ACTOR_ADDR;
+use fil_builtin_actors_state::blockstore::DiskBlockstore;
+use fil_builtin_actors_state::check::{check_state_invariants, Tree};
+use fil_builtin_actors_state::dump::{dump_actor, summarize_actor};
+use fil_builtin_actors_state::snapshot::{
+    load_car, load_manifest, load_policy, load_state_tree, resolve_state_root,
+};
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::{Address, Protocol};
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+
+/// Total FIL in existence, which the balances of all actors must sum to.
+const TOTAL_FILECOIN: i64 = 2_000_000_000;
+
+#[derive(Parser)]
+#[clap(name = "inspect-state")]
+#[clap(version = env!("CARGO_PKG_VERSION"))]
+#[clap(about = "Inspects a state tree loaded from a CAR snapshot.", long_about = None)]
+struct Cli {
+    /// Path to the CAR file holding the snapshot.
+    #[clap(long)]
+    car: String,
+    /// State root to inspect. Defaults to the parent state of the snapshot's first root.
+    #[clap(long)]
+    state_root: Option<String>,
+    /// The epoch preceding the state. Required when it can't be taken from a block header.
+    #[clap(long)]
+    epoch: Option<ChainEpoch>,
+    /// Path to a JSON policy file for the state's network. Defaults to the built-in policy.
+    #[clap(long)]
+    policy: Option<String>,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Checks the invariants of every actor and across actors.
+    Check {
+        /// Print violations as a JSON list, with their codes and context.
+        #[clap(long)]
+        json: bool,
+    },
+    /// Prints an actor's decoded state as JSON.
+    Dump { address: String },
+    /// Prints the state summaries produced by the actors' invariant checks.
+    Summary {
+        /// Only summarize this actor.
+        address: Option<String>,
+    },
+}
+
+fn main() -> anyhow::Result<ExitCode> {
+    let cli = Cli::parse();
+
+    // Snapshots hold far more state than fits in memory, so blocks are kept on disk.
+    let store = DiskBlockstore::temporary()?;
+    let file = File::open(&cli.car).with_context(|| format!("failed to open {}", cli.car))?;
+    let roots = load_car(&store, BufReader::new(file))?;
+    let root = match &cli.state_root {
+        Some(root) => Cid::from_str(root)?,
+        None => *roots.first().ok_or_else(|| anyhow!("CAR has no roots"))?,
+    };
+    let (state_root, header_epoch) = resolve_state_root(&store, &root)?;
+    let prior_epoch = cli
+        .epoch
+        .or(header_epoch)
+        .ok_or_else(|| anyhow!("--epoch is required for state root {state_root}"))?;
+
+    let policy = load_policy(cli.policy.as_deref())?;
+    let tree = load_state_tree(&store, &state_root)?;
+    let manifest = load_manifest(&tree)?;
+
+    match cli.command {
+        Command::Check { json } => {
+            let expected = TokenAmount::from_whole(TOTAL_FILECOIN);
+            let acc = check_state_invariants(&manifest, &policy, tree, &expected, prior_epoch)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&acc.violations())?);
+            } else {
+                for msg in acc.messages() {
+                    println!("{msg}");
+                }
+            }
+            if !acc.is_empty() {
+                eprintln!("{} invariant violations", acc.len());
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Dump { address } => {
+            let address = resolve(&tree, &address)?;
+            let actor =
+                tree.map.get(&address.to_bytes())?.ok_or_else(|| anyhow!("no actor {address}"))?;
+            let typ = manifest.get_by_left(&actor.code).copied();
+            let json = dump_actor(&policy, &tree, typ, &address, actor)?;
+            println!("{}", serde_json::to_string_pretty(&json)?);
+        }
+        Command::Summary { address } => {
+            let only = address.map(|a| resolve(&tree, &a)).transpose()?;
+            tree.for_each(|address, actor| {
+                if only.map_or(false, |only| &only != address) {
+                    return Ok(());
+                }
+                let typ = manifest.get_by_left(&actor.code).copied();
+                let (summary, acc) =
+                    summarize_actor(&policy, &tree, typ, address, actor, prior_epoch)?;
+                println!("{address} ({}): {summary}", typ.map_or("unknown", |t| t.name()));
+                for msg in acc.messages() {
+                    println!("  {msg}");
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Parses an address and resolves it to an ID address through the init actor.
+fn resolve(tree: &Tree<DiskBlockstore>, address: &str) -> anyhow::Result<Address> {
+    let address = Address::from_str(address)?;
+    if address.protocol() == Protocol::ID {
+        return Ok(address);
+    }
+    let init =
+        tree.map.get(&INIT_ACTOR_ADDR.to_bytes())?.ok_or_else(|| anyhow!("no init actor"))?;
+    let state: InitState =
+        tree.store.get_cbor(&init.head)?.ok_or_else(|| anyhow!("init state not found"))?;
+    state.resolve_address(tree.store, &address)?.ok_or_else(|| anyhow!("{address} not found"))
+}
@@ -0,0 +1,117 @@
+//! A blockstore that keeps block data on disk, for state trees too large to hold in memory.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use anyhow::Context;
+use cid::Cid;
+use fvm_ipld_blockstore::Blockstore;
+
+static TEMPORARY_FILES: AtomicU64 = AtomicU64::new(0);
+
+/// Appends blocks to a single file, indexing only their offsets in memory.
+pub struct DiskBlockstore {
+    path: PathBuf,
+    file: RefCell<File>,
+    /// Offset and length of each block in the file.
+    index: RefCell<HashMap<Cid, (u64, usize)>>,
+    remove_on_drop: bool,
+}
+
+impl DiskBlockstore {
+    /// Creates a store writing to a new file at `path`, truncating any existing file.
+    pub fn create<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)
+            .with_context(|| format!("failed to create blockstore {}", path.display()))?;
+        Ok(Self {
+            path,
+            file: RefCell::new(file),
+            index: RefCell::new(HashMap::new()),
+            remove_on_drop: false,
+        })
+    }
+
+    /// Creates a store in the system's temporary directory, removing its file when dropped.
+    pub fn temporary() -> anyhow::Result<Self> {
+        let name = format!(
+            "fil-state-{}-{}.blocks",
+            std::process::id(),
+            TEMPORARY_FILES.fetch_add(1, Ordering::Relaxed)
+        );
+        let mut store = Self::create(std::env::temp_dir().join(name))?;
+        store.remove_on_drop = true;
+        Ok(store)
+    }
+
+    /// The path of the file holding the blocks.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The number of blocks in the store.
+    pub fn len(&self) -> usize {
+        self.index.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.borrow().is_empty()
+    }
+}
+
+impl Blockstore for DiskBlockstore {
+    fn get(&self, k: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        let (offset, len) = match self.index.borrow().get(k) {
+            Some(location) => *location,
+            None => return Ok(None),
+        };
+        let mut file = self.file.borrow_mut();
+        file.seek(SeekFrom::Start(offset))?;
+        let mut block = vec![0; len];
+        file.read_exact(&mut block)
+            .with_context(|| format!("failed to read block {k} from {}", self.path.display()))?;
+        Ok(Some(block))
+    }
+
+    fn put_keyed(&self, k: &Cid, block: &[u8]) -> anyhow::Result<()> {
+        if self.index.borrow().contains_key(k) {
+            return Ok(());
+        }
+        let mut file = self.file.borrow_mut();
+        let offset = file.seek(SeekFrom::End(0))?;
+        file.write_all(block)
+            .with_context(|| format!("failed to write block {k} to {}", self.path.display()))?;
+        self.index.borrow_mut().insert(*k, (offset, block.len()));
+        Ok(())
+    }
+
+    fn has(&self, k: &Cid) -> anyhow::Result<bool> {
+        Ok(self.index.borrow().contains_key(k))
+    }
+}
+
+impl std::fmt::Debug for DiskBlockstore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiskBlockstore")
+            .field("path", &self.path)
+            .field("blocks", &self.len())
+            .finish()
+    }
+}
+
+impl Drop for DiskBlockstore {
+    fn drop(&mut self) {
+        if self.remove_on_drop {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
@@ -7,6 +7,7 @@ use cid::Cid;
 use fil_actor_account::State as AccountState;
 use fil_actor_cron::State as CronState;
 use fil_actor_datacap::State as DataCapState;
+use fil_actor_evm::State as EvmState;
 use fil_actor_init::State as InitState;
 use fil_actor_market::State as MarketState;
 use fil_actor_miner::CronEventPayload;
@@ -15,6 +16,7 @@ use fil_actor_miner::State as MinerState;
 use fil_actor_miner::CRON_EVENT_PROCESS_EARLY_TERMINATIONS;
 use fil_actor_miner::CRON_EVENT_PROVING_DEADLINE;
 use fil_actor_multisig::State as MultisigState;
+use fil_actor_passkey::State as PasskeyState;
 use fil_actor_paych::State as PaychState;
 use fil_actor_power::testing::MinerCronEvent;
 use fil_actor_power::State as PowerState;
@@ -34,6 +36,7 @@ use fvm_shared::address::Protocol;
 
 use fvm_shared::clock::ChainEpoch;
 use fvm_shared::econ::TokenAmount;
+use fvm_shared::ActorID;
 use num_traits::Zero;
 
 use anyhow::anyhow;
@@ -42,10 +45,14 @@ use fvm_ipld_encoding::tuple::*;
 use fil_actor_account::testing as account;
 use fil_actor_cron::testing as cron;
 use fil_actor_datacap::testing as datacap;
+use fil_actor_eam::testing as eam;
+use fil_actor_ethaccount::testing as ethaccount;
+use fil_actor_evm::testing as evm;
 use fil_actor_init::testing as init;
 use fil_actor_market::testing as market;
 use fil_actor_miner::testing as miner;
 use fil_actor_multisig::testing as multisig;
+use fil_actor_passkey::testing as passkey;
 use fil_actor_paych::testing as paych;
 use fil_actor_power::testing as power;
 use fil_actor_reward::testing as reward;
@@ -134,12 +141,17 @@ pub fn check_state_invariants<'a, BS: Blockstore + Debug>(
     let mut reward_summary: Option<reward::StateSummary> = None;
     let mut verifreg_summary: Option<verifreg::StateSummary> = None;
     let mut datacap_summary: Option<frc46_token::token::state::StateSummary> = None;
+    let mut evm_summaries = HashMap::<Address, evm::StateSummary>::new();
+    let mut delegated_addresses = Vec::<(ActorID, Address)>::new();
 
     tree.for_each(|key, actor| {
-        let acc = acc.with_prefix(format!("{key} "));
+        let acc = acc
+            .with_prefix(format!("{key} "))
+            .with_actor(*key, manifest.get_by_left(&actor.code).copied());
 
         if key.protocol() != Protocol::ID {
-            acc.add(format!("unexpected address protocol in state tree root: {key}"));
+            acc.with_code("state.address_protocol")
+                .add(format!("unexpected address protocol in state tree root: {key}"));
         }
         total_fil += &actor.balance;
 
@@ -220,19 +232,52 @@ pub fn check_state_invariants<'a, BS: Blockstore + Debug>(
                 acc.with_prefix("datacap: ").add_all(&msgs);
                 datacap_summary = Some(summary);
             }
-            Some(Type::Placeholder) => {}
-            Some(Type::EVM) => {}
-            Some(Type::EAM) => {}
-            Some(Type::EthAccount) => {}
+            Some(Type::Placeholder) => {
+                // Placeholders are created by sending to an unassigned delegated address.
+                acc.with_code("placeholder.delegated_address").require(
+                    matches!(actor.address.map(|a| a.protocol()), Some(Protocol::Delegated)),
+                    format!("placeholder has no delegated address: {:?}", actor.address),
+                );
+            }
+            Some(Type::EVM) => {
+                let state = get_state!(tree, actor, EvmState);
+                let (summary, msgs) =
+                    evm::check_state_invariants(&state, tree.store, actor.address.as_ref());
+                acc.with_prefix("evm: ").add_all(&msgs);
+                evm_summaries.insert(*key, summary);
+            }
+            Some(Type::EAM) => {
+                let msgs = eam::check_state_invariants(key);
+                acc.with_prefix("eam: ").add_all(&msgs);
+            }
+            Some(Type::EthAccount) => {
+                let msgs = ethaccount::check_state_invariants(actor.address.as_ref());
+                acc.with_prefix("ethaccount: ").add_all(&msgs);
+            }
+            Some(Type::PasskeyAccount) => {
+                let state = get_state!(tree, actor, PasskeyState);
+                let msgs = passkey::check_state_invariants(&state);
+                acc.with_prefix("passkey: ").add_all(&msgs);
+            }
             None => {
                 bail!("unexpected actor code CID {} for address {}", actor.code, key);
             }
         };
 
+        if let (Ok(id), Some(address)) = (key.id(), actor.address) {
+            if address.protocol() == Protocol::Delegated {
+                delegated_addresses.push((id, address));
+            }
+        }
+
         Ok(())
     })?;
 
     // Perform cross-actor checks from state summaries here.
+    if let Some(init_summary) = &init_summary {
+        check_delegated_addresses_against_init(&acc, &delegated_addresses, init_summary);
+    }
+
     if let Some(power_summary) = power_summary {
         check_miner_against_power(&acc, &miner_summaries, &power_summary);
     }
@@ -251,7 +296,9 @@ pub fn check_state_invariants<'a, BS: Blockstore + Debug>(
         check_verifreg_against_miners(&acc, &verifreg_summary, &miner_summaries);
     }
 
-    acc.require(
+    check_evm_tombstones(&acc, manifest, &tree, &evm_summaries)?;
+
+    acc.with_code("state.total_balance").require(
         &total_fil == expected_balance_total,
         format!("total token balance is {total_fil}, expected {expected_balance_total}"),
     );
@@ -259,18 +306,75 @@ pub fn check_state_invariants<'a, BS: Blockstore + Debug>(
     Ok(acc)
 }
 
+fn check_delegated_addresses_against_init(
+    acc: &MessageAccumulator,
+    delegated_addresses: &[(ActorID, Address)],
+    init_summary: &init::StateSummary,
+) {
+    // Every delegated address recorded on an actor must resolve back to that actor.
+    for (id, address) in delegated_addresses {
+        match init_summary.ids_by_address.get(address) {
+            Some(mapped) => acc.with_code("init.delegated_address.mapping").require(
+                mapped == id,
+                format!("delegated address {address} of actor {id} maps to actor {mapped} in init"),
+            ),
+            None => acc.with_code("init.delegated_address.missing").add(format!(
+                "delegated address {address} of actor {id} not found in init address map"
+            )),
+        }
+    }
+}
+
+fn check_evm_tombstones<BS: Blockstore>(
+    acc: &MessageAccumulator,
+    manifest: &BiBTreeMap<Cid, Type>,
+    tree: &Tree<'_, BS>,
+    evm_summaries: &HashMap<Address, evm::StateSummary>,
+) -> anyhow::Result<()> {
+    // A contract is self-destructed by a message sent from an account, whose sequence number
+    // has since been incremented past that of the message.
+    for (address, summary) in evm_summaries {
+        let tombstone = match &summary.tombstone {
+            Some(tombstone) => tombstone,
+            None => continue,
+        };
+        let origin = Address::new_id(tombstone.origin);
+        match tree.map.get(&origin.to_bytes())? {
+            Some(actor) => match manifest.get_by_left(&actor.code) {
+                Some(Type::Account | Type::EthAccount) => {
+                    acc.with_code("evm.tombstone_nonce").require(
+                        tombstone.nonce < actor.call_seq_num,
+                        format!(
+                            "contract {address} tombstone nonce {} not below origin sequence {}",
+                            tombstone.nonce, actor.call_seq_num
+                        ),
+                    )
+                }
+                _ => acc
+                    .with_code("evm.tombstone_origin")
+                    .add(format!("contract {address} tombstone origin {origin} is not an account")),
+            },
+            None => acc
+                .with_code("evm.tombstone_origin")
+                .add(format!("contract {address} tombstone origin {origin} not found")),
+        }
+    }
+    Ok(())
+}
+
 fn check_miner_against_power(
     acc: &MessageAccumulator,
     miner_summaries: &HashMap<Address, miner::StateSummary>,
     power_summary: &power::StateSummary,
 ) {
     for (address, miner_summary) in miner_summaries {
+        let acc = acc.with_actor(*address, Some(Type::Miner));
         //check claim
         if let Some(claim) = power_summary.claims.get(address) {
             let claim_power =
                 PowerPair::new(claim.raw_byte_power.clone(), claim.quality_adj_power.clone());
-            acc.require(miner_summary.active_power == claim_power, format!("miner {address} computed active power {:?} does not match claim {claim_power:?}", miner_summary.active_power));
-            acc.require(
+            acc.with_code("power_miner.active_power").require(miner_summary.active_power == claim_power, format!("miner {address} computed active power {:?} does not match claim {claim_power:?}", miner_summary.active_power));
+            acc.with_code("power_miner.proof_type").require(
                 miner_summary.window_post_proof_type == claim.window_post_proof_type,
                 format!(
                     "miner seal proof type {:?} does not match claim proof type {:?}",
@@ -278,7 +382,8 @@ fn check_miner_against_power(
                 ),
             );
         } else {
-            acc.add(format!("miner {address} has no power claim"));
+            acc.with_code("power_miner.claim_missing")
+                .add(format!("miner {address} has no power claim"));
         }
 
         //check crons
@@ -287,7 +392,7 @@ fn check_miner_against_power(
             for event in crons {
                 match from_slice::<CronEventPayload>(event.payload.bytes()) {
                     Ok(payload) => {
-                        acc.require(
+                        acc.with_code("power_miner.cron_event_type").require(
                             matches!(
                                 payload.event_type,
                                 CRON_EVENT_PROCESS_EARLY_TERMINATIONS | CRON_EVENT_PROVING_DEADLINE
@@ -299,18 +404,18 @@ fn check_miner_against_power(
                         );
                         if payload.event_type == CRON_EVENT_PROVING_DEADLINE {
                             if proving_period_cron.is_some() {
-                                acc.add(format!("miner {address} has duplicate proving period crons at epoch {} and {}", proving_period_cron.as_ref().unwrap().epoch, event.epoch));
+                                acc.with_code("power_miner.duplicate_proving_cron").add(format!("miner {address} has duplicate proving period crons at epoch {} and {}", proving_period_cron.as_ref().unwrap().epoch, event.epoch));
                             }
                             proving_period_cron = Some(event);
                         }
                     }
-                    Err(e) => acc.add(format!(
+                    Err(e) => acc.with_code("power_miner.cron_payload").add(format!(
                         "miner {address} registered cron at epoch {} with wrong or corrupt payload: {e}",
                         event.epoch
                     )),
                 }
-                acc.require(proving_period_cron.is_some() == miner_summary.deadline_cron_active, format!("miner {address} has invalid deadline_cron_active ({}) for proving_period_cron status ({})", miner_summary.deadline_cron_active, proving_period_cron.is_some()));
-                acc.require(
+                acc.with_code("power_miner.deadline_cron_active").require(proving_period_cron.is_some() == miner_summary.deadline_cron_active, format!("miner {address} has invalid deadline_cron_active ({}) for proving_period_cron status ({})", miner_summary.deadline_cron_active, proving_period_cron.is_some()));
+                acc.with_code("power_miner.proving_cron_missing").require(
                     proving_period_cron.is_some(),
                     format!("miner {address} has no proving period cron"),
                 );
@@ -318,7 +423,7 @@ fn check_miner_against_power(
         } else {
             // with deferred and discontinued crons it is normal for a miner actor to have no cron
             // events
-            acc.require(
+            acc.with_code("power_miner.deadline_cron_active").require(
                 !miner_summary.deadline_cron_active,
                 format!("miner {address} has no cron events but the deadline cron is active"),
             );
@@ -335,6 +440,7 @@ fn check_deal_states_against_sectors(
     // We cannot check that all deals referenced within a sector are in the market, because deals
     // can be terminated independently of the sector in which they are included.
     for (deal_id, deal) in &market_summary.deals {
+        let acc = acc.with_actor(deal.provider, Some(Type::Miner)).with_context("deal", deal_id);
         if deal.sector_start_epoch == -1 {
             // deal hasn't been activated yet, make no assertions about sector state
             continue;
@@ -343,7 +449,7 @@ fn check_deal_states_against_sectors(
         let miner_summary = if let Some(miner_summary) = miner_summaries.get(&deal.provider) {
             miner_summary
         } else {
-            acc.add(format!(
+            acc.with_code("market_miner.provider_missing").add(format!(
                 "provider {} for deal {} not found among miners",
                 deal.provider, &deal_id
             ));
@@ -353,7 +459,7 @@ fn check_deal_states_against_sectors(
         let sector_deal = if let Some(sector_deal) = miner_summary.deals.get(deal_id) {
             sector_deal
         } else {
-            acc.require(
+            acc.with_code("market_miner.deal_not_in_sector").require(
                 deal.slash_epoch >= 0,
                 format!(
                     "un-slashed deal {deal_id} not referenced in active sectors of miner {}",
@@ -363,7 +469,7 @@ fn check_deal_states_against_sectors(
             continue;
         };
 
-        acc.require(
+        acc.with_code("market_miner.sector_start").require(
             deal.sector_start_epoch == sector_deal.sector_start,
             format!(
                 "deal state start {} does not match sector start {} for miner {}",
@@ -371,7 +477,7 @@ fn check_deal_states_against_sectors(
             ),
         );
 
-        acc.require(
+        acc.with_code("market_miner.start_after_expiration").require(
             deal.sector_start_epoch <= sector_deal.sector_expiration,
             format!(
                 "deal state start {} activated after sector expiration {} for miner {}",
@@ -379,7 +485,7 @@ fn check_deal_states_against_sectors(
             ),
         );
 
-        acc.require(
+        acc.with_code("market_miner.update_after_expiration").require(
             deal.last_update_epoch <= sector_deal.sector_expiration,
             format!(
                 "deal state update at {} after sector expiration {} for miner {}",
@@ -387,7 +493,7 @@ fn check_deal_states_against_sectors(
             ),
         );
 
-        acc.require(
+        acc.with_code("market_miner.slash_after_expiration").require(
             deal.slash_epoch <= sector_deal.sector_expiration,
             format!(
                 "deal state slashed at {} after sector expiration {} for miner {}",
@@ -404,7 +510,7 @@ fn check_verifreg_against_datacap(
 ) {
     // Verifier and datacap token holders are distinct.
     for verifier in verifreg_summary.verifiers.keys() {
-        acc.require(
+        acc.with_code("verifreg_datacap.verifier_holder").require(
             !datacap_summary.balance_map.as_ref().unwrap().contains_key(&verifier.id().unwrap()),
             format!("verifier {} is also a datacap token holder", verifier),
         );
@@ -419,7 +525,7 @@ fn check_verifreg_against_datacap(
         .get(&VERIFIED_REGISTRY_ACTOR_ADDR.id().unwrap())
         .cloned()
         .unwrap_or_else(TokenAmount::zero);
-    acc.require(
+    acc.with_code("verifreg_datacap.balance").require(
         TokenAmount::from_whole(pending_alloc_total.clone()) == verifreg_balance,
         format!(
             "verifreg datacap balance {} does not match pending allocation size {}",
@@ -439,7 +545,8 @@ fn check_market_against_verifreg(
         // claim is found
         let claim = match verifreg_summary.claims.get(claim_id) {
             None => {
-                acc.add(format!("claim {} not found for activated deal {}", claim_id, deal_id));
+                acc.with_code("market_verifreg.claim_missing")
+                    .add(format!("claim {} not found for activated deal {}", claim_id, deal_id));
                 continue;
             }
             Some(claim) => claim,
@@ -447,7 +554,7 @@ fn check_market_against_verifreg(
 
         let info = match market_summary.deals.get(deal_id) {
             None => {
-                acc.add(format!(
+                acc.with_code("market_verifreg.deal_missing").add(format!(
                     "internal invariant error invalid market state referrences missing deal {}",
                     deal_id
                 ));
@@ -455,18 +562,20 @@ fn check_market_against_verifreg(
             }
             Some(info) => info,
         };
-        // claim and proposal match
-        acc.require(
-            info.provider.id().unwrap() == claim.provider,
+        // claim and proposal match, unless the claim has since been transferred to another provider
+        let deal_provider = info.provider.id().unwrap();
+        let transferred = verifreg_summary
+            .transferred_claims
+            .get(claim_id)
+            .map_or(false, |providers| providers.contains(&deal_provider));
+        acc.with_code("market_verifreg.claim_provider").require(
+            deal_provider == claim.provider || transferred,
             format!(
                 "mismatched providers {} {} on claim {} and deal {}",
-                claim.provider,
-                info.provider.id().unwrap(),
-                claim_id,
-                deal_id
+                claim.provider, deal_provider, claim_id, deal_id
             ),
         );
-        acc.require(
+        acc.with_code("market_verifreg.claim_piece_cid").require(
             info.piece_cid.unwrap() == claim.data,
             format!(
                 "mismatched piece cid {} {} on claim {} and deal {}",
@@ -485,7 +594,7 @@ fn check_market_against_verifreg(
         // allocation is found
         let alloc = match verifreg_summary.allocations.get(allocation_id) {
             None => {
-                acc.add(format!(
+                acc.with_code("market_verifreg.allocation_missing").add(format!(
                     "allocation {} not found for pending deal {}",
                     allocation_id, deal_id
                 ));
@@ -496,7 +605,7 @@ fn check_market_against_verifreg(
         // alloc and proposal match
         let info = match market_summary.deals.get(deal_id) {
             None => {
-                acc.add(format!(
+                acc.with_code("market_verifreg.deal_missing").add(format!(
                     "internal invariant error invalid market state referrences missing deal {}",
                     deal_id
                 ));
@@ -504,7 +613,7 @@ fn check_market_against_verifreg(
             }
             Some(info) => info,
         };
-        acc.require(
+        acc.with_code("market_verifreg.allocation_provider").require(
             info.provider.id().unwrap() == alloc.provider,
             format!(
                 "mismatched providers {} {} on alloc {} and deal {}",
@@ -514,7 +623,7 @@ fn check_market_against_verifreg(
                 deal_id
             ),
         );
-        acc.require(
+        acc.with_code("market_verifreg.allocation_piece_cid").require(
             info.piece_cid.unwrap() == alloc.data,
             format!(
                 "mismatched piece cid {} {} on alloc {} and deal {}",
@@ -535,16 +644,18 @@ fn check_verifreg_against_miners(
     for claim in verifreg_summary.claims.values() {
         // all claims are indexed by valid providers
         let maddr = Address::new_id(claim.provider);
+        let acc = acc.with_actor(maddr, Some(Type::Miner)).with_context("sector", claim.sector);
         let miner_summary = match miner_summaries.get(&maddr) {
             None => {
-                acc.add(format!("claim provider {} is not found in miner summaries", maddr));
+                acc.with_code("verifreg_miner.provider_missing")
+                    .add(format!("claim provider {} is not found in miner summaries", maddr));
                 continue;
             }
             Some(summary) => summary,
         };
 
         // all claims are linked to a valid sector number
-        acc.require(
+        acc.with_code("verifreg_miner.claim_sector").require(
             miner_summary.sectors_with_deals.get(&claim.sector).is_some(),
             format!(
                 "claim sector number {} not recorded as a sector with deals for miner {}",
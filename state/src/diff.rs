@@ -0,0 +1,722 @@
+//! Actor-aware comparison of two state trees.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Debug, Display};
+
+use anyhow::anyhow;
+use bimap::BiBTreeMap;
+use cid::Cid;
+use fil_actor_market::balance_table::BalanceTable;
+use fil_actor_market::{DealArray, DealMetaArray, State as MarketState};
+use fil_actor_miner::{
+    Deadline, Partition, SectorOnChainInfo, SectorPreCommitOnChainInfo, State as MinerState,
+};
+use fil_actor_multisig::{State as MultisigState, Transaction};
+use fil_actor_verifreg::{Allocation, Claim, State as VerifregState};
+use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::{parse_uint_key, Array, Map};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::from_slice;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::check::{Actor, Tree};
+use crate::dump::{bitfield, cid, outer_keys, power, tokens};
+
+/// The differences between two state trees.
+#[derive(Serialize, Debug, Default)]
+pub struct StateDiff {
+    pub added: Vec<ActorEntry>,
+    pub removed: Vec<ActorEntry>,
+    pub changed: Vec<ActorDiff>,
+}
+
+/// An actor present in only one of the trees.
+#[derive(Serialize, Debug)]
+pub struct ActorEntry {
+    pub address: String,
+    #[serde(rename = "type")]
+    pub typ: Option<&'static str>,
+    pub balance: Value,
+}
+
+/// The changes to an actor present in both trees.
+#[derive(Serialize, Debug)]
+pub struct ActorDiff {
+    pub address: String,
+    #[serde(rename = "type")]
+    pub typ: Option<&'static str>,
+    pub changes: Vec<Change>,
+}
+
+/// A single changed value, identified by its path within the actor.
+/// Values that don't exist on one side are null.
+#[derive(Serialize, Debug)]
+pub struct Change {
+    pub path: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// Compares two state trees over the same blockstore.
+/// Each tree comes with the manifest mapping its code CIDs to actor types, which differ
+/// when the trees are either side of a network upgrade.
+pub fn diff_state_trees<BS: Blockstore>(
+    before_manifest: &BiBTreeMap<Cid, Type>,
+    after_manifest: &BiBTreeMap<Cid, Type>,
+    policy: &Policy,
+    before: &Tree<BS>,
+    after: &Tree<BS>,
+) -> anyhow::Result<StateDiff> {
+    let before_actors = load_actors(before)?;
+    let after_actors = load_actors(after)?;
+    let entry = |manifest: &BiBTreeMap<Cid, Type>, address: &String, actor: &Actor| ActorEntry {
+        address: address.clone(),
+        typ: manifest.get_by_left(&actor.code).map(|t| t.name()),
+        balance: tokens(&actor.balance),
+    };
+
+    let mut diff = StateDiff::default();
+    for (address, old) in &before_actors {
+        match after_actors.get(address) {
+            None => diff.removed.push(entry(before_manifest, address, old)),
+            Some(new) if new != old => {
+                let old_type = before_manifest.get_by_left(&old.code);
+                let new_type = after_manifest.get_by_left(&new.code);
+                let changes = diff_actor(policy, after.store, (old_type, old), (new_type, new))?;
+                diff.changed.push(ActorDiff {
+                    address: address.clone(),
+                    typ: new_type.map(|t| t.name()),
+                    changes,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for (address, new) in &after_actors {
+        if !before_actors.contains_key(address) {
+            diff.added.push(entry(after_manifest, address, new));
+        }
+    }
+    Ok(diff)
+}
+
+// Addresses aren't ordered, so collections keyed by address use their string form.
+fn load_actors<BS: Blockstore>(tree: &Tree<BS>) -> anyhow::Result<BTreeMap<String, Actor>> {
+    let mut actors = BTreeMap::new();
+    tree.for_each(|address, actor| {
+        actors.insert(address.to_string(), actor.clone());
+        Ok(())
+    })?;
+    Ok(actors)
+}
+
+fn diff_actor<BS: Blockstore>(
+    policy: &Policy,
+    store: &BS,
+    (old_type, old): (Option<&Type>, &Actor),
+    (new_type, new): (Option<&Type>, &Actor),
+) -> anyhow::Result<Vec<Change>> {
+    let mut changes = Changes::default();
+    changes.value("code", &old.code, &new.code, cid);
+    changes.value("nonce", &old.call_seq_num, &new.call_seq_num, |n| Value::from(*n));
+    changes.value("balance", &old.balance, &new.balance, tokens);
+    if old.head == new.head {
+        return Ok(changes.0);
+    }
+
+    // States of different types can't be compared field by field.
+    if old_type != new_type {
+        changes.value("head", &old.head, &new.head, cid);
+        return Ok(changes.0);
+    }
+    // States that don't decode as the current version of their type, such as those written
+    // by an earlier network version, are compared by head only.
+    let compared = match new_type {
+        Some(Type::Miner) => load_pair(store, old, new)?
+            .map(|(old, new)| diff_miner(&mut changes, policy, store, &old, &new))
+            .transpose()?,
+        Some(Type::Market) => load_pair(store, old, new)?
+            .map(|(old, new)| diff_market(&mut changes, store, &old, &new))
+            .transpose()?,
+        Some(Type::VerifiedRegistry) => load_pair(store, old, new)?
+            .map(|(old, new)| diff_verifreg(&mut changes, store, &old, &new))
+            .transpose()?,
+        Some(Type::Multisig) => load_pair(store, old, new)?
+            .map(|(old, new)| diff_multisig(&mut changes, store, &old, &new))
+            .transpose()?,
+        _ => None,
+    };
+    if compared.is_none() {
+        changes.value("head", &old.head, &new.head, cid);
+    }
+    Ok(changes.0)
+}
+
+fn diff_miner<BS: Blockstore>(
+    changes: &mut Changes,
+    policy: &Policy,
+    store: &BS,
+    old: &MinerState,
+    new: &MinerState,
+) -> anyhow::Result<()> {
+    if old.info != new.info {
+        let (old_info, new_info) = (old.get_info(store)?, new.get_info(store)?);
+        changes.value("info", &old_info, &new_info, debug);
+    }
+    changes.value(
+        "pre_commit_deposits",
+        &old.pre_commit_deposits,
+        &new.pre_commit_deposits,
+        tokens,
+    );
+    changes.value("locked_funds", &old.locked_funds, &new.locked_funds, tokens);
+    changes.value("fee_debt", &old.fee_debt, &new.fee_debt, tokens);
+    changes.value("initial_pledge", &old.initial_pledge, &new.initial_pledge, tokens);
+    changes.value(
+        "proving_period_start",
+        &old.proving_period_start,
+        &new.proving_period_start,
+        |e| Value::from(*e),
+    );
+    changes.value("current_deadline", &old.current_deadline, &new.current_deadline, |d| {
+        Value::from(*d)
+    });
+    changes.value("early_terminations", &old.early_terminations, &new.early_terminations, bitfield);
+
+    if old.sectors != new.sectors {
+        changes.entries(
+            "sectors",
+            &load_array::<SectorOnChainInfo, _>(store, &old.sectors)?,
+            &load_array::<SectorOnChainInfo, _>(store, &new.sectors)?,
+            debug,
+        );
+    }
+    if old.pre_committed_sectors != new.pre_committed_sectors {
+        changes.entries(
+            "pre_committed_sectors",
+            &load_uint_map::<SectorPreCommitOnChainInfo, _>(store, &old.pre_committed_sectors)?,
+            &load_uint_map::<SectorPreCommitOnChainInfo, _>(store, &new.pre_committed_sectors)?,
+            debug,
+        );
+    }
+
+    if old.deadlines != new.deadlines {
+        let (old_deadlines, new_deadlines) =
+            (old.load_deadlines(store)?, new.load_deadlines(store)?);
+        for (idx, (old_due, new_due)) in
+            old_deadlines.due.iter().zip(&new_deadlines.due).enumerate()
+        {
+            if old_due == new_due {
+                continue;
+            }
+            let old_dl = old_deadlines.load_deadline(policy, store, idx as u64)?;
+            let new_dl = new_deadlines.load_deadline(policy, store, idx as u64)?;
+            diff_deadline(changes, store, &format!("deadlines/{idx}"), &old_dl, &new_dl)?;
+        }
+    }
+    Ok(())
+}
+
+fn diff_deadline<BS: Blockstore>(
+    changes: &mut Changes,
+    store: &BS,
+    path: &str,
+    old: &Deadline,
+    new: &Deadline,
+) -> anyhow::Result<()> {
+    changes.value(&format!("{path}/live_sectors"), &old.live_sectors, &new.live_sectors, |n| {
+        Value::from(*n)
+    });
+    changes.value(&format!("{path}/total_sectors"), &old.total_sectors, &new.total_sectors, |n| {
+        Value::from(*n)
+    });
+    changes.value(&format!("{path}/faulty_power"), &old.faulty_power, &new.faulty_power, power);
+    changes.value(
+        &format!("{path}/partitions_posted"),
+        &old.partitions_posted,
+        &new.partitions_posted,
+        bitfield,
+    );
+    changes.value(
+        &format!("{path}/early_terminations"),
+        &old.early_terminations,
+        &new.early_terminations,
+        bitfield,
+    );
+    if old.partitions == new.partitions {
+        return Ok(());
+    }
+
+    let old_partitions = load_array::<Partition, _>(store, &old.partitions)?;
+    let new_partitions = load_array::<Partition, _>(store, &new.partitions)?;
+    let indices: BTreeSet<_> = old_partitions.keys().chain(new_partitions.keys()).collect();
+    for idx in indices {
+        let path = format!("{path}/partitions/{idx}");
+        match (old_partitions.get(idx), new_partitions.get(idx)) {
+            (Some(old), Some(new)) => {
+                changes.value(&format!("{path}/sectors"), &old.sectors, &new.sectors, bitfield);
+                changes.value(&format!("{path}/unproven"), &old.unproven, &new.unproven, bitfield);
+                changes.value(&format!("{path}/faults"), &old.faults, &new.faults, bitfield);
+                changes.value(
+                    &format!("{path}/recoveries"),
+                    &old.recoveries,
+                    &new.recoveries,
+                    bitfield,
+                );
+                changes.value(
+                    &format!("{path}/terminated"),
+                    &old.terminated,
+                    &new.terminated,
+                    bitfield,
+                );
+                changes.value(
+                    &format!("{path}/live_power"),
+                    &old.live_power,
+                    &new.live_power,
+                    power,
+                );
+                changes.value(
+                    &format!("{path}/faulty_power"),
+                    &old.faulty_power,
+                    &new.faulty_power,
+                    power,
+                );
+            }
+            (old, new) => changes.push(
+                path,
+                old.map_or(Value::Null, |p| bitfield(&p.sectors)),
+                new.map_or(Value::Null, |p| bitfield(&p.sectors)),
+            ),
+        }
+    }
+    Ok(())
+}
+
+fn diff_market<BS: Blockstore>(
+    changes: &mut Changes,
+    store: &BS,
+    old: &MarketState,
+    new: &MarketState,
+) -> anyhow::Result<()> {
+    changes.value("next_id", &old.next_id, &new.next_id, |n| Value::from(*n));
+    changes.value(
+        "total_client_locked_collateral",
+        &old.total_client_locked_collateral,
+        &new.total_client_locked_collateral,
+        tokens,
+    );
+    changes.value(
+        "total_provider_locked_collateral",
+        &old.total_provider_locked_collateral,
+        &new.total_provider_locked_collateral,
+        tokens,
+    );
+    changes.value(
+        "total_client_storage_fee",
+        &old.total_client_storage_fee,
+        &new.total_client_storage_fee,
+        tokens,
+    );
+
+    if old.proposals != new.proposals {
+        let load = |root| -> anyhow::Result<_> {
+            let mut proposals = BTreeMap::new();
+            DealArray::load(root, store)?.for_each(|id, proposal| {
+                proposals.insert(id, proposal.clone());
+                Ok(())
+            })?;
+            Ok(proposals)
+        };
+        changes.entries("proposals", &load(&old.proposals)?, &load(&new.proposals)?, debug);
+    }
+    if old.states != new.states {
+        let load = |root| -> anyhow::Result<_> {
+            let mut states = BTreeMap::new();
+            DealMetaArray::load(root, store)?.for_each(|id, state| {
+                states.insert(id, *state);
+                Ok(())
+            })?;
+            Ok(states)
+        };
+        changes.entries("deal_states", &load(&old.states)?, &load(&new.states)?, debug);
+    }
+    for (name, old_root, new_root) in [
+        ("escrow", &old.escrow_table, &new.escrow_table),
+        ("locked", &old.locked_table, &new.locked_table),
+    ] {
+        if old_root != new_root {
+            changes.entries(
+                name,
+                &load_balances(store, old_root)?,
+                &load_balances(store, new_root)?,
+                tokens,
+            );
+        }
+    }
+    Ok(())
+}
+
+fn diff_verifreg<BS: Blockstore>(
+    changes: &mut Changes,
+    store: &BS,
+    old: &VerifregState,
+    new: &VerifregState,
+) -> anyhow::Result<()> {
+    changes.value("root_key", &old.root_key, &new.root_key, |a| Value::from(a.to_string()));
+    if old.verifiers != new.verifiers {
+        let load = |state: &VerifregState| -> anyhow::Result<_> {
+            let mut verifiers = BTreeMap::new();
+            state.load_verifiers(store)?.for_each(|key, cap| {
+                verifiers.insert(Address::from_bytes(key)?.to_string(), cap.0.clone());
+                Ok(())
+            })?;
+            Ok(verifiers)
+        };
+        changes.entries("verifiers", &load(old)?, &load(new)?, |cap| Value::from(cap.to_string()));
+    }
+    // The outer keys of both tables are client (allocations) or provider (claims) IDs,
+    // and the inner keys are unique across the whole table.
+    if old.allocations != new.allocations {
+        let load = |state: &VerifregState| -> anyhow::Result<BTreeMap<u64, Allocation>> {
+            let mut allocations = BTreeMap::new();
+            let mut table = state.load_allocs(store)?;
+            for key in outer_keys(store, &state.allocations)? {
+                table.for_each(key, |id, alloc| {
+                    allocations.insert(parse_uint_key(id)?, alloc.clone());
+                    Ok(())
+                })?;
+            }
+            Ok(allocations)
+        };
+        changes.entries("allocations", &load(old)?, &load(new)?, debug);
+    }
+    if old.claims != new.claims {
+        let load = |state: &VerifregState| -> anyhow::Result<BTreeMap<u64, Claim>> {
+            let mut claims = BTreeMap::new();
+            let mut table = state.load_claims(store)?;
+            for key in outer_keys(store, &state.claims)? {
+                table.for_each(key, |id, claim| {
+                    claims.insert(parse_uint_key(id)?, claim.clone());
+                    Ok(())
+                })?;
+            }
+            Ok(claims)
+        };
+        changes.entries("claims", &load(old)?, &load(new)?, debug);
+    }
+    Ok(())
+}
+
+fn diff_multisig<BS: Blockstore>(
+    changes: &mut Changes,
+    store: &BS,
+    old: &MultisigState,
+    new: &MultisigState,
+) -> anyhow::Result<()> {
+    changes.value("signers", &old.signers, &new.signers, debug);
+    changes.value(
+        "num_approvals_threshold",
+        &old.num_approvals_threshold,
+        &new.num_approvals_threshold,
+        |n| Value::from(*n),
+    );
+    if old.pending_txs != new.pending_txs {
+        let load = |root| -> anyhow::Result<_> {
+            let mut txns = BTreeMap::new();
+            Map::<_, Transaction>::load(root, store)?.for_each(|key, txn| {
+                // Transaction IDs are zigzag varints.
+                let raw = parse_uint_key(key)?;
+                let id = ((raw >> 1) as i64) ^ -((raw & 1) as i64);
+                txns.insert(id, txn.clone());
+                Ok(())
+            })?;
+            Ok(txns)
+        };
+        changes.entries("pending_txs", &load(&old.pending_txs)?, &load(&new.pending_txs)?, debug);
+    }
+    Ok(())
+}
+
+/// Accumulates the changes to one actor.
+#[derive(Default)]
+struct Changes(Vec<Change>);
+
+impl Changes {
+    fn push(&mut self, path: String, before: Value, after: Value) {
+        self.0.push(Change { path, before, after });
+    }
+
+    /// Records a change to a single value, if it differs.
+    fn value<V: PartialEq>(&mut self, path: &str, old: &V, new: &V, render: impl Fn(&V) -> Value) {
+        if old != new {
+            self.push(path.to_string(), render(old), render(new));
+        }
+    }
+
+    /// Records the added, removed and changed entries of a keyed collection.
+    fn entries<K: Ord + Display, V: PartialEq>(
+        &mut self,
+        path: &str,
+        old: &BTreeMap<K, V>,
+        new: &BTreeMap<K, V>,
+        render: impl Fn(&V) -> Value,
+    ) {
+        let keys: BTreeSet<_> = old.keys().chain(new.keys()).collect();
+        for key in keys {
+            let (old, new) = (old.get(key), new.get(key));
+            if old != new {
+                self.push(
+                    format!("{path}/{key}"),
+                    old.map_or(Value::Null, &render),
+                    new.map_or(Value::Null, &render),
+                );
+            }
+        }
+    }
+}
+
+fn debug<V: Debug>(v: &V) -> Value {
+    Value::String(format!("{v:?}"))
+}
+
+/// Loads the states of an actor before and after a change, or returns None if either
+/// doesn't decode as an `S`. Missing states are errors.
+fn load_pair<S: DeserializeOwned, BS: Blockstore>(
+    store: &BS,
+    old: &Actor,
+    new: &Actor,
+) -> anyhow::Result<Option<(S, S)>> {
+    let decode = |actor: &Actor| -> anyhow::Result<Option<S>> {
+        let block =
+            store.get(&actor.head)?.ok_or_else(|| anyhow!("state {} not found", actor.head))?;
+        Ok(from_slice(&block).ok())
+    };
+    Ok(decode(old)?.zip(decode(new)?))
+}
+
+fn load_array<V: DeserializeOwned + Serialize + Clone, BS: Blockstore>(
+    store: &BS,
+    root: &Cid,
+) -> anyhow::Result<BTreeMap<u64, V>> {
+    let mut entries = BTreeMap::new();
+    Array::<V, _>::load(root, store)?.for_each(|idx, v| {
+        entries.insert(idx, v.clone());
+        Ok(())
+    })?;
+    Ok(entries)
+}
+
+fn load_uint_map<V: DeserializeOwned + Serialize + Clone, BS: Blockstore>(
+    store: &BS,
+    root: &Cid,
+) -> anyhow::Result<BTreeMap<u64, V>> {
+    let mut entries = BTreeMap::new();
+    Map::<_, V>::load(root, store)?.for_each(|key, v| {
+        entries.insert(parse_uint_key(key)?, v.clone());
+        Ok(())
+    })?;
+    Ok(entries)
+}
+
+fn load_balances<BS: Blockstore>(
+    store: &BS,
+    root: &Cid,
+) -> anyhow::Result<BTreeMap<String, TokenAmount>> {
+    let mut balances = BTreeMap::new();
+    BalanceTable::from_root(store, root)?.0.for_each(|key, amount| {
+        balances.insert(Address::from_bytes(key)?.to_string(), amount.clone());
+        Ok(())
+    })?;
+    Ok(balances)
+}
+
+#[cfg(test)]
+mod tests {
+    use cid::multihash::{Code, MultihashDigest};
+    use fil_actor_miner::{CompactCommD, MinerInfo, SectorPreCommitInfo};
+    use fil_actors_runtime::make_empty_map;
+    use fvm_ipld_blockstore::MemoryBlockstore;
+    use fvm_ipld_encoding::CborStore;
+    use fvm_shared::sector::{RegisteredPoStProof, RegisteredSealProof};
+    use fvm_shared::{HAMT_BIT_WIDTH, IPLD_RAW};
+
+    use super::*;
+
+    fn code(name: &str) -> Cid {
+        Cid::new_v1(IPLD_RAW, Code::Identity.digest(name.as_bytes()))
+    }
+
+    fn manifest() -> BiBTreeMap<Cid, Type> {
+        let mut manifest = BiBTreeMap::new();
+        manifest.insert(code("account"), Type::Account);
+        manifest.insert(code("storageminer"), Type::Miner);
+        manifest
+    }
+
+    fn account(store: &MemoryBlockstore, nonce: u64, balance: i64) -> Actor {
+        Actor {
+            code: code("account"),
+            head: store.put_cbor(&Address::new_id(1), Code::Blake2b256).unwrap(),
+            call_seq_num: nonce,
+            balance: TokenAmount::from_atto(balance),
+            address: None,
+        }
+    }
+
+    fn tree<'a>(
+        store: &'a MemoryBlockstore,
+        actors: Vec<(u64, Actor)>,
+    ) -> Tree<'a, MemoryBlockstore> {
+        let mut map = make_empty_map(store, HAMT_BIT_WIDTH);
+        for (id, actor) in actors {
+            map.set(Address::new_id(id).to_bytes().into(), actor).unwrap();
+        }
+        Tree { map, store }
+    }
+
+    fn diff(
+        store: &MemoryBlockstore,
+        before: Vec<(u64, Actor)>,
+        after: Vec<(u64, Actor)>,
+    ) -> StateDiff {
+        let (before, after) = (tree(store, before), tree(store, after));
+        diff_state_trees(&manifest(), &manifest(), &Policy::default(), &before, &after).unwrap()
+    }
+
+    fn paths(diff: &ActorDiff) -> Vec<&str> {
+        diff.changes.iter().map(|c| c.path.as_str()).collect()
+    }
+
+    #[test]
+    fn identical_trees_have_no_diff() {
+        let store = MemoryBlockstore::new();
+        let actors = vec![(100, account(&store, 0, 1)), (101, account(&store, 2, 3))];
+        let diff = diff(&store, actors.clone(), actors);
+        assert!(diff.added.is_empty());
+        assert!(diff.removed.is_empty());
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn added_removed_and_changed_actors() {
+        let store = MemoryBlockstore::new();
+        let diff = diff(
+            &store,
+            vec![(100, account(&store, 0, 10)), (101, account(&store, 0, 20))],
+            vec![(100, account(&store, 1, 5)), (102, account(&store, 0, 30))],
+        );
+
+        assert_eq!(1, diff.added.len());
+        assert_eq!(Address::new_id(102).to_string(), diff.added[0].address);
+        assert_eq!(Some("account"), diff.added[0].typ);
+        assert_eq!(Value::from("30"), diff.added[0].balance);
+
+        assert_eq!(1, diff.removed.len());
+        assert_eq!(Address::new_id(101).to_string(), diff.removed[0].address);
+        assert_eq!(Value::from("20"), diff.removed[0].balance);
+
+        assert_eq!(1, diff.changed.len());
+        let changed = &diff.changed[0];
+        assert_eq!(Address::new_id(100).to_string(), changed.address);
+        assert_eq!(vec!["nonce", "balance"], paths(changed));
+        assert_eq!(
+            (Value::from(0u64), Value::from(1u64)),
+            (changed.changes[0].before.clone(), changed.changes[0].after.clone())
+        );
+        assert_eq!(
+            (Value::from("10"), Value::from("5")),
+            (changed.changes[1].before.clone(), changed.changes[1].after.clone())
+        );
+    }
+
+    #[test]
+    fn changed_actor_type_reports_head() {
+        let store = MemoryBlockstore::new();
+        let mut miner = account(&store, 0, 10);
+        miner.code = code("storageminer");
+        miner.head = store.put_cbor(&Address::new_id(2), Code::Blake2b256).unwrap();
+        let diff = diff(&store, vec![(100, account(&store, 0, 10))], vec![(100, miner)]);
+
+        assert_eq!(1, diff.changed.len());
+        assert_eq!(Some("storageminer"), diff.changed[0].typ);
+        assert_eq!(vec!["code", "head"], paths(&diff.changed[0]));
+    }
+
+    #[test]
+    fn undecodable_state_reports_head() {
+        let store = MemoryBlockstore::new();
+        let miner = |head: &str| Actor {
+            code: code("storageminer"),
+            head: store.put_cbor(&head, Code::Blake2b256).unwrap(),
+            call_seq_num: 0,
+            balance: TokenAmount::from_atto(10),
+            address: None,
+        };
+        let diff = diff(&store, vec![(1000, miner("old"))], vec![(1000, miner("new"))]);
+
+        assert_eq!(1, diff.changed.len());
+        assert_eq!(vec!["head"], paths(&diff.changed[0]));
+    }
+
+    #[test]
+    fn changed_miner_state() {
+        let store = MemoryBlockstore::new();
+        let policy = Policy::default();
+        let info = MinerInfo::new(
+            100,
+            100,
+            vec![],
+            b"peer".to_vec(),
+            vec![],
+            RegisteredPoStProof::StackedDRGWindow32GiBV1,
+        )
+        .unwrap();
+        let info = store.put_cbor(&info, Code::Blake2b256).unwrap();
+        let old = MinerState::new(&policy, &store, info, 0, 0).unwrap();
+
+        let mut new = old.clone();
+        new.fee_debt = TokenAmount::from_atto(7);
+        new.put_precommitted_sectors(
+            &store,
+            vec![SectorPreCommitOnChainInfo {
+                info: SectorPreCommitInfo {
+                    seal_proof: RegisteredSealProof::StackedDRG32GiBV1P1,
+                    sector_number: 9,
+                    sealed_cid: code("sealed"),
+                    seal_rand_epoch: 10,
+                    deal_ids: vec![],
+                    expiration: 100_000,
+                    unsealed_cid: CompactCommD::new(None),
+                },
+                pre_commit_deposit: TokenAmount::from_atto(3),
+                pre_commit_epoch: 20,
+                extension: 0,
+            }],
+        )
+        .unwrap();
+
+        let miner = |state: &MinerState| Actor {
+            code: code("storageminer"),
+            head: store.put_cbor(state, Code::Blake2b256).unwrap(),
+            call_seq_num: 0,
+            balance: TokenAmount::from_atto(10),
+            address: None,
+        };
+        let diff = diff(&store, vec![(1000, miner(&old))], vec![(1000, miner(&new))]);
+
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(1, diff.changed.len());
+        let changed = &diff.changed[0];
+        assert_eq!(Some("storageminer"), changed.typ);
+        assert_eq!(vec!["fee_debt", "pre_committed_sectors/9"], paths(changed));
+        assert_eq!(Value::from("0"), changed.changes[0].before);
+        assert_eq!(Value::from("7"), changed.changes[0].after);
+        assert_eq!(Value::Null, changed.changes[1].before);
+        assert_ne!(Value::Null, changed.changes[1].after);
+    }
+}
@@ -0,0 +1,368 @@
+//! Decoded renderings of actor state, for offline inspection.
+
+use std::fmt::Debug;
+
+use anyhow::anyhow;
+use cid::Cid;
+use fil_actor_market::balance_table::BalanceTable;
+use fil_actor_market::{DealArray, DealMetaArray, State as MarketState};
+use fil_actor_miner::{PowerPair, State as MinerState};
+use fil_actor_verifreg::State as VerifregState;
+use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::{make_map_with_root, parse_uint_key, MessageAccumulator};
+use fvm_ipld_bitfield::BitField;
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::econ::TokenAmount;
+use serde_json::{json, Value};
+
+use crate::check::{Actor, Tree};
+
+use fil_actor_account::testing as account;
+use fil_actor_cron::testing as cron;
+use fil_actor_datacap::testing as datacap;
+use fil_actor_eam::testing as eam;
+use fil_actor_ethaccount::testing as ethaccount;
+use fil_actor_evm::testing as evm;
+use fil_actor_init::testing as init;
+use fil_actor_market::testing as market;
+use fil_actor_miner::testing as miner;
+use fil_actor_multisig::testing as multisig;
+use fil_actor_passkey::testing as passkey;
+use fil_actor_paych::testing as paych;
+use fil_actor_power::testing as power;
+use fil_actor_reward::testing as reward;
+use fil_actor_verifreg::testing as verifreg;
+
+/// Renders the state of a single actor as JSON.
+/// Miner, market and verified registry state is decoded in depth, including deadlines,
+/// deals and allocations. Other actor types are rendered from their debug representation.
+pub fn dump_actor<BS: Blockstore>(
+    policy: &Policy,
+    tree: &Tree<BS>,
+    typ: Option<Type>,
+    address: &Address,
+    actor: &Actor,
+) -> anyhow::Result<Value> {
+    let state = match typ {
+        Some(Type::Miner) => dump_miner(policy, tree.store, &load(tree, actor)?)?,
+        Some(Type::Market) => dump_market(tree.store, &load(tree, actor)?)?,
+        Some(Type::VerifiedRegistry) => dump_verifreg(tree.store, &load(tree, actor)?)?,
+        Some(Type::Init) => debug::<fil_actor_init::State, _>(tree, actor)?,
+        Some(Type::Cron) => debug::<fil_actor_cron::State, _>(tree, actor)?,
+        Some(Type::Account) => debug::<fil_actor_account::State, _>(tree, actor)?,
+        Some(Type::Power) => debug::<fil_actor_power::State, _>(tree, actor)?,
+        Some(Type::PaymentChannel) => debug::<fil_actor_paych::State, _>(tree, actor)?,
+        Some(Type::Multisig) => debug::<fil_actor_multisig::State, _>(tree, actor)?,
+        Some(Type::Reward) => debug::<fil_actor_reward::State, _>(tree, actor)?,
+        Some(Type::DataCap) => {
+            let state: fil_actor_datacap::State = load(tree, actor)?;
+            json!({
+                "governor": state.governor.to_string(),
+                "supply": tokens(&state.token.supply),
+                "balances": cid(&state.token.balances),
+                "allowances": cid(&state.token.allowances),
+            })
+        }
+        Some(Type::EVM) => debug::<fil_actor_evm::State, _>(tree, actor)?,
+        Some(Type::PasskeyAccount) => debug::<fil_actor_passkey::State, _>(tree, actor)?,
+        Some(Type::System) => debug::<fil_actor_system::State, _>(tree, actor)?,
+        Some(Type::Placeholder) | Some(Type::EAM) | Some(Type::EthAccount) | None => Value::Null,
+    };
+
+    Ok(json!({
+        "address": address.to_string(),
+        "type": typ.map(|t| t.name()),
+        "code": cid(&actor.code),
+        "head": cid(&actor.head),
+        "nonce": actor.call_seq_num,
+        "balance": tokens(&actor.balance),
+        "delegated_address": actor.address.map(|a| a.to_string()),
+        "state": state,
+    }))
+}
+
+/// Runs the actor's own invariant checks, returning its `StateSummary` in debug form
+/// along with any violations found.
+pub fn summarize_actor<BS: Blockstore + Debug>(
+    policy: &Policy,
+    tree: &Tree<BS>,
+    typ: Option<Type>,
+    address: &Address,
+    actor: &Actor,
+    prior_epoch: ChainEpoch,
+) -> anyhow::Result<(String, MessageAccumulator)> {
+    let store = tree.store;
+    let (summary, acc) = match typ {
+        Some(Type::Init) => summary(init::check_state_invariants(&load(tree, actor)?, store)),
+        Some(Type::Cron) => summary(cron::check_state_invariants(&load(tree, actor)?)),
+        Some(Type::Account) => {
+            summary(account::check_state_invariants(&load(tree, actor)?, address))
+        }
+        Some(Type::Power) => {
+            summary(power::check_state_invariants(policy, &load(tree, actor)?, store))
+        }
+        Some(Type::Miner) => summary(miner::check_state_invariants(
+            policy,
+            &load(tree, actor)?,
+            store,
+            &actor.balance,
+        )),
+        Some(Type::Market) => summary(market::check_state_invariants(
+            &load(tree, actor)?,
+            store,
+            &actor.balance,
+            prior_epoch + 1,
+        )),
+        Some(Type::PaymentChannel) => {
+            summary(paych::check_state_invariants(&load(tree, actor)?, store, &actor.balance))
+        }
+        Some(Type::Multisig) => {
+            summary(multisig::check_state_invariants(&load(tree, actor)?, store))
+        }
+        Some(Type::Reward) => summary(reward::check_state_invariants(
+            &load(tree, actor)?,
+            prior_epoch,
+            &actor.balance,
+        )),
+        Some(Type::VerifiedRegistry) => {
+            summary(verifreg::check_state_invariants(&load(tree, actor)?, store, prior_epoch))
+        }
+        Some(Type::DataCap) => {
+            let (summary, acc) = datacap::check_state_invariants(&load(tree, actor)?, store);
+            let text = format!(
+                "StateSummary {{ total_supply: {}, balance_map: {:#?}, allowance_map: {:#?} }}",
+                summary.total_supply, summary.balance_map, summary.allowance_map
+            );
+            (text, acc)
+        }
+        Some(Type::EVM) => {
+            summary(evm::check_state_invariants(&load(tree, actor)?, store, actor.address.as_ref()))
+        }
+        Some(Type::EAM) => (String::new(), eam::check_state_invariants(address)),
+        Some(Type::EthAccount) => {
+            (String::new(), ethaccount::check_state_invariants(actor.address.as_ref()))
+        }
+        Some(Type::PasskeyAccount) => {
+            (String::new(), passkey::check_state_invariants(&load(tree, actor)?))
+        }
+        Some(Type::System) | Some(Type::Placeholder) => {
+            (String::new(), MessageAccumulator::default())
+        }
+        None => return Err(anyhow!("unknown actor code {} for {}", actor.code, address)),
+    };
+    Ok((summary, acc))
+}
+
+fn summary<S: Debug>((summary, acc): (S, MessageAccumulator)) -> (String, MessageAccumulator) {
+    (format!("{summary:#?}"), acc)
+}
+
+fn load<S: serde::de::DeserializeOwned, BS: Blockstore>(
+    tree: &Tree<BS>,
+    actor: &Actor,
+) -> anyhow::Result<S> {
+    tree.store.get_cbor(&actor.head)?.ok_or_else(|| anyhow!("state {} not found", actor.head))
+}
+
+fn debug<S: serde::de::DeserializeOwned + std::fmt::Debug, BS: Blockstore>(
+    tree: &Tree<BS>,
+    actor: &Actor,
+) -> anyhow::Result<Value> {
+    Ok(Value::String(format!("{:#?}", load::<S, _>(tree, actor)?)))
+}
+
+fn dump_miner<BS: Blockstore>(
+    policy: &Policy,
+    store: &BS,
+    state: &MinerState,
+) -> anyhow::Result<Value> {
+    let info = state.get_info(store)?;
+    let mut deadlines = Vec::new();
+    state.load_deadlines(store)?.for_each(policy, store, |idx, deadline| {
+        let mut partitions = Vec::new();
+        deadline.partitions_amt(store)?.for_each(|pidx, partition| {
+            partitions.push(json!({
+                "index": pidx,
+                "sectors": bitfield(&partition.sectors),
+                "unproven": bitfield(&partition.unproven),
+                "faults": bitfield(&partition.faults),
+                "recoveries": bitfield(&partition.recoveries),
+                "terminated": bitfield(&partition.terminated),
+                "live_power": power(&partition.live_power),
+                "unproven_power": power(&partition.unproven_power),
+                "faulty_power": power(&partition.faulty_power),
+                "recovering_power": power(&partition.recovering_power),
+            }));
+            Ok(())
+        })?;
+        deadlines.push(json!({
+            "index": idx,
+            "live_sectors": deadline.live_sectors,
+            "total_sectors": deadline.total_sectors,
+            "faulty_power": power(&deadline.faulty_power),
+            "partitions_posted": bitfield(&deadline.partitions_posted),
+            "early_terminations": bitfield(&deadline.early_terminations),
+            "partitions": partitions,
+        }));
+        Ok(())
+    })?;
+
+    Ok(json!({
+        "info": {
+            "owner": info.owner.to_string(),
+            "worker": info.worker.to_string(),
+            "control_addresses": info.control_addresses.iter().map(|a| a.to_string()).collect::<Vec<_>>(),
+            "beneficiary": info.beneficiary.to_string(),
+            "peer_id": hex(&info.peer_id),
+            "window_post_proof_type": format!("{:?}", info.window_post_proof_type),
+            "sector_size": info.sector_size as u64,
+            "window_post_partition_sectors": info.window_post_partition_sectors,
+            "consensus_fault_elapsed": info.consensus_fault_elapsed,
+        },
+        "pre_commit_deposits": tokens(&state.pre_commit_deposits),
+        "locked_funds": tokens(&state.locked_funds),
+        "fee_debt": tokens(&state.fee_debt),
+        "initial_pledge": tokens(&state.initial_pledge),
+        "proving_period_start": state.proving_period_start,
+        "current_deadline": state.current_deadline,
+        "deadline_cron_active": state.deadline_cron_active,
+        "early_terminations": bitfield(&state.early_terminations),
+        "deadlines": deadlines,
+    }))
+}
+
+fn dump_market<BS: Blockstore>(store: &BS, state: &MarketState) -> anyhow::Result<Value> {
+    let states = DealMetaArray::load(&state.states, store)?;
+    let mut deals = Vec::new();
+    DealArray::load(&state.proposals, store)?.for_each(|deal_id, proposal| {
+        let deal_state = states.get(deal_id)?;
+        deals.push(json!({
+            "id": deal_id,
+            "piece_cid": cid(&proposal.piece_cid),
+            "piece_size": proposal.piece_size.0,
+            "verified_deal": proposal.verified_deal,
+            "client": proposal.client.to_string(),
+            "provider": proposal.provider.to_string(),
+            "start_epoch": proposal.start_epoch,
+            "end_epoch": proposal.end_epoch,
+            "storage_price_per_epoch": tokens(&proposal.storage_price_per_epoch),
+            "provider_collateral": tokens(&proposal.provider_collateral),
+            "client_collateral": tokens(&proposal.client_collateral),
+            "state": deal_state.map(|s| json!({
+                "sector_start_epoch": s.sector_start_epoch,
+                "last_updated_epoch": s.last_updated_epoch,
+                "slash_epoch": s.slash_epoch,
+                "verified_claim": s.verified_claim,
+            })),
+        }));
+        Ok(())
+    })?;
+
+    Ok(json!({
+        "next_id": state.next_id,
+        "last_cron": state.last_cron,
+        "total_client_locked_collateral": tokens(&state.total_client_locked_collateral),
+        "total_provider_locked_collateral": tokens(&state.total_provider_locked_collateral),
+        "total_client_storage_fee": tokens(&state.total_client_storage_fee),
+        "escrow": balances(store, &state.escrow_table)?,
+        "locked": balances(store, &state.locked_table)?,
+        "deals": deals,
+    }))
+}
+
+fn dump_verifreg<BS: Blockstore>(store: &BS, state: &VerifregState) -> anyhow::Result<Value> {
+    let mut verifiers = serde_json::Map::new();
+    state.load_verifiers(store)?.for_each(|key, cap| {
+        verifiers.insert(Address::from_bytes(key)?.to_string(), Value::String(cap.0.to_string()));
+        Ok(())
+    })?;
+
+    let mut allocations = Vec::new();
+    let mut allocs = state.load_allocs(store)?;
+    let mut claims = Vec::new();
+    let mut all_claims = state.load_claims(store)?;
+    // The outer keys of both tables are client (allocations) or provider (claims) IDs.
+    for key in outer_keys(store, &state.allocations)? {
+        allocs.for_each(key, |id, alloc| {
+            allocations.push(json!({
+                "id": parse_uint_key(id)?,
+                "client": alloc.client,
+                "provider": alloc.provider,
+                "data": cid(&alloc.data),
+                "size": alloc.size.0,
+                "term_min": alloc.term_min,
+                "term_max": alloc.term_max,
+                "expiration": alloc.expiration,
+            }));
+            Ok(())
+        })?;
+    }
+    for key in outer_keys(store, &state.claims)? {
+        all_claims.for_each(key, |id, claim| {
+            claims.push(json!({
+                "id": parse_uint_key(id)?,
+                "provider": claim.provider,
+                "client": claim.client,
+                "data": cid(&claim.data),
+                "size": claim.size.0,
+                "term_min": claim.term_min,
+                "term_max": claim.term_max,
+                "term_start": claim.term_start,
+                "sector": claim.sector,
+            }));
+            Ok(())
+        })?;
+    }
+
+    Ok(json!({
+        "root_key": state.root_key.to_string(),
+        "next_allocation_id": state.next_allocation_id,
+        "verifiers": verifiers,
+        "allocations": allocations,
+        "claims": claims,
+    }))
+}
+
+pub(crate) fn outer_keys<BS: Blockstore>(store: &BS, root: &Cid) -> anyhow::Result<Vec<u64>> {
+    let mut keys = Vec::new();
+    make_map_with_root::<_, Cid>(root, store)?.for_each(|key, _| {
+        keys.push(parse_uint_key(key)?);
+        Ok(())
+    })?;
+    Ok(keys)
+}
+
+fn balances<BS: Blockstore>(store: &BS, root: &Cid) -> anyhow::Result<Value> {
+    let mut entries = serde_json::Map::new();
+    BalanceTable::from_root(store, root)?.0.for_each(|key, amount| {
+        entries.insert(Address::from_bytes(key)?.to_string(), tokens(amount));
+        Ok(())
+    })?;
+    Ok(Value::Object(entries))
+}
+
+pub(crate) fn cid(c: &Cid) -> Value {
+    json!({ "/": c.to_string() })
+}
+
+pub(crate) fn tokens(amount: &TokenAmount) -> Value {
+    Value::String(amount.atto().to_string())
+}
+
+pub(crate) fn power(p: &PowerPair) -> Value {
+    json!({ "raw": p.raw.to_string(), "qa": p.qa.to_string() })
+}
+
+/// Renders a bitfield as its count and inclusive runs of set bits.
+pub(crate) fn bitfield(bf: &BitField) -> Value {
+    let runs: Vec<[u64; 2]> = bf.ranges().map(|r| [r.start, r.end - 1]).collect();
+    json!({ "count": bf.len(), "runs": runs })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,59 @@
+//! JSON codecs for the parameters and return values of every builtin actor's methods,
+//! looked up by actor type and method number.
+
+use anyhow::anyhow;
+use fil_actors_runtime::json::{MethodCodec, Value};
+use fil_actors_runtime::runtime::builtins::Type;
+use fvm_shared::MethodNum;
+
+/// Returns the codecs of all methods of an actor type.
+pub fn methods(typ: Type) -> Vec<MethodCodec> {
+    match typ {
+        Type::System => fil_actor_system::json::methods(),
+        Type::Init => fil_actor_init::json::methods(),
+        Type::Cron => fil_actor_cron::json::methods(),
+        Type::Account => fil_actor_account::json::methods(),
+        Type::Power => fil_actor_power::json::methods(),
+        Type::Miner => fil_actor_miner::json::methods(),
+        Type::Market => fil_actor_market::json::methods(),
+        Type::PaymentChannel => fil_actor_paych::json::methods(),
+        Type::Multisig => fil_actor_multisig::json::methods(),
+        Type::Reward => fil_actor_reward::json::methods(),
+        Type::VerifiedRegistry => fil_actor_verifreg::json::methods(),
+        Type::DataCap => fil_actor_datacap::json::methods(),
+        Type::Placeholder => vec![],
+        Type::EVM => fil_actor_evm::json::methods(),
+        Type::EAM => fil_actor_eam::json::methods(),
+        Type::EthAccount => fil_actor_ethaccount::json::methods(),
+        Type::PasskeyAccount => fil_actor_passkey::json::methods(),
+    }
+}
+
+/// Returns the codecs of a method, if the actor type has a method with that number.
+pub fn method(typ: Type, method: MethodNum) -> Option<MethodCodec> {
+    methods(typ).into_iter().find(|m| m.method == method)
+}
+
+/// Decodes CBOR-encoded method parameters as JSON. Empty parameters decode to null.
+pub fn decode_params(typ: Type, method: MethodNum, params: &[u8]) -> anyhow::Result<Value> {
+    let codec = lookup(typ, method)?;
+    (codec.params.decode)(params)
+        .map_err(|e| anyhow!("{}.{} params: {}", typ.name(), codec.name, e))
+}
+
+/// Decodes a CBOR-encoded method return value as JSON. An empty return value decodes to null.
+pub fn decode_return(typ: Type, method: MethodNum, ret: &[u8]) -> anyhow::Result<Value> {
+    let codec = lookup(typ, method)?;
+    (codec.ret.decode)(ret).map_err(|e| anyhow!("{}.{} return: {}", typ.name(), codec.name, e))
+}
+
+/// Encodes JSON method parameters as CBOR. Null encodes to empty parameters.
+pub fn encode_params(typ: Type, method: MethodNum, params: &Value) -> anyhow::Result<Vec<u8>> {
+    let codec = lookup(typ, method)?;
+    (codec.params.encode)(params)
+        .map_err(|e| anyhow!("{}.{} params: {}", typ.name(), codec.name, e))
+}
+
+fn lookup(typ: Type, num: MethodNum) -> anyhow::Result<MethodCodec> {
+    method(typ, num).ok_or_else(|| anyhow!("{} actor has no method {}", typ.name(), num))
+}
@@ -1 +1,9 @@
+pub mod blockstore;
 pub mod check;
+#[cfg(feature = "cli")]
+pub mod diff;
+#[cfg(feature = "cli")]
+pub mod dump;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod snapshot;
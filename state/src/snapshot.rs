@@ -0,0 +1,140 @@
+//! Loading of state trees from exported chain snapshots.
+
+#[cfg(feature = "cli")]
+use std::io::Read;
+
+use anyhow::{anyhow, Context};
+use bimap::BiBTreeMap;
+use cid::Cid;
+use fil_actor_system::State as SystemState;
+use fil_actors_runtime::runtime::builtins::Type;
+#[cfg(feature = "cli")]
+use fil_actors_runtime::runtime::Policy;
+use fil_actors_runtime::{Map, SYSTEM_ACTOR_ADDR};
+use fvm_ipld_blockstore::Blockstore;
+use fvm_ipld_encoding::tuple::*;
+use fvm_ipld_encoding::{from_slice, CborStore};
+use fvm_shared::clock::ChainEpoch;
+use fvm_shared::HAMT_BIT_WIDTH;
+use num_traits::FromPrimitive;
+use serde::de::{DeserializeOwned, IgnoredAny};
+
+use crate::check::Tree;
+
+/// The versioned root of a state tree (version 1 and later).
+#[derive(Serialize_tuple, Deserialize_tuple, Clone, Debug)]
+pub struct StateRoot {
+    pub version: u64,
+    /// Root of the HAMT of actors.
+    pub actors: Cid,
+    pub info: Cid,
+}
+
+/// The fields of a block header needed to locate its parent state.
+/// Fields that aren't needed are skipped without being decoded.
+#[derive(Deserialize_tuple)]
+struct BlockHeader {
+    _miner: IgnoredAny,
+    _ticket: IgnoredAny,
+    _election_proof: IgnoredAny,
+    _beacon_entries: IgnoredAny,
+    _win_post_proof: IgnoredAny,
+    _parents: IgnoredAny,
+    _parent_weight: IgnoredAny,
+    height: ChainEpoch,
+    parent_state_root: Cid,
+    _parent_message_receipts: IgnoredAny,
+    _messages: IgnoredAny,
+    _bls_aggregate: IgnoredAny,
+    _timestamp: IgnoredAny,
+    _block_sig: IgnoredAny,
+    _fork_signaling: IgnoredAny,
+    _parent_base_fee: IgnoredAny,
+}
+
+/// Streams every block of a CAR file into the store, returning the CAR's roots.
+/// Blocks are written one at a time, so a disk-backed store keeps memory use bounded.
+#[cfg(feature = "cli")]
+pub fn load_car<BS: Blockstore, R: Read + Send + Unpin>(
+    store: &BS,
+    reader: R,
+) -> anyhow::Result<Vec<Cid>> {
+    futures::executor::block_on(fvm_ipld_car::load_car_unchecked(
+        store,
+        futures::io::AllowStdIo::new(reader),
+    ))
+    .map_err(|e| anyhow!("failed to load CAR: {e}"))
+}
+
+/// Reads the policy of the network a snapshot was taken from, from a JSON policy file,
+/// or returns the policy the actors were built with if there is none.
+#[cfg(feature = "cli")]
+pub fn load_policy(path: Option<&str>) -> anyhow::Result<Policy> {
+    match path {
+        Some(path) => {
+            Policy::from_file(path).with_context(|| format!("failed to load policy from {path}"))
+        }
+        None => Ok(Policy::default()),
+    }
+}
+
+/// Resolves a CAR root to the root of the actors HAMT and the epoch preceding that state.
+/// The root may be a block header (as in chain snapshots), a versioned `StateRoot`,
+/// or the actors HAMT itself. The epoch is only known when the root is a block header.
+pub fn resolve_state_root<BS: Blockstore>(
+    store: &BS,
+    root: &Cid,
+) -> anyhow::Result<(Cid, Option<ChainEpoch>)> {
+    let (state_root, prior_epoch) = match get_block_as::<BlockHeader, _>(store, root)? {
+        Some(header) => (header.parent_state_root, Some(header.height - 1)),
+        None => (*root, None),
+    };
+    match get_block_as::<StateRoot, _>(store, &state_root)? {
+        Some(versioned) => Ok((versioned.actors, prior_epoch)),
+        None => Ok((state_root, prior_epoch)),
+    }
+}
+
+/// Decodes a block as a `T`, or returns None if the block is missing or isn't a `T`.
+/// Failures to read the store are returned as errors.
+fn get_block_as<T: DeserializeOwned, BS: Blockstore>(
+    store: &BS,
+    cid: &Cid,
+) -> anyhow::Result<Option<T>> {
+    let block = store.get(cid).with_context(|| format!("failed to read block {cid}"))?;
+    Ok(block.and_then(|block| from_slice(&block).ok()))
+}
+
+/// Loads the tree of actors from a chain state root.
+/// Unlike the test VM, the chain's state tree uses the network's standard HAMT bit width.
+pub fn load_state_tree<'a, BS: Blockstore>(
+    store: &'a BS,
+    root: &Cid,
+) -> anyhow::Result<Tree<'a, BS>> {
+    let map = Map::load_with_bit_width(root, store, HAMT_BIT_WIDTH)?;
+    Ok(Tree { map, store })
+}
+
+/// Builds the mapping of actor code CIDs to actor types from the registry
+/// held by the system actor.
+pub fn load_manifest<BS: Blockstore>(tree: &Tree<BS>) -> anyhow::Result<BiBTreeMap<Cid, Type>> {
+    let system = tree
+        .map
+        .get(&SYSTEM_ACTOR_ADDR.to_bytes())?
+        .ok_or_else(|| anyhow!("system actor not found"))?;
+    let state = tree
+        .store
+        .get_cbor::<SystemState>(&system.head)?
+        .ok_or_else(|| anyhow!("system actor state not found"))?;
+    let registry = state.get_builtin_actors(tree.store).map_err(|e| anyhow!(e))?;
+
+    let mut manifest = BiBTreeMap::new();
+    for (name, code) in registry {
+        let typ = (1..)
+            .map_while(Type::from_i32)
+            .find(|t| t.name() == name)
+            .with_context(|| format!("unknown actor type {name} in builtin actor registry"))?;
+        manifest.insert(code, typ);
+    }
+    Ok(manifest)
+}
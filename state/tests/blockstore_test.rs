@@ -0,0 +1,52 @@
+use cid::multihash::Code;
+use fil_builtin_actors_state::blockstore::DiskBlockstore;
+use fvm_ipld_blockstore::{Blockstore, MemoryBlockstore};
+use fvm_ipld_encoding::CborStore;
+
+#[test]
+fn reads_back_blocks_written_to_disk() {
+    let store = DiskBlockstore::temporary().unwrap();
+    assert!(store.is_empty());
+
+    let first = store.put_cbor(&"first", Code::Blake2b256).unwrap();
+    let second = store.put_cbor(&vec![1u64, 2, 3], Code::Blake2b256).unwrap();
+    assert_eq!(2, store.len());
+    assert!(store.has(&first).unwrap());
+    assert_eq!(Some("first".to_string()), store.get_cbor(&first).unwrap());
+    assert_eq!(Some(vec![1u64, 2, 3]), store.get_cbor(&second).unwrap());
+
+    // Blocks already in the store aren't written again.
+    let len = std::fs::metadata(store.path()).unwrap().len();
+    store.put_cbor(&"first", Code::Blake2b256).unwrap();
+    assert_eq!(2, store.len());
+    assert_eq!(len, std::fs::metadata(store.path()).unwrap().len());
+
+    let missing = MemoryBlockstore::new().put_cbor(&"missing", Code::Blake2b256).unwrap();
+    assert!(!store.has(&missing).unwrap());
+    assert_eq!(None, store.get(&missing).unwrap());
+}
+
+#[test]
+fn removes_temporary_file_on_drop() {
+    let store = DiskBlockstore::temporary().unwrap();
+    let path = store.path().to_path_buf();
+    assert!(path.exists());
+    drop(store);
+    assert!(!path.exists());
+}
+
+#[test]
+fn keeps_created_file_on_drop() {
+    let path = std::env::temp_dir().join(format!("fil-state-test-{}.blocks", std::process::id()));
+    let cid = {
+        let store = DiskBlockstore::create(&path).unwrap();
+        store.put_cbor(&"kept", Code::Blake2b256).unwrap()
+    };
+    assert!(path.exists());
+
+    // A store created over an existing file starts empty.
+    let store = DiskBlockstore::create(&path).unwrap();
+    assert!(!store.has(&cid).unwrap());
+    assert_eq!(0, std::fs::metadata(&path).unwrap().len());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,109 @@
+use cid::multihash::Code;
+use cid::Cid;
+use fil_actors_runtime::make_empty_map;
+use fil_builtin_actors_state::blockstore::DiskBlockstore;
+use fil_builtin_actors_state::check::Actor;
+use fil_builtin_actors_state::snapshot::{
+    load_car, load_state_tree, resolve_state_root, StateRoot,
+};
+use fvm_ipld_blockstore::{Blockstore, MemoryBlockstore};
+use fvm_ipld_encoding::CborStore;
+use fvm_shared::address::Address;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::HAMT_BIT_WIDTH;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct CarHeader {
+    roots: Vec<Cid>,
+    version: u64,
+}
+
+fn write_varint(out: &mut Vec<u8>, mut n: usize) {
+    while n >= 0x80 {
+        out.push((n as u8) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/// Encodes a version 1 CAR holding the given blocks of the store.
+fn encode_car(store: &MemoryBlockstore, roots: Vec<Cid>, blocks: &[Cid]) -> Vec<u8> {
+    let mut car = vec![];
+    let header = fvm_ipld_encoding::to_vec(&CarHeader { roots, version: 1 }).unwrap();
+    write_varint(&mut car, header.len());
+    car.extend(header);
+    for cid in blocks {
+        let cid_bytes = cid.to_bytes();
+        let data = store.get(cid).unwrap().unwrap();
+        write_varint(&mut car, cid_bytes.len() + data.len());
+        car.extend(cid_bytes);
+        car.extend(data);
+    }
+    car
+}
+
+#[test]
+fn loads_state_tree_from_car() {
+    let source = MemoryBlockstore::new();
+    let address = Address::new_id(1000);
+    let actor = Actor {
+        code: Cid::default(),
+        head: source.put_cbor(&"head", Code::Blake2b256).unwrap(),
+        call_seq_num: 3,
+        balance: TokenAmount::from_whole(5),
+        address: None,
+    };
+    let mut actors = make_empty_map(&source, HAMT_BIT_WIDTH);
+    actors.set(address.to_bytes().into(), actor).unwrap();
+    let actors_root = actors.flush().unwrap();
+    let state_root = source
+        .put_cbor(
+            &StateRoot { version: 4, actors: actors_root, info: Cid::default() },
+            Code::Blake2b256,
+        )
+        .unwrap();
+
+    let car = encode_car(&source, vec![state_root], &[state_root, actors_root]);
+    let store = DiskBlockstore::temporary().unwrap();
+    assert_eq!(vec![state_root], load_car(&store, car.as_slice()).unwrap());
+    assert_eq!(2, store.len());
+
+    // A versioned state root resolves to its actors, with no epoch.
+    assert_eq!((actors_root, None), resolve_state_root(&store, &state_root).unwrap());
+    let tree = load_state_tree(&store, &actors_root).unwrap();
+    let loaded = tree.map.get(&address.to_bytes()).unwrap().unwrap();
+    assert_eq!(3, loaded.call_seq_num);
+    assert_eq!(TokenAmount::from_whole(5), loaded.balance);
+}
+
+#[test]
+fn rejects_malformed_car_header() {
+    let store = DiskBlockstore::temporary().unwrap();
+    assert!(load_car(&store, &[0x02, 0xff, 0xff][..]).is_err());
+    assert!(store.is_empty());
+}
+
+// A store that fails every read.
+struct FailingBlockstore;
+
+impl Blockstore for FailingBlockstore {
+    fn get(&self, _: &Cid) -> anyhow::Result<Option<Vec<u8>>> {
+        Err(anyhow::anyhow!("disk error"))
+    }
+
+    fn put_keyed(&self, _: &Cid, _: &[u8]) -> anyhow::Result<()> {
+        Err(anyhow::anyhow!("disk error"))
+    }
+}
+
+#[test]
+fn resolving_state_root_propagates_store_errors() {
+    let err = resolve_state_root(&FailingBlockstore, &Cid::default()).unwrap_err();
+    assert!(format!("{err:#}").contains("disk error"));
+
+    // A root that is neither a block header nor a versioned state root is taken as the actors.
+    let store = MemoryBlockstore::new();
+    let root = store.put_cbor(&"actors", Code::Blake2b256).unwrap();
+    assert_eq!((root, None), resolve_state_root(&store, &root).unwrap());
+}
@@ -13,6 +13,7 @@ use fil_actor_init::{Actor as InitActor, ExecReturn, State as InitState};
 use fil_actor_market::{Actor as MarketActor, Method as MarketMethod, State as MarketState};
 use fil_actor_miner::{Actor as MinerActor, MinerInfo, State as MinerState};
 use fil_actor_multisig::Actor as MultisigActor;
+use fil_actor_passkey::PasskeyAccountActor;
 use fil_actor_paych::Actor as PaychActor;
 use fil_actor_power::{Actor as PowerActor, Method as MethodPower, State as PowerState};
 use fil_actor_reward::{Actor as RewardActor, State as RewardState};
@@ -83,6 +84,7 @@ pub struct VM<'bs> {
     network_version: NetworkVersion,
     curr_epoch: ChainEpoch,
     invocations: RefCell<Vec<InvocationTrace>>,
+    policy: Policy,
 }
 
 pub struct MinerBalances {
@@ -133,6 +135,7 @@ impl<'bs> VM<'bs> {
             network_version: NetworkVersion::V16,
             curr_epoch: ChainEpoch::zero(),
             invocations: RefCell::new(vec![]),
+            policy: test_policy(),
         }
     }
 
@@ -140,6 +143,15 @@ impl<'bs> VM<'bs> {
         Self { total_fil, ..self }
     }
 
+    /// Replaces the policy the actors run with, e.g. one loaded with [`Policy::from_file`].
+    pub fn with_policy(self, policy: Policy) -> Self {
+        Self { policy, ..self }
+    }
+
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
     pub fn new_with_singletons(store: &'bs MemoryBlockstore) -> VM<'bs> {
         let reward_total = TokenAmount::from_whole(1_100_000_000i64);
         let faucet_total = TokenAmount::from_whole(1_000_000_000i64);
@@ -294,6 +306,7 @@ impl<'bs> VM<'bs> {
             network_version: self.network_version,
             curr_epoch: epoch,
             invocations: RefCell::new(vec![]),
+            policy: self.policy,
         }
     }
 
@@ -460,7 +473,7 @@ impl<'bs> VM<'bs> {
             allow_side_effects: true,
             caller_validated: false,
             read_only: false,
-            policy: &Policy::default(),
+            policy: &self.policy,
             subinvocations: RefCell::new(vec![]),
         };
         let res = new_ctx.invoke();
@@ -507,11 +520,10 @@ impl<'bs> VM<'bs> {
             })
             .unwrap();
 
-        let policy = Policy::default();
         let state_tree = Tree::load(&self.store, &self.state_root.borrow()).unwrap();
         check_state_invariants(
             &manifest,
-            &policy,
+            &self.policy,
             state_tree,
             &self.total_fil,
             self.get_epoch() - 1,
@@ -763,6 +775,9 @@ impl<'invocation, 'bs> InvocationCtx<'invocation, 'bs> {
             Type::EVM => EvmContractActor::invoke_method(self, self.msg.method, params),
             Type::EAM => EamActor::invoke_method(self, self.msg.method, params),
             Type::EthAccount => EthAccountActor::invoke_method(self, self.msg.method, params),
+            Type::PasskeyAccount => {
+                PasskeyAccountActor::invoke_method(self, self.msg.method, params)
+            }
         };
         if res.is_ok() && !self.caller_validated {
             res = Err(actor_error!(assertion_failed, "failed to validate caller"));
@@ -264,8 +264,8 @@ pub fn precommit_sectors_v2(
     let expiration = match exp {
         None => {
             v.get_epoch()
-                + Policy::default().min_sector_expiration
-                + max_prove_commit_duration(&Policy::default(), seal_proof).unwrap()
+                + v.policy().min_sector_expiration
+                + max_prove_commit_duration(v.policy(), seal_proof).unwrap()
         }
         Some(e) => e,
     };
@@ -615,28 +615,24 @@ where
 
 pub fn miner_dline_info(v: &VM, m: Address) -> DeadlineInfo {
     let st = v.get_state::<MinerState>(m).unwrap();
-    new_deadline_info_from_offset_and_epoch(
-        &Policy::default(),
-        st.proving_period_start,
-        v.get_epoch(),
-    )
+    new_deadline_info_from_offset_and_epoch(v.policy(), st.proving_period_start, v.get_epoch())
 }
 
 pub fn sector_deadline(v: &VM, m: Address, s: SectorNumber) -> (u64, u64) {
     let st = v.get_state::<MinerState>(m).unwrap();
-    st.find_sector(&Policy::default(), v.store, s).unwrap()
+    st.find_sector(v.policy(), v.store, s).unwrap()
 }
 
 pub fn check_sector_active(v: &VM, m: Address, s: SectorNumber) -> bool {
     let (d_idx, p_idx) = sector_deadline(v, m, s);
     let st = v.get_state::<MinerState>(m).unwrap();
-    st.check_sector_active(&Policy::default(), v.store, d_idx, p_idx, s, true).unwrap()
+    st.check_sector_active(v.policy(), v.store, d_idx, p_idx, s, true).unwrap()
 }
 
 pub fn check_sector_faulty(v: &VM, m: Address, d_idx: u64, p_idx: u64, s: SectorNumber) -> bool {
     let st = v.get_state::<MinerState>(m).unwrap();
     let deadlines = st.load_deadlines(v.store).unwrap();
-    let deadline = deadlines.load_deadline(&Policy::default(), v.store, d_idx).unwrap();
+    let deadline = deadlines.load_deadline(v.policy(), v.store, d_idx).unwrap();
     let partition = deadline.load_partition(v.store, p_idx).unwrap();
     partition.faults.get(s)
 }
@@ -644,7 +640,7 @@ pub fn check_sector_faulty(v: &VM, m: Address, d_idx: u64, p_idx: u64, s: Sector
 pub fn deadline_state(v: &VM, m: Address, d_idx: u64) -> Deadline {
     let st = v.get_state::<MinerState>(m).unwrap();
     let deadlines = st.load_deadlines(v.store).unwrap();
-    deadlines.load_deadline(&Policy::default(), v.store, d_idx).unwrap()
+    deadlines.load_deadline(v.policy(), v.store, d_idx).unwrap()
 }
 
 pub fn sector_info(v: &VM, m: Address, s: SectorNumber) -> SectorOnChainInfo {
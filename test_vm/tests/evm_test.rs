@@ -309,6 +309,46 @@ fn test_evm_empty_initcode() {
     );
 }
 
+#[test]
+fn test_evm_tombstone_invariants() {
+    let store = MemoryBlockstore::new();
+    let v = VM::new_with_singletons(&store);
+
+    let account = create_accounts(&v, 1, TokenAmount::from_whole(10_000))[0];
+    let create_return: fil_actor_eam::CreateExternalReturn = v
+        .apply_message(
+            account,
+            EAM_ACTOR_ADDR,
+            TokenAmount::zero(),
+            fil_actor_eam::Method::CreateExternal as u64,
+            Some(fil_actor_eam::CreateExternalParams(vec![])),
+        )
+        .unwrap()
+        .ret
+        .unwrap()
+        .deserialize()
+        .expect("failed to decode results");
+    let contract = Address::new_id(create_return.actor_id);
+    let set_tombstone = |origin: ActorID, nonce: u64| {
+        v.mutate_state(contract, |st: &mut fil_actor_evm::State| {
+            st.tombstone = Some(fil_actor_evm::Tombstone { origin, nonce })
+        })
+    };
+
+    // The account's create message was its first, so only nonce 0 precedes its sequence number.
+    set_tombstone(account.id().unwrap(), 0);
+    v.assert_state_invariants();
+
+    set_tombstone(account.id().unwrap(), 1);
+    assert_eq!(vec!["evm.tombstone_nonce"], v.check_state_invariants().unwrap().codes());
+
+    set_tombstone(EAM_ACTOR_ID, 0);
+    assert_eq!(vec!["evm.tombstone_origin"], v.check_state_invariants().unwrap().codes());
+
+    set_tombstone(account.id().unwrap() + 1000, 0);
+    assert_eq!(vec!["evm.tombstone_origin"], v.check_state_invariants().unwrap().codes());
+}
+
 #[test]
 #[allow(non_snake_case)]
 fn test_evm_staticcall() {
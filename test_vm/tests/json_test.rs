@@ -0,0 +1,98 @@
+use fil_actor_market::{
+    ClientDealProposal, DealProposal, Label, Method as MarketMethod, PublishStorageDealsParams,
+};
+use fil_actor_miner::{DeclareFaultsParams, FaultDeclaration, Method as MinerMethod};
+use fil_actors_runtime::cbor::serialize;
+use fil_actors_runtime::runtime::builtins::Type;
+use fil_actors_runtime::test_utils::make_piece_cid;
+use fil_builtin_actors_state::json::{decode_params, decode_return, encode_params, methods};
+use fvm_ipld_bitfield::BitField;
+use fvm_shared::address::Address;
+use fvm_shared::crypto::signature::Signature;
+use fvm_shared::econ::TokenAmount;
+use fvm_shared::piece::PaddedPieceSize;
+use num_traits::FromPrimitive;
+use serde_json::json;
+
+#[test]
+fn publish_storage_deals_params_round_trip() {
+    let piece_cid = make_piece_cid(b"piece");
+    let params = PublishStorageDealsParams {
+        deals: vec![ClientDealProposal {
+            proposal: DealProposal {
+                piece_cid,
+                piece_size: PaddedPieceSize(2048),
+                verified_deal: false,
+                client: Address::new_id(101),
+                provider: Address::new_id(102),
+                label: Label::String("label".to_string()),
+                start_epoch: 100,
+                end_epoch: 200,
+                storage_price_per_epoch: TokenAmount::from_atto(10),
+                provider_collateral: TokenAmount::from_whole(1),
+                client_collateral: TokenAmount::from_atto(0),
+            },
+            client_signature: Signature::new_bls(vec![0xab, 0xcd]),
+        }],
+    };
+    let cbor = serialize(&params, "params").unwrap();
+
+    let value = decode_params(Type::Market, MarketMethod::PublishStorageDeals as u64, cbor.bytes())
+        .unwrap();
+    let proposal = &value["deals"][0]["proposal"];
+    assert_eq!(json!({ "/": piece_cid.to_string() }), proposal["piece_cid"]);
+    assert_eq!(json!("f0101"), proposal["client"]);
+    assert_eq!(json!({ "string": "label" }), proposal["label"]);
+    assert_eq!(json!("1000000000000000000"), proposal["provider_collateral"]);
+    assert_eq!(json!({ "type": 2, "data": "abcd" }), value["deals"][0]["client_signature"]);
+
+    let encoded =
+        encode_params(Type::Market, MarketMethod::PublishStorageDeals as u64, &value).unwrap();
+    assert_eq!(cbor.bytes().to_vec(), encoded);
+}
+
+#[test]
+fn bitfields_are_run_lengths() {
+    let params = DeclareFaultsParams {
+        faults: vec![FaultDeclaration {
+            deadline: 1,
+            partition: 0,
+            sectors: BitField::try_from_bits([1, 2, 5]).unwrap(),
+        }],
+    };
+    let cbor = serialize(&params, "params").unwrap();
+
+    let value =
+        decode_params(Type::Miner, MinerMethod::DeclareFaults as u64, cbor.bytes()).unwrap();
+    assert_eq!(json!([1, 2, 2, 1]), value["faults"][0]["sectors"]);
+    assert_eq!(
+        cbor.bytes().to_vec(),
+        encode_params(Type::Miner, MinerMethod::DeclareFaults as u64, &value).unwrap()
+    );
+
+    // Empty runs other than the first are rejected, as they have no canonical encoding.
+    let bad = json!({ "faults": [{ "deadline": 1, "partition": 0, "sectors": [1, 0, 2] }] });
+    assert!(encode_params(Type::Miner, MinerMethod::DeclareFaults as u64, &bad).is_err());
+}
+
+#[test]
+fn absent_values_are_null() {
+    let value = decode_return(Type::Market, MarketMethod::AddBalance as u64, &[]).unwrap();
+    assert!(value.is_null());
+    assert!(encode_params(Type::Miner, MinerMethod::RepayDebt as u64, &json!(null))
+        .unwrap()
+        .is_empty());
+    assert!(decode_params(Type::Market, 9999, &[]).is_err());
+}
+
+#[test]
+fn method_numbers_are_unique() {
+    for code in 1..=17 {
+        let typ = Type::from_i32(code).unwrap();
+        let mut nums: Vec<_> = methods(typ).iter().map(|m| m.method).collect();
+        let count = nums.len();
+        nums.sort_unstable();
+        nums.dedup();
+        assert_eq!(count, nums.len(), "duplicate method number for {}", typ.name());
+    }
+}
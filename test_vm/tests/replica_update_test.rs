@@ -2,9 +2,10 @@ use fil_actor_cron::Method as CronMethod;
 use fil_actor_market::Method as MarketMethod;
 use fil_actor_miner::{
     power_for_sector, DisputeWindowedPoStParams, ExpirationExtension, ExtendSectorExpirationParams,
-    Method as MinerMethod, PowerPair, ProveCommitSectorParams, ProveReplicaUpdatesParams,
-    ProveReplicaUpdatesParams2, ReplicaUpdate, ReplicaUpdate2, SectorOnChainInfo, Sectors,
-    State as MinerState, TerminateSectorsParams, TerminationDeclaration, SECTORS_AMT_BITWIDTH,
+    Method as MinerMethod, PieceActivationManifest, PowerPair, ProveCommitSectorParams,
+    ProveReplicaUpdatesParams, ProveReplicaUpdatesParams2, ProveReplicaUpdatesParams3,
+    ReplicaUpdate, ReplicaUpdate2, ReplicaUpdate3, SectorOnChainInfo, Sectors, State as MinerState,
+    TerminateSectorsParams, TerminationDeclaration, SECTORS_AMT_BITWIDTH,
 };
 use fil_actor_power::{Method as PowerMethod, UpdateClaimedPowerParams};
 use fil_actor_reward::Method as RewardMethod;
@@ -1175,6 +1176,146 @@ fn replica_update_verified_deal_max_term_violated() {
     );
 }
 
+#[test]
+fn replica_update_pieces_without_deals() {
+    let store = &MemoryBlockstore::new();
+    let (v, maddr, robust, worker, d_idx, p_idx) = create_miner_and_sector(store);
+    let sector_number = 100;
+    let old_sector_info = sector_info(&v, maddr, sector_number);
+
+    // The test VM computes the same unsealed CID for any pieces.
+    let new_cid = make_sealed_cid(b"replica1");
+    let replica_update = ReplicaUpdate3 {
+        sector_number,
+        deadline: d_idx,
+        partition: p_idx,
+        new_sealed_cid: new_cid,
+        new_unsealed_cid: make_piece_cid(b"unsealed from itest vm"),
+        deals: vec![],
+        pieces: vec![piece_manifest(b"piece")],
+        update_proof_type: fvm_shared::sector::RegisteredUpdateProof::StackedDRG32GiBV1,
+        replica_proof: vec![],
+    };
+    let updated_sectors: BitField = apply_ok(
+        &v,
+        worker,
+        robust,
+        TokenAmount::zero(),
+        MinerMethod::ProveReplicaUpdates3 as u64,
+        Some(ProveReplicaUpdatesParams3 { updates: vec![replica_update] }),
+    )
+    .deserialize()
+    .unwrap();
+    assert_eq!(vec![sector_number], bf_all(updated_sectors));
+
+    let new_sector_info = sector_info(&v, maddr, sector_number);
+    assert!(new_sector_info.deal_ids.is_empty());
+    assert_eq!(old_sector_info.sealed_cid, new_sector_info.sector_key_cid.unwrap());
+    assert_eq!(new_cid, new_sector_info.sealed_cid);
+    v.assert_state_invariants();
+}
+
+#[test]
+fn replica_update_pieces_not_matching_unsealed_cid_failure() {
+    let store = &MemoryBlockstore::new();
+    let (v, maddr, robust, worker, d_idx, p_idx) = create_miner_and_sector(store);
+    let sector_number = 100;
+    let old_sector_info = sector_info(&v, maddr, sector_number);
+
+    let replica_update = ReplicaUpdate3 {
+        sector_number,
+        deadline: d_idx,
+        partition: p_idx,
+        new_sealed_cid: make_sealed_cid(b"replica1"),
+        new_unsealed_cid: make_piece_cid(b"some other unsealed cid"),
+        deals: vec![],
+        pieces: vec![piece_manifest(b"piece")],
+        update_proof_type: fvm_shared::sector::RegisteredUpdateProof::StackedDRG32GiBV1,
+        replica_proof: vec![],
+    };
+    apply_code(
+        &v,
+        worker,
+        robust,
+        TokenAmount::zero(),
+        MinerMethod::ProveReplicaUpdates3 as u64,
+        Some(ProveReplicaUpdatesParams3 { updates: vec![replica_update] }),
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+    );
+
+    // The sector is untouched.
+    let new_sector_info = sector_info(&v, maddr, sector_number);
+    assert_eq!(old_sector_info.sealed_cid, new_sector_info.sealed_cid);
+    assert!(new_sector_info.sector_key_cid.is_none());
+    v.assert_state_invariants();
+}
+
+#[test]
+fn replica_update_deals_and_pieces_failure() {
+    let store = &MemoryBlockstore::new();
+    let (v, maddr, robust, worker, d_idx, p_idx) = create_miner_and_sector(store);
+    let sector_number = 100;
+    let old_sector_info = sector_info(&v, maddr, sector_number);
+    let deal_ids = create_deals(1, &v, worker, worker, maddr);
+
+    // The unsealed CID matches the deals, but the pieces are not the deals' pieces.
+    let replica_update = ReplicaUpdate3 {
+        sector_number,
+        deadline: d_idx,
+        partition: p_idx,
+        new_sealed_cid: make_sealed_cid(b"replica1"),
+        new_unsealed_cid: make_piece_cid(b"unsealed from itest vm"),
+        deals: deal_ids,
+        pieces: vec![piece_manifest(b"unproven piece")],
+        update_proof_type: fvm_shared::sector::RegisteredUpdateProof::StackedDRG32GiBV1,
+        replica_proof: vec![],
+    };
+    apply_code(
+        &v,
+        worker,
+        robust,
+        TokenAmount::zero(),
+        MinerMethod::ProveReplicaUpdates3 as u64,
+        Some(ProveReplicaUpdatesParams3 { updates: vec![replica_update] }),
+        ExitCode::USR_ILLEGAL_ARGUMENT,
+    );
+
+    // The sector is untouched.
+    let new_sector_info = sector_info(&v, maddr, sector_number);
+    assert_eq!(old_sector_info.sealed_cid, new_sector_info.sealed_cid);
+    assert!(new_sector_info.deal_ids.is_empty());
+    assert!(new_sector_info.sector_key_cid.is_none());
+    v.assert_state_invariants();
+}
+
+fn piece_manifest(data: &[u8]) -> PieceActivationManifest {
+    PieceActivationManifest {
+        cid: make_piece_cid(data),
+        size: PaddedPieceSize(32 << 30),
+        notify: vec![],
+    }
+}
+
+// Creates a miner with a single active, mutable CC sector numbered 100.
+fn create_miner_and_sector(store: &MemoryBlockstore) -> (VM, Address, Address, Address, u64, u64) {
+    let mut v = VM::new_with_singletons(store);
+    let addrs = create_accounts(&v, 1, TokenAmount::from_whole(100_000));
+    let (worker, owner) = (addrs[0], addrs[0]);
+    let seal_proof = RegisteredSealProof::StackedDRG32GiBV1P1;
+    let (maddr, robust) = create_miner(
+        &mut v,
+        owner,
+        worker,
+        seal_proof.registered_window_post_proof().unwrap(),
+        TokenAmount::from_whole(10_000),
+    );
+
+    // advance to have seal randomness epoch in the past
+    let v = v.with_epoch(200);
+    let (v, d_idx, p_idx) = create_sector(v, worker, maddr, 100, seal_proof);
+    (v, maddr, robust, worker, d_idx, p_idx)
+}
+
 fn create_miner_and_upgrade_sector(
     store: &MemoryBlockstore,
     v2: bool,
@@ -8,19 +8,24 @@ use std::ops::Neg;
 use fil_actor_datacap::State as DatacapState;
 use fil_actor_market::State as MarketState;
 use fil_actor_market::{deal_id_key, DealArray, DealMetaArray};
-use fil_actor_miner::{max_prove_commit_duration, PowerPair, SectorClaim, State as MinerState};
+use fil_actor_miner::{
+    max_prove_commit_duration, CompactCommD, DataActivationNotification, Method as MinerMethod,
+    PieceActivationManifest, PowerPair, PreCommitSectorBatchParams2, ProveCommitAggregateParams2,
+    SectorActivationManifest, SectorClaim, SectorPreCommitInfo, State as MinerState,
+};
 use fil_actor_power::State as PowerState;
 use fil_actor_verifreg::{
-    AllocationID, Claim, Method as VerifregMethod, RemoveExpiredClaimsParams,
-    RemoveExpiredClaimsReturn, State as VerifregState,
+    AllocationID, Claim, ClaimTransferProposal, ClaimTransferRequest, Method as VerifregMethod,
+    RemoveExpiredClaimsParams, RemoveExpiredClaimsReturn, State as VerifregState,
+    SIGNATURE_DOMAIN_SEPARATION_TRANSFER_CLAIM,
 };
-use fil_actors_runtime::cbor::deserialize;
+use fil_actors_runtime::cbor::{deserialize, serialize};
 use fil_actors_runtime::runtime::policy_constants::{
     DEAL_UPDATES_INTERVAL, MARKET_DEFAULT_ALLOCATION_TERM_BUFFER,
 };
 use fil_actors_runtime::runtime::Policy;
 use fil_actors_runtime::shared::HAMT_BIT_WIDTH;
-use fil_actors_runtime::test_utils::make_piece_cid;
+use fil_actors_runtime::test_utils::{make_piece_cid, make_sealed_cid};
 use fil_actors_runtime::{
     make_map_with_root_and_bitwidth, DealWeight, Map, DATACAP_TOKEN_ACTOR_ADDR, EPOCHS_IN_DAY,
     STORAGE_MARKET_ACTOR_ADDR, STORAGE_POWER_ACTOR_ADDR, VERIFIED_REGISTRY_ACTOR_ADDR,
@@ -30,8 +35,8 @@ use test_vm::util::{
     advance_by_deadline_to_epoch, advance_by_deadline_to_epoch_while_proving,
     advance_by_deadline_to_index, advance_to_proving_deadline, apply_ok, create_accounts,
     create_miner, cron_tick, datacap_extend_claim, datacap_get_balance, invariant_failure_patterns,
-    market_add_balance, market_publish_deal, miner_extend_sector_expiration2,
-    miner_precommit_sector, miner_prove_sector, sector_deadline, submit_windowed_post,
+    market_add_balance, market_publish_deal, miner_extend_sector_expiration2, miner_power,
+    miner_precommit_sector, miner_prove_sector, sector_deadline, sector_info, submit_windowed_post,
     verifreg_add_client, verifreg_add_verifier, verifreg_extend_claim_terms,
     verifreg_remove_expired_allocations,
 };
@@ -425,3 +430,191 @@ fn expired_allocations() {
         &[invariant_failure_patterns::REWARD_STATE_EPOCH_MISMATCH.to_owned()],
     );
 }
+
+// Tests that a verified claim transferred to another provider's sector moves the claim's
+// verified power from the previous provider to the new one.
+#[test]
+fn transferred_claim_moves_verified_power() {
+    let store = MemoryBlockstore::new();
+    let mut v = VM::new_with_singletons(&store);
+    let addrs = create_accounts(&v, 4, TokenAmount::from_whole(100_000));
+    let seal_proof = RegisteredSealProof::StackedDRG32GiBV1P1;
+    let (worker, worker2, verifier, verified_client) = (addrs[0], addrs[1], addrs[2], addrs[3]);
+    let sector_number: SectorNumber = 100;
+    let policy = Policy::default();
+
+    // Create the miner holding the claim, and the miner to which it is transferred.
+    let (miner_id, _) = create_miner(
+        &mut v,
+        worker,
+        worker,
+        seal_proof.registered_window_post_proof().unwrap(),
+        TokenAmount::from_whole(10_000),
+    );
+    let (miner2_id, _) = create_miner(
+        &mut v,
+        worker2,
+        worker2,
+        seal_proof.registered_window_post_proof().unwrap(),
+        TokenAmount::from_whole(10_000),
+    );
+    let v = v.with_epoch(200);
+
+    let datacap = StoragePower::from(32_u128 << 40);
+    verifreg_add_verifier(&v, verifier, &datacap * 2);
+    verifreg_add_client(&v, verifier, verified_client, datacap);
+    market_add_balance(&v, verified_client, verified_client, TokenAmount::from_whole(3));
+    market_add_balance(&v, worker, miner_id, TokenAmount::from_whole(64));
+
+    // Publish a verified deal for total sector capacity, and precommit its sector.
+    let deal_start = v.get_epoch() + max_prove_commit_duration(&policy, seal_proof).unwrap();
+    let deal_term_min = 180 * EPOCHS_IN_DAY;
+    let deal_size = 32u64 << 30;
+    let deals = market_publish_deal(
+        &v,
+        worker,
+        verified_client,
+        miner_id,
+        "deal1".to_string(),
+        PaddedPieceSize(deal_size),
+        true,
+        deal_start,
+        deal_term_min,
+    )
+    .ids;
+    miner_precommit_sector(
+        &v,
+        worker,
+        miner_id,
+        seal_proof,
+        sector_number,
+        deals.clone(),
+        deal_start + deal_term_min,
+    );
+
+    // The second miner precommits a sector to receive the claim's data, along with CC sectors
+    // to make up a minimal aggregate. Its expiration satisfies the claim's term from
+    // activation of the first sector.
+    let activation = v.get_epoch() + policy.pre_commit_challenge_delay + 1;
+    let expiration2 = activation + deal_term_min + 10 * EPOCHS_IN_DAY;
+    let precommits: Vec<SectorPreCommitInfo> = (0..policy.min_aggregated_sectors)
+        .map(|i| SectorPreCommitInfo {
+            seal_proof,
+            sector_number: sector_number + i,
+            sealed_cid: make_sealed_cid(format!("sn: {}", sector_number + i).as_bytes()),
+            seal_rand_epoch: v.get_epoch() - 1,
+            deal_ids: vec![],
+            expiration: expiration2,
+            unsealed_cid: CompactCommD::new(
+                (i == 0).then(|| make_piece_cid(b"unsealed from itest vm")),
+            ),
+        })
+        .collect();
+    apply_ok(
+        &v,
+        worker2,
+        miner2_id,
+        TokenAmount::zero(),
+        MinerMethod::PreCommitSectorBatch2 as u64,
+        Some(PreCommitSectorBatchParams2 { sectors: precommits }),
+    );
+
+    // Prove the first miner's sector and activate its power.
+    let v = advance_by_deadline_to_epoch(v, miner_id, activation).0;
+    miner_prove_sector(&v, worker, miner_id, sector_number);
+    cron_tick(&v);
+    let (deadline_info, partition_index, v) =
+        advance_to_proving_deadline(v, miner_id, sector_number);
+    let verified_power =
+        PowerPair { raw: StoragePower::from(deal_size), qa: StoragePower::from(10 * deal_size) };
+    submit_windowed_post(
+        &v,
+        worker,
+        miner_id,
+        deadline_info,
+        partition_index,
+        Some(verified_power.clone()),
+    );
+    assert_eq!(verified_power, miner_power(&v, miner_id));
+
+    let market_state: MarketState = v.get_state(STORAGE_MARKET_ACTOR_ADDR).unwrap();
+    let deal_states = DealMetaArray::load(&market_state.states, v.store).unwrap();
+    let claim_id = deal_states.get(deals[0]).unwrap().unwrap().verified_claim;
+
+    // The second miner activates its sector with the claim's piece, requesting its transfer
+    // with the client's signed approval.
+    let proposal = ClaimTransferProposal {
+        provider: miner_id.id().unwrap(),
+        claim_id,
+        new_provider: miner2_id.id().unwrap(),
+        expiration: v.get_epoch(),
+    };
+    let signature = [
+        SIGNATURE_DOMAIN_SEPARATION_TRANSFER_CLAIM,
+        serialize(&proposal, "claim transfer proposal").unwrap().bytes(),
+    ]
+    .concat();
+    let request = ClaimTransferRequest {
+        provider: proposal.provider,
+        claim_id,
+        expiration: proposal.expiration,
+        signature,
+    };
+    let mut sector_activations: Vec<SectorActivationManifest> = (0..policy.min_aggregated_sectors)
+        .map(|i| SectorActivationManifest { sector_number: sector_number + i, pieces: vec![] })
+        .collect();
+    sector_activations[0].pieces = vec![PieceActivationManifest {
+        cid: make_piece_cid("deal1".as_bytes()),
+        size: PaddedPieceSize(deal_size),
+        notify: vec![DataActivationNotification {
+            address: VERIFIED_REGISTRY_ACTOR_ADDR,
+            payload: serialize(&request, "claim transfer request").unwrap(),
+        }],
+    }];
+    apply_ok(
+        &v,
+        worker2,
+        miner2_id,
+        TokenAmount::zero(),
+        MinerMethod::ProveCommitAggregate2 as u64,
+        Some(ProveCommitAggregateParams2 { sector_activations, aggregate_proof: vec![] }),
+    );
+
+    // The claim is held by the second miner's sector.
+    let verifreg_state: VerifregState = v.get_state(VERIFIED_REGISTRY_ACTOR_ADDR).unwrap();
+    let mut claims = verifreg_state.load_claims(v.store).unwrap();
+    assert!(claims.get(miner_id.id().unwrap(), claim_id).unwrap().is_none());
+    let claim = claims.get(miner2_id.id().unwrap(), claim_id).unwrap().unwrap();
+    assert_eq!(sector_number, claim.sector);
+
+    // The first miner's sector immediately loses the claim's verified weight and power.
+    assert!(sector_info(&v, miner_id, sector_number).verified_deal_weight.is_zero());
+    let raw_power =
+        PowerPair { raw: StoragePower::from(deal_size), qa: StoragePower::from(deal_size) };
+    assert_eq!(raw_power, miner_power(&v, miner_id));
+
+    // The second miner gains the claim's verified power once its sectors are proven.
+    let sector2 = sector_info(&v, miner2_id, sector_number);
+    assert_eq!(
+        DealWeight::from(deal_size) * (sector2.expiration - sector2.activation),
+        sector2.verified_deal_weight
+    );
+    let (deadline_info, partition_index, v) =
+        advance_to_proving_deadline(v, miner2_id, sector_number);
+    let cc_sectors = policy.min_aggregated_sectors - 1;
+    let expected_power = PowerPair {
+        raw: StoragePower::from(deal_size * policy.min_aggregated_sectors),
+        qa: StoragePower::from(10 * deal_size + cc_sectors * deal_size),
+    };
+    submit_windowed_post(
+        &v,
+        worker2,
+        miner2_id,
+        deadline_info,
+        partition_index,
+        Some(expected_power.clone()),
+    );
+    assert_eq!(expected_power, miner_power(&v, miner2_id));
+
+    v.assert_state_invariants();
+}